@@ -1,5 +1,20 @@
+use tachys_sym::SharedSymbolStore;
 
-
+/// State shared by all expressions in a document
+#[derive(Default)]
 pub struct Runtime {
+    symbols: SharedSymbolStore,
+}
+
+impl Runtime {
+    /// Create a runtime that interns symbols into the given store, allowing the store to be shared
+    /// with the UI
+    pub fn new(symbols: SharedSymbolStore) -> Self {
+        Self { symbols }
+    }
 
+    /// Get the symbol table used by all expressions in this document
+    pub fn symbols(&self) -> &SharedSymbolStore {
+        &self.symbols
+    }
 }
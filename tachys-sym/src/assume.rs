@@ -0,0 +1,735 @@
+use std::collections::HashMap;
+
+use num_traits::ToPrimitive;
+
+use crate::atom::{symbol::Symbol, Atom, AtomId, ExprPool, Function, Rational, Relation};
+
+/// Largest error in units in the last place allowed for in the results of the platform's math
+/// library functions
+const LIBM_ULPS: u32 = 4;
+
+/// Fact about the values a symbol may take
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Assumption {
+    /// Takes real values
+    Real,
+    /// Takes integer values
+    Integer,
+    /// Takes real values greater than zero
+    Positive,
+    /// Takes real values less than zero
+    Negative,
+    /// Takes real values greater than or equal to zero
+    Nonnegative,
+    /// Takes real values less than or equal to zero
+    Nonpositive,
+    /// Never takes the value zero, but may take complex values
+    Nonzero,
+    /// Takes real values within the interval
+    In(Interval),
+}
+
+/// Set of real numbers between two bounds, either of which may be excluded. Infinite bounds are
+/// always excluded
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Interval {
+    pub lower: f64,
+    pub upper: f64,
+    pub lower_open: bool,
+    pub upper_open: bool,
+}
+
+/// Error produced when an assumption leaves no value that a symbol could take
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Assumption contradicts what is already assumed about the symbol")]
+pub struct Contradiction {
+    pub symbol: Symbol,
+}
+
+/// Facts assumed about symbols, which the simplifier, solver and integrator consult through
+/// [ExprPool::simplify_assuming], [ExprPool::solve_assuming] and
+/// [ExprPool::integrate_assuming]. Symbols without assumptions may take any complex value
+#[derive(Clone, Default, Debug)]
+pub struct Assumptions {
+    facts: HashMap<Symbol, Facts>,
+}
+
+/// Everything assumed about a single symbol
+#[derive(Clone, Copy, Default, Debug)]
+struct Facts {
+    /// Interval containing every value of the symbol, which is `None` unless it is real
+    interval: Option<Interval>,
+    integer: bool,
+    nonzero: bool,
+}
+
+impl Assumptions {
+    /// Create a context in which nothing is assumed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assume a fact about `symbol` in addition to those already assumed, failing if they
+    /// leave no value for it
+    pub fn assume(&mut self, symbol: Symbol, assumption: Assumption) -> Result<(), Contradiction> {
+        let mut facts = self.facts.get(&symbol).copied().unwrap_or_default();
+        let interval = match assumption {
+            Assumption::Real => Some(Interval::REAL),
+            Assumption::Integer => {
+                facts.integer = true;
+                Some(Interval::REAL)
+            }
+            Assumption::Positive => Some(Interval::open(0., f64::INFINITY)),
+            Assumption::Negative => Some(Interval::open(f64::NEG_INFINITY, 0.)),
+            Assumption::Nonnegative => Some(Interval::closed(0., f64::INFINITY)),
+            Assumption::Nonpositive => Some(Interval::closed(f64::NEG_INFINITY, 0.)),
+            Assumption::Nonzero => {
+                facts.nonzero = true;
+                None
+            }
+            Assumption::In(interval) => Some(interval),
+        };
+
+        // Tighten the interval by the other facts, so positive integers lie in `[1, ∞)`
+        if let Some(interval) = interval.or(facts.interval) {
+            let mut interval = match facts.interval {
+                Some(known) => known.intersect(&interval),
+                None => interval,
+            };
+            if facts.nonzero {
+                interval.lower_open |= interval.lower == 0.;
+                interval.upper_open |= interval.upper == 0.;
+            }
+            if facts.integer {
+                interval = interval.integers();
+            }
+            if interval.is_empty() {
+                return Err(Contradiction { symbol });
+            }
+            facts.interval = Some(interval);
+        }
+
+        self.facts.insert(symbol, facts);
+        Ok(())
+    }
+
+    /// Forget everything assumed about `symbol`
+    pub fn forget(&mut self, symbol: Symbol) {
+        self.facts.remove(&symbol);
+    }
+
+    /// Get the interval containing every value of `symbol`, or `None` if it is not assumed to be
+    /// real
+    pub fn interval(&self, symbol: Symbol) -> Option<Interval> {
+        self.facts.get(&symbol)?.interval
+    }
+
+    /// Check if `symbol` is assumed to take integer values
+    pub fn is_integer(&self, symbol: Symbol) -> bool {
+        self.facts.get(&symbol).is_some_and(|facts| facts.integer)
+    }
+
+    /// Check if `symbol` is assumed never to be zero
+    pub fn is_nonzero(&self, symbol: Symbol) -> bool {
+        self.facts.get(&symbol).is_some_and(|facts| {
+            facts.nonzero
+                || facts
+                    .interval
+                    .is_some_and(|i| i.decide(Relation::NotEqual) == Some(true))
+        })
+    }
+}
+
+impl Interval {
+    /// Every real number
+    pub const REAL: Self = Self::open(f64::NEG_INFINITY, f64::INFINITY);
+
+    /// Create the interval of numbers between `lower` and `upper` including both bounds
+    pub const fn closed(lower: f64, upper: f64) -> Self {
+        Self::new(lower, upper, false, false)
+    }
+
+    /// Create the interval of numbers between `lower` and `upper` excluding both bounds
+    pub const fn open(lower: f64, upper: f64) -> Self {
+        Self::new(lower, upper, true, true)
+    }
+
+    /// Create the interval containing only `value`
+    pub const fn point(value: f64) -> Self {
+        Self::closed(value, value)
+    }
+
+    const fn new(lower: f64, upper: f64, lower_open: bool, upper_open: bool) -> Self {
+        Self {
+            lower,
+            upper,
+            lower_open: lower_open || lower.is_infinite(),
+            upper_open: upper_open || upper.is_infinite(),
+        }
+    }
+
+    /// Check if `value` lies within the interval
+    pub fn contains(&self, value: f64) -> bool {
+        let above = value > self.lower || (value == self.lower && !self.lower_open);
+        let below = value < self.upper || (value == self.upper && !self.upper_open);
+        above && below
+    }
+
+    /// Check if no number lies within the interval
+    pub fn is_empty(&self) -> bool {
+        self.lower > self.upper
+            || (self.lower == self.upper && (self.lower_open || self.upper_open))
+            || self.lower.is_nan()
+            || self.upper.is_nan()
+    }
+
+    /// Decide whether `x op 0` holds for every `x` in the interval, returning `None` if it holds
+    /// for some numbers in the interval but not for others
+    pub fn decide(&self, op: Relation) -> Option<bool> {
+        let positive = self.lower > 0. || (self.lower == 0. && self.lower_open);
+        let negative = self.upper < 0. || (self.upper == 0. && self.upper_open);
+        let (holds, fails) = match op {
+            Relation::NotEqual => (!self.contains(0.), *self == Self::point(0.)),
+            Relation::Less => (negative, self.lower >= 0.),
+            Relation::LessEq => (self.upper <= 0., positive),
+            Relation::Greater => (positive, self.upper <= 0.),
+            Relation::GreaterEq => (self.lower >= 0., negative),
+        };
+        match (holds, fails) {
+            (true, _) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => None,
+        }
+    }
+
+    fn intersect(&self, other: &Self) -> Self {
+        let (lower, lower_open) = match self.lower.total_cmp(&other.lower) {
+            std::cmp::Ordering::Less => (other.lower, other.lower_open),
+            std::cmp::Ordering::Greater => (self.lower, self.lower_open),
+            std::cmp::Ordering::Equal => (self.lower, self.lower_open || other.lower_open),
+        };
+        let (upper, upper_open) = match self.upper.total_cmp(&other.upper) {
+            std::cmp::Ordering::Less => (self.upper, self.upper_open),
+            std::cmp::Ordering::Greater => (other.upper, other.upper_open),
+            std::cmp::Ordering::Equal => (self.upper, self.upper_open || other.upper_open),
+        };
+        Self::new(lower, upper, lower_open, upper_open)
+    }
+
+    /// Shrink the bounds to the integers within the interval
+    fn integers(&self) -> Self {
+        // An excluded integer bound steps to the next integer, unless that is not a float
+        let round = |bound: f64, open: bool, up: bool| match bound.is_finite() {
+            false => (bound, open),
+            true if open && bound.fract() == 0. => {
+                let step = if up { 1. } else { -1. };
+                match (bound + step) - bound == step {
+                    true => (bound + step, false),
+                    false => (bound, true),
+                }
+            }
+            true if up => (bound.ceil(), false),
+            true => (bound.floor(), false),
+        };
+        let (lower, lower_open) = round(self.lower, self.lower_open, true);
+        let (upper, upper_open) = round(self.upper, self.upper_open, false);
+        Self::new(lower, upper, lower_open, upper_open)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self::new(
+            add_bound(self.lower, other.lower, false),
+            add_bound(self.upper, other.upper, true),
+            self.lower_open || other.lower_open,
+            self.upper_open || other.upper_open,
+        )
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        // Products of bounds rounded down and up, where zero absorbs infinity and a product is
+        // only attained if both bounds are or one of them is an attained zero
+        let product = |(a, a_open): (f64, bool), (b, b_open): (f64, bool)| match (a == 0.
+            && !a_open)
+            || (b == 0. && !b_open)
+        {
+            true => (0., 0., false),
+            false if a == 0. || b == 0. => (0., 0., true),
+            false => (
+                mul_bound(a, b, false),
+                mul_bound(a, b, true),
+                a_open || b_open,
+            ),
+        };
+        let (lower, upper) = (
+            [(self.lower, self.lower_open), (self.upper, self.upper_open)],
+            [
+                (other.lower, other.lower_open),
+                (other.upper, other.upper_open),
+            ],
+        );
+        Self::hull(&[
+            product(lower[0], upper[0]),
+            product(lower[0], upper[1]),
+            product(lower[1], upper[0]),
+            product(lower[1], upper[1]),
+        ])
+    }
+
+    /// Get the smallest interval containing candidate bounds, each given as its value rounded
+    /// down and up and whether it is excluded
+    fn hull(candidates: &[(f64, f64, bool)]) -> Self {
+        let extreme = |value: fn(&(f64, f64, bool)) -> f64, pick: fn(f64, f64) -> bool| {
+            candidates.iter().fold(
+                (value(&candidates[0]), candidates[0].2),
+                |best, candidate| match (pick(value(candidate), best.0), value(candidate) == best.0)
+                {
+                    (true, _) => (value(candidate), candidate.2),
+                    (false, true) => (best.0, best.1 && candidate.2),
+                    (false, false) => best,
+                },
+            )
+        };
+        let (lower, lower_open) = extreme(|c| c.0, |a, b| a < b);
+        let (upper, upper_open) = extreme(|c| c.1, |a, b| a > b);
+        Self::new(lower, upper, lower_open, upper_open)
+    }
+
+    /// Apply an increasing function to both bounds, given a way to find its value at a bound
+    /// rounded down or up
+    fn increasing(&self, f: impl Fn(f64, bool) -> f64) -> Self {
+        Self::new(
+            f(self.lower, false),
+            f(self.upper, true),
+            self.lower_open,
+            self.upper_open,
+        )
+    }
+
+    /// Apply a decreasing function to both bounds, given a way to find its value at a bound
+    /// rounded down or up
+    fn decreasing(&self, f: impl Fn(f64, bool) -> f64) -> Self {
+        Self::new(
+            f(self.upper, false),
+            f(self.lower, true),
+            self.upper_open,
+            self.lower_open,
+        )
+    }
+
+    /// Get the reciprocals of the numbers in the interval, or `None` if it contains zero
+    fn recip(&self) -> Option<Self> {
+        match self.decide(Relation::NotEqual)? {
+            // The reciprocal of an excluded zero bound is infinite with the sign of the interval
+            true => Some(self.decreasing(|x, up| match x != 0. {
+                true => {
+                    let recip = 1. / x;
+                    let exact =
+                        x.is_infinite() || (recip.is_normal() && x.mul_add(recip, -1.) == 0.);
+                    match exact {
+                        true => recip,
+                        false => round(recip, up, 1),
+                    }
+                }
+                false if self.lower == 0. => f64::INFINITY,
+                false => f64::NEG_INFINITY,
+            })),
+            false => None,
+        }
+    }
+
+    /// Get the absolute values of the numbers in the interval
+    fn abs(&self) -> Self {
+        match (
+            self.decide(Relation::GreaterEq),
+            self.decide(Relation::LessEq),
+        ) {
+            (Some(true), _) => *self,
+            (_, Some(true)) => self.decreasing(|x, _| x.abs()),
+            _ => {
+                let (upper, upper_open) = match (-self.lower).total_cmp(&self.upper) {
+                    std::cmp::Ordering::Less => (self.upper, self.upper_open),
+                    std::cmp::Ordering::Greater => (-self.lower, self.lower_open),
+                    std::cmp::Ordering::Equal => (self.upper, self.lower_open && self.upper_open),
+                };
+                Self::new(0., upper, false, upper_open)
+            }
+        }
+    }
+
+    /// Raise the numbers in the interval to an integer power, or get `None` if the power is
+    /// negative and the interval contains zero
+    fn powi(&self, exponent: i32) -> Option<Self> {
+        let n = exponent.unsigned_abs();
+        let power = |x: f64, up: bool| match x < 0. {
+            true => -powi_bound(-x, n, !up),
+            false => powi_bound(x, n, up),
+        };
+        let out = match n {
+            0 => Self::point(1.),
+            n if n % 2 != 0 => self.increasing(power),
+            _ => self.abs().increasing(power),
+        };
+        match exponent < 0 {
+            true => out.recip(),
+            false => Some(out),
+        }
+    }
+
+    /// Raise the numbers in the interval to the real powers in `exponent`, or get `None` if the
+    /// interval contains negative numbers or, for powers that may not be positive, zero
+    fn powf(&self, exponent: &Self) -> Option<Self> {
+        let base = match exponent.decide(Relation::Greater) {
+            Some(true) => self.decide(Relation::GreaterEq),
+            _ => self.decide(Relation::Greater),
+        };
+        if base != Some(true) {
+            return None;
+        }
+
+        // Powers are monotonic in the base and in the exponent, so the extremes lie at the
+        // corners, which are only attained at an excluded base if the exponent is zero
+        let mut corners = Vec::with_capacity(4);
+        for (x, open) in [(self.lower, self.lower_open), (self.upper, self.upper_open)] {
+            for e in [exponent.lower, exponent.upper] {
+                let power = |x: f64, up| libm_bound(x, x.powf(e), up);
+                corners.push((power(x, false), power(x, true), open && e != 0.));
+            }
+        }
+        Some(Self::hull(&corners))
+    }
+}
+
+/// Round a bound computed in floating point down or up by `ulps` units in the last place
+fn round(value: f64, up: bool, ulps: u32) -> f64 {
+    (0..ulps).fold(value, |value, _| match up {
+        true => value.next_up(),
+        false => value.next_down(),
+    })
+}
+
+/// Add two bounds, rounding the sum down or up unless it is exact
+fn add_bound(a: f64, b: f64, up: bool) -> f64 {
+    let sum = a + b;
+    if !a.is_finite() || !b.is_finite() {
+        return sum;
+    }
+    // The rounding error of a finite sum is itself a float, found exactly by Knuth's two-sum
+    let b_part = sum - a;
+    let error = (a - (sum - b_part)) + (b - b_part);
+    match sum.is_finite() && error == 0. {
+        true => sum,
+        false => round(sum, up, 1),
+    }
+}
+
+/// Multiply two nonzero bounds, rounding the product down or up unless it is exact
+fn mul_bound(a: f64, b: f64, up: bool) -> f64 {
+    let product = a * b;
+    if !a.is_finite() || !b.is_finite() {
+        return product;
+    }
+    // The rounding error of a product that does not underflow is found exactly by a fused
+    // multiply-add
+    let exact = product.is_normal() && a.mul_add(b, -product) == 0.;
+    match exact {
+        true => product,
+        false => round(product, up, 1),
+    }
+}
+
+/// Raise a nonnegative bound to an integer power by repeated squaring, rounding the result down
+/// or up
+fn powi_bound(x: f64, exponent: u32, up: bool) -> f64 {
+    let mul = |a: f64, b: f64| match a == 0. || b == 0. {
+        true => 0.,
+        false => mul_bound(a, b, up),
+    };
+    let (mut result, mut base, mut exponent) = (1., x, exponent);
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            result = mul(result, base);
+        }
+        exponent /= 2;
+        if exponent > 0 {
+            base = mul(base, base);
+        }
+    }
+    result
+}
+
+/// Take the square root of a nonnegative bound, rounding it down or up unless it is exact
+fn sqrt_bound(x: f64, up: bool) -> f64 {
+    let root = x.sqrt();
+    let exact = x.is_infinite() || x == 0. || (root.is_normal() && root.mul_add(root, -x) == 0.);
+    match exact {
+        true => root,
+        false => round(root, up, 1),
+    }
+}
+
+/// Round the value `y` of a math library function at the bound `x` down or up to allow for its
+/// error, unless both are zero, one or infinite, where the functions used are exact
+fn libm_bound(x: f64, y: f64, up: bool) -> f64 {
+    match [x, y]
+        .iter()
+        .all(|v| *v == 0. || *v == 1. || v.is_infinite())
+    {
+        true => y,
+        false => round(y, up, LIBM_ULPS),
+    }
+}
+
+/// Apply a math library function to a bound, rounding the result down or up
+fn libm(f: fn(f64) -> f64) -> impl Fn(f64, bool) -> f64 {
+    move |x, up| libm_bound(x, f(x), up)
+}
+
+impl ExprPool {
+    /// Get an interval containing every value of the expression rooted at `id` under
+    /// `assumptions`, or `None` if it is not known to be real.
+    ///
+    /// Bounds are found by interval arithmetic in floating point, rounding outward wherever a
+    /// bound is not exact, so they are not tight when a symbol appears more than once, as in
+    /// `x - x`. Floats stand for the decimals they were written as, so they are widened as well
+    pub fn interval(&self, id: AtomId, assumptions: &Assumptions) -> Option<Interval> {
+        let interval = |id| self.interval(id, assumptions);
+        let out = match self.get(id) {
+            Atom::Integer(_) | Atom::Rational(_) | Atom::Float(_) => self.literal_interval(id)?,
+            Atom::Complex { re, im } => match self.value(*im)? {
+                0. => interval(*re)?,
+                _ => return None,
+            },
+            Atom::Symbol(symbol) => assumptions.interval(*symbol)?,
+            Atom::Sum(terms) => terms.iter().try_fold(Interval::point(0.), |sum, term| {
+                Some(sum.add(&interval(*term)?))
+            })?,
+            Atom::Product(factors) => factors
+                .iter()
+                .try_fold(Interval::point(1.), |product, factor| {
+                    Some(product.mul(&interval(*factor)?))
+                })?,
+            Atom::Neg(inner) => interval(*inner)?.mul(&Interval::point(-1.)),
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => interval(*numerator)?.mul(&interval(*denominator)?.recip()?),
+            Atom::Power { base, exponent } => {
+                let base = interval(*base)?;
+                match self.get(*exponent) {
+                    Atom::Integer(i) => base.powi(i.to_i32()?)?,
+                    _ => {
+                        let exponent = interval(*exponent)?;
+                        let bounded = exponent.lower.is_finite() && exponent.upper.is_finite();
+                        match bounded.then(|| base.powf(&exponent)).flatten() {
+                            Some(power) => power,
+                            // Positive numbers stay positive under any real power
+                            None => match base.decide(Relation::Greater)? {
+                                true => Interval::open(0., f64::INFINITY),
+                                false => return None,
+                            },
+                        }
+                    }
+                }
+            }
+            Atom::Apply { func, args } => {
+                let [arg] = **args else {
+                    return None;
+                };
+                self.apply_interval(*func, arg, assumptions)?
+            }
+            _ => return None,
+        };
+        // A bound of `∞ - ∞` says nothing about the value
+        (!out.lower.is_nan() && !out.upper.is_nan()).then_some(out)
+    }
+
+    /// Get an interval containing the value of a number literal, which is a single point if it
+    /// is exactly a float
+    fn literal_interval(&self, id: AtomId) -> Option<Interval> {
+        let (value, exact) = match self.get(id) {
+            Atom::Float(f) => (f.value(), false),
+            atom => {
+                let exact = atom.to_rational()?;
+                let value = exact.to_f64();
+                (value, Rational::from_f64(value).is_some_and(|v| v == exact))
+            }
+        };
+        match (value.is_finite(), exact) {
+            (true, true) => Some(Interval::point(value)),
+            (true, false) => Some(Interval::closed(
+                round(value, false, 1),
+                round(value, true, 1),
+            )),
+            // Rationals too large for a float are beyond its largest finite value
+            (false, _) => match self.get(id).to_rational()? {
+                exact if exact.is_negative() => Some(Interval::closed(f64::NEG_INFINITY, f64::MIN)),
+                _ => Some(Interval::closed(f64::MAX, f64::INFINITY)),
+            },
+        }
+    }
+
+    /// Get an interval containing the values of `func` applied to the expression rooted at `arg`
+    fn apply_interval(
+        &self,
+        func: Function,
+        arg: AtomId,
+        assumptions: &Assumptions,
+    ) -> Option<Interval> {
+        use std::f64::consts::PI;
+
+        // Real and imaginary parts are real whether or not their argument is
+        match func {
+            Function::Re | Function::Im | Function::Abs | Function::Arg => (),
+            _ => {
+                self.interval(arg, assumptions)?;
+            }
+        }
+        let x = || self.interval(arg, assumptions);
+        Some(match func {
+            Function::Sqrt if x()?.decide(Relation::GreaterEq)? => x()?.increasing(sqrt_bound),
+            Function::Exp => x()?.increasing(libm(f64::exp)),
+            Function::Ln | Function::Log if x()?.decide(Relation::Greater)? => match func {
+                Function::Ln => x()?.increasing(libm(f64::ln)),
+                _ => x()?.increasing(libm(f64::log10)),
+            },
+            Function::Sin | Function::Cos => Interval::closed(-1., 1.),
+            Function::Atan => x()?.increasing(libm(f64::atan)),
+            Function::Sinh => x()?.increasing(libm(f64::sinh)),
+            Function::Cosh => x()?.abs().increasing(libm(f64::cosh)),
+            Function::Tanh => x()?.increasing(libm(f64::tanh)),
+            Function::Asin | Function::Acos if x()?.lower >= -1. && x()?.upper <= 1. => {
+                match func {
+                    Function::Asin => x()?.increasing(libm(f64::asin)),
+                    _ => x()?.decreasing(libm(f64::acos)),
+                }
+            }
+            Function::Abs => match x() {
+                Some(x) => x.abs(),
+                None => Interval::closed(0., f64::INFINITY),
+            },
+            Function::Re | Function::Conj => x()?,
+            Function::Im => match x() {
+                Some(_) => Interval::point(0.),
+                None => Interval::REAL,
+            },
+            // The float nearest π is below it
+            Function::Arg => match x().and_then(|x| x.decide(Relation::GreaterEq)) {
+                Some(true) => Interval::point(0.),
+                Some(false) => Interval::closed(PI, PI.next_up()),
+                None => Interval::new(-PI.next_up(), PI.next_up(), true, false),
+            },
+            _ => return None,
+        })
+    }
+
+    /// Decide whether the expression rooted at `id` is real under `assumptions`, or get `None`
+    /// if it cannot be told
+    pub fn is_real(&self, id: AtomId, assumptions: &Assumptions) -> Option<bool> {
+        if self.interval(id, assumptions).is_some() {
+            return Some(true);
+        }
+        match self.get(id) {
+            Atom::Symbol(Symbol::IMAGINARY_UNIT) => Some(false),
+            Atom::Complex { re, im } => {
+                let parts = self.value(*re).zip(self.value(*im));
+                parts.map(|(_, im)| im == 0.)
+            }
+            _ => None,
+        }
+    }
+
+    /// Decide whether the expression rooted at `id` is a positive real number under
+    /// `assumptions`, or get `None` if it cannot be told
+    pub fn is_positive(&self, id: AtomId, assumptions: &Assumptions) -> Option<bool> {
+        self.sign(id, Relation::Greater, assumptions)
+    }
+
+    /// Decide whether the expression rooted at `id` is a negative real number under
+    /// `assumptions`, or get `None` if it cannot be told
+    pub fn is_negative(&self, id: AtomId, assumptions: &Assumptions) -> Option<bool> {
+        self.sign(id, Relation::Less, assumptions)
+    }
+
+    /// Decide whether the expression rooted at `id` is a nonnegative real number under
+    /// `assumptions`, or get `None` if it cannot be told
+    pub fn is_nonnegative(&self, id: AtomId, assumptions: &Assumptions) -> Option<bool> {
+        self.sign(id, Relation::GreaterEq, assumptions)
+    }
+
+    /// Decide whether the expression rooted at `id` is never zero under `assumptions`, or get
+    /// `None` if it cannot be told
+    pub fn is_nonzero(&self, id: AtomId, assumptions: &Assumptions) -> Option<bool> {
+        if let Some(nonzero) = self.sign(id, Relation::NotEqual, assumptions) {
+            return Some(nonzero);
+        }
+        let nonzero = |id: &AtomId| self.is_nonzero(*id, assumptions) == Some(true);
+        match self.get(id) {
+            Atom::Symbol(symbol) if assumptions.is_nonzero(*symbol) => Some(true),
+            Atom::Product(factors) if factors.iter().all(nonzero) => Some(true),
+            Atom::Neg(inner) => self.is_nonzero(*inner, assumptions),
+            Atom::Power { base, .. } if nonzero(base) => Some(true),
+            Atom::Apply {
+                func: Function::Exp,
+                ..
+            } => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Decide whether the expression rooted at `id` takes only integer values under
+    /// `assumptions`, or get `None` if it cannot be told
+    pub fn is_integer(&self, id: AtomId, assumptions: &Assumptions) -> Option<bool> {
+        let integer = |id: &AtomId| self.is_integer(*id, assumptions) == Some(true);
+        match self.get(id) {
+            Atom::Integer(_) => Some(true),
+            Atom::Rational(_) => Some(false),
+            Atom::Symbol(symbol) if assumptions.is_integer(*symbol) => Some(true),
+            Atom::Sum(items) | Atom::Product(items) if items.iter().all(integer) => Some(true),
+            Atom::Neg(inner) => self.is_integer(*inner, assumptions),
+            Atom::Power { base, exponent }
+                if integer(base)
+                    && integer(exponent)
+                    && self.is_nonnegative(*exponent, assumptions) == Some(true) =>
+            {
+                Some(true)
+            }
+            // Values strictly between two integers are not integers, as for `sqrt(2)`, and the
+            // bounds only meet where they are exact
+            _ => match self.interval(id, assumptions) {
+                Some(i) if i.integers().is_empty() => Some(false),
+                Some(i) if i.lower == i.upper => Some(true),
+                Some(_) => None,
+                None => match self.is_real(id, assumptions) {
+                    Some(false) => Some(false),
+                    _ => None,
+                },
+            },
+        }
+    }
+
+    /// Get the value of a real number literal, or `None` if it is not exactly a finite float
+    fn value(&self, id: AtomId) -> Option<f64> {
+        let value = match self.get(id) {
+            Atom::Float(f) => f.value(),
+            atom => {
+                let exact = atom.to_rational()?;
+                let value = exact.to_f64();
+                Rational::from_f64(value).filter(|v| *v == exact)?;
+                value
+            }
+        };
+        value.is_finite().then_some(value)
+    }
+
+    /// Decide whether `x op 0` holds for the value `x` of the expression rooted at `id`, which
+    /// fails for complex numbers other than for `x ≠ 0`
+    fn sign(&self, id: AtomId, op: Relation, assumptions: &Assumptions) -> Option<bool> {
+        match self.interval(id, assumptions) {
+            Some(interval) => interval.decide(op),
+            None => match self.is_real(id, assumptions)? {
+                true => None,
+                false => Some(op == Relation::NotEqual),
+            },
+        }
+    }
+}
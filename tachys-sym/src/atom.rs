@@ -1,18 +1,337 @@
+use num_traits::{One, Signed};
 use symbol::Symbol;
 
+pub mod function;
+pub mod number;
+pub mod pool;
 pub mod symbol;
+pub mod unit;
 
-pub struct AtomId();
+pub use function::Function;
+pub use number::{BigInt, Float, Rational};
+pub use pool::ExprPool;
+pub use unit::{Dimension, Unit};
 
+/// Index of an [Atom] stored in an [ExprPool].
+/// Because pools deduplicate structurally identical atoms, two IDs from the same pool are equal if
+/// and only if the expressions they refer to are equal
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct AtomId(u32);
+
+/// A single node in an expression tree, referencing child expressions by their [AtomId]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Atom {
+    Integer(BigInt),
+    Rational(Rational),
+    Float(Float),
+    /// Complex number literal `re + im·i`, whose parts are real numeric literals
+    Complex {
+        re: AtomId,
+        im: AtomId,
+    },
     Symbol(Symbol),
+    /// Unit of measurement, which multiplies a number to form a quantity as in `9.81 m/s^2`
+    Unit(Unit),
+    /// Sum of any number of terms
+    Sum(Box<[AtomId]>),
+    /// Product of any number of factors
+    Product(Box<[AtomId]>),
+    Power {
+        base: AtomId,
+        exponent: AtomId,
+    },
+    Neg(AtomId),
+    Apply {
+        func: Function,
+        args: Box<[AtomId]>,
+    },
+    Equals {
+        lhs: AtomId,
+        rhs: AtomId,
+    },
+    Relation {
+        op: Relation,
+        lhs: AtomId,
+        rhs: AtomId,
+    },
+    /// Integral of `integrand` with respect to `variable`, which is indefinite if no bounds are
+    /// given
     Integral {
-        upper: AtomId,
-        lower: AtomId,
+        variable: Symbol,
+        upper: Option<AtomId>,
+        lower: Option<AtomId>,
         integrand: AtomId,
     },
+    /// Sum or product of `term` over the integers `index` from `lower` to `upper` inclusive,
+    /// where `upper` may be infinite
+    BigOperator {
+        op: BigOperator,
+        index: Symbol,
+        lower: AtomId,
+        upper: AtomId,
+        term: AtomId,
+    },
     Fraction {
         numerator: AtomId,
         denominator: AtomId,
     },
+    /// Unevaluated derivative of `inner` taken `order` times with respect to `variable`
+    Derivative {
+        variable: Symbol,
+        order: u32,
+        inner: AtomId,
+    },
+    /// Matrix with `columns` columns whose entries are stored row by row. Vectors are matrices
+    /// with a single column
+    Matrix {
+        columns: u32,
+        entries: Box<[AtomId]>,
+    },
+    /// Unevaluated limit of `inner` as `variable` approaches `point`, from one side if a
+    /// direction is given
+    Limit {
+        variable: Symbol,
+        point: AtomId,
+        direction: Option<Direction>,
+        inner: AtomId,
+    },
+    /// Remainder `O(term)` of a truncated series, standing for terms that vanish at least as fast
+    /// as `term` at the expansion point
+    Order(AtomId),
+}
+
+/// Comparison operator of an [Atom::Relation]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Relation {
+    NotEqual,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+}
+
+/// Operation repeated over the index of an [Atom::BigOperator]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BigOperator {
+    /// Summation, written `∑`
+    Sum,
+    /// Repeated multiplication, written `∏`
+    Product,
+}
+
+/// Side from which the variable of an [Atom::Limit] approaches its point
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Direction {
+    /// From smaller values, written `x → a⁻`
+    Below,
+    /// From larger values, written `x → a⁺`
+    Above,
+}
+
+impl AtomId {
+    /// Get the index of this atom in the pool that created it
+    pub const fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Atom {
+    /// Iterate over the IDs of all direct children of this atom
+    pub fn children(&self) -> impl Iterator<Item = AtomId> + '_ {
+        let (fixed, rest): ([Option<AtomId>; 3], &[AtomId]) = match self {
+            Self::Integer(_)
+            | Self::Rational(_)
+            | Self::Float(_)
+            | Self::Symbol(_)
+            | Self::Unit(_) => ([None; 3], &[]),
+            Self::Sum(terms) => ([None; 3], terms),
+            Self::Product(factors) => ([None; 3], factors),
+            Self::Power { base, exponent } => ([Some(*base), Some(*exponent), None], &[]),
+            Self::Complex { re, im } => ([Some(*re), Some(*im), None], &[]),
+            Self::Neg(inner) | Self::Order(inner) => ([Some(*inner), None, None], &[]),
+            Self::Apply { args, .. } => ([None; 3], args),
+            Self::Equals { lhs, rhs } | Self::Relation { lhs, rhs, .. } => {
+                ([Some(*lhs), Some(*rhs), None], &[])
+            }
+            Self::Integral {
+                upper,
+                lower,
+                integrand,
+                ..
+            } => ([*upper, *lower, Some(*integrand)], &[]),
+            Self::BigOperator {
+                lower, upper, term, ..
+            } => ([Some(*lower), Some(*upper), Some(*term)], &[]),
+            Self::Fraction {
+                numerator,
+                denominator,
+            } => ([Some(*numerator), Some(*denominator), None], &[]),
+            Self::Derivative { inner, .. } => ([Some(*inner), None, None], &[]),
+            Self::Limit { point, inner, .. } => ([Some(*point), Some(*inner), None], &[]),
+            Self::Matrix { entries, .. } => ([None; 3], entries),
+        };
+
+        fixed.into_iter().flatten().chain(rest.iter().copied())
+    }
+
+    /// Create a copy of this atom with every direct child replaced by the result of `f`
+    pub fn map_children<F: FnMut(AtomId) -> AtomId>(&self, mut f: F) -> Self {
+        match self {
+            Self::Integer(_)
+            | Self::Rational(_)
+            | Self::Float(_)
+            | Self::Symbol(_)
+            | Self::Unit(_) => self.clone(),
+            Self::Sum(terms) => Self::Sum(terms.iter().map(|t| f(*t)).collect()),
+            Self::Product(factors) => Self::Product(factors.iter().map(|t| f(*t)).collect()),
+            Self::Power { base, exponent } => Self::Power {
+                base: f(*base),
+                exponent: f(*exponent),
+            },
+            Self::Complex { re, im } => Self::Complex {
+                re: f(*re),
+                im: f(*im),
+            },
+            Self::Neg(inner) => Self::Neg(f(*inner)),
+            Self::Order(term) => Self::Order(f(*term)),
+            Self::Apply { func, args } => Self::Apply {
+                func: *func,
+                args: args.iter().map(|a| f(*a)).collect(),
+            },
+            Self::Equals { lhs, rhs } => Self::Equals {
+                lhs: f(*lhs),
+                rhs: f(*rhs),
+            },
+            Self::Relation { op, lhs, rhs } => Self::Relation {
+                op: *op,
+                lhs: f(*lhs),
+                rhs: f(*rhs),
+            },
+            Self::Integral {
+                variable,
+                upper,
+                lower,
+                integrand,
+            } => Self::Integral {
+                variable: *variable,
+                upper: upper.map(&mut f),
+                lower: lower.map(&mut f),
+                integrand: f(*integrand),
+            },
+            Self::BigOperator {
+                op,
+                index,
+                lower,
+                upper,
+                term,
+            } => Self::BigOperator {
+                op: *op,
+                index: *index,
+                lower: f(*lower),
+                upper: f(*upper),
+                term: f(*term),
+            },
+            Self::Fraction {
+                numerator,
+                denominator,
+            } => Self::Fraction {
+                numerator: f(*numerator),
+                denominator: f(*denominator),
+            },
+            Self::Derivative {
+                variable,
+                order,
+                inner,
+            } => Self::Derivative {
+                variable: *variable,
+                order: *order,
+                inner: f(*inner),
+            },
+            Self::Limit {
+                variable,
+                point,
+                direction,
+                inner,
+            } => Self::Limit {
+                variable: *variable,
+                point: f(*point),
+                direction: *direction,
+                inner: f(*inner),
+            },
+            Self::Matrix { columns, entries } => Self::Matrix {
+                columns: *columns,
+                entries: entries.iter().map(|e| f(*e)).collect(),
+            },
+        }
+    }
+
+    /// Check if this atom has no children
+    pub fn is_leaf(&self) -> bool {
+        self.children().next().is_none()
+    }
+
+    /// Check if this atom is a numeric literal, which may be complex
+    pub const fn is_number(&self) -> bool {
+        matches!(
+            self,
+            Self::Integer(_) | Self::Rational(_) | Self::Float(_) | Self::Complex { .. }
+        )
+    }
+
+    /// Check if this atom is a zero literal
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Self::Float(f) => f.value() == 0.,
+            atom => atom.to_rational().is_some_and(|r| r.is_zero()),
+        }
+    }
+
+    /// Check if this atom is the integer `-1`
+    pub fn is_minus_one(&self) -> bool {
+        matches!(self, Self::Integer(i) if i.is_negative() && i.magnitude().is_one())
+    }
+
+    /// Get the exact value of this atom if it is an integer or rational literal
+    pub fn to_rational(&self) -> Option<Rational> {
+        match self {
+            Self::Integer(i) => Some(Rational::integer(i.clone())),
+            Self::Rational(r) => Some(r.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Relation {
+    /// Get the relation that holds when the operands of this relation are swapped
+    pub const fn flip(&self) -> Self {
+        match self {
+            Self::NotEqual => Self::NotEqual,
+            Self::Less => Self::Greater,
+            Self::LessEq => Self::GreaterEq,
+            Self::Greater => Self::Less,
+            Self::GreaterEq => Self::LessEq,
+        }
+    }
+
+    /// Get the ASCII operator used to write this relation
+    pub const fn ascii(&self) -> &'static str {
+        match self {
+            Self::NotEqual => "!=",
+            Self::Less => "<",
+            Self::LessEq => "<=",
+            Self::Greater => ">",
+            Self::GreaterEq => ">=",
+        }
+    }
+
+    /// Get the Unicode operator used to write this relation
+    pub const fn unicode(&self) -> &'static str {
+        match self {
+            Self::NotEqual => "≠",
+            Self::Less => "<",
+            Self::LessEq => "≤",
+            Self::Greater => ">",
+            Self::GreaterEq => "≥",
+        }
+    }
 }
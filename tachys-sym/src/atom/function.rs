@@ -0,0 +1,98 @@
+use super::symbol::Symbol;
+
+/// A function that may be applied to arguments in an [Atom::Apply](super::Atom::Apply)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    /// Natural logarithm
+    Ln,
+    /// Base 10 logarithm
+    Log,
+    Sqrt,
+    /// Absolute value, or modulus of a complex number
+    Abs,
+    /// Real part of a complex number
+    Re,
+    /// Imaginary part of a complex number
+    Im,
+    /// Complex conjugate
+    Conj,
+    /// Principal argument of a complex number in `(-π, π]`
+    Arg,
+    /// A function defined by the user and identified only by its name
+    User(Symbol),
+}
+
+impl Function {
+    /// All builtin functions, excluding [Function::User]
+    pub const BUILTIN: [Self; 18] = [
+        Self::Sin,
+        Self::Cos,
+        Self::Tan,
+        Self::Asin,
+        Self::Acos,
+        Self::Atan,
+        Self::Sinh,
+        Self::Cosh,
+        Self::Tanh,
+        Self::Exp,
+        Self::Ln,
+        Self::Log,
+        Self::Sqrt,
+        Self::Abs,
+        Self::Re,
+        Self::Im,
+        Self::Conj,
+        Self::Arg,
+    ];
+
+    /// Get the builtin function with the given name, if any
+    pub fn builtin(name: &str) -> Option<Self> {
+        Self::BUILTIN
+            .into_iter()
+            .find(|f| f.builtin_name() == Some(name))
+    }
+
+    /// Get the name used to write this function in linear text, or `None` for user functions
+    pub const fn builtin_name(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Sin => "sin",
+            Self::Cos => "cos",
+            Self::Tan => "tan",
+            Self::Asin => "asin",
+            Self::Acos => "acos",
+            Self::Atan => "atan",
+            Self::Sinh => "sinh",
+            Self::Cosh => "cosh",
+            Self::Tanh => "tanh",
+            Self::Exp => "exp",
+            Self::Ln => "ln",
+            Self::Log => "log",
+            Self::Sqrt => "sqrt",
+            Self::Abs => "abs",
+            Self::Re => "re",
+            Self::Im => "im",
+            Self::Conj => "conj",
+            Self::Arg => "arg",
+            Self::User(_) => return None,
+        })
+    }
+
+    /// Get the number of arguments this function accepts, or `None` if it is a user function that
+    /// may accept any number
+    pub const fn arity(&self) -> Option<usize> {
+        match self {
+            Self::User(_) => None,
+            _ => Some(1),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+pub use num_bigint::BigInt;
+
+/// A floating point literal that can be hashed and compared for equality by its bit pattern,
+/// allowing it to be stored in an [ExprPool](super::ExprPool).
+/// Note that under this comparison `NaN == NaN` and `0.0 != -0.0`
+#[derive(Clone, Copy, Debug)]
+pub struct Float(pub f64);
+
+/// An exact arbitrary-precision rational number in lowest terms with a strictly positive
+/// denominator
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Rational(BigRational);
+
+impl Float {
+    /// Get the floating point value of this literal
+    pub const fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for Float {}
+
+impl Hash for Float {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state)
+    }
+}
+
+impl From<f64> for Float {
+    fn from(value: f64) -> Self {
+        Self(value)
+    }
+}
+
+impl Rational {
+    /// Create a rational number from the given numerator and denominator, reducing it to lowest
+    /// terms. Returns `None` if the denominator is zero
+    pub fn new(numerator: impl Into<BigInt>, denominator: impl Into<BigInt>) -> Option<Self> {
+        let denominator = denominator.into();
+        match denominator.is_zero() {
+            true => None,
+            false => Some(Self(BigRational::new(numerator.into(), denominator))),
+        }
+    }
+
+    /// Create a rational number with a denominator of one
+    pub fn integer(value: impl Into<BigInt>) -> Self {
+        Self(BigRational::from_integer(value.into()))
+    }
+
+    /// Get the exact value of the given float, or `None` if it is infinite or NaN
+    pub fn from_f64(value: f64) -> Option<Self> {
+        BigRational::from_float(value).map(Self)
+    }
+
+    /// Get the numerator of this number in lowest terms
+    pub fn numerator(&self) -> &BigInt {
+        self.0.numer()
+    }
+
+    /// Get the always-positive denominator of this number in lowest terms
+    pub fn denominator(&self) -> &BigInt {
+        self.0.denom()
+    }
+
+    /// Check if this number has a denominator of one
+    pub fn is_integer(&self) -> bool {
+        self.0.is_integer()
+    }
+
+    /// Check if this number is zero
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// Check if this number is one
+    pub fn is_one(&self) -> bool {
+        self.0.is_one()
+    }
+
+    /// Check if this number is less than zero
+    pub fn is_negative(&self) -> bool {
+        self.0.is_negative()
+    }
+
+    /// Get the absolute value of this number
+    pub fn abs(&self) -> Self {
+        Self(self.0.abs())
+    }
+
+    /// Get the multiplicative inverse of this number, or `None` if it is zero
+    pub fn recip(&self) -> Option<Self> {
+        match self.is_zero() {
+            true => None,
+            false => Some(Self(self.0.recip())),
+        }
+    }
+
+    /// Divide this number by `rhs`, returning `None` if `rhs` is zero
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        match rhs.is_zero() {
+            true => None,
+            false => Some(Self(&self.0 / &rhs.0)),
+        }
+    }
+
+    /// Raise this number to an integer power, returning `None` when raising zero to a negative
+    /// power
+    pub fn pow(&self, exponent: i32) -> Option<Self> {
+        match self.is_zero() && exponent < 0 {
+            true => None,
+            false => Some(Self(num_traits::Pow::pow(&self.0, exponent))),
+        }
+    }
+
+    /// Get the integer part of this number, rounding towards negative infinity
+    pub fn floor(&self) -> BigInt {
+        self.0.floor().to_integer()
+    }
+
+    /// Get the closest floating point approximation of this number
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_f64().unwrap_or(f64::NAN)
+    }
+
+    /// Consume this number, returning its numerator and denominator in lowest terms
+    pub fn into_parts(self) -> (BigInt, BigInt) {
+        self.0.into_raw()
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(value: BigInt) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl Add for &Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Self) -> Rational {
+        Rational(&self.0 + &rhs.0)
+    }
+}
+
+impl Sub for &Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Self) -> Rational {
+        Rational(&self.0 - &rhs.0)
+    }
+}
+
+impl Mul for &Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Self) -> Rational {
+        Rational(&self.0 * &rhs.0)
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational(-&self.0)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Get the greatest common divisor of the given integers, always non-negative
+pub fn gcd(a: &BigInt, b: &BigInt) -> BigInt {
+    a.gcd(b)
+}
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use super::{
+    function::Function,
+    number::{BigInt, Float, Rational},
+    symbol::Symbol,
+    unit::Unit,
+    Atom, AtomId, BigOperator, Relation,
+};
+
+/// Arena owning all [Atom]s of a set of expression trees.
+/// Atoms are hash-consed on insertion so that every structurally unique subtree is stored exactly
+/// once, making comparison of whole expressions a comparison of their [AtomId]s
+#[derive(Default, Debug, Clone)]
+pub struct ExprPool {
+    atoms: Vec<Atom>,
+    lookup: HashMap<Atom, AtomId>,
+}
+
+impl ExprPool {
+    /// Create a new pool with no atoms
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert the given atom into the pool, returning the ID of an existing identical atom if one
+    /// has already been inserted
+    pub fn insert(&mut self, atom: Atom) -> AtomId {
+        let atom = match atom {
+            Atom::Rational(r) if r.is_integer() => Atom::Integer(r.into_parts().0),
+            atom => atom,
+        };
+        if let Some(id) = self.lookup.get(&atom) {
+            return *id;
+        }
+
+        let id = AtomId(
+            u32::try_from(self.atoms.len()).expect("Expression pool exceeded u32::MAX atoms"),
+        );
+        self.atoms.push(atom.clone());
+        self.lookup.insert(atom, id);
+
+        id
+    }
+
+    /// Get the ID of the given atom if it has been inserted into this pool
+    pub fn find(&self, atom: &Atom) -> Option<AtomId> {
+        self.lookup.get(atom).copied()
+    }
+
+    /// Get the atom with the given ID.
+    ///
+    /// Panics if the ID was created by a different pool
+    pub fn get(&self, id: AtomId) -> &Atom {
+        &self.atoms[id.index()]
+    }
+
+    /// Get the number of unique atoms stored in this pool
+    pub fn len(&self) -> usize {
+        self.atoms.len()
+    }
+
+    /// Check if this pool contains no atoms
+    pub fn is_empty(&self) -> bool {
+        self.atoms.is_empty()
+    }
+
+    /// Iterate over every atom in the subtree rooted at `id` in pre-order, visiting shared
+    /// subtrees once for each time they are referenced
+    pub fn descendants(&self, id: AtomId) -> impl Iterator<Item = AtomId> + '_ {
+        let mut stack = vec![id];
+        std::iter::from_fn(move || {
+            let next = stack.pop()?;
+            let start = stack.len();
+            stack.extend(self.get(next).children());
+            stack[start..].reverse();
+            Some(next)
+        })
+    }
+
+    /// Check if the expression rooted at `haystack` contains the expression `needle`
+    pub fn contains(&self, haystack: AtomId, needle: AtomId) -> bool {
+        self.descendants(haystack).any(|id| id == needle)
+    }
+
+    /// Check if the given symbol appears anywhere in the expression rooted at `id`
+    pub fn depends_on(&self, id: AtomId, sym: Symbol) -> bool {
+        self.descendants(id)
+            .any(|id| matches!(self.get(id), Atom::Symbol(s) if *s == sym))
+    }
+
+    /// Replace every occurrence of `from` in the expression rooted at `id` with `to`
+    pub fn substitute(&mut self, id: AtomId, from: AtomId, to: AtomId) -> AtomId {
+        let mut memo = HashMap::new();
+        self.substitute_memo(id, from, to, &mut memo)
+    }
+
+    fn substitute_memo(
+        &mut self,
+        id: AtomId,
+        from: AtomId,
+        to: AtomId,
+        memo: &mut HashMap<AtomId, AtomId>,
+    ) -> AtomId {
+        if id == from {
+            return to;
+        }
+        if let Some(done) = memo.get(&id) {
+            return *done;
+        }
+
+        let atom = self.get(id).clone();
+        let replaced = atom.map_children(|child| self.substitute_memo(child, from, to, memo));
+        let out = self.insert(replaced);
+        memo.insert(id, out);
+        out
+    }
+
+    /// Insert an integer literal
+    pub fn integer(&mut self, value: impl Into<BigInt>) -> AtomId {
+        self.insert(Atom::Integer(value.into()))
+    }
+
+    /// Insert the exact rational number `numerator / denominator`, which is stored as an integer if
+    /// it reduces to one. Returns `None` if the denominator is zero
+    pub fn rational(
+        &mut self,
+        numerator: impl Into<BigInt>,
+        denominator: impl Into<BigInt>,
+    ) -> Option<AtomId> {
+        Rational::new(numerator, denominator).map(|value| self.number(value))
+    }
+
+    /// Insert an exact rational number, which is stored as an integer if it has a denominator of
+    /// one
+    pub fn number(&mut self, value: Rational) -> AtomId {
+        self.insert(Atom::Rational(value))
+    }
+
+    /// Insert a floating point literal
+    pub fn float(&mut self, value: f64) -> AtomId {
+        self.insert(Atom::Float(Float(value)))
+    }
+
+    /// Insert the complex literal `re + im·i`, whose parts should be real numeric literals
+    pub fn complex(&mut self, re: AtomId, im: AtomId) -> AtomId {
+        self.insert(Atom::Complex { re, im })
+    }
+
+    /// Insert a symbol atom
+    pub fn symbol(&mut self, sym: Symbol) -> AtomId {
+        self.insert(Atom::Symbol(sym))
+    }
+
+    /// Insert a unit atom
+    pub fn unit(&mut self, unit: Unit) -> AtomId {
+        self.insert(Atom::Unit(unit))
+    }
+
+    /// Insert the sum of the given terms.
+    /// An empty sum is inserted as the integer `0` and a sum of one term is that term
+    pub fn sum<I: IntoIterator<Item = AtomId>>(&mut self, terms: I) -> AtomId {
+        let terms = terms.into_iter().collect::<Box<[_]>>();
+        match *terms {
+            [] => self.integer(0),
+            [term] => term,
+            _ => self.insert(Atom::Sum(terms)),
+        }
+    }
+
+    /// Insert the product of the given factors.
+    /// An empty product is inserted as the integer `1` and a product of one factor is that factor
+    pub fn product<I: IntoIterator<Item = AtomId>>(&mut self, factors: I) -> AtomId {
+        let factors = factors.into_iter().collect::<Box<[_]>>();
+        match *factors {
+            [] => self.integer(1),
+            [factor] => factor,
+            _ => self.insert(Atom::Product(factors)),
+        }
+    }
+
+    /// Insert `base` raised to the power of `exponent`
+    pub fn power(&mut self, base: AtomId, exponent: AtomId) -> AtomId {
+        self.insert(Atom::Power { base, exponent })
+    }
+
+    /// Insert the negation of `inner`
+    pub fn neg(&mut self, inner: AtomId) -> AtomId {
+        self.insert(Atom::Neg(inner))
+    }
+
+    /// Insert the series remainder `O(term)`
+    pub fn order(&mut self, term: AtomId) -> AtomId {
+        self.insert(Atom::Order(term))
+    }
+
+    /// Insert the application of `func` to the given arguments
+    pub fn apply<I: IntoIterator<Item = AtomId>>(&mut self, func: Function, args: I) -> AtomId {
+        self.insert(Atom::Apply {
+            func,
+            args: args.into_iter().collect(),
+        })
+    }
+
+    /// Insert the equation `lhs = rhs`
+    pub fn equals(&mut self, lhs: AtomId, rhs: AtomId) -> AtomId {
+        self.insert(Atom::Equals { lhs, rhs })
+    }
+
+    /// Insert the relation `lhs op rhs`
+    pub fn relation(&mut self, op: Relation, lhs: AtomId, rhs: AtomId) -> AtomId {
+        self.insert(Atom::Relation { op, lhs, rhs })
+    }
+
+    /// Insert a definite integral of `integrand` with respect to `variable` from `lower` to
+    /// `upper`
+    pub fn integral(
+        &mut self,
+        variable: Symbol,
+        lower: AtomId,
+        upper: AtomId,
+        integrand: AtomId,
+    ) -> AtomId {
+        self.insert(Atom::Integral {
+            variable,
+            upper: Some(upper),
+            lower: Some(lower),
+            integrand,
+        })
+    }
+
+    /// Insert an indefinite integral of `integrand` with respect to `variable`
+    pub fn antiderivative(&mut self, variable: Symbol, integrand: AtomId) -> AtomId {
+        self.insert(Atom::Integral {
+            variable,
+            upper: None,
+            lower: None,
+            integrand,
+        })
+    }
+
+    /// Insert the sum or product of `term` over the integers `index` from `lower` to `upper`
+    pub fn big_operator(
+        &mut self,
+        op: BigOperator,
+        index: Symbol,
+        lower: AtomId,
+        upper: AtomId,
+        term: AtomId,
+    ) -> AtomId {
+        self.insert(Atom::BigOperator {
+            op,
+            index,
+            lower,
+            upper,
+            term,
+        })
+    }
+
+    /// Insert the unevaluated `order`th derivative of `inner` with respect to `variable`
+    pub fn derivative(&mut self, variable: Symbol, order: u32, inner: AtomId) -> AtomId {
+        self.insert(Atom::Derivative {
+            variable,
+            order,
+            inner,
+        })
+    }
+
+    /// Insert the fraction `numerator / denominator`
+    pub fn fraction(&mut self, numerator: AtomId, denominator: AtomId) -> AtomId {
+        self.insert(Atom::Fraction {
+            numerator,
+            denominator,
+        })
+    }
+
+    /// Insert a matrix with the given rows, or return `None` if there are no rows, the rows are
+    /// empty or they differ in length
+    pub fn matrix<R: IntoIterator<Item = Vec<AtomId>>>(&mut self, rows: R) -> Option<AtomId> {
+        let mut rows = rows.into_iter().peekable();
+        let columns = rows.peek()?.len();
+        let mut entries = Vec::new();
+        for row in rows {
+            if row.len() != columns {
+                return None;
+            }
+            entries.extend(row);
+        }
+
+        match columns {
+            0 => None,
+            _ => Some(self.insert(Atom::Matrix {
+                columns: columns.try_into().ok()?,
+                entries: entries.into(),
+            })),
+        }
+    }
+
+    /// Insert a vector, written as a matrix with a single column, or return `None` if it is empty
+    pub fn vector<I: IntoIterator<Item = AtomId>>(&mut self, entries: I) -> Option<AtomId> {
+        self.matrix(entries.into_iter().map(|entry| vec![entry]))
+    }
+}
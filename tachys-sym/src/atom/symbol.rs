@@ -1,11 +1,231 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
 
-#[derive(Default, Debug)]
+use internment::ArcIntern;
+
+/// Table of all symbol names used in an expression context, mapping between [Symbol] handles and
+/// their textual names.
+///
+/// Every store starts with `i`, `e` and `π` interned as [Symbol::IMAGINARY_UNIT], [Symbol::E] and
+/// [Symbol::PI]
+#[derive(Debug, Clone)]
 pub struct SymbolStore {
-    
+    names: Vec<ArcIntern<str>>,
+    lookup: HashMap<ArcIntern<str>, Symbol>,
 }
 
+/// A [SymbolStore] behind a reference counted lock, allowing multiple owners across threads to
+/// intern and resolve symbols from the same table
+#[derive(Default, Debug, Clone)]
+pub struct SharedSymbolStore(Arc<RwLock<SymbolStore>>);
+
 /// A lightweight value used to identify unique strings when performing expression transformations
-#[derive(PartialEq, Eq, Hash,)]
-pub struct Symbol {
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Symbol(u32);
+
+impl SymbolStore {
+    /// Create a new store with only the built-in constants interned
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the symbol for the given name, interning the name if it has not been seen before
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        // Interned strings hash by address, so lookups must go through the global intern table
+        // instead of borrowing as `str`
+        let name = ArcIntern::<str>::from(name);
+        if let Some(sym) = self.lookup.get(&name) {
+            return *sym;
+        }
+
+        let sym = Symbol(self.names.len() as u32);
+        self.names.push(name.clone());
+        self.lookup.insert(name, sym);
+
+        sym
+    }
+
+    /// Get the symbol for the given name only if it has already been interned
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.lookup.get(&ArcIntern::<str>::from(name)).copied()
+    }
+
+    /// Get the name that the given symbol was interned from.
+    ///
+    /// Panics if the symbol was created by a different store
+    pub fn name(&self, sym: Symbol) -> &str {
+        &self.names[sym.0 as usize]
+    }
+
+    /// Get a reference counted handle to the name of the given symbol that may outlive this store
+    pub fn name_arc(&self, sym: Symbol) -> ArcIntern<str> {
+        self.names[sym.0 as usize].clone()
+    }
+
+    /// Get the number of unique symbols interned in this store besides the built-in constants
+    pub fn len(&self) -> usize {
+        self.names.len() - Symbol::BUILTIN.len()
+    }
+
+    /// Check if no symbols other than the built-in constants have been interned in this store
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the symbols interned besides the built-in constants and their names in the
+    /// order they were interned
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .skip(Symbol::BUILTIN.len())
+            .map(|(i, name)| (Symbol(i as u32), name.as_ref()))
+    }
+
+    /// Move this store behind a lock so that it can be shared between threads
+    pub fn into_shared(self) -> SharedSymbolStore {
+        SharedSymbolStore(Arc::new(RwLock::new(self)))
+    }
+}
+
+impl Default for SymbolStore {
+    fn default() -> Self {
+        let mut store = Self {
+            names: Vec::new(),
+            lookup: HashMap::new(),
+        };
+        for name in Symbol::BUILTIN {
+            store.intern(name);
+        }
+        store
+    }
+}
+
+impl SharedSymbolStore {
+    /// Create a new shared store with only the built-in constants interned
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the symbol for the given name, interning the name if it has not been seen before.
+    /// Only takes the write lock if the name is not yet interned
+    pub fn intern(&self, name: &str) -> Symbol {
+        if let Some(sym) = self.read().get(name) {
+            return sym;
+        }
+
+        self.write().intern(name)
+    }
+
+    /// Get the symbol for the given name only if it has already been interned
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.read().get(name)
+    }
+
+    /// Get a handle to the name that the given symbol was interned from
+    pub fn name(&self, sym: Symbol) -> ArcIntern<str> {
+        self.read().name_arc(sym)
+    }
+
+    /// Lock the store for reading, allowing borrowed access to symbol names
+    pub fn read(&self) -> RwLockReadGuard<'_, SymbolStore> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Lock the store for writing
+    pub fn write(&self) -> RwLockWriteGuard<'_, SymbolStore> {
+        self.0.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl From<SymbolStore> for SharedSymbolStore {
+    fn from(value: SymbolStore) -> Self {
+        value.into_shared()
+    }
+}
+
+impl Symbol {
+    /// The imaginary unit `i`, which is interned first in every [SymbolStore] so that it can be
+    /// recognized without one
+    pub const IMAGINARY_UNIT: Self = Self(0);
+
+    /// Euler's number `e`, interned second in every [SymbolStore]
+    pub const E: Self = Self(1);
+
+    /// The circle constant `π`, interned third in every [SymbolStore]
+    pub const PI: Self = Self(2);
+
+    /// Names of the symbols interned in every [SymbolStore], in the order of their handles
+    const BUILTIN: [&'static str; 3] = ["i", "e", "π"];
+
+    /// Get the index of this symbol in the store it was interned in
+    pub const fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Split a symbol name into its base and subscript, e.g. `x_1` into `x` and `1`.
+/// Braced subscripts as in `x_{10}` have their braces removed
+pub fn split_subscript(name: &str) -> (&str, Option<&str>) {
+    match name.split_once('_') {
+        Some((base, sub)) if !base.is_empty() && !sub.is_empty() => {
+            let sub = sub
+                .strip_prefix('{')
+                .and_then(|s| s.strip_suffix('}'))
+                .unwrap_or(sub);
+            (base, Some(sub))
+        }
+        _ => (name, None),
+    }
+}
+
+/// Spelled out names of Greek letters and the characters they represent
+pub const GREEK: [(&str, char); 35] = [
+    ("alpha", 'α'),
+    ("beta", 'β'),
+    ("gamma", 'γ'),
+    ("delta", 'δ'),
+    ("epsilon", 'ε'),
+    ("zeta", 'ζ'),
+    ("eta", 'η'),
+    ("theta", 'θ'),
+    ("iota", 'ι'),
+    ("kappa", 'κ'),
+    ("lambda", 'λ'),
+    ("mu", 'μ'),
+    ("nu", 'ν'),
+    ("xi", 'ξ'),
+    ("omicron", 'ο'),
+    ("pi", 'π'),
+    ("rho", 'ρ'),
+    ("sigma", 'σ'),
+    ("tau", 'τ'),
+    ("upsilon", 'υ'),
+    ("phi", 'φ'),
+    ("chi", 'χ'),
+    ("psi", 'ψ'),
+    ("omega", 'ω'),
+    ("Gamma", 'Γ'),
+    ("Delta", 'Δ'),
+    ("Theta", 'Θ'),
+    ("Lambda", 'Λ'),
+    ("Xi", 'Ξ'),
+    ("Pi", 'Π'),
+    ("Sigma", 'Σ'),
+    ("Upsilon", 'Υ'),
+    ("Phi", 'Φ'),
+    ("Psi", 'Ψ'),
+    ("Omega", 'Ω'),
+];
+
+/// Get the Greek letter spelled out by the given name, e.g. `α` for `alpha`
+pub fn greek_letter(name: &str) -> Option<char> {
+    GREEK.iter().find(|(n, _)| *n == name).map(|(_, c)| *c)
+}
 
+/// Get the spelled out name of the given Greek letter, e.g. `alpha` for `α`
+pub fn greek_name(letter: char) -> Option<&'static str> {
+    GREEK.iter().find(|(_, c)| *c == letter).map(|(n, _)| *n)
 }
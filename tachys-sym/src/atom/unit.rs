@@ -0,0 +1,452 @@
+use std::fmt;
+
+use super::{BigInt, Rational};
+
+/// Number of SI base dimensions
+const BASE_DIMENSIONS: usize = 7;
+
+/// Symbols of the SI base units in the order of the exponents of a [Dimension], which are used to
+/// display dimensions
+const BASE_SYMBOLS: [&str; BASE_DIMENSIONS] = ["kg", "m", "s", "A", "K", "mol", "cd"];
+
+/// Physical dimension as integer exponents of the SI base dimensions mass, length, time, electric
+/// current, temperature, amount of substance and luminous intensity
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Dimension([i8; BASE_DIMENSIONS]);
+
+/// A unit of measurement with an optional decimal prefix, as in `km` or `s`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Unit {
+    pub prefix: Option<Prefix>,
+    pub base: BaseUnit,
+}
+
+/// SI prefix scaling a unit by a power of ten
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum Prefix {
+    Quecto,
+    Ronto,
+    Yocto,
+    Zepto,
+    Atto,
+    Femto,
+    Pico,
+    Nano,
+    Micro,
+    Milli,
+    Centi,
+    Deci,
+    Deca,
+    Hecto,
+    Kilo,
+    Mega,
+    Giga,
+    Tera,
+    Peta,
+    Exa,
+    Zetta,
+    Yotta,
+    Ronna,
+    Quetta,
+}
+
+/// A named unit that may be combined with a [Prefix]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum BaseUnit {
+    Metre,
+    Gram,
+    Second,
+    Ampere,
+    Kelvin,
+    Mole,
+    Candela,
+    Hertz,
+    Newton,
+    Pascal,
+    Joule,
+    Watt,
+    Coulomb,
+    Volt,
+    Ohm,
+    Farad,
+    Tesla,
+    Litre,
+    Minute,
+    Hour,
+    Day,
+    Electronvolt,
+    Bar,
+    Atmosphere,
+    Inch,
+    Foot,
+    Mile,
+    Pound,
+}
+
+impl Dimension {
+    /// The dimension of pure numbers
+    pub const NONE: Self = Self([0; BASE_DIMENSIONS]);
+    pub const MASS: Self = Self::base(0);
+    pub const LENGTH: Self = Self::base(1);
+    pub const TIME: Self = Self::base(2);
+    pub const CURRENT: Self = Self::base(3);
+    pub const TEMPERATURE: Self = Self::base(4);
+    pub const AMOUNT: Self = Self::base(5);
+    pub const LUMINOUS_INTENSITY: Self = Self::base(6);
+
+    const fn base(index: usize) -> Self {
+        let mut exponents = [0; BASE_DIMENSIONS];
+        exponents[index] = 1;
+        Self(exponents)
+    }
+
+    /// Build a dimension from its exponents of mass, length, time and current, which covers every
+    /// derived unit defined here
+    const fn mechanical(mass: i8, length: i8, time: i8, current: i8) -> Self {
+        Self([mass, length, time, current, 0, 0, 0])
+    }
+
+    /// Get the exponents of mass, length, time, electric current, temperature, amount of substance
+    /// and luminous intensity
+    pub const fn exponents(&self) -> [i8; BASE_DIMENSIONS] {
+        self.0
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i].saturating_add(rhs.0[i])))
+    }
+
+    pub fn div(&self, rhs: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i].saturating_sub(rhs.0[i])))
+    }
+
+    /// Raise to the rational power `numerator / denominator`, or get `None` if an exponent would
+    /// not be an integer
+    pub fn pow(&self, numerator: i8, denominator: i8) -> Option<Self> {
+        let mut exponents = [0; BASE_DIMENSIONS];
+        for (out, exponent) in exponents.iter_mut().zip(self.0) {
+            let scaled = exponent.checked_mul(numerator)?;
+            if scaled % denominator != 0 {
+                return None;
+            }
+            *out = scaled / denominator;
+        }
+        Some(Self(exponents))
+    }
+}
+
+impl fmt::Display for Dimension {
+    /// Write the dimension in SI base units, as in `kg·m·s^-2`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_none() {
+            return f.write_str("1");
+        }
+
+        let mut first = true;
+        for (symbol, exponent) in BASE_SYMBOLS.iter().zip(self.0) {
+            if exponent == 0 {
+                continue;
+            }
+            if !first {
+                f.write_str("·")?;
+            }
+            first = false;
+            match exponent {
+                1 => f.write_str(symbol)?,
+                _ => write!(f, "{symbol}^{exponent}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Prefix {
+    /// All prefixes, from smallest to largest
+    pub const ALL: [Self; 24] = [
+        Self::Quecto,
+        Self::Ronto,
+        Self::Yocto,
+        Self::Zepto,
+        Self::Atto,
+        Self::Femto,
+        Self::Pico,
+        Self::Nano,
+        Self::Micro,
+        Self::Milli,
+        Self::Centi,
+        Self::Deci,
+        Self::Deca,
+        Self::Hecto,
+        Self::Kilo,
+        Self::Mega,
+        Self::Giga,
+        Self::Tera,
+        Self::Peta,
+        Self::Exa,
+        Self::Zetta,
+        Self::Yotta,
+        Self::Ronna,
+        Self::Quetta,
+    ];
+
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            Self::Quecto => "q",
+            Self::Ronto => "r",
+            Self::Yocto => "y",
+            Self::Zepto => "z",
+            Self::Atto => "a",
+            Self::Femto => "f",
+            Self::Pico => "p",
+            Self::Nano => "n",
+            Self::Micro => "µ",
+            Self::Milli => "m",
+            Self::Centi => "c",
+            Self::Deci => "d",
+            Self::Deca => "da",
+            Self::Hecto => "h",
+            Self::Kilo => "k",
+            Self::Mega => "M",
+            Self::Giga => "G",
+            Self::Tera => "T",
+            Self::Peta => "P",
+            Self::Exa => "E",
+            Self::Zetta => "Z",
+            Self::Yotta => "Y",
+            Self::Ronna => "R",
+            Self::Quetta => "Q",
+        }
+    }
+
+    /// Get the power of ten this prefix scales by
+    pub const fn exponent(&self) -> i32 {
+        match self {
+            Self::Quecto => -30,
+            Self::Ronto => -27,
+            Self::Yocto => -24,
+            Self::Zepto => -21,
+            Self::Atto => -18,
+            Self::Femto => -15,
+            Self::Pico => -12,
+            Self::Nano => -9,
+            Self::Micro => -6,
+            Self::Milli => -3,
+            Self::Centi => -2,
+            Self::Deci => -1,
+            Self::Deca => 1,
+            Self::Hecto => 2,
+            Self::Kilo => 3,
+            Self::Mega => 6,
+            Self::Giga => 9,
+            Self::Tera => 12,
+            Self::Peta => 15,
+            Self::Exa => 18,
+            Self::Zetta => 21,
+            Self::Yotta => 24,
+            Self::Ronna => 27,
+            Self::Quetta => 30,
+        }
+    }
+
+    /// Get the prefix written as `symbol`, also accepting `u` and the Greek letter `μ` for micro
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "u" | "μ" => Some(Self::Micro),
+            symbol => Self::ALL.into_iter().find(|p| p.symbol() == symbol),
+        }
+    }
+}
+
+impl BaseUnit {
+    /// All named units
+    pub const ALL: [Self; 28] = [
+        Self::Metre,
+        Self::Gram,
+        Self::Second,
+        Self::Ampere,
+        Self::Kelvin,
+        Self::Mole,
+        Self::Candela,
+        Self::Hertz,
+        Self::Newton,
+        Self::Pascal,
+        Self::Joule,
+        Self::Watt,
+        Self::Coulomb,
+        Self::Volt,
+        Self::Ohm,
+        Self::Farad,
+        Self::Tesla,
+        Self::Litre,
+        Self::Minute,
+        Self::Hour,
+        Self::Day,
+        Self::Electronvolt,
+        Self::Bar,
+        Self::Atmosphere,
+        Self::Inch,
+        Self::Foot,
+        Self::Mile,
+        Self::Pound,
+    ];
+
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            Self::Metre => "m",
+            Self::Gram => "g",
+            Self::Second => "s",
+            Self::Ampere => "A",
+            Self::Kelvin => "K",
+            Self::Mole => "mol",
+            Self::Candela => "cd",
+            Self::Hertz => "Hz",
+            Self::Newton => "N",
+            Self::Pascal => "Pa",
+            Self::Joule => "J",
+            Self::Watt => "W",
+            Self::Coulomb => "C",
+            Self::Volt => "V",
+            Self::Ohm => "Ω",
+            Self::Farad => "F",
+            Self::Tesla => "T",
+            Self::Litre => "L",
+            Self::Minute => "min",
+            Self::Hour => "h",
+            Self::Day => "day",
+            Self::Electronvolt => "eV",
+            Self::Bar => "bar",
+            Self::Atmosphere => "atm",
+            Self::Inch => "in",
+            Self::Foot => "ft",
+            Self::Mile => "mi",
+            Self::Pound => "lb",
+        }
+    }
+
+    /// Get the unit written as `symbol`, also accepting `ohm` and `l` for the ohm and litre
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "ohm" => Some(Self::Ohm),
+            "l" => Some(Self::Litre),
+            symbol => Self::ALL.into_iter().find(|u| u.symbol() == symbol),
+        }
+    }
+
+    /// Check if this unit may be written with a prefix, which is only the case for SI units and
+    /// the litre, electronvolt and bar
+    pub const fn takes_prefix(&self) -> bool {
+        !matches!(
+            self,
+            Self::Minute
+                | Self::Hour
+                | Self::Day
+                | Self::Atmosphere
+                | Self::Inch
+                | Self::Foot
+                | Self::Mile
+                | Self::Pound
+        )
+    }
+
+    pub const fn dimension(&self) -> Dimension {
+        match self {
+            Self::Metre | Self::Inch | Self::Foot | Self::Mile => Dimension::LENGTH,
+            Self::Gram | Self::Pound => Dimension::MASS,
+            Self::Second | Self::Minute | Self::Hour | Self::Day => Dimension::TIME,
+            Self::Ampere => Dimension::CURRENT,
+            Self::Kelvin => Dimension::TEMPERATURE,
+            Self::Mole => Dimension::AMOUNT,
+            Self::Candela => Dimension::LUMINOUS_INTENSITY,
+            Self::Hertz => Dimension::mechanical(0, 0, -1, 0),
+            Self::Newton => Dimension::mechanical(1, 1, -2, 0),
+            Self::Pascal | Self::Bar | Self::Atmosphere => Dimension::mechanical(1, -1, -2, 0),
+            Self::Joule | Self::Electronvolt => Dimension::mechanical(1, 2, -2, 0),
+            Self::Watt => Dimension::mechanical(1, 2, -3, 0),
+            Self::Coulomb => Dimension::mechanical(0, 0, 1, 1),
+            Self::Volt => Dimension::mechanical(1, 2, -3, -1),
+            Self::Ohm => Dimension::mechanical(1, 2, -3, -2),
+            Self::Farad => Dimension::mechanical(-1, -2, 4, 2),
+            Self::Tesla => Dimension::mechanical(1, 0, -2, -1),
+            Self::Litre => Dimension::mechanical(0, 3, 0, 0),
+        }
+    }
+
+    /// Get the exact size of this unit in coherent SI units, such as `1/1000` for the gram since
+    /// the kilogram is the SI base unit of mass
+    pub fn scale(&self) -> Rational {
+        let ratio = |numerator: i64, denominator: i64| {
+            Rational::new(numerator, denominator).expect("Denominator is not zero")
+        };
+        match self {
+            Self::Gram | Self::Litre => ratio(1, 1000),
+            Self::Minute => ratio(60, 1),
+            Self::Hour => ratio(3600, 1),
+            Self::Day => ratio(86400, 1),
+            Self::Electronvolt => &ratio(1_602_176_634, 1) * &power_of_ten(-28),
+            Self::Bar => ratio(100_000, 1),
+            Self::Atmosphere => ratio(101_325, 1),
+            Self::Inch => ratio(127, 5000),
+            Self::Foot => ratio(381, 1250),
+            Self::Mile => ratio(201_168, 125),
+            Self::Pound => ratio(45_359_237, 100_000_000),
+            _ => Rational::integer(1),
+        }
+    }
+}
+
+impl Unit {
+    /// Get the unit written as `symbol`, which is either a named unit or a prefix followed by one
+    /// that takes prefixes. Named units are preferred, so `min` is a minute and not a milli-inch
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        if let Some(base) = BaseUnit::from_symbol(symbol) {
+            return Some(Self { prefix: None, base });
+        }
+
+        symbol
+            .char_indices()
+            .skip(1)
+            .filter_map(|(i, _)| {
+                let prefix = Prefix::from_symbol(&symbol[..i])?;
+                let base = BaseUnit::from_symbol(&symbol[i..])?;
+                base.takes_prefix().then_some(Self {
+                    prefix: Some(prefix),
+                    base,
+                })
+            })
+            .next()
+    }
+
+    pub const fn dimension(&self) -> Dimension {
+        self.base.dimension()
+    }
+
+    /// Get the exact size of this unit in coherent SI units
+    pub fn scale(&self) -> Rational {
+        let scale = self.base.scale();
+        match self.prefix {
+            Some(prefix) => &scale * &power_of_ten(prefix.exponent()),
+            None => scale,
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(prefix) = self.prefix {
+            f.write_str(prefix.symbol())?;
+        }
+        f.write_str(self.base.symbol())
+    }
+}
+
+fn power_of_ten(exponent: i32) -> Rational {
+    let magnitude = num_traits::pow(BigInt::from(10), exponent.unsigned_abs() as usize);
+    match exponent < 0 {
+        true => Rational::new(1, magnitude).expect("Denominator is not zero"),
+        false => Rational::integer(magnitude),
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+
+use crate::atom::{symbol::Symbol, Atom, AtomId, BigOperator, ExprPool, Function};
+
+impl ExprPool {
+    /// Differentiate the expression rooted at `id` with respect to `variable` and simplify the
+    /// result. Derivatives that cannot be computed, such as those of user functions, are left as
+    /// unevaluated [Atom::Derivative]s
+    pub fn diff(&mut self, id: AtomId, variable: Symbol) -> AtomId {
+        self.diff_n(id, variable, 1)
+    }
+
+    /// Take the `order`th derivative of the expression rooted at `id` with respect to `variable`,
+    /// simplifying after each differentiation
+    pub fn diff_n(&mut self, id: AtomId, variable: Symbol, order: u32) -> AtomId {
+        (0..order).fold(id, |current, _| {
+            let derivative = Differentiator::new(self, variable).derive(current);
+            self.simplify(derivative)
+        })
+    }
+
+    /// Take the mixed partial derivative of the expression rooted at `id` with respect to each of
+    /// `variables` in turn
+    pub fn diff_partial(&mut self, id: AtomId, variables: &[Symbol]) -> AtomId {
+        variables
+            .iter()
+            .fold(id, |current, variable| self.diff(current, *variable))
+    }
+
+    /// Evaluate every [Atom::Derivative] in the expression rooted at `id` that can be computed
+    pub fn eval_derivatives(&mut self, id: AtomId) -> AtomId {
+        let atom = self.get(id).clone();
+        let atom = atom.map_children(|child| self.eval_derivatives(child));
+        match atom {
+            Atom::Derivative {
+                variable,
+                order,
+                inner,
+            } => self.diff_n(inner, variable, order),
+            atom => self.insert(atom),
+        }
+    }
+}
+
+/// Computes the unsimplified derivative of expressions with respect to a single variable
+struct Differentiator<'a> {
+    pool: &'a mut ExprPool,
+    variable: Symbol,
+    memo: HashMap<AtomId, AtomId>,
+}
+
+impl<'a> Differentiator<'a> {
+    fn new(pool: &'a mut ExprPool, variable: Symbol) -> Self {
+        Self {
+            pool,
+            variable,
+            memo: HashMap::new(),
+        }
+    }
+
+    fn derive(&mut self, id: AtomId) -> AtomId {
+        if let Some(done) = self.memo.get(&id) {
+            return *done;
+        }
+
+        let out = match self.pool.depends_on(id, self.variable) {
+            true => self.derive_atom(id),
+            false => self.pool.integer(0),
+        };
+        self.memo.insert(id, out);
+        out
+    }
+
+    fn derive_atom(&mut self, id: AtomId) -> AtomId {
+        match self.pool.get(id).clone() {
+            Atom::Integer(_)
+            | Atom::Rational(_)
+            | Atom::Float(_)
+            | Atom::Complex { .. }
+            | Atom::Unit(_) => self.pool.integer(0),
+            Atom::Symbol(sym) => self.pool.integer((sym == self.variable) as i64),
+            Atom::Sum(terms) => {
+                let terms = terms.iter().map(|t| self.derive(*t)).collect::<Vec<_>>();
+                self.pool.sum(terms)
+            }
+            Atom::Product(factors) => {
+                let mut terms = Vec::new();
+                for (i, factor) in factors.iter().enumerate() {
+                    let derivative = self.derive(*factor);
+                    let mut rest = factors.to_vec();
+                    rest[i] = derivative;
+                    terms.push(self.pool.product(rest));
+                }
+                self.pool.sum(terms)
+            }
+            Atom::Power { base, exponent } => self.power(base, exponent),
+            Atom::Neg(inner) => {
+                let inner = self.derive(inner);
+                self.pool.neg(inner)
+            }
+            Atom::Apply { func, args } => self.apply(id, func, &args),
+            Atom::Equals { lhs, rhs } => {
+                let (lhs, rhs) = (self.derive(lhs), self.derive(rhs));
+                self.pool.equals(lhs, rhs)
+            }
+            Atom::Relation { .. } | Atom::Limit { .. } => self.unevaluated(id),
+            Atom::Integral {
+                variable,
+                upper,
+                lower,
+                integrand,
+            } => self.integral(variable, upper, lower, integrand),
+            Atom::BigOperator {
+                op,
+                index,
+                lower,
+                upper,
+                term,
+            } => self.big_operator(id, op, index, [lower, upper], term),
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => {
+                // (n/d)' = (n'd - nd') / d^2
+                let (dn, dd) = (self.derive(numerator), self.derive(denominator));
+                let lhs = self.pool.product([dn, denominator]);
+                let rhs = self.pool.product([numerator, dd]);
+                let rhs = self.pool.neg(rhs);
+                let top = self.pool.sum([lhs, rhs]);
+                let two = self.pool.integer(2);
+                let bottom = self.pool.power(denominator, two);
+                self.pool.fraction(top, bottom)
+            }
+            Atom::Derivative {
+                variable,
+                order,
+                inner,
+            } => {
+                // Evaluate the inner derivative first, keeping it if it cannot be computed
+                let inner = self.pool.diff_n(inner, variable, order);
+                match inner == id {
+                    true => self.unevaluated(id),
+                    false => self.derive(inner),
+                }
+            }
+            // Differentiating a remainder term by term lowers its order
+            Atom::Order(term) => {
+                let term = self.derive(term);
+                self.pool.order(term)
+            }
+            // Matrices are differentiated entry by entry
+            atom @ Atom::Matrix { .. } => {
+                let atom = atom.map_children(|entry| self.derive(entry));
+                self.pool.insert(atom)
+            }
+        }
+    }
+
+    /// Get the derivative of `base ^ exponent`
+    fn power(&mut self, base: AtomId, exponent: AtomId) -> AtomId {
+        let (db, de) = (self.derive(base), self.derive(exponent));
+        let power = self.pool.power(base, exponent);
+        let one = self.pool.integer(1);
+        let minus_one = self.pool.integer(-1);
+
+        match (
+            self.pool.depends_on(base, self.variable),
+            self.pool.depends_on(exponent, self.variable),
+        ) {
+            // (u^n)' = n u^(n - 1) u'
+            (_, false) => {
+                let reduced = self.pool.sum([exponent, minus_one]);
+                let reduced = self.pool.power(base, reduced);
+                self.pool.product([exponent, reduced, db])
+            }
+            // (e^v)' = e^v v', written without the factor ln(e) = 1
+            (false, _) if *self.pool.get(base) == Atom::Symbol(Symbol::E) => {
+                self.pool.product([power, de])
+            }
+            // (a^v)' = a^v ln(a) v'
+            (false, _) => {
+                let ln = self.pool.apply(Function::Ln, [base]);
+                self.pool.product([power, ln, de])
+            }
+            // (u^v)' = u^v (v' ln(u) + v u' / u)
+            (true, true) => {
+                let ln = self.pool.apply(Function::Ln, [base]);
+                let lhs = self.pool.product([de, ln]);
+                let quotient = self.pool.fraction(one, base);
+                let rhs = self.pool.product([exponent, db, quotient]);
+                let sum = self.pool.sum([lhs, rhs]);
+                self.pool.product([power, sum])
+            }
+        }
+    }
+
+    /// Get the derivative of a function application by the chain rule
+    fn apply(&mut self, id: AtomId, func: Function, args: &[AtomId]) -> AtomId {
+        let [arg] = *args else {
+            return self.unevaluated(id);
+        };
+
+        let outer = match func {
+            Function::User(_) => return self.unevaluated(id),
+            // The variable is real, so these commute with differentiation
+            Function::Re | Function::Im | Function::Conj => {
+                let inner = self.derive(arg);
+                return self.pool.apply(func, [inner]);
+            }
+            // arg(u)' = im(u'/u)
+            Function::Arg => {
+                let inner = self.derive(arg);
+                let quotient = self.pool.fraction(inner, arg);
+                return self.pool.apply(Function::Im, [quotient]);
+            }
+            func => self.outer(func, arg),
+        };
+        let inner = self.derive(arg);
+        self.pool.product([outer, inner])
+    }
+
+    /// Get the derivative of a builtin function evaluated at `u`
+    fn outer(&mut self, func: Function, u: AtomId) -> AtomId {
+        let one = self.pool.integer(1);
+        let two = self.pool.integer(2);
+        let square = self.pool.power(u, two);
+        let neg_square = self.pool.neg(square);
+
+        match func {
+            Function::Sin => self.pool.apply(Function::Cos, [u]),
+            Function::Cos => {
+                let sin = self.pool.apply(Function::Sin, [u]);
+                self.pool.neg(sin)
+            }
+            Function::Tan => {
+                let cos = self.pool.apply(Function::Cos, [u]);
+                let cos_squared = self.pool.power(cos, two);
+                self.pool.fraction(one, cos_squared)
+            }
+            Function::Asin | Function::Acos => {
+                let radicand = self.pool.sum([one, neg_square]);
+                let root = self.pool.apply(Function::Sqrt, [radicand]);
+                let derivative = self.pool.fraction(one, root);
+                match func {
+                    Function::Asin => derivative,
+                    _ => self.pool.neg(derivative),
+                }
+            }
+            Function::Atan => {
+                let denominator = self.pool.sum([one, square]);
+                self.pool.fraction(one, denominator)
+            }
+            Function::Sinh => self.pool.apply(Function::Cosh, [u]),
+            Function::Cosh => self.pool.apply(Function::Sinh, [u]),
+            Function::Tanh => {
+                let tanh = self.pool.apply(Function::Tanh, [u]);
+                let tanh_squared = self.pool.power(tanh, two);
+                let neg = self.pool.neg(tanh_squared);
+                self.pool.sum([one, neg])
+            }
+            Function::Exp => self.pool.apply(Function::Exp, [u]),
+            Function::Ln => self.pool.fraction(one, u),
+            Function::Log => {
+                let ten = self.pool.integer(10);
+                let ln_ten = self.pool.apply(Function::Ln, [ten]);
+                let denominator = self.pool.product([u, ln_ten]);
+                self.pool.fraction(one, denominator)
+            }
+            Function::Sqrt => {
+                let root = self.pool.apply(Function::Sqrt, [u]);
+                let denominator = self.pool.product([two, root]);
+                self.pool.fraction(one, denominator)
+            }
+            Function::Abs => {
+                let abs = self.pool.apply(Function::Abs, [u]);
+                self.pool.fraction(abs, u)
+            }
+            Function::Re | Function::Im | Function::Conj | Function::Arg | Function::User(_) => {
+                unreachable!("Derivative is not found by the chain rule")
+            }
+        }
+    }
+
+    /// Get the derivative of an integral, differentiating under the integral sign and applying
+    /// the Leibniz rule to bounds that depend on the variable
+    fn integral(
+        &mut self,
+        variable: Symbol,
+        upper: Option<AtomId>,
+        lower: Option<AtomId>,
+        integrand: AtomId,
+    ) -> AtomId {
+        // The derivative of an antiderivative with respect to its own variable is the integrand
+        if variable == self.variable && upper.is_none() && lower.is_none() {
+            return integrand;
+        }
+
+        let mut terms = Vec::new();
+        let bound = self.pool.symbol(variable);
+        for (limit, sign) in [(upper, 1), (lower, -1)] {
+            let Some(limit) = limit else {
+                continue;
+            };
+
+            let at_limit = self.pool.substitute(integrand, bound, limit);
+            let slope = self.derive(limit);
+            let sign = self.pool.integer(sign);
+            terms.push(self.pool.product([sign, at_limit, slope]));
+        }
+
+        // The integrand is constant with respect to its own bound variable
+        if variable != self.variable && self.pool.depends_on(integrand, self.variable) {
+            let inner = self.derive(integrand);
+            terms.push(self.pool.insert(Atom::Integral {
+                variable,
+                upper,
+                lower,
+                integrand: inner,
+            }));
+        }
+
+        self.pool.sum(terms)
+    }
+
+    /// Differentiate a sum or product term by term, leaving it unevaluated if a bound depends on
+    /// the variable since the index only takes integer values
+    fn big_operator(
+        &mut self,
+        id: AtomId,
+        op: BigOperator,
+        index: Symbol,
+        bounds: [AtomId; 2],
+        term: AtomId,
+    ) -> AtomId {
+        if bounds
+            .iter()
+            .any(|b| self.pool.depends_on(*b, self.variable))
+        {
+            return self.unevaluated(id);
+        }
+        // The term is constant with respect to its own index
+        if index == self.variable {
+            return self.pool.integer(0);
+        }
+
+        let [lower, upper] = bounds;
+        let inner = self.derive(term);
+        match op {
+            BigOperator::Sum => {
+                self.pool
+                    .big_operator(BigOperator::Sum, index, lower, upper, inner)
+            }
+            BigOperator::Product => {
+                // (∏ f)' = ∏ f · ∑ f'/f
+                let ratio = self.pool.fraction(inner, term);
+                let sum = self
+                    .pool
+                    .big_operator(BigOperator::Sum, index, lower, upper, ratio);
+                self.pool.product([id, sum])
+            }
+        }
+    }
+
+    /// Leave the derivative of the expression unevaluated, merging it with an unevaluated
+    /// derivative with respect to the same variable
+    fn unevaluated(&mut self, id: AtomId) -> AtomId {
+        match *self.pool.get(id) {
+            Atom::Derivative {
+                variable,
+                order,
+                inner,
+            } if variable == self.variable => self.pool.derivative(variable, order + 1, inner),
+            _ => self.pool.derivative(self.variable, 1, id),
+        }
+    }
+}
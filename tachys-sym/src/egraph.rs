@@ -0,0 +1,696 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, ExprPool,
+    },
+    rewrite::{same_head, Constraint, Pattern, Rule},
+};
+
+/// Identifier of an equivalence class of expressions in an [EGraph]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct ClassId(AtomId);
+
+/// Values bound to the wildcards of a [Pattern] when matching against an [EGraph]
+type ClassBindings = HashMap<Symbol, ClassId>;
+
+/// A set of expressions partitioned into classes of expressions known to be equal, allowing many
+/// rewrites of the same expression to be explored at once without committing to any of them.
+///
+/// Nodes of the graph are stored in the [ExprPool] as atoms whose children are the
+/// representative [ClassId]s of other classes, so the pool's deduplication of atoms also
+/// detects when a node is added twice
+pub struct EGraph<'a> {
+    pool: &'a mut ExprPool,
+    /// Union-find links from merged classes towards the class they were merged into
+    links: HashMap<ClassId, ClassId>,
+    classes: HashMap<ClassId, EClass>,
+    /// Class of every node with canonical children
+    memo: HashMap<AtomId, ClassId>,
+    /// Classes that have been merged since the graph was last rebuilt
+    pending: Vec<ClassId>,
+}
+
+#[derive(Default)]
+struct EClass {
+    nodes: Vec<AtomId>,
+    /// Nodes that have this class as a child, with the class each belongs to
+    uses: Vec<(AtomId, ClassId)>,
+}
+
+/// Bounds on the growth of an [EGraph] during [saturation](EGraph::saturate)
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    pub max_nodes: usize,
+    pub max_iterations: usize,
+    pub timeout: Duration,
+}
+
+/// Reason that [saturation](EGraph::saturate) of an [EGraph] stopped
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StopReason {
+    /// No rule can add any new equality
+    Saturated,
+    NodeLimit,
+    IterationLimit,
+    TimeLimit,
+}
+
+/// Measure of how desirable an expression is, used to [extract](EGraph::extract) the cheapest
+/// expression of a class.
+/// Any function `FnMut(&Atom, &[f64]) -> f64` is also a cost function
+pub trait CostFunction {
+    /// Get the cost of an expression rooted at `atom` whose children have the given costs, in the
+    /// order of [Atom::children]. The cost must be greater than the cost of every child
+    fn cost(&mut self, atom: &Atom, children: &[f64]) -> f64;
+}
+
+/// Cost equal to the number of atoms in an expression
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NodeCount;
+
+/// Cost equal to the depth of an expression's tree
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Depth;
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_nodes: 10_000,
+            max_iterations: 30,
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+impl CostFunction for NodeCount {
+    fn cost(&mut self, _: &Atom, children: &[f64]) -> f64 {
+        1. + children.iter().sum::<f64>()
+    }
+}
+
+impl CostFunction for Depth {
+    fn cost(&mut self, _: &Atom, children: &[f64]) -> f64 {
+        1. + children.iter().copied().fold(0., f64::max)
+    }
+}
+
+impl<F: FnMut(&Atom, &[f64]) -> f64> CostFunction for F {
+    fn cost(&mut self, atom: &Atom, children: &[f64]) -> f64 {
+        self(atom, children)
+    }
+}
+
+impl<'a> EGraph<'a> {
+    /// Create an empty graph storing its nodes in the given pool
+    pub fn new(pool: &'a mut ExprPool) -> Self {
+        Self {
+            pool,
+            links: HashMap::new(),
+            classes: HashMap::new(),
+            memo: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Get the pool that nodes of this graph are stored in
+    pub fn pool(&self) -> &ExprPool {
+        self.pool
+    }
+
+    /// Get the number of distinct nodes in this graph
+    pub fn node_count(&self) -> usize {
+        self.memo.len()
+    }
+
+    /// Get the number of equivalence classes in this graph
+    pub fn class_count(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Get the representative of the class that `class` has been merged into
+    pub fn find(&self, mut class: ClassId) -> ClassId {
+        while let Some(next) = self.links.get(&class) {
+            class = *next;
+        }
+        class
+    }
+
+    /// Check if the given classes have been merged
+    pub fn equivalent(&self, a: ClassId, b: ClassId) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Add the expression rooted at `id` and all of its subexpressions to the graph, returning the
+    /// class it belongs to
+    pub fn add(&mut self, id: AtomId) -> ClassId {
+        let atom = self.pool.get(id).clone();
+        let node = atom.map_children(|child| self.add(child).0);
+        self.add_node(node)
+    }
+
+    /// Get the class of the expression rooted at `id` if it is already in the graph
+    pub fn lookup(&self, id: AtomId) -> Option<ClassId> {
+        let atom = self.pool.get(id);
+        let mut children = Vec::new();
+        for child in atom.children() {
+            children.push(self.lookup(child)?.0);
+        }
+
+        let mut children = children.into_iter();
+        let node = atom.map_children(|_| children.next().unwrap_or(id));
+        let node = self.pool.find(&node)?;
+        self.memo.get(&node).map(|class| self.find(*class))
+    }
+
+    /// Record that the expressions of two classes are equal, returning `false` if they already
+    /// were. The graph must be [rebuilt](EGraph::rebuild) before it is searched again
+    pub fn union(&mut self, a: ClassId, b: ClassId) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let size = |class: &EClass| class.nodes.len() + class.uses.len();
+        let (root, merged) = match size(&self.classes[&a]) >= size(&self.classes[&b]) {
+            true => (a, b),
+            false => (b, a),
+        };
+
+        self.links.insert(merged, root);
+        let merged = self.classes.remove(&merged).unwrap_or_default();
+        let class = self.classes.entry(root).or_default();
+        class.nodes.extend(merged.nodes);
+        class.uses.extend(merged.uses);
+        self.pending.push(root);
+        true
+    }
+
+    /// Restore the invariants of the graph after classes have been merged, merging the classes of
+    /// any nodes that have become equal because their children were merged
+    pub fn rebuild(&mut self) {
+        while let Some(class) = self.pending.pop() {
+            let class = self.find(class);
+            self.repair(class);
+        }
+
+        let classes = self.classes.keys().copied().collect::<Vec<_>>();
+        for class in classes {
+            let nodes = std::mem::take(&mut self.class_mut(class).nodes);
+            let mut nodes = nodes
+                .into_iter()
+                .map(|node| self.canonical(node))
+                .collect::<Vec<_>>();
+            nodes.sort();
+            nodes.dedup();
+            self.class_mut(class).nodes = nodes;
+        }
+    }
+
+    /// Repeatedly apply every rule to every class of the graph, merging each match with its
+    /// replacement, until no new equalities are found or one of the limits is reached
+    pub fn saturate(&mut self, rules: &[Rule], limits: &Limits) -> StopReason {
+        let start = Instant::now();
+        for _ in 0..limits.max_iterations {
+            let classes = self.classes.keys().copied().collect::<Vec<_>>();
+            let mut matches = Vec::new();
+            for rule in rules {
+                let sum = matches!(self.pool.get(rule.pattern().root()), Atom::Sum(_));
+                for class in &classes {
+                    let found = self.search_partial(rule.pattern(), *class);
+                    matches.extend(
+                        found
+                            .into_iter()
+                            .map(|(b, rest)| (rule.replacement(), *class, b, rest, sum)),
+                    );
+                }
+
+                if start.elapsed() > limits.timeout {
+                    return StopReason::TimeLimit;
+                }
+            }
+
+            let mut changed = false;
+            for (replacement, class, bindings, mut rest, sum) in matches {
+                let mut replaced = self.instantiate(replacement, &bindings);
+                if !rest.is_empty() {
+                    rest.insert(0, replaced.0);
+                    let items = self.flatten(&rest, sum);
+                    replaced = match sum {
+                        true => self.add_node(Atom::Sum(items)),
+                        false => self.add_node(Atom::Product(items)),
+                    };
+                }
+
+                changed |= self.union(class, replaced);
+                if self.node_count() > limits.max_nodes {
+                    self.rebuild();
+                    return StopReason::NodeLimit;
+                }
+            }
+
+            self.rebuild();
+            if !changed {
+                return StopReason::Saturated;
+            }
+            if start.elapsed() > limits.timeout {
+                return StopReason::TimeLimit;
+            }
+        }
+
+        StopReason::IterationLimit
+    }
+
+    /// Find every set of bindings under which `pattern` matches an expression of the given class
+    pub fn search(&mut self, pattern: &Pattern, class: ClassId) -> Vec<HashMap<Symbol, ClassId>> {
+        let class = self.find(class);
+        self.ematch(pattern, pattern.root(), class, ClassBindings::new())
+    }
+
+    /// Find matches of `pattern` in the given class as in [EGraph::search], also allowing a sum or
+    /// product pattern to match only some terms of a sum or product, returning the terms left over
+    fn search_partial(
+        &mut self,
+        pattern: &Pattern,
+        class: ClassId,
+    ) -> Vec<(ClassBindings, Vec<AtomId>)> {
+        let class = self.find(class);
+        let (sum, patterns) = match self.pool.get(pattern.root()) {
+            Atom::Sum(terms) => (true, terms.to_vec()),
+            Atom::Product(factors) => (false, factors.to_vec()),
+            _ => {
+                return self
+                    .search(pattern, class)
+                    .into_iter()
+                    .map(|bindings| (bindings, Vec::new()))
+                    .collect()
+            }
+        };
+
+        let nodes = self.classes.get(&class).map(|c| c.nodes.clone());
+        let mut out = Vec::new();
+        for node in nodes.unwrap_or_default() {
+            let terms = match (self.pool.get(node), sum) {
+                (Atom::Sum(terms), true) | (Atom::Product(terms), false) => terms.to_vec(),
+                _ => continue,
+            };
+            let bindings = ClassBindings::new();
+            out.extend(self.commutative(pattern, patterns.clone(), terms, sum, bindings));
+        }
+
+        out
+    }
+
+    /// Build the cheapest expression of the given class under `cost`, inserting it into the pool
+    pub fn extract(&mut self, class: ClassId, cost: &mut impl CostFunction) -> AtomId {
+        let best = self.best_nodes(cost);
+        let mut built = HashMap::new();
+        self.build(self.find(class), &best, &mut built)
+    }
+
+    fn class_mut(&mut self, class: ClassId) -> &mut EClass {
+        self.classes.entry(class).or_default()
+    }
+
+    /// Add a node whose children are classes of this graph, returning its class
+    fn add_node(&mut self, atom: Atom) -> ClassId {
+        let atom = atom.map_children(|child| self.find(ClassId(child)).0);
+        let node = self.pool.insert(atom);
+        if let Some(class) = self.memo.get(&node) {
+            return self.find(*class);
+        }
+
+        let class = ClassId(node);
+        let children = self.pool.get(node).children().collect::<Vec<_>>();
+        for child in children {
+            self.class_mut(ClassId(child)).uses.push((node, class));
+        }
+        self.classes.insert(
+            class,
+            EClass {
+                nodes: vec![node],
+                uses: Vec::new(),
+            },
+        );
+        self.memo.insert(node, class);
+        class
+    }
+
+    /// Get the node with every child replaced by the representative of its class
+    fn canonical(&mut self, node: AtomId) -> AtomId {
+        let atom = self.pool.get(node).clone();
+        let atom = atom.map_children(|child| self.find(ClassId(child)).0);
+        self.pool.insert(atom)
+    }
+
+    /// Re-canonicalize every node using the given class, merging the classes of nodes that become
+    /// identical
+    fn repair(&mut self, class: ClassId) {
+        let uses = std::mem::take(&mut self.class_mut(class).uses);
+        for (node, _) in &uses {
+            self.memo.remove(node);
+        }
+
+        let mut deduped = HashMap::<AtomId, ClassId>::new();
+        for (node, user) in uses {
+            let node = self.canonical(node);
+            if let Some(other) = deduped.insert(node, user) {
+                self.union(other, user);
+            }
+            let user = self.find(user);
+            if let Some(other) = self.memo.insert(node, user) {
+                self.union(other, user);
+            }
+        }
+
+        let uses = deduped
+            .into_iter()
+            .map(|(node, user)| (node, self.find(user)))
+            .collect::<Vec<_>>();
+        let class = self.find(class);
+        self.class_mut(class).uses.extend(uses);
+    }
+
+    fn wildcard(&self, pattern: &Pattern, id: AtomId) -> Option<(Symbol, Constraint)> {
+        match self.pool.get(id) {
+            Atom::Symbol(sym) => pattern.constraint(*sym).map(|c| (*sym, c)),
+            _ => None,
+        }
+    }
+
+    fn ematch(
+        &mut self,
+        pattern: &Pattern,
+        p: AtomId,
+        class: ClassId,
+        bindings: ClassBindings,
+    ) -> Vec<ClassBindings> {
+        if let Some((sym, constraint)) = self.wildcard(pattern, p) {
+            return match bindings.get(&sym) {
+                Some(bound) if self.find(*bound) != class => vec![],
+                Some(_) => vec![bindings],
+                None if self.satisfies(class, constraint) => {
+                    let mut bindings = bindings;
+                    bindings.insert(sym, class);
+                    vec![bindings]
+                }
+                None => vec![],
+            };
+        }
+
+        let has_wildcards = self
+            .pool
+            .descendants(p)
+            .any(|id| self.wildcard(pattern, id).is_some());
+        if !has_wildcards {
+            return match self.lookup(p) == Some(class) {
+                true => vec![bindings],
+                false => vec![],
+            };
+        }
+
+        let nodes = self.classes.get(&class).map(|c| c.nodes.clone());
+        let mut out = Vec::new();
+        for node in nodes.unwrap_or_default() {
+            match (self.pool.get(p), self.pool.get(node)) {
+                (Atom::Sum(patterns), Atom::Sum(terms)) => {
+                    let (patterns, terms) = (patterns.to_vec(), terms.to_vec());
+                    let found = self.commutative(pattern, patterns, terms, true, bindings.clone());
+                    out.extend(complete(found));
+                }
+                (Atom::Product(patterns), Atom::Product(factors)) => {
+                    let (patterns, factors) = (patterns.to_vec(), factors.to_vec());
+                    let found =
+                        self.commutative(pattern, patterns, factors, false, bindings.clone());
+                    out.extend(complete(found));
+                }
+                (a, b) if same_head(a, b) => {
+                    let pairs = a.children().zip(b.children()).collect::<Vec<_>>();
+                    let found =
+                        pairs
+                            .into_iter()
+                            .fold(vec![bindings.clone()], |candidates, (p, child)| {
+                                let child = self.find(ClassId(child));
+                                candidates
+                                    .into_iter()
+                                    .flat_map(|bindings| self.ematch(pattern, p, child, bindings))
+                                    .collect()
+                            });
+                    out.extend(found);
+                }
+                _ => (),
+            }
+        }
+
+        out
+    }
+
+    /// Match the terms of a sum or product pattern against some of the child classes of a node in
+    /// any order as in [Pattern], returning the bindings of each match with the unmatched terms
+    fn commutative(
+        &mut self,
+        pattern: &Pattern,
+        mut patterns: Vec<AtomId>,
+        terms: Vec<AtomId>,
+        sum: bool,
+        bindings: ClassBindings,
+    ) -> Vec<(ClassBindings, Vec<AtomId>)> {
+        patterns.sort_by_key(|p| {
+            self.wildcard(pattern, *p)
+                .map(|(_, c)| c == Constraint::Any)
+        });
+
+        let mut out = Vec::new();
+        self.assign(pattern, &patterns, terms, sum, bindings, &mut out);
+        out
+    }
+
+    fn assign(
+        &mut self,
+        pattern: &Pattern,
+        patterns: &[AtomId],
+        terms: Vec<AtomId>,
+        sum: bool,
+        bindings: ClassBindings,
+        out: &mut Vec<(ClassBindings, Vec<AtomId>)>,
+    ) {
+        let Some((first, rest)) = patterns.split_first() else {
+            out.push((bindings, terms));
+            return;
+        };
+
+        if rest.is_empty() && terms.len() > 1 && self.wildcard(pattern, *first).is_some() {
+            let combined = match sum {
+                true => self.add_node(Atom::Sum(terms.into())),
+                false => self.add_node(Atom::Product(terms.into())),
+            };
+            let found = self.ematch(pattern, *first, combined, bindings);
+            out.extend(found.into_iter().map(|bindings| (bindings, Vec::new())));
+            return;
+        }
+
+        for i in 0..terms.len() {
+            let term = self.find(ClassId(terms[i]));
+            for bindings in self.ematch(pattern, *first, term, bindings.clone()) {
+                let mut remaining = terms.clone();
+                remaining.remove(i);
+                self.assign(pattern, rest, remaining, sum, bindings, out);
+            }
+        }
+    }
+
+    /// Check if any expression of the class satisfies the constraint
+    fn satisfies(&self, class: ClassId, constraint: Constraint) -> bool {
+        match constraint {
+            Constraint::Any => true,
+            Constraint::Constant => self.is_constant(class, &mut Vec::new()),
+            _ => self.classes.get(&class).is_some_and(|c| {
+                c.nodes
+                    .iter()
+                    .any(|node| constraint.check(self.pool, *node))
+            }),
+        }
+    }
+
+    fn is_constant(&self, class: ClassId, visiting: &mut Vec<ClassId>) -> bool {
+        if visiting.contains(&class) {
+            return false;
+        }
+
+        visiting.push(class);
+        let constant = self.classes.get(&class).is_some_and(|c| {
+            c.nodes.iter().any(|node| {
+                let atom = self.pool.get(*node);
+                !matches!(atom, Atom::Symbol(sym) if *sym != Symbol::IMAGINARY_UNIT)
+                    && atom
+                        .children()
+                        .all(|child| self.is_constant(self.find(ClassId(child)), visiting))
+            })
+        });
+        visiting.pop();
+        constant
+    }
+
+    /// Instantiate a replacement template, adding it to the graph and returning its class
+    fn instantiate(&mut self, template: AtomId, bindings: &ClassBindings) -> ClassId {
+        if let Atom::Symbol(sym) = self.pool.get(template) {
+            if let Some(class) = bindings.get(sym) {
+                return self.find(*class);
+            }
+        }
+
+        let atom = self.pool.get(template).clone();
+        let atom = atom.map_children(|child| self.instantiate(child, bindings).0);
+        let atom = match atom {
+            Atom::Sum(terms) => Atom::Sum(self.flatten(&terms, true)),
+            Atom::Product(factors) => Atom::Product(self.flatten(&factors, false)),
+            atom => atom,
+        };
+        self.add_node(atom)
+    }
+
+    /// Splice the children of classes consisting only of a sum (or product) into a sum (or
+    /// product) of the given classes
+    fn flatten(&self, items: &[AtomId], sum: bool) -> Box<[AtomId]> {
+        items
+            .iter()
+            .flat_map(|item| {
+                let nodes = self
+                    .classes
+                    .get(&ClassId(*item))
+                    .map(|c| c.nodes.as_slice());
+                match nodes.map(|nodes| (nodes, nodes.first().map(|n| self.pool.get(*n)))) {
+                    Some(([_], Some(Atom::Sum(terms)))) if sum => terms.to_vec(),
+                    Some(([_], Some(Atom::Product(factors)))) if !sum => factors.to_vec(),
+                    _ => vec![*item],
+                }
+            })
+            .collect()
+    }
+
+    /// Find the cheapest node of every class for which one exists
+    fn best_nodes(&self, cost: &mut impl CostFunction) -> HashMap<ClassId, (f64, AtomId)> {
+        let mut best = HashMap::<ClassId, (f64, AtomId)>::new();
+        for _ in 0..=self.classes.len() {
+            let mut changed = false;
+            for (class, data) in &self.classes {
+                for node in &data.nodes {
+                    let atom = self.pool.get(*node);
+                    let children = atom
+                        .children()
+                        .map(|child| best.get(&self.find(ClassId(child))).map(|(c, _)| *c))
+                        .collect::<Option<Vec<_>>>();
+                    let Some(children) = children else {
+                        continue;
+                    };
+
+                    let cost = cost.cost(atom, &children);
+                    if best
+                        .get(class)
+                        .is_none_or(|(b, n)| cost < *b || (cost == *b && node < n))
+                    {
+                        best.insert(*class, (cost, *node));
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        best
+    }
+
+    fn build(
+        &mut self,
+        class: ClassId,
+        best: &HashMap<ClassId, (f64, AtomId)>,
+        built: &mut HashMap<ClassId, AtomId>,
+    ) -> AtomId {
+        if let Some(id) = built.get(&class) {
+            return *id;
+        }
+
+        let node = best.get(&class).map_or(class.0, |(_, node)| *node);
+        let atom = self.pool.get(node).clone();
+        let atom = atom.map_children(|child| self.build(self.find(ClassId(child)), best, built));
+        let id = self.pool.insert_flattened(atom);
+        built.insert(class, id);
+        id
+    }
+}
+
+/// Keep only the bindings of matches that covered every term of a sum or product
+fn complete(matches: Vec<(ClassBindings, Vec<AtomId>)>) -> impl Iterator<Item = ClassBindings> {
+    matches
+        .into_iter()
+        .filter(|(_, rest)| rest.is_empty())
+        .map(|(bindings, _)| bindings)
+}
+
+impl ExprPool {
+    /// Find the cheapest expression equal to the one rooted at `id` under `cost` by saturating an
+    /// [EGraph] with the given rules
+    pub fn saturate(
+        &mut self,
+        id: AtomId,
+        rules: &[Rule],
+        limits: &Limits,
+        cost: &mut impl CostFunction,
+    ) -> AtomId {
+        let mut graph = EGraph::new(self);
+        let class = graph.add(id);
+        graph.saturate(rules, limits);
+        graph.extract(class, cost)
+    }
+}
+
+/// Trigonometric, exponential and logarithmic identities, written in the form produced by the
+/// [parser](crate::parse::Parser).
+///
+/// Identities that only hold on part of the complex plane are constrained to it, such as the
+/// logarithm laws which fail for negative arguments as in `ln((-1)(-1)) ≠ 2ln(-1)`
+const IDENTITIES: &[(&str, &str)] = &[
+    ("sin(?x)^2 + cos(?x)^2", "1"),
+    ("1 - sin(?x)^2", "cos(?x)^2"),
+    ("1 - cos(?x)^2", "sin(?x)^2"),
+    ("tan(?x)", "sin(?x) / cos(?x)"),
+    ("sin(?x) / cos(?x)", "tan(?x)"),
+    ("2sin(?x)cos(?x)", "sin(2?x)"),
+    ("cos(?x)^2 - sin(?x)^2", "cos(2?x)"),
+    ("sin(-?x)", "-sin(?x)"),
+    ("cos(-?x)", "cos(?x)"),
+    ("ln(?a: positive ?b: positive)", "ln(?a) + ln(?b)"),
+    ("ln(?a: positive) + ln(?b: positive)", "ln(?a ?b)"),
+    ("ln(?a: positive) - ln(?b: positive)", "ln(?a / ?b)"),
+    ("ln(?a: positive / ?b: positive)", "ln(?a) - ln(?b)"),
+    ("ln(?a: positive ^ ?n: real)", "?n ln(?a)"),
+    ("?n: real ln(?a: positive)", "ln(?a ^ ?n)"),
+    ("ln(exp(?x: real))", "?x"),
+    ("exp(ln(?x: nonzero))", "?x"),
+    ("exp(?a) exp(?b)", "exp(?a + ?b)"),
+    ("exp(?a + ?b)", "exp(?a) exp(?b)"),
+    ("?a - ?a", "0"),
+    ("?a + 0", "?a"),
+    ("?a: nonzero / ?a", "1"),
+    ("?a / ?b: nonzero * ?b", "?a"),
+    ("1 ?a", "?a"),
+    ("ln(1)", "0"),
+];
+
+/// Create rules for common trigonometric, exponential and logarithmic identities, for use with
+/// [EGraph::saturate]
+pub fn identities(pool: &mut ExprPool, symbols: &mut SymbolStore) -> Vec<Rule> {
+    IDENTITIES
+        .iter()
+        .map(|(pattern, replacement)| {
+            Rule::parse(pool, symbols, pattern, replacement).expect("identity must parse")
+        })
+        .collect()
+}
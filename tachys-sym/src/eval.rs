@@ -0,0 +1,655 @@
+use std::collections::HashMap;
+
+use num_integer::Integer;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::{
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, BigOperator, ExprPool, Function, Rational,
+    },
+    quadrature::{self, Quadrature, QuadratureOptions},
+    units::{UnitError, UnitErrorKind},
+};
+
+pub use num_complex::Complex64;
+
+/// Number of terms of an infinite series added directly before the rest is estimated
+const SERIES_TERMS: usize = 1024;
+
+/// Largest number of terms of a finite sum or product that is evaluated
+const MAX_TERMS: f64 = 1e7;
+
+/// Values bound to symbols when numerically evaluating an expression.
+///
+/// Unbound symbols named `π`, `e` and `∞` evaluate to their usual constants and
+/// [Symbol::IMAGINARY_UNIT] to `i`, so binding one of these shadows the constant
+#[derive(Clone, Default, Debug)]
+pub struct Env {
+    values: HashMap<Symbol, f64>,
+}
+
+/// Error produced when an expression cannot be evaluated, with the ID of the sub-expression that
+/// caused it
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+#[error("{kind}")]
+pub struct EvalError {
+    pub kind: EvalErrorKind,
+    pub atom: AtomId,
+}
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum EvalErrorKind {
+    #[error("Symbol '{0}' has no value")]
+    Unbound(String),
+    #[error("Function '{0}' has no definition")]
+    UndefinedFunction(String),
+    #[error("Division by zero")]
+    DivisionByZero,
+    #[error("'{func}' is undefined at {value}")]
+    Domain { func: &'static str, value: f64 },
+    #[error("Negative number {0} raised to a fractional power")]
+    NegativeBase(f64),
+    #[error("Function '{name}' expects {expected} argument(s) but was given {found}")]
+    Arity {
+        name: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Expression does not have a numeric value")]
+    NotNumeric,
+    #[error("Expression does not have a real value")]
+    NotReal,
+    #[error("Integral did not converge, the estimate {value} has error {error}")]
+    NotConverged { value: f64, error: f64 },
+    #[error("Bounds of a sum or product must be integers, but one is {0}")]
+    Bound(f64),
+    #[error("Sum or product has {0} terms, which is too many to evaluate")]
+    TooManyTerms(f64),
+    #[error("Series does not converge")]
+    Diverges,
+    #[error(transparent)]
+    Units(UnitErrorKind),
+}
+
+impl Env {
+    /// Create an environment with no symbols bound
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind `symbol` to the given value, replacing any previous binding
+    pub fn bind(&mut self, symbol: Symbol, value: f64) -> &mut Self {
+        self.values.insert(symbol, value);
+        self
+    }
+
+    /// Remove the binding of `symbol`, returning its value if it was bound
+    pub fn unbind(&mut self, symbol: Symbol) -> Option<f64> {
+        self.values.remove(&symbol)
+    }
+
+    /// Get the value bound to `symbol`
+    pub fn get(&self, symbol: Symbol) -> Option<f64> {
+        self.values.get(&symbol).copied()
+    }
+}
+
+impl FromIterator<(Symbol, f64)> for Env {
+    fn from_iter<T: IntoIterator<Item = (Symbol, f64)>>(iter: T) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl ExprPool {
+    /// Numerically evaluate the expression rooted at `id`, looking up the values of symbols in
+    /// `env`.
+    ///
+    /// Expressions involving complex numbers are evaluated with [ExprPool::eval_complex] and
+    /// accepted if their value is real, as for `(1 + i)(1 - i)`. Units evaluate to their size in
+    /// coherent SI units once the expression is checked to be dimensionally consistent, so
+    /// `1 km + 1 m` is `1001`
+    pub fn eval(&self, id: AtomId, symbols: &SymbolStore, env: &Env) -> Result<f64, EvalError> {
+        self.check_dimension(id)?;
+        let evaluator = Evaluator {
+            pool: self,
+            symbols,
+            env,
+        };
+        match evaluator.eval(id) {
+            Err(e) if e.kind == EvalErrorKind::NotReal => {
+                // Allow for rounding in the imaginary part, as in `e^(iπ)`
+                let value = evaluator.complex(id)?;
+                match value.im.abs() <= 16. * f64::EPSILON * value.norm() {
+                    true => Ok(value.re),
+                    false => Err(e),
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// Numerically evaluate the expression rooted at `id` as a complex number, looking up the
+    /// values of symbols in `env`.
+    ///
+    /// Multivalued functions and powers take their principal branch, so `sqrt(-4) = 2i` and
+    /// `(-8)^(1/3) = 1 + 1.732…i` rather than the real cube root. Units are checked and evaluated
+    /// as by [ExprPool::eval]
+    pub fn eval_complex(
+        &self,
+        id: AtomId,
+        symbols: &SymbolStore,
+        env: &Env,
+    ) -> Result<Complex64, EvalError> {
+        self.check_dimension(id)?;
+        Evaluator {
+            pool: self,
+            symbols,
+            env,
+        }
+        .complex(id)
+    }
+
+    /// Numerically evaluate the definite [Atom::Integral] at `id`, returning the estimated error
+    /// alongside the value even when the quadrature did not converge
+    pub fn quadrature(
+        &self,
+        id: AtomId,
+        symbols: &SymbolStore,
+        env: &Env,
+        options: &QuadratureOptions,
+    ) -> Result<Quadrature, EvalError> {
+        self.check_dimension(id)?;
+        Evaluator {
+            pool: self,
+            symbols,
+            env,
+        }
+        .integral(id, options)
+    }
+
+    /// Check that the expression rooted at `id` is dimensionally consistent before evaluating it
+    fn check_dimension(&self, id: AtomId) -> Result<(), EvalError> {
+        match self.dimension(id) {
+            Ok(_) => Ok(()),
+            Err(UnitError { kind, atom }) => Err(EvalError {
+                kind: EvalErrorKind::Units(kind),
+                atom,
+            }),
+        }
+    }
+}
+
+struct Evaluator<'a> {
+    pool: &'a ExprPool,
+    symbols: &'a SymbolStore,
+    env: &'a Env,
+}
+
+impl Evaluator<'_> {
+    fn eval(&self, id: AtomId) -> Result<f64, EvalError> {
+        let error = |kind| EvalError { kind, atom: id };
+
+        Ok(match self.pool.get(id) {
+            Atom::Integer(i) => i.to_f64().unwrap_or(f64::NAN),
+            Atom::Rational(r) => r.to_f64(),
+            Atom::Float(v) => v.value(),
+            Atom::Complex { re, im } => match self.eval(*im)? {
+                0. => self.eval(*re)?,
+                _ => return Err(error(EvalErrorKind::NotReal)),
+            },
+            Atom::Unit(unit) => unit.scale().to_f64(),
+            Atom::Symbol(sym) => match self.env.get(*sym) {
+                Some(value) => value,
+                None if *sym == Symbol::IMAGINARY_UNIT => {
+                    return Err(error(EvalErrorKind::NotReal))
+                }
+                None => match self.symbols.name(*sym) {
+                    "π" => std::f64::consts::PI,
+                    "e" => std::f64::consts::E,
+                    "∞" => f64::INFINITY,
+                    name => return Err(error(EvalErrorKind::Unbound(name.to_owned()))),
+                },
+            },
+            Atom::Sum(terms) => terms
+                .iter()
+                .map(|term| self.eval(*term))
+                .sum::<Result<_, _>>()?,
+            Atom::Product(factors) => factors
+                .iter()
+                .map(|factor| self.eval(*factor))
+                .product::<Result<_, _>>()?,
+            Atom::Power { base, exponent } => self.power(id, *base, *exponent)?,
+            Atom::Neg(inner) => -self.eval(*inner)?,
+            Atom::Apply {
+                func: Function::User(sym),
+                ..
+            } => {
+                return Err(error(EvalErrorKind::UndefinedFunction(
+                    self.symbols.name(*sym).to_owned(),
+                )))
+            }
+            Atom::Apply { func, args } => {
+                let name = func.builtin_name().unwrap_or_default();
+                let [arg] = **args else {
+                    return Err(error(EvalErrorKind::Arity {
+                        name,
+                        expected: 1,
+                        found: args.len(),
+                    }));
+                };
+
+                apply(*func, name, self.eval(arg)?).map_err(error)?
+            }
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => {
+                let numerator = self.eval(*numerator)?;
+                match self.eval(*denominator)? {
+                    0. => return Err(error(EvalErrorKind::DivisionByZero)),
+                    denominator => numerator / denominator,
+                }
+            }
+            Atom::Integral { .. } => match self.integral(id, &QuadratureOptions::default())? {
+                Quadrature {
+                    value,
+                    converged: true,
+                    ..
+                } => value,
+                Quadrature {
+                    value, error: e, ..
+                } => return Err(error(EvalErrorKind::NotConverged { value, error: e })),
+            },
+            Atom::BigOperator { .. } => self.big_operator(id)?,
+            Atom::Equals { .. }
+            | Atom::Relation { .. }
+            | Atom::Derivative { .. }
+            | Atom::Limit { .. }
+            | Atom::Matrix { .. }
+            | Atom::Order(_) => return Err(error(EvalErrorKind::NotNumeric)),
+        })
+    }
+
+    fn complex(&self, id: AtomId) -> Result<Complex64, EvalError> {
+        let error = |kind| EvalError { kind, atom: id };
+
+        Ok(match self.pool.get(id) {
+            Atom::Complex { re, im } => Complex64::new(self.eval(*re)?, self.eval(*im)?),
+            Atom::Symbol(sym) if *sym == Symbol::IMAGINARY_UNIT && self.env.get(*sym).is_none() => {
+                Complex64::i()
+            }
+            Atom::Sum(terms) => terms
+                .iter()
+                .map(|term| self.complex(*term))
+                .sum::<Result<_, _>>()?,
+            Atom::Product(factors) => factors
+                .iter()
+                .map(|factor| self.complex(*factor))
+                .product::<Result<_, _>>()?,
+            Atom::Power { base, exponent } => self.complex_power(id, *base, *exponent)?,
+            Atom::Neg(inner) => -self.complex(*inner)?,
+            Atom::Apply { func, args } if !matches!(func, Function::User(_)) => {
+                let name = func.builtin_name().unwrap_or_default();
+                let [arg] = **args else {
+                    return Err(error(EvalErrorKind::Arity {
+                        name,
+                        expected: 1,
+                        found: args.len(),
+                    }));
+                };
+
+                apply_complex(*func, name, self.complex(arg)?).map_err(error)?
+            }
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => {
+                let numerator = self.complex(*numerator)?;
+                match self.complex(*denominator)? {
+                    denominator if denominator.is_zero() => {
+                        return Err(error(EvalErrorKind::DivisionByZero))
+                    }
+                    denominator => numerator / denominator,
+                }
+            }
+            _ => self.eval(id)?.into(),
+        })
+    }
+
+    /// Integrate a definite integral numerically, binding its variable to each sample point in a
+    /// copy of the environment
+    fn integral(&self, id: AtomId, options: &QuadratureOptions) -> Result<Quadrature, EvalError> {
+        let &Atom::Integral {
+            variable,
+            upper: Some(upper),
+            lower: Some(lower),
+            integrand,
+        } = self.pool.get(id)
+        else {
+            return Err(EvalError {
+                kind: EvalErrorKind::NotNumeric,
+                atom: id,
+            });
+        };
+
+        let (a, b) = (self.eval(lower)?, self.eval(upper)?);
+        let mut env = self.env.clone();
+        let mut failure = None;
+        let result = quadrature::integrate(
+            |x| {
+                if failure.is_some() {
+                    return f64::NAN;
+                }
+
+                env.bind(variable, x);
+                let sample = Evaluator {
+                    pool: self.pool,
+                    symbols: self.symbols,
+                    env: &env,
+                }
+                .eval(integrand);
+                sample.unwrap_or_else(|e| {
+                    failure = Some(e);
+                    f64::NAN
+                })
+            },
+            a,
+            b,
+            options,
+        );
+
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(result),
+        }
+    }
+
+    /// Evaluate a sum or product by binding its index to each integer in turn in a copy of the
+    /// environment. The tails of infinite sums and products are estimated once the terms are small
+    fn big_operator(&self, id: AtomId) -> Result<f64, EvalError> {
+        let error = |kind| EvalError { kind, atom: id };
+        let &Atom::BigOperator {
+            op,
+            index,
+            lower,
+            upper,
+            term,
+        } = self.pool.get(id)
+        else {
+            return Err(error(EvalErrorKind::NotNumeric));
+        };
+
+        let (a, b) = (self.eval(lower)?, self.eval(upper)?);
+        if let Some(bound) = [a, b]
+            .into_iter()
+            .find(|x| x.fract() != 0. && *x != f64::INFINITY)
+        {
+            return Err(error(EvalErrorKind::Bound(bound)));
+        }
+        if b - a + 1. > MAX_TERMS && b.is_finite() {
+            return Err(error(EvalErrorKind::TooManyTerms(b - a + 1.)));
+        }
+
+        let mut env = self.env.clone();
+        let mut at = |k: f64| {
+            env.bind(index, k);
+            Evaluator {
+                pool: self.pool,
+                symbols: self.symbols,
+                env: &env,
+            }
+            .eval(term)
+        };
+
+        match (op, b.is_finite()) {
+            // Folding from `0` rather than using `Sum`, which starts from `-0`, gives an empty
+            // sum the value `0`
+            (BigOperator::Sum, true) => {
+                (0..=(b - a) as i64).try_fold(0., |sum, n| Ok(sum + at(a + n as f64)?))
+            }
+            (BigOperator::Product, true) => {
+                (0..=(b - a) as i64).map(|n| at(a + n as f64)).product()
+            }
+            (BigOperator::Sum, false) => self.series(id, a, &mut at),
+            // Sum the logarithms of the terms after the first few, which must then be positive
+            (BigOperator::Product, false) => {
+                let head = (0..SERIES_TERMS)
+                    .map(|n| at(a + n as f64))
+                    .product::<Result<f64, _>>()?;
+                let tail = self.series(id, a + SERIES_TERMS as f64, &mut |k| match at(k)? {
+                    t if t > 0. => Ok(t.ln()),
+                    _ => Err(error(EvalErrorKind::Diverges)),
+                })?;
+                Ok(head * tail.exp())
+            }
+        }
+    }
+
+    /// Sum `f(k)` over the integers `k` from `start` to infinity.
+    ///
+    /// The first [SERIES_TERMS] terms are added directly. Partial sums of alternating series are
+    /// then averaged repeatedly, while the tail of other series is estimated from the integral
+    /// of `f` by the Euler–Maclaurin formula
+    fn series(
+        &self,
+        id: AtomId,
+        start: f64,
+        f: &mut dyn FnMut(f64) -> Result<f64, EvalError>,
+    ) -> Result<f64, EvalError> {
+        let diverges = EvalError {
+            kind: EvalErrorKind::Diverges,
+            atom: id,
+        };
+
+        let mut terms = Vec::with_capacity(SERIES_TERMS);
+        let mut partial = Vec::with_capacity(SERIES_TERMS);
+        let mut sum = 0.;
+        for n in 0..SERIES_TERMS {
+            let term = f(start + n as f64)?;
+            sum += term;
+            terms.push(term);
+            partial.push(sum);
+        }
+
+        let last = terms[SERIES_TERMS - 1].abs();
+        if !sum.is_finite() {
+            return Err(diverges);
+        }
+        if last <= f64::EPSILON * sum.abs() {
+            return Ok(sum);
+        }
+        // Terms must keep shrinking for the series to converge, so `1 + 1/k` is rejected
+        if last > 0.99 * terms[SERIES_TERMS / 2].abs() {
+            return Err(diverges);
+        }
+
+        // Partial sums of terms that change sign oscillate, and repeated averaging damps the
+        // oscillation, as for `∑ (-1)^k/k` or `∑ sin(k)/k^2`
+        let window = &terms[SERIES_TERMS - 32..];
+        if window.iter().any(|term| term * window[0] < 0.) {
+            let mut averages = partial[SERIES_TERMS - 32..].to_vec();
+            while averages.len() > 1 {
+                averages = averages
+                    .windows(2)
+                    .map(|pair| (pair[0] + pair[1]) / 2.)
+                    .collect();
+            }
+            return Ok(averages[0]);
+        }
+
+        // ∑_{k ≥ n} f(k) ≈ ∫_n^∞ f + f(n)/2 - f'(n)/12
+        let n = start + SERIES_TERMS as f64;
+        let mut failure = None;
+        let integral = quadrature::integrate(
+            |x| {
+                f(x).unwrap_or_else(|e| {
+                    failure.get_or_insert(e);
+                    f64::NAN
+                })
+            },
+            n,
+            f64::INFINITY,
+            &QuadratureOptions {
+                relative: 1e-6,
+                ..QuadratureOptions::default()
+            },
+        );
+        if let Some(e) = failure {
+            return Err(e);
+        }
+        // A tail far larger than the terms summed so far means the integral test failed, as for
+        // `∑ 1/k`, even when the quadrature settles on a finite value
+        if !integral.converged || integral.value.abs() > 2. * sum.abs() {
+            return Err(diverges);
+        }
+        let slope = (f(n + 1.)? - f(n - 1.)?) / 2.;
+        Ok(sum + integral.value + f(n)? / 2. - slope / 12.)
+    }
+
+    fn power(&self, id: AtomId, base: AtomId, exponent: AtomId) -> Result<f64, EvalError> {
+        let error = |kind| EvalError { kind, atom: id };
+        let b = self.eval(base)?;
+        let value = match self.pool.get(exponent) {
+            Atom::Integer(i) => match i.to_i32() {
+                Some(i) => b.powi(i),
+                None => b.powf(i.to_f64().unwrap_or(f64::NAN)),
+            },
+            // Odd roots of negative numbers are real, as in `(-8)^(1/3) = -2`
+            _ if b < 0. => match self.exact_ratio(exponent) {
+                Some(ratio) if ratio.denominator().is_odd() => {
+                    let magnitude = (-b).powf(ratio.to_f64());
+                    match ratio.numerator().is_even() {
+                        true => magnitude,
+                        false => -magnitude,
+                    }
+                }
+                _ => match self.eval(exponent)? {
+                    e if e.fract() == 0. => b.powf(e),
+                    _ => return Err(error(EvalErrorKind::NegativeBase(b))),
+                },
+            },
+            _ => b.powf(self.eval(exponent)?),
+        };
+
+        match b == 0. && value.is_infinite() {
+            true => Err(error(EvalErrorKind::DivisionByZero)),
+            false => Ok(value),
+        }
+    }
+
+    /// Raise a complex base to a power on the principal branch of the logarithm
+    fn complex_power(
+        &self,
+        id: AtomId,
+        base: AtomId,
+        exponent: AtomId,
+    ) -> Result<Complex64, EvalError> {
+        let error = |kind| EvalError { kind, atom: id };
+        let b = self.complex(base)?;
+        let e = match self.pool.get(exponent) {
+            Atom::Integer(i) if i.to_i32().is_some() && !b.is_zero() => {
+                return Ok(b.powi(i.to_i32().expect("Exponent fits")))
+            }
+            _ => self.complex(exponent)?,
+        };
+
+        match b.is_zero() {
+            true if e.is_zero() => Ok(Complex64::new(1., 0.)),
+            true if e.re > 0. => Ok(Complex64::zero()),
+            true => Err(error(EvalErrorKind::DivisionByZero)),
+            false => Ok(b.powc(e)),
+        }
+    }
+
+    /// Get the value of an exactly written rational number
+    fn exact_ratio(&self, id: AtomId) -> Option<Rational> {
+        match self.pool.get(id) {
+            Atom::Rational(r) => Some(r.clone()),
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => match (self.pool.get(*numerator), self.pool.get(*denominator)) {
+                (Atom::Integer(n), Atom::Integer(d)) => Rational::new(n.clone(), d.clone()),
+                _ => None,
+            },
+            Atom::Neg(inner) => self.exact_ratio(*inner).map(|r| -&r),
+            _ => None,
+        }
+    }
+}
+
+/// Apply a builtin function to a real argument
+pub(crate) fn apply(func: Function, name: &'static str, x: f64) -> Result<f64, EvalErrorKind> {
+    let domain = |valid: bool, value: f64| match valid {
+        true => Ok(value),
+        false => Err(EvalErrorKind::Domain {
+            func: name,
+            value: x,
+        }),
+    };
+
+    match func {
+        Function::Sin => Ok(x.sin()),
+        Function::Cos => Ok(x.cos()),
+        Function::Tan => Ok(x.tan()),
+        Function::Asin => domain((-1. ..=1.).contains(&x), x.asin()),
+        Function::Acos => domain((-1. ..=1.).contains(&x), x.acos()),
+        Function::Atan => Ok(x.atan()),
+        Function::Sinh => Ok(x.sinh()),
+        Function::Cosh => Ok(x.cosh()),
+        Function::Tanh => Ok(x.tanh()),
+        Function::Exp => Ok(x.exp()),
+        Function::Ln => domain(x > 0., x.ln()),
+        Function::Log => domain(x > 0., x.log10()),
+        Function::Sqrt => domain(x >= 0., x.sqrt()),
+        Function::Abs => Ok(x.abs()),
+        Function::Re | Function::Conj => Ok(x),
+        Function::Im => Ok(0.),
+        Function::Arg => Ok(match x < 0. {
+            true => std::f64::consts::PI,
+            false => 0.,
+        }),
+        Function::User(_) => unreachable!("User functions have no builtin definition"),
+    }
+}
+
+/// Apply a builtin function to a complex argument, taking the principal branch of multivalued
+/// functions
+pub(crate) fn apply_complex(
+    func: Function,
+    name: &'static str,
+    z: Complex64,
+) -> Result<Complex64, EvalErrorKind> {
+    let domain = |valid: bool, value: Complex64| match valid {
+        true => Ok(value),
+        false => Err(EvalErrorKind::Domain {
+            func: name,
+            value: z.re,
+        }),
+    };
+
+    match func {
+        Function::Sin => Ok(z.sin()),
+        Function::Cos => Ok(z.cos()),
+        Function::Tan => Ok(z.tan()),
+        Function::Asin => Ok(z.asin()),
+        Function::Acos => Ok(z.acos()),
+        Function::Atan => Ok(z.atan()),
+        Function::Sinh => Ok(z.sinh()),
+        Function::Cosh => Ok(z.cosh()),
+        Function::Tanh => Ok(z.tanh()),
+        Function::Exp => Ok(z.exp()),
+        Function::Ln => domain(!z.is_zero(), z.ln()),
+        Function::Log => domain(!z.is_zero(), z.log10()),
+        Function::Sqrt => Ok(z.sqrt()),
+        Function::Abs => Ok(z.norm().into()),
+        Function::Re => Ok(z.re.into()),
+        Function::Im => Ok(z.im.into()),
+        Function::Conj => Ok(z.conj()),
+        Function::Arg => Ok(z.arg().into()),
+        Function::User(_) => unreachable!("User functions have no builtin definition"),
+    }
+}
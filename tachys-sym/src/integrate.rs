@@ -0,0 +1,684 @@
+use crate::{
+    assume::Assumptions,
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, Direction, ExprPool, Function, Rational,
+    },
+    eval::Env,
+    series::Point,
+};
+
+use num_traits::Signed;
+
+pub(crate) mod rational;
+
+/// Deepest nesting of substitutions and integrations by parts attempted before giving up
+const MAX_DEPTH: usize = 8;
+
+impl ExprPool {
+    /// Find an antiderivative of `integrand` with respect to `variable`, omitting the constant of
+    /// integration, or `None` if none of the integrator's methods apply.
+    ///
+    /// Polynomials, rational functions with rational coefficients, standard integrals of
+    /// elementary functions of a linear argument, substitutions of the form `f(u(x)) u'(x)` and
+    /// integration by parts of products are attempted in turn
+    pub fn integrate(&mut self, integrand: AtomId, variable: Symbol) -> Option<AtomId> {
+        self.integrate_assuming(integrand, variable, &Assumptions::new())
+    }
+
+    /// Find an antiderivative like [ExprPool::integrate], simplifying the integrand and the
+    /// result under `assumptions`, as in `∫ 1/x dx = ln(x)` for positive `x`. Powers with a
+    /// symbolic exponent `n` are only integrated when the assumptions rule out `n = -1`
+    pub fn integrate_assuming(
+        &mut self,
+        integrand: AtomId,
+        variable: Symbol,
+        assumptions: &Assumptions,
+    ) -> Option<AtomId> {
+        let normal = self.normal_form_assuming(integrand, assumptions);
+        let mut integrator = Integrator {
+            pool: self,
+            variable,
+            assumptions,
+            depth: 0,
+        };
+        let antiderivative = integrator.integrate(normal)?;
+        Some(self.simplify_assuming(antiderivative, assumptions))
+    }
+
+    /// Evaluate every [Atom::Integral] in the expression rooted at `id` that has a closed form,
+    /// applying the bounds of definite integrals to their antiderivative.
+    ///
+    /// Bounds are applied as one-sided [limits](ExprPool::limit) from within the interval of
+    /// integration, so they may be infinite. Definite integrals are left in place if they diverge
+    /// at a bound or if the integrand or antiderivative may be singular between the bounds, as
+    /// for `∫_-1^1 1/x^2 dx`
+    pub fn eval_integrals(&mut self, symbols: &mut SymbolStore, id: AtomId) -> AtomId {
+        let atom = self.get(id).clone();
+        let atom = atom.map_children(|child| self.eval_integrals(symbols, child));
+        let Atom::Integral {
+            variable,
+            upper,
+            lower,
+            integrand,
+        } = atom
+        else {
+            return self.insert(atom);
+        };
+
+        let Some(antiderivative) = self.integrate(integrand, variable) else {
+            return self.insert(atom);
+        };
+
+        let x = self.symbol(variable);
+        let mut at = |bound| self.substitute(antiderivative, x, bound);
+        let value = match (upper, lower) {
+            (None, None) => return antiderivative,
+            (Some(upper), Some(lower)) => {
+                let definite =
+                    self.definite(symbols, integrand, antiderivative, variable, lower, upper);
+                match definite {
+                    Some(value) => value,
+                    None => return self.insert(atom),
+                }
+            }
+            (Some(upper), None) => at(upper),
+            (None, Some(lower)) => {
+                let lower = at(lower);
+                self.neg(lower)
+            }
+        };
+        self.simplify(value)
+    }
+
+    /// Apply the bounds of a definite integral to an antiderivative of its integrand, or return
+    /// `None` if the integral may not equal the difference of the antiderivative at its bounds
+    fn definite(
+        &mut self,
+        symbols: &mut SymbolStore,
+        integrand: AtomId,
+        antiderivative: AtomId,
+        variable: Symbol,
+        lower: AtomId,
+        upper: AtomId,
+    ) -> Option<AtomId> {
+        let env = Env::new();
+        let bounds = match (
+            self.eval(lower, symbols, &env),
+            self.eval(upper, symbols, &env),
+        ) {
+            (Ok(a), Ok(b)) if !a.is_nan() && !b.is_nan() => Some((a, b)),
+            _ => None,
+        };
+        let interval = bounds.map(|(a, b)| (a.min(b), a.max(b)));
+
+        let mut candidates = Vec::new();
+        self.singular_candidates(integrand, variable, &mut candidates);
+        self.singular_candidates(antiderivative, variable, &mut candidates);
+        for candidate in candidates {
+            if self.may_vanish_within(symbols, candidate, variable, interval) {
+                return None;
+            }
+        }
+
+        // Each bound is approached from within the interval of integration
+        let (to_upper, to_lower) = match bounds {
+            Some((a, b)) if a > b => (Some(Direction::Above), Some(Direction::Below)),
+            Some(_) => (Some(Direction::Below), Some(Direction::Above)),
+            None => (None, None),
+        };
+        let upper = self.bound_value(symbols, antiderivative, variable, upper, to_upper)?;
+        let lower = self.bound_value(symbols, antiderivative, variable, lower, to_lower)?;
+        let lower = self.neg(lower);
+        Some(self.sum([upper, lower]))
+    }
+
+    /// Get the limit of an antiderivative at a bound of integration, or `None` if it has no
+    /// finite limit there
+    fn bound_value(
+        &mut self,
+        symbols: &mut SymbolStore,
+        antiderivative: AtomId,
+        variable: Symbol,
+        bound: AtomId,
+        direction: Option<Direction>,
+    ) -> Option<AtomId> {
+        let value = self
+            .limit(symbols, antiderivative, variable, bound, direction)
+            .ok()?;
+        match Point::of(self, symbols, value, variable).ok()? {
+            Point::Finite(value) => Some(value),
+            Point::Infinity { .. } => None,
+        }
+    }
+
+    /// Collect the subexpressions of the expression rooted at `id` whose zeros may be
+    /// singularities or branch points of it, such as the base of a negative power or the argument
+    /// of a logarithm
+    fn singular_candidates(&mut self, id: AtomId, variable: Symbol, out: &mut Vec<AtomId>) {
+        let descendants = self.descendants(id).collect::<Vec<_>>();
+        for node in descendants {
+            let candidate = match self.get(node).clone() {
+                Atom::Power { base, exponent } => match self.get(exponent) {
+                    Atom::Integer(n) if !n.is_negative() => continue,
+                    _ => base,
+                },
+                Atom::Fraction { denominator, .. } => denominator,
+                Atom::Apply { func, args } => match (func, &*args) {
+                    (Function::Ln | Function::Log, [u]) => match self.get(*u) {
+                        Atom::Apply {
+                            func: Function::Abs,
+                            args,
+                        } if args.len() == 1 => args[0],
+                        _ => *u,
+                    },
+                    (Function::Sqrt, [u]) => *u,
+                    (Function::Tan, [u]) => self.apply(Function::Cos, [*u]),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            if self.depends_on(candidate, variable) && !out.contains(&candidate) {
+                out.push(candidate);
+            }
+        }
+    }
+
+    /// Check if `id` may be zero for some value of `variable` strictly between the given bounds,
+    /// or for any real value if the bounds are not numeric
+    fn may_vanish_within(
+        &mut self,
+        symbols: &mut SymbolStore,
+        id: AtomId,
+        variable: Symbol,
+        interval: Option<(f64, f64)>,
+    ) -> bool {
+        let Ok(solutions) = self.solve(symbols, id, variable) else {
+            return true;
+        };
+
+        let env = Env::new();
+        let zero = self.integer(0);
+        let one = self.integer(1);
+        for solution in solutions {
+            // A solution with a branch parameter `n` is taken to be linear in `n`
+            let (at_zero, step) = match *solution.parameters {
+                [] => (solution.value, None),
+                [n] => {
+                    let n = self.symbol(n);
+                    let at_zero = self.substitute(solution.value, n, zero);
+                    let at_one = self.substitute(solution.value, n, one);
+                    (at_zero, Some(at_one))
+                }
+                _ => return true,
+            };
+
+            let real = |pool: &Self, id| match pool.eval_complex(id, symbols, &env) {
+                Ok(z) if z.im.abs() <= 1e-12 * (1.0 + z.re.abs()) => Some(Some(z.re)),
+                Ok(_) => Some(None),
+                Err(_) => None,
+            };
+            let Some(root) = real(self, at_zero) else {
+                return true;
+            };
+            let Some(root) = root else {
+                continue;
+            };
+            let Some((a, b)) = interval else {
+                return true;
+            };
+
+            let inside = match step.map(|step| real(self, step)) {
+                None => a < root && root < b,
+                Some(Some(Some(next))) if next != root => {
+                    // Some integer n has a < root + n (next - root) < b
+                    let (low, high) = ((a - root) / (next - root), (b - root) / (next - root));
+                    let (low, high) = (low.min(high), low.max(high));
+                    low.floor() + 1.0 < high
+                }
+                Some(_) => return true,
+            };
+            if inside {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// State of a single integration, operating on expressions in the simplifier's normal form
+struct Integrator<'a> {
+    pool: &'a mut ExprPool,
+    variable: Symbol,
+    assumptions: &'a Assumptions,
+    depth: usize,
+}
+
+impl Integrator<'_> {
+    fn x(&mut self) -> AtomId {
+        self.pool.symbol(self.variable)
+    }
+
+    fn depends(&self, id: AtomId) -> bool {
+        self.pool.depends_on(id, self.variable)
+    }
+
+    fn integrate(&mut self, id: AtomId) -> Option<AtomId> {
+        if !self.depends(id) {
+            let x = self.x();
+            return Some(self.pool.product([id, x]));
+        }
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+
+        self.depth += 1;
+        let out = self.integrate_dependent(id);
+        self.depth -= 1;
+        out
+    }
+
+    fn integrate_dependent(&mut self, id: AtomId) -> Option<AtomId> {
+        if let Atom::Sum(terms) = self.pool.get(id) {
+            let terms = terms.to_vec();
+            let integrated = terms
+                .into_iter()
+                .map(|term| self.integrate(term))
+                .collect::<Option<Vec<_>>>()?;
+            return Some(self.pool.sum(integrated));
+        }
+
+        // Pull constant factors out of the integral
+        if let Atom::Product(factors) = self.pool.get(id) {
+            let (constant, dependent): (Vec<_>, Vec<_>) =
+                factors.iter().partition(|f| !self.depends(**f));
+            if !constant.is_empty() {
+                let dependent = self.pool.product(dependent);
+                let integrated = self.integrate(dependent)?;
+                return Some(self.pool.product(constant.into_iter().chain([integrated])));
+            }
+        }
+
+        self.table(id)
+            .or_else(|| self.rational(id))
+            .or_else(|| self.substitution(id))
+            .or_else(|| self.parts(id))
+    }
+
+    /// Get the coefficient `a` if `u = ax + b` for constants `a` and `b`
+    fn linear(&mut self, u: AtomId) -> Option<AtomId> {
+        let slope = self.pool.diff(u, self.variable);
+        match self.depends(slope)
+            || self
+                .pool
+                .get(slope)
+                .to_rational()
+                .is_some_and(|r| r.is_zero())
+        {
+            true => None,
+            false => Some(slope),
+        }
+    }
+
+    /// Divide an antiderivative with respect to a linear argument by the argument's slope
+    fn over(&mut self, antiderivative: AtomId, slope: AtomId) -> AtomId {
+        let minus_one = self.pool.integer(-1);
+        let reciprocal = self.pool.power(slope, minus_one);
+        self.pool.product([reciprocal, antiderivative])
+    }
+
+    /// Take the absolute value of an expression if it is real wherever the variable is, judging
+    /// by whether it contains complex literals, and otherwise leave it as it is
+    fn abs_if_real(&mut self, id: AtomId) -> AtomId {
+        let complex = self.pool.descendants(id).any(|node| {
+            matches!(
+                self.pool.get(node),
+                Atom::Complex { .. } | Atom::Symbol(Symbol::IMAGINARY_UNIT)
+            )
+        });
+        match complex {
+            true => id,
+            false => self.pool.apply(Function::Abs, [id]),
+        }
+    }
+
+    fn is_number(&self, id: AtomId, value: i64) -> bool {
+        self.pool.get(id).to_rational() == Some(Rational::integer(value))
+    }
+
+    /// Look up the integral of a single elementary function of a linear argument
+    fn table(&mut self, id: AtomId) -> Option<AtomId> {
+        match self.pool.get(id).clone() {
+            Atom::Symbol(_) => {
+                let x = self.x();
+                let two = self.pool.integer(2);
+                let square = self.pool.power(x, two);
+                let half = self.pool.rational(1, 2)?;
+                Some(self.pool.product([half, square]))
+            }
+            Atom::Power { base, exponent } => self.table_power(base, exponent),
+            Atom::Apply { func, args } => {
+                let [u] = *args else {
+                    return None;
+                };
+                let slope = self.linear(u)?;
+                let antiderivative = self.table_function(func, u)?;
+                Some(self.over(antiderivative, slope))
+            }
+            _ => None,
+        }
+    }
+
+    fn table_power(&mut self, base: AtomId, exponent: AtomId) -> Option<AtomId> {
+        let one = self.pool.integer(1);
+        let two = self.pool.integer(2);
+        let minus_one = self.pool.integer(-1);
+
+        // a^u = a^u / (u' ln(a)) for constant a, and e^u = e^u / u'
+        if !self.depends(base) {
+            let slope = self.linear(exponent)?;
+            let power = self.pool.power(base, exponent);
+            let denominator = match self.pool.get(base) {
+                Atom::Symbol(Symbol::E) => slope,
+                _ => {
+                    let ln = self.pool.apply(Function::Ln, [base]);
+                    self.pool.product([slope, ln])
+                }
+            };
+            return Some(self.over(power, denominator));
+        }
+        if self.depends(exponent) {
+            return None;
+        }
+
+        if let Some(slope) = self.linear(base) {
+            let antiderivative = match self.is_number(exponent, -1) {
+                // u^-1 = ln|u|, or ln(u) if u is not real on the real line
+                true => {
+                    let argument = self.abs_if_real(base);
+                    self.pool.apply(Function::Ln, [argument])
+                }
+                // u^n = u^(n + 1) / (n + 1), only if n = -1 is ruled out
+                false => {
+                    let raised = self.pool.sum([exponent, one]);
+                    if self.pool.is_nonzero(raised, self.assumptions) != Some(true) {
+                        return None;
+                    }
+                    let power = self.pool.power(base, raised);
+                    let reciprocal = self.pool.power(raised, minus_one);
+                    self.pool.product([reciprocal, power])
+                }
+            };
+            return Some(self.over(antiderivative, slope));
+        }
+
+        match self.pool.get(base).clone() {
+            // Squares of trigonometric functions, sin(u)^2 = u/2 - sin(2u)/4
+            Atom::Apply { func, args } if self.is_number(exponent, 2) => {
+                let [u] = *args else {
+                    return None;
+                };
+                let slope = self.linear(u)?;
+                let sign = match func {
+                    Function::Sin => -1,
+                    Function::Cos => 1,
+                    _ => return None,
+                };
+                let half = self.pool.rational(1, 2)?;
+                let quarter = self.pool.rational(sign, 4)?;
+                let double = self.pool.product([two, u]);
+                let sin = self.pool.apply(Function::Sin, [double]);
+                let lhs = self.pool.product([half, u]);
+                let rhs = self.pool.product([quarter, sin]);
+                let antiderivative = self.pool.sum([lhs, rhs]);
+                Some(self.over(antiderivative, slope))
+            }
+            // cos(u)^-2 = tan(u)
+            Atom::Apply {
+                func: Function::Cos,
+                args,
+            } if self.is_number(exponent, -2) => {
+                let [u] = *args else {
+                    return None;
+                };
+                let slope = self.linear(u)?;
+                let tan = self.pool.apply(Function::Tan, [u]);
+                Some(self.over(tan, slope))
+            }
+            // (a + bx^2)^(-1/2) integrates to an arcsine or inverse hyperbolic sine
+            Atom::Sum(_) if self.pool.get(exponent).to_rational() == Rational::new(-1, 2) => {
+                self.inverse_sqrt(base)
+            }
+            _ => None,
+        }
+    }
+
+    /// Integrate `(a + bx^2)^(-1/2)` for rational `a` and `b`
+    fn inverse_sqrt(&mut self, base: AtomId) -> Option<AtomId> {
+        let (numerator, denominator) = rational::rational_function(self.pool, base, self.variable)?;
+        if denominator.degree() != 0
+            || numerator.degree() != 2
+            || !numerator.coefficient(1).is_zero()
+        {
+            return None;
+        }
+
+        let scale = denominator.coefficient(0).recip()?;
+        let a = &numerator.coefficient(0) * &scale;
+        let b = &numerator.coefficient(2) * &scale;
+        if a.is_negative() || a.is_zero() {
+            return None;
+        }
+
+        let x = self.x();
+        let half = self.pool.rational(1, 2)?;
+        let minus_one = self.pool.integer(-1);
+        let root_b = self.pool.number(b.abs());
+        let root_b = self.pool.power(root_b, half);
+        let reciprocal = self.pool.power(root_b, minus_one);
+        match b.is_negative() {
+            // asin(x sqrt(-b/a)) / sqrt(-b)
+            true => {
+                let ratio = self.pool.number(b.abs().checked_div(&a)?);
+                let ratio = self.pool.power(ratio, half);
+                let argument = self.pool.product([ratio, x]);
+                let asin = self.pool.apply(Function::Asin, [argument]);
+                Some(self.pool.product([reciprocal, asin]))
+            }
+            // ln(x sqrt(b) + sqrt(a + bx^2)) / sqrt(b)
+            false => {
+                let scaled = self.pool.product([root_b, x]);
+                let root = self.pool.power(base, half);
+                let inner = self.pool.sum([scaled, root]);
+                let ln = self.pool.apply(Function::Ln, [inner]);
+                Some(self.pool.product([reciprocal, ln]))
+            }
+        }
+    }
+
+    /// Get the antiderivative of a builtin function with respect to its argument `u`
+    fn table_function(&mut self, func: Function, u: AtomId) -> Option<AtomId> {
+        let one = self.pool.integer(1);
+        let two = self.pool.integer(2);
+        let minus_one = self.pool.integer(-1);
+        let half = self.pool.rational(1, 2)?;
+        let square = self.pool.power(u, two);
+        let apply = |pool: &mut ExprPool, func| pool.apply(func, [u]);
+
+        Some(match func {
+            Function::Sin => {
+                let cos = apply(self.pool, Function::Cos);
+                self.pool.product([minus_one, cos])
+            }
+            Function::Cos => apply(self.pool, Function::Sin),
+            // -ln|cos(u)|
+            Function::Tan => {
+                let cos = apply(self.pool, Function::Cos);
+                let argument = self.abs_if_real(cos);
+                let ln = self.pool.apply(Function::Ln, [argument]);
+                self.pool.product([minus_one, ln])
+            }
+            Function::Sinh => apply(self.pool, Function::Cosh),
+            Function::Cosh => apply(self.pool, Function::Sinh),
+            Function::Tanh => {
+                let cosh = apply(self.pool, Function::Cosh);
+                self.pool.apply(Function::Ln, [cosh])
+            }
+            Function::Exp => apply(self.pool, Function::Exp),
+            // u ln(u) - u, divided by ln(10) for the base 10 logarithm
+            Function::Ln | Function::Log => {
+                let ln = apply(self.pool, Function::Ln);
+                let product = self.pool.product([u, ln]);
+                let negative = self.pool.product([minus_one, u]);
+                let antiderivative = self.pool.sum([product, negative]);
+                match func {
+                    Function::Ln => antiderivative,
+                    _ => {
+                        let ten = self.pool.integer(10);
+                        let ln_ten = self.pool.apply(Function::Ln, [ten]);
+                        let reciprocal = self.pool.power(ln_ten, minus_one);
+                        self.pool.product([reciprocal, antiderivative])
+                    }
+                }
+            }
+            // u asin(u) + sqrt(1 - u^2), and u acos(u) - sqrt(1 - u^2)
+            Function::Asin | Function::Acos => {
+                let inverse = apply(self.pool, func);
+                let product = self.pool.product([u, inverse]);
+                let negative = self.pool.product([minus_one, square]);
+                let radicand = self.pool.sum([one, negative]);
+                let root = self.pool.power(radicand, half);
+                let root = match func {
+                    Function::Asin => root,
+                    _ => self.pool.product([minus_one, root]),
+                };
+                self.pool.sum([product, root])
+            }
+            // u atan(u) - ln(1 + u^2)/2
+            Function::Atan => {
+                let atan = apply(self.pool, Function::Atan);
+                let product = self.pool.product([u, atan]);
+                let sum = self.pool.sum([one, square]);
+                let ln = self.pool.apply(Function::Ln, [sum]);
+                let minus_half = self.pool.rational(-1, 2)?;
+                let ln = self.pool.product([minus_half, ln]);
+                self.pool.sum([product, ln])
+            }
+            // u|u|/2
+            Function::Abs => {
+                let abs = apply(self.pool, Function::Abs);
+                self.pool.product([half, u, abs])
+            }
+            Function::Sqrt => {
+                let three_halves = self.pool.rational(3, 2)?;
+                let two_thirds = self.pool.rational(2, 3)?;
+                let power = self.pool.power(u, three_halves);
+                self.pool.product([two_thirds, power])
+            }
+            Function::Re | Function::Im | Function::Conj | Function::Arg | Function::User(_) => {
+                return None
+            }
+        })
+    }
+
+    /// Integrate a quotient of polynomials with rational coefficients by partial fractions
+    fn rational(&mut self, id: AtomId) -> Option<AtomId> {
+        let (numerator, denominator) = rational::rational_function(self.pool, id, self.variable)?;
+        rational::integrate_rational(self.pool, &numerator, &denominator, self.variable)
+    }
+
+    /// Integrate `f(u(x)) u'(x)` as `F(u(x))` for each subexpression `u` of the integrand
+    fn substitution(&mut self, id: AtomId) -> Option<AtomId> {
+        let x = self.x();
+        let mut candidates = Vec::new();
+        for u in self.pool.descendants(id) {
+            let composite = matches!(
+                self.pool.get(u),
+                Atom::Apply { .. } | Atom::Power { .. } | Atom::Sum(_)
+            );
+            if composite && u != id && self.depends(u) && !candidates.contains(&u) {
+                candidates.push(u);
+            }
+        }
+
+        let zero = self.pool.integer(0);
+        let minus_one = self.pool.integer(-1);
+        for u in candidates {
+            let slope = self.pool.diff(u, self.variable);
+            let slope = self.pool.normal_form(slope);
+            if self.is_number(slope, 0) {
+                continue;
+            }
+
+            let reciprocal = self.pool.power(slope, minus_one);
+            let quotient = self.pool.product([id, reciprocal]);
+            let quotient = self.pool.normal_form(quotient);
+
+            // The quotient must depend on x only through u
+            let without = self.pool.substitute(quotient, u, zero);
+            if self.depends(without) {
+                continue;
+            }
+
+            let outer = self.pool.substitute(quotient, u, x);
+            let outer = self.pool.normal_form(outer);
+            if let Some(antiderivative) = self.integrate(outer) {
+                return Some(self.pool.substitute(antiderivative, x, u));
+            }
+        }
+
+        None
+    }
+
+    /// Integrate a product by parts, differentiating the factor that simplifies most when
+    /// differentiated: logarithms, then inverse trigonometric functions, then polynomials
+    fn parts(&mut self, id: AtomId) -> Option<AtomId> {
+        let Atom::Product(factors) = self.pool.get(id) else {
+            return None;
+        };
+
+        let factors = factors.to_vec();
+        let (index, _) = factors
+            .iter()
+            .enumerate()
+            .filter_map(|(i, f)| self.priority(*f).map(|p| (i, p)))
+            .min_by_key(|(_, p)| *p)?;
+
+        // ∫ u dv = uv - ∫ v du
+        let u = factors[index];
+        let mut rest = factors;
+        rest.remove(index);
+        let dv = self.pool.product(rest);
+        let v = self.integrate(dv)?;
+        let du = self.pool.diff(u, self.variable);
+        let v_du = self.pool.product([v, du]);
+        let v_du = self.pool.normal_form(v_du);
+        let remaining = self.integrate(v_du)?;
+
+        let uv = self.pool.product([u, v]);
+        let minus_one = self.pool.integer(-1);
+        let negative = self.pool.product([minus_one, remaining]);
+        Some(self.pool.sum([uv, negative]))
+    }
+
+    /// Get the priority of a factor as the part differentiated when integrating by parts, with
+    /// lower values preferred
+    fn priority(&self, factor: AtomId) -> Option<u8> {
+        match self.pool.get(factor) {
+            Atom::Apply {
+                func: Function::Ln | Function::Log,
+                ..
+            } => Some(0),
+            Atom::Apply {
+                func: Function::Asin | Function::Acos | Function::Atan,
+                ..
+            } => Some(1),
+            _ => match rational::rational_function(self.pool, factor, self.variable) {
+                Some((_, denominator)) if denominator.degree() == 0 => Some(2),
+                _ => None,
+            },
+        }
+    }
+}
@@ -0,0 +1,466 @@
+use num_integer::Integer;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use crate::atom::{symbol::Symbol, Atom, AtomId, BigInt, ExprPool, Function, Rational};
+
+/// Largest magnitude of a coefficient whose divisors are searched for rational roots
+const MAX_ROOT_SEARCH: u64 = 1_000_000_000_000;
+
+/// Largest integer exponent expanded when converting a power into a polynomial
+const MAX_EXPONENT: u32 = 64;
+
+/// A dense polynomial in one variable with rational coefficients, stored from the constant term
+/// up with no trailing zero coefficients
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct UniPoly(Vec<Rational>);
+
+fn zero() -> Rational {
+    Rational::integer(0)
+}
+
+impl UniPoly {
+    pub fn new(mut coefficients: Vec<Rational>) -> Self {
+        while coefficients.last().is_some_and(Rational::is_zero) {
+            coefficients.pop();
+        }
+        Self(coefficients)
+    }
+
+    pub fn constant(value: Rational) -> Self {
+        Self::new(vec![value])
+    }
+
+    /// Get the polynomial `x - root`
+    pub fn linear(root: &Rational) -> Self {
+        Self::new(vec![-root, Rational::integer(1)])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the degree of this polynomial, taking the degree of zero to be zero
+    pub fn degree(&self) -> usize {
+        self.0.len().saturating_sub(1)
+    }
+
+    pub fn coefficient(&self, power: usize) -> Rational {
+        self.0.get(power).cloned().unwrap_or_else(zero)
+    }
+
+    fn leading(&self) -> Rational {
+        self.0.last().cloned().unwrap_or_else(zero)
+    }
+
+    pub fn add(&self, rhs: &Self) -> Self {
+        let len = self.0.len().max(rhs.0.len());
+        Self::new(
+            (0..len)
+                .map(|i| &self.coefficient(i) + &rhs.coefficient(i))
+                .collect(),
+        )
+    }
+
+    pub fn sub(&self, rhs: &Self) -> Self {
+        self.add(&rhs.scale(&Rational::integer(-1)))
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        if self.is_zero() || rhs.is_zero() {
+            return Self(Vec::new());
+        }
+
+        let mut out = vec![zero(); self.0.len() + rhs.0.len() - 1];
+        for (i, a) in self.0.iter().enumerate() {
+            for (j, b) in rhs.0.iter().enumerate() {
+                out[i + j] = &out[i + j] + &(a * b);
+            }
+        }
+        Self::new(out)
+    }
+
+    pub fn scale(&self, factor: &Rational) -> Self {
+        Self::new(self.0.iter().map(|c| c * factor).collect())
+    }
+
+    pub fn pow(&self, exponent: u32) -> Self {
+        (0..exponent).fold(Self::constant(Rational::integer(1)), |acc, _| acc.mul(self))
+    }
+
+    /// Divide this polynomial by a nonzero `divisor`, returning the quotient and remainder
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let lead = divisor.leading();
+        let mut remainder = self.clone();
+        let mut quotient = vec![zero(); self.0.len().saturating_sub(divisor.degree())];
+        while !remainder.is_zero() && remainder.degree() >= divisor.degree() {
+            let shift = remainder.degree() - divisor.degree();
+            let factor = remainder
+                .leading()
+                .checked_div(&lead)
+                .expect("divisor must be nonzero");
+            let mut term = vec![zero(); shift];
+            term.push(factor.clone());
+            remainder = remainder.sub(&divisor.mul(&Self(term)));
+            quotient[shift] = factor;
+        }
+        (Self::new(quotient), remainder)
+    }
+
+    /// Get the monic greatest common divisor of two polynomials
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let remainder = a.div_rem(&b).1;
+            a = b;
+            b = remainder;
+        }
+        a.monic()
+    }
+
+    /// Scale this polynomial so that its leading coefficient is one
+    pub fn monic(&self) -> Self {
+        match self.leading().recip() {
+            Some(factor) => self.scale(&factor),
+            None => self.clone(),
+        }
+    }
+
+    pub fn eval(&self, x: &Rational) -> Rational {
+        self.0.iter().rev().fold(zero(), |acc, c| &(&acc * x) + c)
+    }
+
+    /// Insert this polynomial as a sum of terms in descending powers of `x`
+    pub fn to_atom(&self, pool: &mut ExprPool, x: AtomId) -> AtomId {
+        let mut terms = Vec::new();
+        for (power, c) in self.0.iter().enumerate().rev() {
+            if c.is_zero() {
+                continue;
+            }
+
+            let c = pool.number(c.clone());
+            let power = pool.integer(power as i64);
+            let monomial = pool.power(x, power);
+            terms.push(pool.product([c, monomial]));
+        }
+        pool.sum(terms)
+    }
+
+    /// Get the distinct rational roots of this polynomial
+    pub fn rational_roots(&self) -> Vec<Rational> {
+        if self.degree() == 0 {
+            return Vec::new();
+        }
+
+        // Clear denominators to find candidates p/q with p dividing the constant term and q the
+        // leading coefficient
+        let scale = self
+            .0
+            .iter()
+            .fold(BigInt::from(1), |acc, c| acc.lcm(c.denominator()));
+        let integers = self
+            .0
+            .iter()
+            .map(|c| (c * &Rational::integer(scale.clone())).numerator().clone())
+            .collect::<Vec<_>>();
+
+        let mut roots = Vec::new();
+        let lowest = integers.iter().position(|c| !c.is_zero()).unwrap_or(0);
+        if lowest > 0 {
+            roots.push(zero());
+        }
+
+        let (Some(ps), Some(qs)) = (
+            divisors(&integers[lowest]),
+            divisors(integers.last().expect("polynomial must be nonzero")),
+        ) else {
+            return roots;
+        };
+
+        for p in &ps {
+            for q in &qs {
+                for sign in [1, -1] {
+                    let Some(candidate) = Rational::new(p * sign, *q) else {
+                        continue;
+                    };
+                    if !roots.contains(&candidate) && self.eval(&candidate).is_zero() {
+                        roots.push(candidate);
+                    }
+                }
+            }
+        }
+        roots
+    }
+}
+
+/// Get the positive divisors of `value`, or `None` if it is too large to search
+fn divisors(value: &BigInt) -> Option<Vec<i64>> {
+    let value = value.abs().to_u64().filter(|v| *v <= MAX_ROOT_SEARCH)? as i64;
+    let mut out = Vec::new();
+    let mut i = 1;
+    while i * i <= value {
+        if value % i == 0 {
+            out.push(i);
+            if i * i != value {
+                out.push(value / i);
+            }
+        }
+        i += 1;
+    }
+    Some(out)
+}
+
+/// Convert a normal-form expression into a quotient of polynomials in `x` with rational
+/// coefficients, if it is one
+pub(crate) fn rational_function(
+    pool: &ExprPool,
+    id: AtomId,
+    x: Symbol,
+) -> Option<(UniPoly, UniPoly)> {
+    let one = || UniPoly::constant(Rational::integer(1));
+    match pool.get(id) {
+        Atom::Integer(_) | Atom::Rational(_) => {
+            Some((UniPoly::constant(pool.get(id).to_rational()?), one()))
+        }
+        Atom::Symbol(sym) if *sym == x => {
+            Some((UniPoly::new(vec![zero(), Rational::integer(1)]), one()))
+        }
+        Atom::Sum(terms) => {
+            terms
+                .iter()
+                .try_fold((UniPoly::new(Vec::new()), one()), |(n, d), term| {
+                    let (tn, td) = rational_function(pool, *term, x)?;
+                    Some((n.mul(&td).add(&tn.mul(&d)), d.mul(&td)))
+                })
+        }
+        Atom::Product(factors) => factors.iter().try_fold((one(), one()), |(n, d), factor| {
+            let (fn_, fd) = rational_function(pool, *factor, x)?;
+            Some((n.mul(&fn_), d.mul(&fd)))
+        }),
+        Atom::Power { base, exponent } => {
+            let exponent = pool.get(*exponent).to_rational()?;
+            if !exponent.is_integer() {
+                return None;
+            }
+
+            let power = exponent.numerator().to_i64()?;
+            let magnitude = u32::try_from(power.unsigned_abs())
+                .ok()
+                .filter(|p| *p <= MAX_EXPONENT)?;
+            let (n, d) = rational_function(pool, *base, x)?;
+            match power < 0 {
+                true if n.is_zero() => None,
+                true => Some((d.pow(magnitude), n.pow(magnitude))),
+                false => Some((n.pow(magnitude), d.pow(magnitude))),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Integrate the quotient of two polynomials in `x` by polynomial division and partial fractions,
+/// returning `None` if the denominator has repeated or higher degree irreducible factors
+pub(crate) fn integrate_rational(
+    pool: &mut ExprPool,
+    numerator: &UniPoly,
+    denominator: &UniPoly,
+    x: Symbol,
+) -> Option<AtomId> {
+    let common = numerator.gcd(denominator);
+    let numerator = numerator.div_rem(&common).0;
+    let denominator = denominator.div_rem(&common).0;
+    let lead = denominator.leading().recip()?;
+    let (numerator, denominator) = (numerator.scale(&lead), denominator.scale(&lead));
+
+    let (quotient, remainder) = numerator.div_rem(&denominator);
+    let x_atom = pool.symbol(x);
+    let mut terms = vec![polynomial_antiderivative(pool, &quotient, x_atom)];
+    if remainder.is_zero() {
+        return Some(pool.sum(terms));
+    }
+
+    // Split the denominator into linear factors with multiplicity and at most one quadratic
+    let mut rest = denominator.clone();
+    let mut linear = Vec::new();
+    for root in denominator.rational_roots() {
+        let factor = UniPoly::linear(&root);
+        let mut multiplicity = 0;
+        loop {
+            let (quotient, remainder) = rest.div_rem(&factor);
+            if !remainder.is_zero() {
+                break;
+            }
+            rest = quotient;
+            multiplicity += 1;
+        }
+        linear.push((root, multiplicity));
+    }
+
+    let quadratic = match rest.degree() {
+        0 => None,
+        2 => Some(rest.clone()),
+        _ => return None,
+    };
+
+    // Solve for the numerators of each partial fraction by equating coefficients
+    let mut basis = Vec::new();
+    for (root, multiplicity) in &linear {
+        for k in 1..=*multiplicity {
+            let power = UniPoly::linear(root).pow(k);
+            basis.push(denominator.div_rem(&power).0);
+        }
+    }
+    if let Some(quadratic) = &quadratic {
+        let cofactor = denominator.div_rem(quadratic).0;
+        basis.push(cofactor.mul(&UniPoly::new(vec![zero(), Rational::integer(1)])));
+        basis.push(cofactor);
+    }
+
+    let n = denominator.degree();
+    let matrix = (0..n)
+        .map(|row| basis.iter().map(|b| b.coefficient(row)).collect())
+        .collect();
+    let rhs = (0..n).map(|row| remainder.coefficient(row)).collect();
+    let solution = solve(matrix, rhs)?;
+
+    let mut coefficients = solution.into_iter();
+    for (root, multiplicity) in &linear {
+        let shifted = UniPoly::linear(root).to_atom(pool, x_atom);
+        for k in 1..=*multiplicity {
+            let a = coefficients.next()?;
+            if a.is_zero() {
+                continue;
+            }
+
+            // ∫ a/(x - r)^k = a ln|x - r| if k = 1, else a/((1 - k)(x - r)^(k - 1))
+            let term = match k {
+                1 => {
+                    let abs = pool.apply(Function::Abs, [shifted]);
+                    let ln = pool.apply(Function::Ln, [abs]);
+                    let a = pool.number(a);
+                    pool.product([a, ln])
+                }
+                k => {
+                    let c = a.checked_div(&Rational::integer(1 - k as i64))?;
+                    let c = pool.number(c);
+                    let exponent = pool.integer(1 - k as i64);
+                    let power = pool.power(shifted, exponent);
+                    pool.product([c, power])
+                }
+            };
+            terms.push(term);
+        }
+    }
+
+    if let Some(quadratic) = quadratic {
+        let (b, c) = (coefficients.next()?, coefficients.next()?);
+        terms.push(integrate_quadratic(pool, &quadratic, b, c, x_atom)?);
+    }
+
+    Some(pool.sum(terms))
+}
+
+/// Integrate `(bx + c) / (x^2 + px + q)` for a monic quadratic with no rational roots
+fn integrate_quadratic(
+    pool: &mut ExprPool,
+    quadratic: &UniPoly,
+    b: Rational,
+    c: Rational,
+    x: AtomId,
+) -> Option<AtomId> {
+    let (p, q) = (quadratic.coefficient(1), quadratic.coefficient(0));
+    let half = Rational::new(1, 2)?;
+    let quadratic_atom = quadratic.to_atom(pool, x);
+    let mut terms = Vec::new();
+
+    // b/2 (2x + p) / (x^2 + px + q) integrates to b/2 ln|x^2 + px + q|
+    let discriminant = &(&p * &p) - &(&q * &Rational::integer(4));
+    if !b.is_zero() {
+        let abs = pool.apply(Function::Abs, [quadratic_atom]);
+        let ln = pool.apply(Function::Ln, [abs]);
+        let factor = pool.number(&b * &half);
+        terms.push(pool.product([factor, ln]));
+    }
+
+    // The remaining constant numerator c - bp/2 integrates to an arctangent or logarithm
+    let constant = &c - &(&(&b * &p) * &half);
+    if !constant.is_zero() {
+        let linear = UniPoly::new(vec![p.clone(), Rational::integer(2)]).to_atom(pool, x);
+        let half_atom = pool.number(half.clone());
+        let root = pool.number(discriminant.abs());
+        let root = pool.power(root, half_atom);
+        let minus_one = pool.integer(-1);
+        let reciprocal = pool.power(root, minus_one);
+        let term = match discriminant.is_negative() {
+            // 2/s atan((2x + p)/s) with s = sqrt(4q - p^2)
+            true => {
+                let argument = pool.product([linear, reciprocal]);
+                let atan = pool.apply(Function::Atan, [argument]);
+                let factor = pool.number(&constant * &Rational::integer(2));
+                pool.product([factor, reciprocal, atan])
+            }
+            // 1/s ln|(2x + p - s)/(2x + p + s)| with s = sqrt(p^2 - 4q)
+            false => {
+                let negative = pool.neg(root);
+                let lower = pool.sum([linear, negative]);
+                let upper = pool.sum([linear, root]);
+                let upper = pool.power(upper, minus_one);
+                let quotient = pool.product([lower, upper]);
+                let abs = pool.apply(Function::Abs, [quotient]);
+                let ln = pool.apply(Function::Ln, [abs]);
+                let factor = pool.number(constant);
+                pool.product([factor, reciprocal, ln])
+            }
+        };
+        terms.push(term);
+    }
+
+    Some(pool.sum(terms))
+}
+
+/// Integrate a polynomial term by term
+fn polynomial_antiderivative(pool: &mut ExprPool, poly: &UniPoly, x: AtomId) -> AtomId {
+    let integrated = (0..=poly.degree())
+        .map(|power| {
+            let divisor = Rational::integer(power as i64 + 1);
+            poly.coefficient(power)
+                .checked_div(&divisor)
+                .unwrap_or_else(zero)
+        })
+        .collect::<Vec<_>>();
+
+    let mut coefficients = vec![zero()];
+    coefficients.extend(integrated);
+    UniPoly::new(coefficients).to_atom(pool, x)
+}
+
+/// Solve a square system of linear equations by Gaussian elimination, returning `None` if it is
+/// singular
+pub(crate) fn solve(
+    mut matrix: Vec<Vec<Rational>>,
+    mut rhs: Vec<Rational>,
+) -> Option<Vec<Rational>> {
+    let n = rhs.len();
+    for col in 0..n {
+        let pivot = (col..n).find(|row| !matrix[*row][col].is_zero())?;
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        for row in 0..n {
+            if row == col || matrix[row][col].is_zero() {
+                continue;
+            }
+
+            let factor = matrix[row][col].checked_div(&matrix[col][col])?;
+            let pivot_row = matrix[col].clone();
+            for (entry, pivot) in matrix[row].iter_mut().zip(&pivot_row).skip(col) {
+                *entry = &*entry - &(&factor * pivot);
+            }
+            let delta = &factor * &rhs[col];
+            rhs[row] = &rhs[row] - &delta;
+        }
+    }
+
+    (0..n)
+        .map(|row| rhs[row].checked_div(&matrix[row][row]))
+        .collect()
+}
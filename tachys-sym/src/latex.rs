@@ -0,0 +1,556 @@
+use std::fmt::{self, Write};
+
+use num_traits::{One, Signed};
+
+use crate::atom::{
+    symbol::{self, SymbolStore},
+    Atom, AtomId, BigOperator, Direction, ExprPool, Function, Relation, Unit,
+};
+
+mod import;
+
+pub use import::{parse_latex, LatexMacros, LatexParser};
+
+/// [Display](fmt::Display) adapter rendering an expression as LaTeX math mode source
+#[derive(Clone, Copy)]
+pub struct Latex<'a> {
+    pool: &'a ExprPool,
+    symbols: &'a SymbolStore,
+    root: AtomId,
+    options: LatexOptions,
+}
+
+/// Options controlling the style of emitted LaTeX
+#[derive(Clone, Copy, Default, Debug)]
+pub struct LatexOptions {
+    /// Write fractions with `\dfrac` instead of `\frac`, forcing display style sizing
+    pub dfrac: bool,
+    /// Separate all factors of products with `\cdot` instead of juxtaposing them.
+    /// Adjacent numbers are always separated by `\cdot`
+    pub cdot: bool,
+}
+
+/// Binding strength of emitted operators, where higher levels bind more tightly
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Prec {
+    Relation,
+    Sum,
+    Product,
+    Neg,
+    Power,
+    Atom,
+}
+
+impl ExprPool {
+    /// Get a [Display](fmt::Display) adapter writing the expression rooted at `id` as LaTeX
+    pub fn latex<'a>(&'a self, id: AtomId, symbols: &'a SymbolStore) -> Latex<'a> {
+        Latex {
+            pool: self,
+            symbols,
+            root: id,
+            options: LatexOptions::default(),
+        }
+    }
+}
+
+impl<'a> Latex<'a> {
+    /// Render using the given style options
+    pub fn options(self, options: LatexOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    fn atom(&self, id: AtomId) -> &'a Atom {
+        self.pool.get(id)
+    }
+
+    /// Check if the emitted form of the given expression starts with a minus sign
+    fn leading_negative(&self, id: AtomId) -> bool {
+        match self.atom(id) {
+            Atom::Integer(i) => i.is_negative(),
+            Atom::Rational(r) => r.is_negative(),
+            Atom::Float(f) => f.value().is_sign_negative(),
+            Atom::Complex { re, im } => match self.atom(*re).is_zero() {
+                true => self.leading_negative(*im),
+                false => self.leading_negative(*re),
+            },
+            Atom::Neg(_) => true,
+            Atom::Product(factors) => self.leading_negative(factors[0]),
+            Atom::Sum(terms) => self.leading_negative(terms[0]),
+            _ => false,
+        }
+    }
+
+    fn prec(&self, id: AtomId) -> Prec {
+        let prec = match self.atom(id) {
+            // Written in scientific notation as `m \times 10^{e}`
+            Atom::Float(v) if format!("{:?}", v.value()).contains('e') => Prec::Product,
+            Atom::Integer(_)
+            | Atom::Rational(_)
+            | Atom::Float(_)
+            | Atom::Symbol(_)
+            | Atom::Unit(_)
+            | Atom::Apply { .. }
+            | Atom::Fraction { .. }
+            | Atom::Matrix { .. }
+            | Atom::Order(_) => Prec::Atom,
+            Atom::Product(_) => Prec::Product,
+            Atom::Complex { re, im } => match (self.atom(*re).is_zero(), self.atom(*im)) {
+                (true, Atom::Integer(i)) if i.magnitude().is_one() => Prec::Atom,
+                (true, _) => Prec::Product,
+                (false, _) => Prec::Sum,
+            },
+            Atom::Sum(_) => Prec::Sum,
+            Atom::Power { .. } => Prec::Power,
+            Atom::Neg(_) => Prec::Neg,
+            Atom::Integral { .. }
+            | Atom::BigOperator { .. }
+            | Atom::Derivative { .. }
+            | Atom::Limit { .. } => Prec::Product,
+            Atom::Equals { .. } | Atom::Relation { .. } => Prec::Relation,
+        };
+
+        match self.leading_negative(id) {
+            true => prec.min(Prec::Neg),
+            false => prec,
+        }
+    }
+
+    fn write_prec(&self, f: &mut dyn Write, id: AtomId, min: Prec) -> fmt::Result {
+        match self.prec(id) < min {
+            true => {
+                f.write_str("\\left(")?;
+                self.write(f, id)?;
+                f.write_str("\\right)")
+            }
+            false => self.write(f, id),
+        }
+    }
+
+    /// Write the base of a superscript, parenthesized if it binds less tightly than a power or is a
+    /// fraction or root, to which a superscript would otherwise attach ambiguously
+    fn write_base(&self, f: &mut dyn Write, base: AtomId) -> fmt::Result {
+        let compound = matches!(
+            self.atom(base),
+            Atom::Rational(_)
+                | Atom::Fraction { .. }
+                | Atom::Apply {
+                    func: Function::Sqrt,
+                    ..
+                }
+        );
+        match compound {
+            true => {
+                f.write_str("\\left(")?;
+                self.write(f, base)?;
+                f.write_str("\\right)")
+            }
+            false => self.write_prec(f, base, Prec::Atom),
+        }
+    }
+
+    fn frac(&self) -> &'static str {
+        match self.options.dfrac {
+            true => "\\dfrac",
+            false => "\\frac",
+        }
+    }
+
+    fn write_symbol(&self, f: &mut dyn Write, name: &str) -> fmt::Result {
+        let (base, sub) = symbol::split_subscript(name);
+        write_symbol_base(f, base)?;
+        match sub {
+            Some(sub) => {
+                f.write_str("_{")?;
+                write_symbol_base(f, sub)?;
+                f.write_char('}')
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn write_float(&self, f: &mut dyn Write, value: f64) -> fmt::Result {
+        if value.is_infinite() {
+            return match value < 0. {
+                true => f.write_str("-\\infty"),
+                false => f.write_str("\\infty"),
+            };
+        }
+
+        let repr = format!("{value:?}");
+        match repr.split_once('e') {
+            Some((mantissa, exponent)) => write!(f, "{mantissa} \\times 10^{{{exponent}}}"),
+            None => f.write_str(&repr),
+        }
+    }
+
+    /// Write a complex literal as in `3 - 2i` or `\frac{1}{2}i`
+    fn write_complex(&self, f: &mut dyn Write, re: AtomId, im: AtomId) -> fmt::Result {
+        let negative = self.atom(im).is_number() && self.leading_negative(im);
+        if !self.atom(re).is_zero() {
+            self.write(f, re)?;
+            match negative {
+                true => f.write_str(" - ")?,
+                false => f.write_str(" + ")?,
+            }
+        } else if negative {
+            f.write_char('-')?;
+        }
+
+        match self.atom(im) {
+            Atom::Integer(i) if i.magnitude().is_one() => f.write_char('i'),
+            Atom::Integer(i) => write!(f, "{}i", i.magnitude()),
+            Atom::Rational(r) => write!(
+                f,
+                "{}{{{}}}{{{}}}i",
+                self.frac(),
+                r.numerator().magnitude(),
+                r.denominator()
+            ),
+            Atom::Float(v) => {
+                self.write_float(f, v.value().abs())?;
+                f.write_char('i')
+            }
+            _ => {
+                self.write_prec(f, im, Prec::Power)?;
+                f.write_str(" i")
+            }
+        }
+    }
+
+    fn write_sum(&self, f: &mut dyn Write, terms: &[AtomId]) -> fmt::Result {
+        for (i, term) in terms.iter().copied().enumerate() {
+            if i == 0 {
+                match self.atom(term) {
+                    Atom::Sum(_) => self.write_prec(f, term, Prec::Neg)?,
+                    _ => self.write_prec(f, term, Prec::Product)?,
+                }
+                continue;
+            }
+
+            let mut positive = String::new();
+            let negative = match self.atom(term) {
+                Atom::Neg(inner) => {
+                    self.write_prec(&mut positive, *inner, Prec::Product)?;
+                    true
+                }
+                _ if self.leading_negative(term) => {
+                    self.write_prec(&mut positive, term, Prec::Product)?;
+                    positive.remove(0);
+                    true
+                }
+                _ => {
+                    self.write_prec(&mut positive, term, Prec::Product)?;
+                    false
+                }
+            };
+
+            match negative {
+                true => f.write_str(" - ")?,
+                false => f.write_str(" + ")?,
+            }
+            f.write_str(&positive)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_product(&self, f: &mut dyn Write, factors: &[AtomId]) -> fmt::Result {
+        let Some((first, rest)) = factors.split_first() else {
+            return Ok(());
+        };
+
+        let mut separate = true;
+        let mut previous_number = false;
+        match self.atom(*first) {
+            atom if atom.is_minus_one() && !rest.is_empty() => {
+                f.write_char('-')?;
+                separate = false;
+            }
+            Atom::Product(_) => self.write_prec(f, *first, Prec::Power)?,
+            atom => {
+                self.write_prec(f, *first, Prec::Product)?;
+                previous_number = atom.is_number();
+            }
+        }
+
+        for factor in rest.iter().copied() {
+            let mut rendered = String::new();
+            self.write_prec(&mut rendered, factor, Prec::Power)?;
+
+            // Digits and fractions following a number could be misread as part of it, as in `3 2`
+            // or the mixed number `3\frac{1}{2}`
+            let cdot = self.options.cdot
+                || rendered.starts_with(|c: char| c.is_ascii_digit())
+                || (previous_number && rendered.starts_with(self.frac()));
+
+            match (separate, cdot) {
+                (true, true) => f.write_str(" \\cdot ")?,
+                (true, false) => f.write_char(' ')?,
+                (false, _) => (),
+            }
+            f.write_str(&rendered)?;
+
+            separate = true;
+            previous_number = self.atom(factor).is_number();
+        }
+
+        Ok(())
+    }
+
+    fn write_apply(&self, f: &mut dyn Write, func: Function, args: &[AtomId]) -> fmt::Result {
+        let name = match func {
+            Function::Sqrt => {
+                f.write_str("\\sqrt{")?;
+                for arg in args {
+                    self.write(f, *arg)?;
+                }
+                return f.write_char('}');
+            }
+            Function::Abs => {
+                f.write_str("\\left|")?;
+                for arg in args {
+                    self.write(f, *arg)?;
+                }
+                return f.write_str("\\right|");
+            }
+            Function::User(sym) => {
+                let name = self.symbols.name(sym);
+                match name.chars().count() {
+                    1 => self.write_symbol(f, name)?,
+                    _ => write!(f, "\\operatorname{{{name}}}")?,
+                }
+                None
+            }
+            Function::Conj => {
+                f.write_str("\\overline{")?;
+                for arg in args {
+                    self.write(f, *arg)?;
+                }
+                return f.write_char('}');
+            }
+            Function::Re => Some("\\operatorname{Re}"),
+            Function::Im => Some("\\operatorname{Im}"),
+            Function::Asin => Some("\\arcsin"),
+            Function::Acos => Some("\\arccos"),
+            Function::Atan => Some("\\arctan"),
+            func => func.builtin_name(),
+        };
+
+        if let Some(name) = name {
+            if !name.starts_with('\\') {
+                f.write_char('\\')?;
+            }
+            f.write_str(name)?;
+        }
+
+        f.write_str("\\left(")?;
+        for (i, arg) in args.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            self.write(f, *arg)?;
+        }
+        f.write_str("\\right)")
+    }
+
+    fn write(&self, f: &mut dyn Write, id: AtomId) -> fmt::Result {
+        match self.atom(id) {
+            Atom::Integer(i) => write!(f, "{i}"),
+            Atom::Rational(r) => {
+                if r.is_negative() {
+                    f.write_char('-')?;
+                }
+                write!(
+                    f,
+                    "{}{{{}}}{{{}}}",
+                    self.frac(),
+                    r.numerator().magnitude(),
+                    r.denominator()
+                )
+            }
+            Atom::Float(v) => self.write_float(f, v.value()),
+            Atom::Complex { re, im } => self.write_complex(f, *re, *im),
+            Atom::Unit(unit) => write_unit(f, unit),
+            Atom::Symbol(sym) => self.write_symbol(f, self.symbols.name(*sym)),
+            Atom::Sum(terms) => self.write_sum(f, terms),
+            Atom::Product(factors) => self.write_product(f, factors),
+            Atom::Power { base, exponent } => {
+                self.write_base(f, *base)?;
+                f.write_str("^{")?;
+                self.write(f, *exponent)?;
+                f.write_char('}')
+            }
+            Atom::Neg(inner) => {
+                f.write_char('-')?;
+                match self.leading_negative(*inner) {
+                    true => self.write_prec(f, *inner, Prec::Atom),
+                    false => self.write_prec(f, *inner, Prec::Product),
+                }
+            }
+            Atom::Apply { func, args } => self.write_apply(f, *func, args),
+            Atom::Order(term) => {
+                f.write_str("O\\left(")?;
+                self.write(f, *term)?;
+                f.write_str("\\right)")
+            }
+            Atom::Equals { lhs, rhs } => {
+                self.write_prec(f, *lhs, Prec::Sum)?;
+                f.write_str(" = ")?;
+                self.write_prec(f, *rhs, Prec::Sum)
+            }
+            Atom::Relation { op, lhs, rhs } => {
+                self.write_prec(f, *lhs, Prec::Sum)?;
+                f.write_str(match op {
+                    Relation::NotEqual => " \\neq ",
+                    Relation::Less => " < ",
+                    Relation::LessEq => " \\leq ",
+                    Relation::Greater => " > ",
+                    Relation::GreaterEq => " \\geq ",
+                })?;
+                self.write_prec(f, *rhs, Prec::Sum)
+            }
+            Atom::Integral {
+                variable,
+                upper,
+                lower,
+                integrand,
+            } => {
+                f.write_str("\\int")?;
+                if let Some(lower) = lower {
+                    f.write_str("_{")?;
+                    self.write(f, *lower)?;
+                    f.write_char('}')?;
+                }
+                if let Some(upper) = upper {
+                    f.write_str("^{")?;
+                    self.write(f, *upper)?;
+                    f.write_char('}')?;
+                }
+                f.write_char(' ')?;
+                self.write_prec(f, *integrand, Prec::Sum)?;
+                f.write_str(" \\,d")?;
+                self.write_symbol(f, self.symbols.name(*variable))
+            }
+            Atom::BigOperator {
+                op,
+                index,
+                lower,
+                upper,
+                term,
+            } => {
+                f.write_str(match op {
+                    BigOperator::Sum => "\\sum_{",
+                    BigOperator::Product => "\\prod_{",
+                })?;
+                self.write_symbol(f, self.symbols.name(*index))?;
+                f.write_char('=')?;
+                self.write(f, *lower)?;
+                f.write_str("}^{")?;
+                self.write(f, *upper)?;
+                f.write_str("} ")?;
+                self.write_prec(f, *term, Prec::Product)
+            }
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => {
+                write!(f, "{}{{", self.frac())?;
+                self.write(f, *numerator)?;
+                f.write_str("}{")?;
+                self.write(f, *denominator)?;
+                f.write_char('}')
+            }
+            Atom::Derivative {
+                variable,
+                order,
+                inner,
+            } => {
+                write!(f, "{}{{d", self.frac())?;
+                if *order != 1 {
+                    write!(f, "^{{{order}}}")?;
+                }
+                f.write_str("}{d")?;
+                self.write_symbol(f, self.symbols.name(*variable))?;
+                if *order != 1 {
+                    write!(f, "^{{{order}}}")?;
+                }
+                f.write_str("} ")?;
+                self.write_prec(f, *inner, Prec::Power)
+            }
+            Atom::Limit {
+                variable,
+                point,
+                direction,
+                inner,
+            } => {
+                f.write_str("\\lim_{")?;
+                self.write_symbol(f, self.symbols.name(*variable))?;
+                f.write_str(" \\to ")?;
+                match direction {
+                    Some(direction) => {
+                        self.write_base(f, *point)?;
+                        f.write_str(match direction {
+                            Direction::Below => "^{-}",
+                            Direction::Above => "^{+}",
+                        })?;
+                    }
+                    None => self.write(f, *point)?,
+                }
+                f.write_str("} ")?;
+                self.write_prec(f, *inner, Prec::Product)
+            }
+            Atom::Matrix { columns, entries } => {
+                f.write_str("\\begin{pmatrix}")?;
+                for (i, row) in entries.chunks(*columns as usize).enumerate() {
+                    if i != 0 {
+                        f.write_str(" \\\\ ")?;
+                    }
+                    for (j, entry) in row.iter().enumerate() {
+                        if j != 0 {
+                            f.write_str(" & ")?;
+                        }
+                        self.write(f, *entry)?;
+                    }
+                }
+                f.write_str("\\end{pmatrix}")
+            }
+        }
+    }
+}
+
+/// Write a unit in upright type as in `\mathrm{km}`, spelling `µ` and `Ω` as macros
+fn write_unit(f: &mut dyn Write, unit: &Unit) -> fmt::Result {
+    f.write_str("\\mathrm{")?;
+    for c in unit.to_string().chars() {
+        match c {
+            'µ' => f.write_str("\\mu ")?,
+            'Ω' => f.write_str("\\Omega")?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('}')
+}
+
+/// Write a symbol name without subscript as a single letter, Greek letter macro, or upright
+/// multi-letter name
+fn write_symbol_base(f: &mut dyn Write, base: &str) -> fmt::Result {
+    let mut chars = base.chars();
+    match (chars.next(), chars.next()) {
+        (Some('∞'), None) => f.write_str("\\infty"),
+        (Some(letter), None) => match symbol::greek_name(letter) {
+            Some(name) => write!(f, "\\{name}"),
+            None => f.write_char(letter),
+        },
+        _ if base.chars().all(|c| c.is_ascii_digit()) => f.write_str(base),
+        _ => match symbol::greek_letter(base) {
+            Some(_) => write!(f, "\\{base}"),
+            None => write!(f, "\\mathrm{{{base}}}"),
+        },
+    }
+}
+
+impl fmt::Display for Latex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, self.root)
+    }
+}
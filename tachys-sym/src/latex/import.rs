@@ -0,0 +1,1269 @@
+use std::{collections::HashMap, ops::Range};
+
+use crate::{
+    atom::{
+        symbol::{self, SymbolStore},
+        Atom, AtomId, BigInt, BigOperator, Direction, ExprPool, Function, Relation,
+    },
+    parse::{ParseError, ParseErrorKind},
+};
+
+/// Maximum number of user macro expansions performed on a single input before assuming that a
+/// macro expands recursively
+const EXPANSION_LIMIT: usize = 1024;
+
+/// Parser for LaTeX math mode source such as `\frac{a}{b}` or `\int_0^\infty e^{-x^2} dx`.
+///
+/// Unlike linear text, adjacent letters are separate symbols as they would be typeset by LaTeX, so
+/// `xy` is the product of `x` and `y`. Multi-letter symbols may be written with `\mathrm{}`
+pub struct LatexParser<'a> {
+    pool: &'a mut ExprPool,
+    symbols: &'a mut SymbolStore,
+    macros: Option<&'a LatexMacros>,
+}
+
+/// Registry of user defined macros that are expanded before parsing, as if by `\newcommand`
+#[derive(Default, Clone, Debug)]
+pub struct LatexMacros {
+    macros: HashMap<String, Macro>,
+}
+
+#[derive(Clone, Debug)]
+struct Macro {
+    arity: u8,
+    body: Vec<TokenKind>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    span: Range<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    /// Control sequence without the leading backslash
+    Command(String),
+    Letter(char),
+    Number(String),
+    /// Macro parameter such as `#1`
+    Param(u8),
+    /// Any other single character
+    Char(char),
+    Eof,
+}
+
+/// Parse the given LaTeX source into `pool`, interning any symbols into `symbols`
+pub fn parse_latex(
+    pool: &mut ExprPool,
+    symbols: &mut SymbolStore,
+    src: &str,
+) -> Result<AtomId, ParseError> {
+    LatexParser::new(pool, symbols).parse(src)
+}
+
+impl LatexMacros {
+    /// Create a registry with no macros defined
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define a macro named `name`, without its leading backslash, that takes `arity` arguments
+    /// and expands to `body`. Arguments are referenced in the body as `#1` through `#9`
+    pub fn define(&mut self, name: &str, arity: u8, body: &str) -> Result<(), ParseError> {
+        let mut body = tokenize(body)?;
+        body.pop();
+
+        if let Some(token) = body
+            .iter()
+            .find(|t| matches!(t.kind, TokenKind::Param(n) if n == 0 || n > arity))
+        {
+            return Err(ParseError {
+                kind: ParseErrorKind::Unexpected {
+                    expected: "a parameter within the macro's arity",
+                    found: token.kind.to_string(),
+                },
+                span: token.span.clone(),
+            });
+        }
+
+        self.macros.insert(
+            name.trim_start_matches('\\').to_owned(),
+            Macro {
+                arity,
+                body: body.into_iter().map(|t| t.kind).collect(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Remove the macro with the given name, returning `true` if it was defined
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.macros.remove(name.trim_start_matches('\\')).is_some()
+    }
+
+    /// Expand all macros in the given tokens, rescanning the result of each expansion
+    fn expand(&self, tokens: Vec<Token>) -> Result<Vec<Token>, ParseError> {
+        let mut out = Vec::with_capacity(tokens.len());
+        let mut stack = tokens;
+        stack.reverse();
+        let mut expansions = 0;
+
+        while let Some(token) = stack.pop() {
+            let TokenKind::Command(ref name) = token.kind else {
+                out.push(token);
+                continue;
+            };
+            let Some(mac) = self.macros.get(name) else {
+                out.push(token);
+                continue;
+            };
+
+            expansions += 1;
+            if expansions > EXPANSION_LIMIT {
+                return Err(ParseError {
+                    kind: ParseErrorKind::MacroRecursion(name.clone()),
+                    span: token.span,
+                });
+            }
+
+            let mut args = Vec::with_capacity(mac.arity as usize);
+            for _ in 0..mac.arity {
+                args.push(macro_argument(&mut stack, &token)?);
+            }
+
+            for kind in mac.body.iter().rev() {
+                match kind {
+                    TokenKind::Param(n) => {
+                        stack.extend(args[*n as usize - 1].iter().rev().cloned())
+                    }
+                    kind => stack.push(Token {
+                        kind: kind.clone(),
+                        span: token.span.clone(),
+                    }),
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// Pop a single macro argument from the reversed token stack, which is either a brace delimited
+/// group or a single token
+fn macro_argument(stack: &mut Vec<Token>, invocation: &Token) -> Result<Vec<Token>, ParseError> {
+    let Some(first) = stack.pop().filter(|t| t.kind != TokenKind::Eof) else {
+        return Err(ParseError {
+            kind: ParseErrorKind::Unexpected {
+                expected: "a macro argument",
+                found: TokenKind::Eof.to_string(),
+            },
+            span: invocation.span.clone(),
+        });
+    };
+
+    if first.kind != TokenKind::Char('{') {
+        return Ok(vec![first]);
+    }
+
+    let mut depth = 1;
+    let mut group = Vec::new();
+    while let Some(token) = stack.pop() {
+        match token.kind {
+            TokenKind::Char('{') => depth += 1,
+            TokenKind::Char('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(group);
+                }
+            }
+            TokenKind::Eof => break,
+            _ => (),
+        }
+        group.push(token);
+    }
+
+    Err(ParseError {
+        kind: ParseErrorKind::Unclosed('{'),
+        span: first.span,
+    })
+}
+
+impl<'a> LatexParser<'a> {
+    /// Create a parser that inserts atoms into the given pool with no user macros defined
+    pub fn new(pool: &'a mut ExprPool, symbols: &'a mut SymbolStore) -> Self {
+        Self {
+            pool,
+            symbols,
+            macros: None,
+        }
+    }
+
+    /// Expand the macros defined in the given registry before parsing
+    pub fn with_macros(self, macros: &'a LatexMacros) -> Self {
+        Self {
+            macros: Some(macros),
+            ..self
+        }
+    }
+
+    /// Parse a complete expression, equation or relation from the given LaTeX source
+    pub fn parse(&mut self, src: &str) -> Result<AtomId, ParseError> {
+        let mut tokens = tokenize(src)?;
+        if let Some(macros) = self.macros {
+            tokens = macros.expand(tokens)?;
+        }
+
+        let mut state = State {
+            pool: &mut *self.pool,
+            symbols: &mut *self.symbols,
+            tokens,
+            pos: 0,
+            integral_depth: 0,
+            abs_depth: 0,
+        };
+
+        let expr = state.relation()?;
+        state.expect(&TokenKind::Eof, "an operator or end of input")?;
+        Ok(expr)
+    }
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+
+    while let Some((start, ch)) = chars.next() {
+        let kind = match ch {
+            ch if ch.is_whitespace() || ch == '~' => continue,
+            '\\' => {
+                let mut name = String::new();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_alphabetic()) {
+                    name.push(c);
+                }
+                if name.is_empty() {
+                    if let Some((_, c)) = chars.next() {
+                        name.push(c);
+                    }
+                }
+
+                match name.as_str() {
+                    "," | ";" | ":" | "!" | " " | "quad" | "qquad" | "displaystyle" => continue,
+                    _ => TokenKind::Command(name),
+                }
+            }
+            '#' => match chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                Some((_, digit)) => TokenKind::Param(digit as u8 - b'0'),
+                None => TokenKind::Char('#'),
+            },
+            '0'..='9' | '.' => {
+                let mut text = String::from(ch);
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_digit() || *c == '.') {
+                    text.push(c);
+                }
+                TokenKind::Number(text)
+            }
+            ch if ch.is_alphabetic() => TokenKind::Letter(ch),
+            '$' => continue,
+            ch => TokenKind::Char(ch),
+        };
+
+        let end = chars.peek().map(|(i, _)| *i).unwrap_or(src.len());
+        tokens.push(Token {
+            kind,
+            span: start..end,
+        });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        span: src.len()..src.len(),
+    });
+    Ok(tokens)
+}
+
+struct State<'p> {
+    pool: &'p mut ExprPool,
+    symbols: &'p mut SymbolStore,
+    tokens: Vec<Token>,
+    pos: usize,
+    integral_depth: usize,
+    abs_depth: usize,
+}
+
+impl State<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_nth(&self, n: usize) -> &TokenKind {
+        self.tokens
+            .get(self.pos + n)
+            .map(|t| &t.kind)
+            .unwrap_or(&TokenKind::Eof)
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        let matches = self.peek().kind == *kind;
+        if matches {
+            self.next();
+        }
+        matches
+    }
+
+    fn eat_command(&mut self, name: &str) -> bool {
+        self.eat(&TokenKind::Command(name.to_owned()))
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        let token = self.peek();
+        ParseError {
+            kind: ParseErrorKind::Unexpected {
+                expected,
+                found: token.kind.to_string(),
+            },
+            span: token.span.clone(),
+        }
+    }
+
+    fn expect(&mut self, kind: &TokenKind, expected: &'static str) -> Result<Token, ParseError> {
+        match self.peek().kind == *kind {
+            true => Ok(self.next()),
+            false => Err(self.unexpected(expected)),
+        }
+    }
+
+    /// Expect the closing delimiter of a group opened by `open`
+    fn close(&mut self, close: char, open: &Token) -> Result<(), ParseError> {
+        if self.eat(&TokenKind::Char(close)) {
+            return Ok(());
+        }
+
+        match self.peek().kind {
+            TokenKind::Eof => Err(ParseError {
+                kind: ParseErrorKind::Unclosed(match open.kind {
+                    TokenKind::Char(c) => c,
+                    _ => '(',
+                }),
+                span: open.span.clone(),
+            }),
+            _ => Err(self.unexpected(match close {
+                '}' => "'}'",
+                ']' => "']'",
+                '|' => "'|'",
+                _ => "')'",
+            })),
+        }
+    }
+
+    /// Get the variable and length in tokens of the integral differential at the current token,
+    /// if parsing an integrand and the differential is its last factor, so that the `d` in
+    /// `delta\, dx` is read as a letter. The differentials of enclosing integrals may follow it,
+    /// as in `\int \int xy \,dx \,dy`
+    fn differential(&self) -> Option<(char, usize)> {
+        if self.integral_depth == 0 {
+            return None;
+        }
+
+        let (variable, len) = self.differential_at(0)?;
+        let mut end = len;
+        for _ in 1..self.integral_depth {
+            match self.differential_at(end) {
+                Some((_, len)) => end += len,
+                None => break,
+            }
+        }
+
+        let last =
+            !self.operand_at(end) && !matches!(self.peek_nth(end), TokenKind::Char('^' | '_'));
+        last.then_some((variable, len))
+    }
+
+    /// Get the variable and length in tokens of the differential such as `dx` or `\mathrm{d}x`
+    /// starting `n` tokens ahead of the current one, whether or not it ends an integrand
+    fn differential_at(&self, n: usize) -> Option<(char, usize)> {
+        let skip = match self.peek_nth(n) {
+            TokenKind::Letter('d') => 1,
+            TokenKind::Command(c)
+                if matches!(c.as_str(), "mathrm" | "text" | "operatorname")
+                    && self.peek_nth(n + 1) == &TokenKind::Char('{')
+                    && self.peek_nth(n + 2) == &TokenKind::Letter('d')
+                    && self.peek_nth(n + 3) == &TokenKind::Char('}') =>
+            {
+                4
+            }
+            _ => return None,
+        };
+
+        let variable = match self.peek_nth(n + skip) {
+            TokenKind::Letter(v) => *v,
+            TokenKind::Command(c) => greek(c)?,
+            _ => return None,
+        };
+        Some((variable, skip + 1))
+    }
+
+    /// Check if the current token may start an operand that is implicitly multiplied with the
+    /// previous one
+    fn starts_operand(&self) -> bool {
+        self.differential().is_none() && self.operand_at(0)
+    }
+
+    /// Check if the token `n` ahead of the current one may start an operand that is implicitly
+    /// multiplied with the previous one, ignoring integral differentials
+    fn operand_at(&self, n: usize) -> bool {
+        match self.peek_nth(n) {
+            TokenKind::Number(_) | TokenKind::Letter(_) => true,
+            TokenKind::Char('(' | '[' | '{') => true,
+            TokenKind::Char('|') => self.abs_depth == 0,
+            TokenKind::Command(c) => !matches!(
+                c.as_str(),
+                "cdot"
+                    | "times"
+                    | "div"
+                    | "le"
+                    | "leq"
+                    | "ge"
+                    | "geq"
+                    | "ne"
+                    | "neq"
+                    | "lt"
+                    | "gt"
+                    | "right"
+                    | "end"
+                    | "\\"
+                    | "}"
+            ),
+            _ => false,
+        }
+    }
+
+    fn relation(&mut self) -> Result<AtomId, ParseError> {
+        let lhs = self.additive()?;
+
+        let op = match &self.peek().kind {
+            TokenKind::Char('=') => None,
+            TokenKind::Char('<') => Some(Relation::Less),
+            TokenKind::Char('>') => Some(Relation::Greater),
+            TokenKind::Command(c) => match c.as_str() {
+                "lt" => Some(Relation::Less),
+                "gt" => Some(Relation::Greater),
+                "le" | "leq" => Some(Relation::LessEq),
+                "ge" | "geq" => Some(Relation::GreaterEq),
+                "ne" | "neq" => Some(Relation::NotEqual),
+                _ => return Ok(lhs),
+            },
+            _ => return Ok(lhs),
+        };
+
+        self.next();
+        let rhs = self.additive()?;
+        Ok(match op {
+            Some(op) => self.pool.relation(op, lhs, rhs),
+            None => self.pool.equals(lhs, rhs),
+        })
+    }
+
+    fn additive(&mut self) -> Result<AtomId, ParseError> {
+        let mut terms = vec![self.multiplicative()?];
+
+        loop {
+            match self.peek().kind {
+                TokenKind::Char('+') => {
+                    self.next();
+                    terms.push(self.multiplicative()?);
+                }
+                TokenKind::Char('-') => {
+                    self.next();
+                    let term = self.multiplicative()?;
+                    terms.push(self.negate(term));
+                }
+                _ => break Ok(self.pool.sum(terms)),
+            }
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<AtomId, ParseError> {
+        let mut factors = vec![self.unary()?];
+
+        loop {
+            let kind = self.peek().kind.clone();
+            match kind {
+                TokenKind::Char('*') => {
+                    self.next();
+                    factors.push(self.unary()?);
+                }
+                TokenKind::Command(ref c) if c == "cdot" || c == "times" => {
+                    self.next();
+                    factors.push(self.unary()?);
+                }
+                TokenKind::Char('/') => {
+                    self.next();
+                    let denominator = self.unary()?;
+                    let numerator = self.pool.product(factors.drain(..));
+                    factors.push(self.pool.fraction(numerator, denominator));
+                }
+                TokenKind::Command(ref c) if c == "div" => {
+                    self.next();
+                    let denominator = self.unary()?;
+                    let numerator = self.pool.product(factors.drain(..));
+                    factors.push(self.pool.fraction(numerator, denominator));
+                }
+                _ if self.starts_operand() => factors.push(self.power()?),
+                _ => break Ok(self.pool.product(factors)),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<AtomId, ParseError> {
+        match self.peek().kind {
+            TokenKind::Char('-') => {
+                self.next();
+                let operand = self.unary()?;
+                Ok(self.negate(operand))
+            }
+            TokenKind::Char('+') => {
+                self.next();
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    /// Negate the given expression, folding the sign into numeric literals
+    fn negate(&mut self, operand: AtomId) -> AtomId {
+        match self.pool.get(operand) {
+            Atom::Integer(i) => self.pool.integer(-i),
+            Atom::Float(f) => self.pool.float(-f.value()),
+            _ => self.pool.neg(operand),
+        }
+    }
+
+    fn power(&mut self) -> Result<AtomId, ParseError> {
+        let base = self.primary()?;
+
+        match self.peek().kind {
+            TokenKind::Char('^') => {
+                self.next();
+                let exponent = self.argument()?;
+                Ok(self.pool.power(base, exponent))
+            }
+            TokenKind::Char('_') => Err(self.unexpected("a subscript only after a symbol")),
+            _ => Ok(base),
+        }
+    }
+
+    /// Parse a script or macro argument, which is either a brace delimited group or a single
+    /// token. Multi-digit numbers only contribute their first digit, as in `x^23` or `\frac12`
+    fn argument(&mut self) -> Result<AtomId, ParseError> {
+        let token = self.peek().clone();
+        match token.kind {
+            TokenKind::Char('{') => {
+                self.next();
+                let inner = self.group(|s| s.relation())?;
+                self.close('}', &token)?;
+                Ok(inner)
+            }
+            TokenKind::Number(ref text) => {
+                let digit = text.chars().next().unwrap_or('0');
+                let digit_end = token.span.start + 1;
+                if text.len() > 1 {
+                    self.tokens[self.pos] = Token {
+                        kind: TokenKind::Number(text[1..].to_owned()),
+                        span: digit_end.min(token.span.end)..token.span.end,
+                    };
+                } else {
+                    self.next();
+                }
+                self.number(&digit.to_string(), token.span)
+            }
+            TokenKind::Letter(ch) => {
+                self.next();
+                Ok(self.symbol(&ch.to_string()))
+            }
+            TokenKind::Command(_) => self.primary(),
+            _ => Err(self.unexpected("an argument")),
+        }
+    }
+
+    /// Run the given parse function with integral and absolute value state reset, as within a
+    /// delimited group
+    fn group<F: FnOnce(&mut Self) -> Result<AtomId, ParseError>>(
+        &mut self,
+        f: F,
+    ) -> Result<AtomId, ParseError> {
+        let integral_depth = std::mem::take(&mut self.integral_depth);
+        let abs_depth = std::mem::take(&mut self.abs_depth);
+        let result = f(self);
+        self.integral_depth = integral_depth;
+        self.abs_depth = abs_depth;
+        result
+    }
+
+    fn number(&mut self, text: &str, span: Range<usize>) -> Result<AtomId, ParseError> {
+        let invalid = || ParseError {
+            kind: ParseErrorKind::InvalidNumber,
+            span: span.clone(),
+        };
+
+        match text.bytes().all(|b| b.is_ascii_digit()) {
+            true => text
+                .parse::<BigInt>()
+                .map(|i| self.pool.integer(i))
+                .map_err(|_| invalid()),
+            false => text
+                .parse::<f64>()
+                .map(|f| self.pool.float(f))
+                .map_err(|_| invalid()),
+        }
+    }
+
+    fn symbol(&mut self, name: &str) -> AtomId {
+        let sym = self.symbols.intern(name);
+        self.pool.symbol(sym)
+    }
+
+    /// Read the raw text of a subscript following `_`, which may be a group of letters and
+    /// digits
+    fn subscript(&mut self) -> Result<String, ParseError> {
+        let token = self.next();
+        let mut text = String::new();
+        let mut push = |kind: &TokenKind| match kind {
+            TokenKind::Letter(c) => {
+                text.push(*c);
+                true
+            }
+            TokenKind::Number(n) => {
+                text.push_str(n);
+                true
+            }
+            TokenKind::Command(c) => match greek(c) {
+                Some(letter) => {
+                    text.push(letter);
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        };
+
+        match token.kind {
+            TokenKind::Char('{') => {
+                while push(&self.peek().kind.clone()) {
+                    self.next();
+                }
+                self.close('}', &token)?;
+            }
+            TokenKind::Number(ref n) => {
+                let mut digits = n.chars();
+                text.extend(digits.next());
+                let rest = digits.as_str();
+                if !rest.is_empty() {
+                    self.pos -= 1;
+                    self.tokens[self.pos] = Token {
+                        kind: TokenKind::Number(rest.to_owned()),
+                        span: token.span.start + 1..token.span.end,
+                    };
+                }
+            }
+            ref kind if push(kind) => (),
+            _ => {
+                self.pos -= 1;
+                return Err(self.unexpected("a subscript"));
+            }
+        }
+
+        match text.is_empty() {
+            true => Err(ParseError {
+                kind: ParseErrorKind::Unexpected {
+                    expected: "a subscript",
+                    found: "an empty group".to_owned(),
+                },
+                span: token.span,
+            }),
+            false => Ok(text),
+        }
+    }
+
+    /// Parse a symbol with the given base name and an optional subscript
+    fn named_symbol(&mut self, base: String) -> Result<AtomId, ParseError> {
+        let mut name = base;
+        if self.eat(&TokenKind::Char('_')) {
+            name.push('_');
+            name.push_str(&self.subscript()?);
+        }
+        Ok(self.symbol(&name))
+    }
+
+    /// Read the text of a brace delimited group containing only letters, as in `\mathrm{speed}`
+    fn text_group(&mut self) -> Result<String, ParseError> {
+        let open = self.expect(&TokenKind::Char('{'), "'{'")?;
+        let mut text = String::new();
+        loop {
+            match self.peek().kind.clone() {
+                TokenKind::Letter(c) => text.push(c),
+                TokenKind::Number(n) => text.push_str(&n),
+                _ => break,
+            }
+            self.next();
+        }
+        self.close('}', &open)?;
+        Ok(text)
+    }
+
+    fn primary(&mut self) -> Result<AtomId, ParseError> {
+        let token = self.peek().clone();
+
+        match token.kind {
+            TokenKind::Number(ref text) => {
+                self.next();
+                self.number(text, token.span)
+            }
+            TokenKind::Letter(ch) => {
+                self.next();
+                self.named_symbol(ch.to_string())
+            }
+            TokenKind::Char(open @ ('(' | '[' | '{')) => {
+                self.next();
+                let inner = self.group(|s| s.relation())?;
+                self.close(
+                    match open {
+                        '(' => ')',
+                        '[' => ']',
+                        _ => '}',
+                    },
+                    &token,
+                )?;
+                Ok(inner)
+            }
+            TokenKind::Char('|') => {
+                self.next();
+                let integral_depth = std::mem::take(&mut self.integral_depth);
+                self.abs_depth += 1;
+                let inner = self.relation();
+                self.abs_depth -= 1;
+                self.integral_depth = integral_depth;
+                let inner = inner?;
+                self.close('|', &token)?;
+                Ok(self.pool.apply(Function::Abs, [inner]))
+            }
+            // Operators such as `\cdot` cannot start an operand, so one found here is missing its
+            // left operand rather than being an unknown macro
+            TokenKind::Command(ref name) if self.operand_at(0) => {
+                self.next();
+                self.command(name, token.span)
+            }
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+
+    fn command(&mut self, name: &str, span: Range<usize>) -> Result<AtomId, ParseError> {
+        if let Some(letter) = greek(name) {
+            return self.named_symbol(letter.to_string());
+        }
+
+        let func = match name {
+            "sin" => Some(Function::Sin),
+            "cos" => Some(Function::Cos),
+            "tan" => Some(Function::Tan),
+            "arcsin" => Some(Function::Asin),
+            "arccos" => Some(Function::Acos),
+            "arctan" => Some(Function::Atan),
+            "sinh" => Some(Function::Sinh),
+            "cosh" => Some(Function::Cosh),
+            "tanh" => Some(Function::Tanh),
+            "exp" => Some(Function::Exp),
+            "ln" => Some(Function::Ln),
+            "log" => Some(Function::Log),
+            "arg" => Some(Function::Arg),
+            "Re" => Some(Function::Re),
+            "Im" => Some(Function::Im),
+            _ => None,
+        };
+        if let Some(func) = func {
+            return self.function(func);
+        }
+
+        match name {
+            "infty" => Ok(self.symbol("∞")),
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.argument()?;
+                let denominator = self.argument()?;
+                Ok(self.pool.fraction(numerator, denominator))
+            }
+            "sqrt" => {
+                let index = match self.peek().kind == TokenKind::Char('[') {
+                    true => {
+                        let open = self.next();
+                        let index = self.group(|s| s.relation())?;
+                        self.close(']', &open)?;
+                        Some(index)
+                    }
+                    false => None,
+                };
+                let radicand = self.argument()?;
+                Ok(match index {
+                    Some(index) => {
+                        let one = self.pool.integer(1);
+                        let exponent = self.pool.fraction(one, index);
+                        self.pool.power(radicand, exponent)
+                    }
+                    None => self.pool.apply(Function::Sqrt, [radicand]),
+                })
+            }
+            "left" => self.delimited(span),
+            "overline" => {
+                let inner = self.argument()?;
+                Ok(self.pool.apply(Function::Conj, [inner]))
+            }
+            "operatorname" => {
+                let name = self.text_group()?;
+                let func = match name.as_str() {
+                    "Re" => Some(Function::Re),
+                    "Im" => Some(Function::Im),
+                    name => Function::builtin(name),
+                };
+                match func {
+                    Some(func) => self.function(func),
+                    None => {
+                        let func = Function::User(self.symbols.intern(&name));
+                        self.function(func)
+                    }
+                }
+            }
+            "mathrm" | "text" | "mathit" | "mathsf" => {
+                let name = self.text_group()?;
+                self.named_symbol(name)
+            }
+            "int" => self.integral(span),
+            "sum" => self.big_operator(BigOperator::Sum, span),
+            "prod" => self.big_operator(BigOperator::Product, span),
+            "lim" => self.limit(),
+            "begin" => self.environment(span),
+            other => Err(ParseError {
+                kind: ParseErrorKind::UnsupportedMacro(other.to_owned()),
+                span,
+            }),
+        }
+    }
+
+    /// Parse the remainder of a matrix environment such as `\begin{pmatrix} a & b \\ c & d
+    /// \end{pmatrix}`, whose entries are separated by `&` and rows by `\\`
+    fn environment(&mut self, span: Range<usize>) -> Result<AtomId, ParseError> {
+        let name = self.text_group()?;
+        if !matches!(
+            name.as_str(),
+            "matrix" | "pmatrix" | "bmatrix" | "Bmatrix" | "smallmatrix"
+        ) {
+            return Err(ParseError {
+                kind: ParseErrorKind::UnsupportedMacro(format!("begin{{{name}}}")),
+                span: span.start..self.tokens[self.pos - 1].span.end,
+            });
+        }
+
+        let mut rows = vec![Vec::new()];
+        loop {
+            let entry = self.group(|s| s.relation())?;
+            rows.last_mut().expect("There is always a row").push(entry);
+            if self.eat(&TokenKind::Char('&')) {
+                continue;
+            }
+            if self.eat_command("\\") {
+                // A line break directly before `\end` does not start another row
+                if self.eat_command("end") {
+                    break;
+                }
+                rows.push(Vec::new());
+                continue;
+            }
+            if self.eat_command("end") {
+                break;
+            }
+            return Err(self.unexpected("'&', '\\\\' or '\\end'"));
+        }
+
+        let start = self.peek().span.start;
+        let end_name = self.text_group()?;
+        if end_name != name {
+            return Err(ParseError {
+                kind: ParseErrorKind::Unexpected {
+                    expected: "a matching '\\end'",
+                    found: format!("'\\end{{{end_name}}}'"),
+                },
+                span: start..self.tokens[self.pos - 1].span.end,
+            });
+        }
+        let end = self.tokens[self.pos - 1].span.end;
+        self.pool.matrix(rows).ok_or(ParseError {
+            kind: ParseErrorKind::RaggedMatrix,
+            span: span.start..end,
+        })
+    }
+
+    /// Parse the remainder of a group opened with `\left`
+    fn delimited(&mut self, span: Range<usize>) -> Result<AtomId, ParseError> {
+        let open = self.next();
+        let close = match open.kind {
+            TokenKind::Char('(') => ')',
+            TokenKind::Char('[') => ']',
+            TokenKind::Char('|') => '|',
+            TokenKind::Char('.') => '.',
+            TokenKind::Command(ref c) if c == "{" => '}',
+            TokenKind::Command(ref c) if c == "|" => '|',
+            _ => {
+                self.pos -= 1;
+                return Err(self.unexpected("a delimiter after '\\left'"));
+            }
+        };
+
+        let inner = self.group(|s| s.relation())?;
+
+        if !self.eat_command("right") {
+            return Err(match self.peek().kind {
+                TokenKind::Eof => ParseError {
+                    kind: ParseErrorKind::Unclosed(match close {
+                        ')' => '(',
+                        ']' => '[',
+                        '}' => '{',
+                        other => other,
+                    }),
+                    span: span.start..open.span.end,
+                },
+                _ => self.unexpected("'\\right'"),
+            });
+        }
+
+        let delimiter = self.next();
+        let matches = match delimiter.kind {
+            TokenKind::Char(c) => c == close || c == '.',
+            TokenKind::Command(ref c) => (c == "}" && close == '}') || (c == "|" && close == '|'),
+            _ => false,
+        };
+        if !matches {
+            self.pos -= 1;
+            return Err(self.unexpected("a matching delimiter after '\\right'"));
+        }
+
+        Ok(match close {
+            '|' => self.pool.apply(Function::Abs, [inner]),
+            _ => inner,
+        })
+    }
+
+    /// Parse the argument of a builtin or user function, optionally raised to a power as in
+    /// `\sin^2 x`. An exponent of `-1` denotes the inverse function as in `\cos^{-1} x`, and a
+    /// logarithm may be given a base as in `\log_2 x`
+    fn function(&mut self, func: Function) -> Result<AtomId, ParseError> {
+        let mut base = None;
+        let mut exponent = None;
+        loop {
+            match self.peek().kind {
+                TokenKind::Char('_') if func == Function::Log && base.is_none() => {
+                    self.next();
+                    base = Some(self.argument()?);
+                }
+                TokenKind::Char('^') if exponent.is_none() => {
+                    self.next();
+                    exponent = Some(self.argument()?);
+                }
+                _ => break,
+            }
+        }
+        let inverse = exponent.is_some_and(|e| self.pool.get(e).is_minus_one())
+            && matches!(
+                func,
+                Function::Sin
+                    | Function::Cos
+                    | Function::Tan
+                    | Function::Sinh
+                    | Function::Cosh
+                    | Function::Tanh
+            );
+
+        let parenthesized = match &self.peek().kind {
+            TokenKind::Char('(') => true,
+            TokenKind::Command(c) => c == "left" && self.peek_nth(1) == &TokenKind::Char('('),
+            _ => false,
+        };
+
+        let args = match parenthesized {
+            true => {
+                let left = self.eat_command("left");
+                let open = self.next();
+                let mut args = vec![self.group(|s| s.relation())?];
+                while self.eat(&TokenKind::Char(',')) {
+                    args.push(self.group(|s| s.relation())?);
+                }
+                if left && !self.eat_command("right") {
+                    return Err(self.unexpected("'\\right'"));
+                }
+                self.close(')', &open)?;
+                args
+            }
+            false => vec![self.power()?],
+        };
+
+        let applied = match (inverse, base) {
+            (true, _) => self.inverse(func, args),
+            (false, Some(base)) => {
+                let numerator = self.pool.apply(Function::Ln, args);
+                let denominator = self.pool.apply(Function::Ln, [base]);
+                self.pool.fraction(numerator, denominator)
+            }
+            (false, None) => self.pool.apply(func, args),
+        };
+        Ok(match exponent {
+            Some(exponent) if !inverse => self.pool.power(applied, exponent),
+            _ => applied,
+        })
+    }
+
+    /// Apply the inverse of a trigonometric or hyperbolic function, writing the inverse hyperbolic
+    /// functions in terms of logarithms since they have no [Function] of their own
+    fn inverse(&mut self, func: Function, args: Vec<AtomId>) -> AtomId {
+        let inverse = match func {
+            Function::Sin => Function::Asin,
+            Function::Cos => Function::Acos,
+            Function::Tan => Function::Atan,
+            _ => {
+                let x = self.pool.product(args);
+                let one = self.pool.integer(1);
+                let two = self.pool.integer(2);
+                let arg = match func {
+                    // ln(x + sqrt(x^2 ± 1))
+                    Function::Sinh | Function::Cosh => {
+                        let square = self.pool.power(x, two);
+                        let one = match func {
+                            Function::Sinh => one,
+                            _ => self.pool.integer(-1),
+                        };
+                        let radicand = self.pool.sum([square, one]);
+                        let root = self.pool.apply(Function::Sqrt, [radicand]);
+                        self.pool.sum([x, root])
+                    }
+                    // ln((1 + x)/(1 - x))/2
+                    _ => {
+                        let numerator = self.pool.sum([one, x]);
+                        let minus_x = self.pool.neg(x);
+                        let denominator = self.pool.sum([one, minus_x]);
+                        self.pool.fraction(numerator, denominator)
+                    }
+                };
+                let ln = self.pool.apply(Function::Ln, [arg]);
+                return match func {
+                    Function::Tanh => self.pool.fraction(ln, two),
+                    _ => ln,
+                };
+            }
+        };
+        self.pool.apply(inverse, args)
+    }
+
+    fn integral(&mut self, span: Range<usize>) -> Result<AtomId, ParseError> {
+        let mut lower = None;
+        let mut upper = None;
+        loop {
+            match self.peek().kind {
+                TokenKind::Char('_') if lower.is_none() => {
+                    self.next();
+                    lower = Some(self.argument()?);
+                }
+                TokenKind::Char('^') if upper.is_none() => {
+                    self.next();
+                    upper = Some(self.argument()?);
+                }
+                _ => break,
+            }
+        }
+
+        self.integral_depth += 1;
+        let integrand = match self.differential() {
+            Some(_) => Ok(self.pool.integer(1)),
+            None => self.additive(),
+        };
+        let differential = self.differential();
+        self.integral_depth -= 1;
+        let integrand = integrand?;
+
+        let Some((variable, len)) = differential else {
+            return Err(ParseError {
+                kind: ParseErrorKind::ExpectedDifferential,
+                span: span.start..self.peek().span.start.max(span.end),
+            });
+        };
+        self.pos += len;
+
+        let variable = self.symbols.intern(&variable.to_string());
+        Ok(self.pool.insert(Atom::Integral {
+            variable,
+            upper,
+            lower,
+            integrand,
+        }))
+    }
+
+    /// Parse the remainder of a sum or product such as `\sum_{k=1}^n k^2`, whose term extends to
+    /// the next `+` or `-`
+    fn big_operator(&mut self, op: BigOperator, span: Range<usize>) -> Result<AtomId, ParseError> {
+        self.eat_command("limits");
+        let mut range = None;
+        let mut upper = None;
+        loop {
+            match self.peek().kind {
+                TokenKind::Char('_') if range.is_none() => {
+                    self.next();
+                    let start = self.peek().span.start;
+                    let lower = self.argument()?;
+                    let Atom::Equals { lhs, rhs } = *self.pool.get(lower) else {
+                        return Err(self.expected_index(start));
+                    };
+                    let Atom::Symbol(index) = *self.pool.get(lhs) else {
+                        return Err(self.expected_index(start));
+                    };
+                    range = Some((index, rhs));
+                }
+                TokenKind::Char('^') if upper.is_none() => {
+                    self.next();
+                    upper = Some(self.argument()?);
+                }
+                _ => break,
+            }
+        }
+
+        let (Some((index, lower)), Some(upper)) = (range, upper) else {
+            return Err(ParseError {
+                kind: ParseErrorKind::Unexpected {
+                    expected: "a range such as '_{k=1}^n'",
+                    found: self.peek().kind.to_string(),
+                },
+                span: span.start..self.peek().span.start.max(span.end),
+            });
+        };
+
+        let term = self.multiplicative()?;
+        Ok(self.pool.big_operator(op, index, lower, upper, term))
+    }
+
+    /// Get the error for a subscript starting at `start` that is not an index range such as `k=1`
+    fn expected_index(&self, start: usize) -> ParseError {
+        ParseError {
+            kind: ParseErrorKind::Unexpected {
+                expected: "an index range such as 'k=1'",
+                found: "another expression".to_owned(),
+            },
+            span: start..self.tokens[self.pos - 1].span.end,
+        }
+    }
+
+    /// Parse the remainder of a limit such as `\lim_{x \to 0^+} f(x)`, whose operand extends to
+    /// the next `+` or `-`
+    fn limit(&mut self) -> Result<AtomId, ParseError> {
+        self.eat_command("limits");
+        if !self.eat(&TokenKind::Char('_')) {
+            return Err(self.unexpected("'_' followed by the limit point"));
+        }
+        let open = self.expect(&TokenKind::Char('{'), "'{'")?;
+
+        let variable = match &self.peek().kind {
+            TokenKind::Letter(v) => Some(*v),
+            TokenKind::Command(c) => greek(c),
+            _ => None,
+        };
+        let Some(variable) = variable else {
+            return Err(self.unexpected("the variable of the limit"));
+        };
+        self.next();
+        if !(self.eat_command("to") || self.eat_command("rightarrow")) {
+            return Err(self.unexpected("'\\to'"));
+        }
+
+        let direction = self.limit_direction();
+        let point = self.group(|s| s.additive())?;
+        self.close('}', &open)?;
+
+        let inner = self.multiplicative()?;
+        let variable = self.symbols.intern(&variable.to_string());
+        Ok(self.pool.insert(Atom::Limit {
+            variable,
+            point,
+            direction,
+            inner,
+        }))
+    }
+
+    /// Remove a trailing `^+` or `^-` from the subscript of a limit being parsed, as in
+    /// `\lim_{x \to 0^+}`, and get the direction it gives
+    fn limit_direction(&mut self) -> Option<Direction> {
+        let mut depth = 0usize;
+        let end = (self.pos..self.tokens.len()).find(|&i| match self.tokens[i].kind {
+            TokenKind::Char('{') => {
+                depth += 1;
+                false
+            }
+            TokenKind::Char('}') if depth == 0 => true,
+            TokenKind::Char('}') => {
+                depth -= 1;
+                false
+            }
+            _ => false,
+        })?;
+
+        let (sign, len) = match &self.tokens[self.pos..end] {
+            [.., Token {
+                kind: TokenKind::Char('^'),
+                ..
+            }, Token {
+                kind: TokenKind::Char(sign),
+                ..
+            }] => (*sign, 2),
+            [.., Token {
+                kind: TokenKind::Char('^'),
+                ..
+            }, Token {
+                kind: TokenKind::Char('{'),
+                ..
+            }, Token {
+                kind: TokenKind::Char(sign),
+                ..
+            }, Token {
+                kind: TokenKind::Char('}'),
+                ..
+            }] => (*sign, 4),
+            _ => return None,
+        };
+        let direction = match sign {
+            '+' => Direction::Above,
+            '-' => Direction::Below,
+            _ => return None,
+        };
+
+        self.tokens.drain(end - len..end);
+        Some(direction)
+    }
+}
+
+/// Get the Greek letter named by the given macro, including variant forms such as `\varphi`
+fn greek(name: &str) -> Option<char> {
+    symbol::greek_letter(name).or_else(|| symbol::greek_letter(name.strip_prefix("var")?))
+}
+
+impl std::fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Command(c) => write!(f, "'\\{c}'"),
+            Self::Letter(c) | Self::Char(c) => write!(f, "'{c}'"),
+            Self::Number(n) => write!(f, "number '{n}'"),
+            Self::Param(n) => write!(f, "'#{n}'"),
+            Self::Eof => write!(f, "end of input"),
+        }
+    }
+}
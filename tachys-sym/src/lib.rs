@@ -1 +1,25 @@
-mod atom;
+pub mod assume;
+pub mod atom;
+pub mod diff;
+pub mod egraph;
+pub mod eval;
+pub mod integrate;
+pub mod latex;
+pub mod limit;
+pub mod mathml;
+pub mod matrix;
+pub mod parse;
+pub mod poly;
+pub mod print;
+pub mod quadrature;
+pub mod rewrite;
+pub mod series;
+pub mod simplify;
+pub mod solve;
+pub mod summation;
+pub mod units;
+
+pub use atom::{
+    symbol::{SharedSymbolStore, Symbol, SymbolStore},
+    Atom, AtomId, BigOperator, Direction, ExprPool, Function, Relation,
+};
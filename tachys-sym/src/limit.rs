@@ -0,0 +1,556 @@
+use crate::{
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, Direction, ExprPool, Function, Rational,
+    },
+    eval::Env,
+    series::Point,
+};
+
+/// Deepest nesting of applications of L'Hôpital's rule attempted before giving up
+const MAX_DEPTH: usize = 16;
+
+/// Error produced when a limit does not exist or cannot be found, with the ID of the
+/// sub-expression that caused it
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("{kind}")]
+pub struct LimitError {
+    pub kind: LimitErrorKind,
+    pub atom: AtomId,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum LimitErrorKind {
+    #[error("Limit point must not depend on the variable")]
+    Point,
+    #[error("Limit does not exist, since the expression oscillates")]
+    Oscillates,
+    #[error("Limit does not exist, since the limits from below and above differ")]
+    Sides,
+    #[error("Cannot find the limit")]
+    Unknown,
+}
+
+/// Limit of an expression as the variable grows without bound
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Value {
+    Finite(AtomId),
+    Infinite {
+        negative: bool,
+    },
+    /// Stays within finite bounds without converging, as `sin(x)` does
+    Bounded,
+}
+
+impl ExprPool {
+    /// Find the limit of the expression rooted at `id` as `variable` approaches `point`, which
+    /// may be `∞` or `-∞`. The limit is taken from one side if `direction` is given, which has
+    /// no effect at infinity.
+    ///
+    /// The limit is read off the leading term of a series expansion where one exists. Otherwise
+    /// the limits of subexpressions are combined, resolving the indeterminate forms `0/0`, `∞/∞`
+    /// and `0·∞` by L'Hôpital's rule. An infinite limit is `∞` or `-∞`, and limits that do not
+    /// exist because the expression oscillates or its one-sided limits differ are reported as
+    /// errors
+    pub fn limit(
+        &mut self,
+        symbols: &mut SymbolStore,
+        id: AtomId,
+        variable: Symbol,
+        point: AtomId,
+        direction: Option<Direction>,
+    ) -> Result<AtomId, LimitError> {
+        let point = Point::of(self, symbols, point, variable).map_err(|e| LimitError {
+            kind: LimitErrorKind::Point,
+            atom: e.atom,
+        })?;
+
+        // Every limit is found as `x → ∞` after substituting `x = -x` or `x = a ± 1/x`
+        let x = self.symbol(variable);
+        let sides = match (point, direction) {
+            (Point::Infinity { negative: false }, _) => vec![id],
+            (Point::Infinity { negative: true }, _) => {
+                let minus_x = self.neg(x);
+                vec![self.substitute(id, x, minus_x)]
+            }
+            (Point::Finite(a), direction) => {
+                let directions = match direction {
+                    Some(direction) => vec![direction],
+                    None => vec![Direction::Below, Direction::Above],
+                };
+                let one = self.integer(1);
+                let reciprocal = self.fraction(one, x);
+                directions
+                    .into_iter()
+                    .map(|direction| {
+                        let offset = match direction {
+                            Direction::Below => self.neg(reciprocal),
+                            Direction::Above => reciprocal,
+                        };
+                        let approach = self.sum([a, offset]);
+                        self.substitute(id, x, approach)
+                    })
+                    .collect()
+            }
+        };
+
+        let mut limiter = Limiter {
+            pool: self,
+            symbols,
+            variable,
+            depth: 0,
+        };
+        let mut values = Vec::with_capacity(sides.len());
+        for side in sides {
+            values.push(limiter.value(side)?);
+        }
+        if values.windows(2).any(|pair| pair[0] != pair[1]) {
+            return Err(LimitError {
+                kind: LimitErrorKind::Sides,
+                atom: id,
+            });
+        }
+
+        Ok(match values[0] {
+            Value::Finite(value) => value,
+            Value::Infinite { negative } => {
+                let infinity = limiter.infinity();
+                match negative {
+                    true => {
+                        let minus_one = limiter.pool.integer(-1);
+                        limiter.pool.product([minus_one, infinity])
+                    }
+                    false => infinity,
+                }
+            }
+            Value::Bounded => {
+                return Err(LimitError {
+                    kind: LimitErrorKind::Oscillates,
+                    atom: id,
+                })
+            }
+        })
+    }
+
+    /// Evaluate every [Atom::Limit] in the expression rooted at `id` that can be found, leaving
+    /// the others in place
+    pub fn eval_limits(&mut self, symbols: &mut SymbolStore, id: AtomId) -> AtomId {
+        let atom = self.get(id).clone();
+        let atom = atom.map_children(|child| self.eval_limits(symbols, child));
+        let Atom::Limit {
+            variable,
+            point,
+            direction,
+            inner,
+        } = atom
+        else {
+            return self.insert(atom);
+        };
+
+        match self.limit(symbols, inner, variable, point, direction) {
+            Ok(value) => value,
+            Err(_) => self.insert(atom),
+        }
+    }
+}
+
+/// State of a single limit as the variable grows without bound
+struct Limiter<'a> {
+    pool: &'a mut ExprPool,
+    symbols: &'a mut SymbolStore,
+    variable: Symbol,
+    depth: usize,
+}
+
+impl Limiter<'_> {
+    fn depends(&self, id: AtomId) -> bool {
+        self.pool.depends_on(id, self.variable)
+    }
+
+    fn infinity(&mut self) -> AtomId {
+        let infinity = self.symbols.intern("∞");
+        self.pool.symbol(infinity)
+    }
+
+    fn unknown(id: AtomId) -> LimitError {
+        LimitError {
+            kind: LimitErrorKind::Unknown,
+            atom: id,
+        }
+    }
+
+    /// Get the sign of a constant if it evaluates to a nonzero number
+    fn sign(&self, id: AtomId) -> Option<bool> {
+        match self.pool.eval(id, self.symbols, &Env::new()) {
+            Ok(value) if value != 0. && !value.is_nan() => Some(value < 0.),
+            _ => None,
+        }
+    }
+
+    /// Get the leading coefficient and exponent of the series of an expression in `1/x`
+    fn leading_term(&mut self, id: AtomId) -> Option<(AtomId, i32)> {
+        let point = Point::Infinity { negative: false };
+        let series = self
+            .pool
+            .local_series(self.symbols, id, self.variable, point, 1)
+            .ok()?;
+        match series.coefficients.first() {
+            Some(first) => Some((*first, series.valuation)),
+            // Every term below `x^0` is known to vanish
+            None if series.precision.is_none_or(|p| p >= 1) => {
+                Some((self.pool.integer(0), i32::MAX))
+            }
+            None => None,
+        }
+    }
+
+    /// Get whether an expression is negative for large enough `x`, from its leading term or
+    /// else from functions that are never negative
+    fn sign_near(&mut self, id: AtomId) -> Option<bool> {
+        if let Some((coefficient, _)) = self.leading_term(id) {
+            return self.sign(coefficient);
+        }
+        match self.pool.get(id) {
+            Atom::Apply {
+                func: Function::Exp | Function::Cosh | Function::Abs,
+                ..
+            } => Some(false),
+            _ => None,
+        }
+    }
+
+    fn value(&mut self, id: AtomId) -> Result<Value, LimitError> {
+        if !self.depends(id) {
+            return Ok(Value::Finite(self.pool.simplify(id)));
+        }
+
+        if let Some((coefficient, exponent)) = self.leading_term(id) {
+            match exponent {
+                1.. => return Ok(Value::Finite(self.pool.integer(0))),
+                0 => return Ok(Value::Finite(coefficient)),
+                _ => {
+                    if let Some(negative) = self.sign(coefficient) {
+                        return Ok(Value::Infinite { negative });
+                    }
+                }
+            }
+        }
+
+        let normal = self.pool.normal_form(id);
+        self.combine(normal)
+    }
+
+    /// Find a limit from the limits of the children of an expression in normal form
+    fn combine(&mut self, id: AtomId) -> Result<Value, LimitError> {
+        match self.pool.get(id).clone() {
+            Atom::Symbol(_) => Ok(Value::Infinite { negative: false }),
+            Atom::Sum(terms) => {
+                let mut sum = Value::Finite(self.pool.integer(0));
+                for term in terms.iter() {
+                    sum = match (sum, self.value(*term)?) {
+                        (Value::Finite(a), Value::Finite(b)) => {
+                            let sum = self.pool.sum([a, b]);
+                            Value::Finite(self.pool.simplify(sum))
+                        }
+                        (Value::Infinite { negative: a }, Value::Infinite { negative: b })
+                            if a != b =>
+                        {
+                            return Err(Self::unknown(id))
+                        }
+                        (infinite @ Value::Infinite { .. }, _)
+                        | (_, infinite @ Value::Infinite { .. }) => infinite,
+                        _ => Value::Bounded,
+                    };
+                }
+                Ok(sum)
+            }
+            Atom::Product(factors) => {
+                // Factors with negative exponents form the denominator of a quotient
+                let (denominator, numerator): (Vec<_>, Vec<_>) =
+                    factors
+                        .iter()
+                        .partition(|factor| match self.pool.get(**factor) {
+                            Atom::Power { exponent, .. } => self
+                                .pool
+                                .get(*exponent)
+                                .to_rational()
+                                .is_some_and(|e| e.is_negative()),
+                            _ => false,
+                        });
+                if denominator.is_empty() {
+                    return self.product(id, &numerator);
+                }
+
+                let denominator = denominator
+                    .into_iter()
+                    .map(|factor| {
+                        let minus_one = self.pool.integer(-1);
+                        let reciprocal = self.pool.power(factor, minus_one);
+                        self.pool.simplify(reciprocal)
+                    })
+                    .collect::<Vec<_>>();
+                let numerator = self.pool.product(numerator);
+                let denominator = self.pool.product(denominator);
+                self.quotient(id, numerator, denominator)
+            }
+            Atom::Power { base, exponent } if self.depends(exponent) => {
+                // `b^e = exp(e ln(b))`
+                let exponent = match self.pool.get(base) {
+                    Atom::Symbol(sym) if self.symbols.name(*sym) == "e" => exponent,
+                    _ => {
+                        let ln = self.pool.apply(Function::Ln, [base]);
+                        self.pool.product([exponent, ln])
+                    }
+                };
+                let exponent = self.value(exponent)?;
+                self.apply(id, Function::Exp, exponent)
+            }
+            Atom::Power { base, exponent } => {
+                let base_value = self.value(base)?;
+                self.power(id, base, base_value, exponent)
+            }
+            Atom::Apply { func, args } if args.len() == 1 => {
+                let arg = self.value(args[0])?;
+                self.apply(id, func, arg)
+            }
+            _ => Err(Self::unknown(id)),
+        }
+    }
+
+    fn product(&mut self, id: AtomId, factors: &[AtomId]) -> Result<Value, LimitError> {
+        let mut zeros = Vec::new();
+        let mut infinities = Vec::new();
+        let mut finite = Vec::new();
+        let mut negative = false;
+        let mut bounded = false;
+        for factor in factors {
+            match self.value(*factor)? {
+                Value::Finite(value) if self.pool.get(value).is_zero() => zeros.push(*factor),
+                Value::Finite(value) => finite.push(value),
+                Value::Infinite { negative: sign } => {
+                    infinities.push(*factor);
+                    negative ^= sign;
+                }
+                Value::Bounded => bounded = true,
+            }
+        }
+
+        // Resolve `0·∞` as the quotient `∞/(1/0)`
+        if !zeros.is_empty() && !infinities.is_empty() {
+            let infinite = self.pool.product(infinities);
+            let zero = self.pool.product(zeros);
+            let minus_one = self.pool.integer(-1);
+            let reciprocal = self.pool.power(zero, minus_one);
+            let reciprocal = self.pool.simplify(reciprocal);
+            let quotient = self.quotient(id, infinite, reciprocal)?;
+            return match (quotient, bounded) {
+                (Value::Finite(value), false) => {
+                    let product = self.pool.product(finite.into_iter().chain([value]));
+                    Ok(Value::Finite(self.pool.simplify(product)))
+                }
+                (Value::Finite(value), true) if self.pool.get(value).is_zero() => {
+                    Ok(Value::Finite(value))
+                }
+                (Value::Infinite { negative }, false) => {
+                    let product = self.pool.product(finite);
+                    let sign = self.sign(product).ok_or(Self::unknown(id))?;
+                    Ok(Value::Infinite {
+                        negative: sign ^ negative,
+                    })
+                }
+                _ => Err(Self::unknown(id)),
+            };
+        }
+
+        let product = self.pool.product(finite);
+        let product = self.pool.simplify(product);
+        match (zeros.is_empty(), infinities.is_empty(), bounded) {
+            (false, _, _) => Ok(Value::Finite(self.pool.integer(0))),
+            (true, false, false) => {
+                let sign = self.sign(product).ok_or(Self::unknown(id))?;
+                Ok(Value::Infinite {
+                    negative: sign ^ negative,
+                })
+            }
+            (true, false, true) => Err(Self::unknown(id)),
+            (true, true, true) => Ok(Value::Bounded),
+            (true, true, false) => Ok(Value::Finite(product)),
+        }
+    }
+
+    fn quotient(
+        &mut self,
+        id: AtomId,
+        numerator: AtomId,
+        denominator: AtomId,
+    ) -> Result<Value, LimitError> {
+        let zero = |pool: &ExprPool, value: &Value| match value {
+            Value::Finite(value) => pool.get(*value).is_zero(),
+            _ => false,
+        };
+
+        let top = self.value(numerator)?;
+        let bottom = self.value(denominator)?;
+        match (top, bottom) {
+            _ if zero(self.pool, &top) && zero(self.pool, &bottom) => {
+                self.lhopital(id, numerator, denominator)
+            }
+            (Value::Infinite { .. }, Value::Infinite { .. }) => {
+                self.lhopital(id, numerator, denominator)
+            }
+            (Value::Finite(_) | Value::Bounded, Value::Infinite { .. }) => {
+                Ok(Value::Finite(self.pool.integer(0)))
+            }
+            // Division by a vanishing denominator grows without bound
+            (Value::Finite(n), Value::Finite(_)) if zero(self.pool, &bottom) => {
+                let sign = self.sign(n).ok_or(Self::unknown(id))?;
+                let side = self.sign_near(denominator).ok_or(Self::unknown(id))?;
+                Ok(Value::Infinite {
+                    negative: sign ^ side,
+                })
+            }
+            (Value::Infinite { negative }, Value::Finite(_)) if zero(self.pool, &bottom) => {
+                let side = self.sign_near(denominator).ok_or(Self::unknown(id))?;
+                Ok(Value::Infinite {
+                    negative: negative ^ side,
+                })
+            }
+            (Value::Finite(n), Value::Finite(d)) => {
+                let quotient = self.pool.fraction(n, d);
+                Ok(Value::Finite(self.pool.simplify(quotient)))
+            }
+            (Value::Infinite { negative }, Value::Finite(d)) => {
+                let sign = self.sign(d).ok_or(Self::unknown(id))?;
+                Ok(Value::Infinite {
+                    negative: negative ^ sign,
+                })
+            }
+            (Value::Bounded, Value::Finite(_)) => Ok(Value::Bounded),
+            _ => Err(Self::unknown(id)),
+        }
+    }
+
+    /// Resolve `0/0` or `∞/∞` as the limit of the quotient of derivatives
+    fn lhopital(
+        &mut self,
+        id: AtomId,
+        numerator: AtomId,
+        denominator: AtomId,
+    ) -> Result<Value, LimitError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Self::unknown(id));
+        }
+
+        let numerator = self.pool.diff(numerator, self.variable);
+        let denominator = self.pool.diff(denominator, self.variable);
+        let quotient = self.pool.fraction(numerator, denominator);
+        let quotient = self.pool.simplify(quotient);
+
+        self.depth += 1;
+        let value = self.value(quotient);
+        self.depth -= 1;
+        value
+    }
+
+    fn power(
+        &mut self,
+        id: AtomId,
+        base: AtomId,
+        value: Value,
+        exponent: AtomId,
+    ) -> Result<Value, LimitError> {
+        let exponent = self.pool.simplify(exponent);
+        let Some(r) = self.pool.get(exponent).to_rational() else {
+            return match value {
+                Value::Finite(b) if !self.pool.get(b).is_zero() => {
+                    let power = self.pool.power(b, exponent);
+                    Ok(Value::Finite(self.pool.simplify(power)))
+                }
+                _ => Err(Self::unknown(id)),
+            };
+        };
+        let odd = r.is_integer() && r.numerator().bit(0);
+
+        Ok(match value {
+            Value::Finite(b) if self.pool.get(b).is_zero() => match r.is_negative() {
+                false => Value::Finite(b),
+                true => {
+                    let negative = match odd {
+                        true => self.sign_near(base).ok_or(Self::unknown(id))?,
+                        false => false,
+                    };
+                    Value::Infinite { negative }
+                }
+            },
+            Value::Finite(b) => {
+                let power = self.pool.power(b, exponent);
+                Value::Finite(self.pool.simplify(power))
+            }
+            Value::Infinite { .. } if r.is_negative() => Value::Finite(self.pool.integer(0)),
+            Value::Infinite { negative } if !negative || r.is_integer() => Value::Infinite {
+                negative: negative && odd,
+            },
+            Value::Bounded if !r.is_negative() && r.is_integer() => Value::Bounded,
+            _ => return Err(Self::unknown(id)),
+        })
+    }
+
+    fn apply(&mut self, id: AtomId, func: Function, value: Value) -> Result<Value, LimitError> {
+        let half = |pool: &mut ExprPool, pi: AtomId, negative: bool| {
+            let half = Rational::new(if negative { -1 } else { 1 }, 2).expect("Nonzero");
+            let half = pool.number(half);
+            let product = pool.product([half, pi]);
+            pool.simplify(product)
+        };
+
+        Ok(match (func, value) {
+            (Function::Ln | Function::Log, Value::Finite(c)) if self.pool.get(c).is_zero() => {
+                Value::Infinite { negative: true }
+            }
+            (func, Value::Finite(c)) => {
+                let applied = self.pool.apply(func, [c]);
+                let applied = self.pool.simplify(applied);
+                match self.pool.eval(applied, self.symbols, &Env::new()) {
+                    Ok(value) if !value.is_finite() => return Err(Self::unknown(id)),
+                    _ => Value::Finite(applied),
+                }
+            }
+            (Function::Exp, Value::Infinite { negative: true }) => {
+                Value::Finite(self.pool.integer(0))
+            }
+            (
+                Function::Exp | Function::Ln | Function::Log | Function::Sqrt,
+                Value::Infinite { negative: false },
+            )
+            | (Function::Cosh | Function::Abs, Value::Infinite { .. }) => {
+                Value::Infinite { negative: false }
+            }
+            (Function::Sinh, infinite @ Value::Infinite { .. }) => infinite,
+            (Function::Atan, Value::Infinite { negative }) => {
+                let pi = self.symbols.intern("π");
+                let pi = self.pool.symbol(pi);
+                Value::Finite(half(self.pool, pi, negative))
+            }
+            (Function::Tanh, Value::Infinite { negative }) => {
+                Value::Finite(self.pool.integer(if negative { -1 } else { 1 }))
+            }
+            (Function::Sin | Function::Cos, Value::Infinite { .. }) => Value::Bounded,
+            (Function::Tan, Value::Infinite { .. }) => {
+                return Err(LimitError {
+                    kind: LimitErrorKind::Oscillates,
+                    atom: id,
+                })
+            }
+            (
+                Function::Sin
+                | Function::Cos
+                | Function::Atan
+                | Function::Tanh
+                | Function::Exp
+                | Function::Abs,
+                Value::Bounded,
+            ) => Value::Bounded,
+            _ => return Err(Self::unknown(id)),
+        })
+    }
+}
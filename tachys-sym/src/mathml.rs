@@ -0,0 +1,778 @@
+use std::fmt::{self, Write};
+
+use num_traits::{One, Signed};
+
+use crate::atom::{
+    symbol::{self, SymbolStore},
+    Atom, AtomId, BigOperator, Direction, ExprPool, Function, Relation,
+};
+
+/// [Display](fmt::Display) adapter rendering an expression as a MathML `<math>` element.
+///
+/// Presentation markup is written by default, describing how the expression is laid out.
+/// [MathMl::content] instead writes content markup describing what the expression means, so that
+/// it can be re-interpreted by other tools
+#[derive(Clone, Copy)]
+pub struct MathMl<'a> {
+    pool: &'a ExprPool,
+    symbols: &'a SymbolStore,
+    root: AtomId,
+    content: bool,
+}
+
+/// Binding strength of presented operators, where higher levels bind more tightly
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Prec {
+    Relation,
+    Sum,
+    Product,
+    Neg,
+    Power,
+    Atom,
+}
+
+/// Invisible operator placed between juxtaposed factors
+const INVISIBLE_TIMES: &str = "&#x2062;";
+/// Invisible operator placed between a function name and its arguments
+const FUNCTION_APPLICATION: &str = "&#x2061;";
+
+impl ExprPool {
+    /// Get a [Display](fmt::Display) adapter writing the expression rooted at `id` as MathML
+    pub fn mathml<'a>(&'a self, id: AtomId, symbols: &'a SymbolStore) -> MathMl<'a> {
+        MathMl {
+            pool: self,
+            symbols,
+            root: id,
+            content: false,
+        }
+    }
+}
+
+impl<'a> MathMl<'a> {
+    /// Render content markup instead of presentation markup
+    pub fn content(self) -> Self {
+        Self {
+            content: true,
+            ..self
+        }
+    }
+
+    fn atom(&self, id: AtomId) -> &'a Atom {
+        self.pool.get(id)
+    }
+
+    /// Check if the presented form of the given expression starts with a minus sign
+    fn leading_negative(&self, id: AtomId) -> bool {
+        match self.atom(id) {
+            Atom::Integer(i) => i.is_negative(),
+            Atom::Rational(r) => r.is_negative(),
+            Atom::Float(f) => f.value().is_sign_negative(),
+            Atom::Complex { re, im } => match self.atom(*re).is_zero() {
+                true => self.leading_negative(*im),
+                false => self.leading_negative(*re),
+            },
+            Atom::Neg(_) => true,
+            Atom::Product(factors) => self.leading_negative(factors[0]),
+            Atom::Sum(terms) => self.leading_negative(terms[0]),
+            _ => false,
+        }
+    }
+
+    fn prec(&self, id: AtomId) -> Prec {
+        let prec = match self.atom(id) {
+            Atom::Float(v) if format!("{:?}", v.value()).contains('e') => Prec::Product,
+            Atom::Integer(_)
+            | Atom::Rational(_)
+            | Atom::Float(_)
+            | Atom::Symbol(_)
+            | Atom::Unit(_)
+            | Atom::Apply { .. }
+            | Atom::Fraction { .. }
+            | Atom::Matrix { .. }
+            | Atom::Order(_) => Prec::Atom,
+            Atom::Product(_)
+            | Atom::Integral { .. }
+            | Atom::BigOperator { .. }
+            | Atom::Derivative { .. }
+            | Atom::Limit { .. } => Prec::Product,
+            Atom::Complex { re, im } => match (self.atom(*re).is_zero(), self.atom(*im)) {
+                (true, Atom::Integer(i)) if i.magnitude().is_one() => Prec::Atom,
+                (true, _) => Prec::Product,
+                (false, _) => Prec::Sum,
+            },
+            Atom::Sum(_) => Prec::Sum,
+            Atom::Power { .. } => Prec::Power,
+            Atom::Neg(_) => Prec::Neg,
+            Atom::Equals { .. } | Atom::Relation { .. } => Prec::Relation,
+        };
+
+        match self.leading_negative(id) {
+            true => prec.min(Prec::Neg),
+            false => prec,
+        }
+    }
+
+    fn present_prec(&self, f: &mut dyn Write, id: AtomId, min: Prec) -> fmt::Result {
+        match self.prec(id) < min {
+            true => {
+                f.write_str("<mrow><mo>(</mo>")?;
+                self.write_present(f, id)?;
+                f.write_str("<mo>)</mo></mrow>")
+            }
+            false => self.write_present(f, id),
+        }
+    }
+
+    /// Write a symbol name without subscript as an identifier, or a number if it is all digits
+    fn present_name(&self, f: &mut dyn Write, name: &str) -> fmt::Result {
+        if name.chars().all(|c| c.is_ascii_digit()) {
+            return write!(f, "<mn>{name}</mn>");
+        }
+
+        let name = match symbol::greek_letter(name) {
+            Some(letter) => letter.to_string(),
+            None => name.to_owned(),
+        };
+        f.write_str("<mi>")?;
+        write_escaped(f, &name)?;
+        f.write_str("</mi>")
+    }
+
+    fn present_symbol(&self, f: &mut dyn Write, name: &str) -> fmt::Result {
+        match symbol::split_subscript(name) {
+            (base, Some(sub)) => {
+                f.write_str("<msub>")?;
+                self.present_name(f, base)?;
+                self.present_name(f, sub)?;
+                f.write_str("</msub>")
+            }
+            (base, None) => self.present_name(f, base),
+        }
+    }
+
+    fn present_float(&self, f: &mut dyn Write, value: f64) -> fmt::Result {
+        let sign = match value.is_sign_negative() && !value.is_nan() {
+            true => "<mo>−</mo>",
+            false => "",
+        };
+
+        if value.is_infinite() {
+            return write!(f, "<mrow>{sign}<mi>∞</mi></mrow>");
+        }
+
+        let repr = format!("{:?}", value.abs());
+        match repr.split_once('e') {
+            Some((mantissa, exponent)) => write!(
+                f,
+                "<mrow>{sign}<mn>{mantissa}</mn><mo>×</mo><msup><mn>10</mn><mn>{exponent}</mn></msup></mrow>"
+            ),
+            None if sign.is_empty() => write!(f, "<mn>{repr}</mn>"),
+            None => write!(f, "<mrow>{sign}<mn>{repr}</mn></mrow>"),
+        }
+    }
+
+    fn present_sum(&self, f: &mut dyn Write, terms: &[AtomId]) -> fmt::Result {
+        f.write_str("<mrow>")?;
+        for (i, term) in terms.iter().copied().enumerate() {
+            if i == 0 {
+                match self.atom(term) {
+                    Atom::Sum(_) => self.present_prec(f, term, Prec::Neg)?,
+                    _ => self.present_prec(f, term, Prec::Product)?,
+                }
+                continue;
+            }
+
+            match self.atom(term) {
+                Atom::Neg(inner) => {
+                    f.write_str("<mo>−</mo>")?;
+                    self.present_prec(f, *inner, Prec::Product)?;
+                }
+                Atom::Integer(_) | Atom::Rational(_) | Atom::Float(_)
+                    if self.leading_negative(term) =>
+                {
+                    f.write_str("<mo>−</mo>")?;
+                    self.present_magnitude(f, term)?;
+                }
+                Atom::Complex { re, im }
+                    if self.atom(*re).is_zero() && self.leading_negative(term) =>
+                {
+                    f.write_str("<mo>−</mo>")?;
+                    self.present_imaginary(f, *im)?;
+                }
+                Atom::Product(factors) if self.negative_coefficient(factors[0]) => {
+                    f.write_str("<mo>−</mo><mrow>")?;
+                    let separate = match self.atom(factors[0]).is_minus_one() {
+                        true => false,
+                        false => {
+                            self.present_magnitude(f, factors[0])?;
+                            true
+                        }
+                    };
+                    self.present_factors(f, &factors[1..], separate)?;
+                    f.write_str("</mrow>")?;
+                }
+                _ => {
+                    f.write_str("<mo>+</mo>")?;
+                    self.present_prec(f, term, Prec::Product)?;
+                }
+            }
+        }
+        f.write_str("</mrow>")
+    }
+
+    /// Check if the given factor is a negative number or negation that may be written as the
+    /// sign of a term
+    fn negative_coefficient(&self, id: AtomId) -> bool {
+        match self.atom(id) {
+            Atom::Integer(_) | Atom::Rational(_) | Atom::Float(_) => self.leading_negative(id),
+            Atom::Complex { re, .. } => self.atom(*re).is_zero() && self.leading_negative(id),
+            Atom::Neg(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Write the absolute value of a number or the operand of a negation
+    fn present_magnitude(&self, f: &mut dyn Write, id: AtomId) -> fmt::Result {
+        match self.atom(id) {
+            Atom::Integer(i) => write!(f, "<mn>{}</mn>", i.magnitude()),
+            Atom::Rational(r) => write!(
+                f,
+                "<mfrac><mn>{}</mn><mn>{}</mn></mfrac>",
+                r.numerator().magnitude(),
+                r.denominator()
+            ),
+            Atom::Float(v) => self.present_float(f, v.value().abs()),
+            Atom::Complex { re, im } if self.atom(*re).is_zero() => {
+                f.write_str("<mrow>")?;
+                self.present_imaginary(f, *im)?;
+                f.write_str("</mrow>")
+            }
+            Atom::Neg(inner) => self.present_prec(f, *inner, Prec::Power),
+            _ => self.present_prec(f, id, Prec::Power),
+        }
+    }
+
+    /// Write the imaginary part of a complex literal without its sign, followed by `i`
+    fn present_imaginary(&self, f: &mut dyn Write, im: AtomId) -> fmt::Result {
+        match self.atom(im) {
+            Atom::Integer(i) if i.magnitude().is_one() => (),
+            atom if atom.is_number() => {
+                self.present_magnitude(f, im)?;
+                write!(f, "<mo>{INVISIBLE_TIMES}</mo>")?;
+            }
+            _ => {
+                self.present_prec(f, im, Prec::Power)?;
+                write!(f, "<mo>{INVISIBLE_TIMES}</mo>")?;
+            }
+        }
+        f.write_str("<mi>i</mi>")
+    }
+
+    /// Write a complex literal as in `3 - 2i`
+    fn present_complex(&self, f: &mut dyn Write, re: AtomId, im: AtomId) -> fmt::Result {
+        let negative = self.atom(im).is_number() && self.leading_negative(im);
+        f.write_str("<mrow>")?;
+        if !self.atom(re).is_zero() {
+            self.write_present(f, re)?;
+            match negative {
+                true => f.write_str("<mo>−</mo>")?,
+                false => f.write_str("<mo>+</mo>")?,
+            }
+        } else if negative {
+            f.write_str("<mo>−</mo>")?;
+        }
+        self.present_imaginary(f, im)?;
+        f.write_str("</mrow>")
+    }
+
+    /// Write the given factors of a product, each preceded by a multiplication operator if
+    /// `separate_first` is set or the factor is not the first
+    fn present_factors(
+        &self,
+        f: &mut dyn Write,
+        factors: &[AtomId],
+        separate_first: bool,
+    ) -> fmt::Result {
+        for (i, factor) in factors.iter().copied().enumerate() {
+            if i != 0 || separate_first {
+                match self.atom(factor).is_number() {
+                    true => f.write_str("<mo>·</mo>")?,
+                    false => write!(f, "<mo>{INVISIBLE_TIMES}</mo>")?,
+                }
+            }
+            self.present_prec(f, factor, Prec::Power)?;
+        }
+        Ok(())
+    }
+
+    fn present_product(&self, f: &mut dyn Write, factors: &[AtomId]) -> fmt::Result {
+        let Some((first, rest)) = factors.split_first() else {
+            return Ok(());
+        };
+
+        f.write_str("<mrow>")?;
+        match self.atom(*first) {
+            atom if atom.is_minus_one() && !rest.is_empty() => {
+                f.write_str("<mo>−</mo>")?;
+                self.present_factors(f, rest, false)?;
+            }
+            _ => {
+                self.present_prec(f, *first, Prec::Product)?;
+                self.present_factors(f, rest, true)?;
+            }
+        }
+        f.write_str("</mrow>")
+    }
+
+    fn present_apply(&self, f: &mut dyn Write, func: Function, args: &[AtomId]) -> fmt::Result {
+        match func {
+            Function::Sqrt => {
+                f.write_str("<msqrt>")?;
+                for arg in args {
+                    self.write_present(f, *arg)?;
+                }
+                return f.write_str("</msqrt>");
+            }
+            Function::Abs => {
+                f.write_str("<mrow><mo>|</mo>")?;
+                for arg in args {
+                    self.write_present(f, *arg)?;
+                }
+                return f.write_str("<mo>|</mo></mrow>");
+            }
+            Function::Conj => {
+                f.write_str("<mover accent=\"true\"><mrow>")?;
+                for arg in args {
+                    self.write_present(f, *arg)?;
+                }
+                return f.write_str("</mrow><mo>¯</mo></mover>");
+            }
+            _ => (),
+        }
+
+        f.write_str("<mrow>")?;
+        match func {
+            Function::User(sym) => self.present_symbol(f, self.symbols.name(sym))?,
+            Function::Re => f.write_str("<mi>Re</mi>")?,
+            Function::Im => f.write_str("<mi>Im</mi>")?,
+            func => write!(f, "<mi>{}</mi>", func.builtin_name().unwrap_or_default())?,
+        }
+        write!(f, "<mo>{FUNCTION_APPLICATION}</mo><mrow><mo>(</mo>")?;
+        for (i, arg) in args.iter().enumerate() {
+            if i != 0 {
+                f.write_str("<mo>,</mo>")?;
+            }
+            self.write_present(f, *arg)?;
+        }
+        f.write_str("<mo>)</mo></mrow></mrow>")
+    }
+
+    fn write_present(&self, f: &mut dyn Write, id: AtomId) -> fmt::Result {
+        match self.atom(id) {
+            Atom::Integer(_) | Atom::Rational(_) if self.leading_negative(id) => {
+                f.write_str("<mrow><mo>−</mo>")?;
+                self.present_magnitude(f, id)?;
+                f.write_str("</mrow>")
+            }
+            Atom::Integer(_) | Atom::Rational(_) => self.present_magnitude(f, id),
+            Atom::Float(v) => self.present_float(f, v.value()),
+            Atom::Complex { re, im } => self.present_complex(f, *re, *im),
+            Atom::Symbol(sym) => self.present_symbol(f, self.symbols.name(*sym)),
+            Atom::Unit(unit) => write!(f, "<mi mathvariant=\"normal\">{unit}</mi>"),
+            Atom::Sum(terms) => self.present_sum(f, terms),
+            Atom::Product(factors) => self.present_product(f, factors),
+            Atom::Power { base, exponent } => {
+                f.write_str("<msup>")?;
+                self.present_prec(f, *base, Prec::Atom)?;
+                self.write_present(f, *exponent)?;
+                f.write_str("</msup>")
+            }
+            Atom::Neg(inner) => {
+                f.write_str("<mrow><mo>−</mo>")?;
+                match self.leading_negative(*inner) {
+                    true => self.present_prec(f, *inner, Prec::Atom)?,
+                    false => self.present_prec(f, *inner, Prec::Product)?,
+                }
+                f.write_str("</mrow>")
+            }
+            Atom::Apply { func, args } => self.present_apply(f, *func, args),
+            Atom::Order(term) => {
+                f.write_str("<mrow><mi>O</mi><mrow><mo>(</mo>")?;
+                self.write_present(f, *term)?;
+                f.write_str("<mo>)</mo></mrow></mrow>")
+            }
+            Atom::Equals { lhs, rhs } => {
+                f.write_str("<mrow>")?;
+                self.present_prec(f, *lhs, Prec::Sum)?;
+                f.write_str("<mo>=</mo>")?;
+                self.present_prec(f, *rhs, Prec::Sum)?;
+                f.write_str("</mrow>")
+            }
+            Atom::Relation { op, lhs, rhs } => {
+                f.write_str("<mrow>")?;
+                self.present_prec(f, *lhs, Prec::Sum)?;
+                f.write_str(match op {
+                    Relation::Less => "<mo>&lt;</mo>",
+                    Relation::Greater => "<mo>&gt;</mo>",
+                    op => match op.unicode() {
+                        "≠" => "<mo>≠</mo>",
+                        "≤" => "<mo>≤</mo>",
+                        _ => "<mo>≥</mo>",
+                    },
+                })?;
+                self.present_prec(f, *rhs, Prec::Sum)?;
+                f.write_str("</mrow>")
+            }
+            Atom::Integral {
+                variable,
+                upper,
+                lower,
+                integrand,
+            } => {
+                f.write_str("<mrow>")?;
+                match (lower, upper) {
+                    (Some(lower), Some(upper)) => {
+                        f.write_str("<msubsup><mo>∫</mo>")?;
+                        self.write_present(f, *lower)?;
+                        self.write_present(f, *upper)?;
+                        f.write_str("</msubsup>")?;
+                    }
+                    (Some(lower), None) => {
+                        f.write_str("<msub><mo>∫</mo>")?;
+                        self.write_present(f, *lower)?;
+                        f.write_str("</msub>")?;
+                    }
+                    (None, Some(upper)) => {
+                        f.write_str("<msup><mo>∫</mo>")?;
+                        self.write_present(f, *upper)?;
+                        f.write_str("</msup>")?;
+                    }
+                    (None, None) => f.write_str("<mo>∫</mo>")?,
+                }
+                self.present_prec(f, *integrand, Prec::Sum)?;
+                f.write_str("<mrow><mo>&#x2146;</mo>")?;
+                self.present_symbol(f, self.symbols.name(*variable))?;
+                f.write_str("</mrow></mrow>")
+            }
+            Atom::BigOperator {
+                op,
+                index,
+                lower,
+                upper,
+                term,
+            } => {
+                f.write_str(match op {
+                    BigOperator::Sum => "<mrow><munderover><mo>∑</mo><mrow>",
+                    BigOperator::Product => "<mrow><munderover><mo>∏</mo><mrow>",
+                })?;
+                self.present_symbol(f, self.symbols.name(*index))?;
+                f.write_str("<mo>=</mo>")?;
+                self.write_present(f, *lower)?;
+                f.write_str("</mrow>")?;
+                self.write_present(f, *upper)?;
+                f.write_str("</munderover>")?;
+                self.present_prec(f, *term, Prec::Product)?;
+                f.write_str("</mrow>")
+            }
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => {
+                f.write_str("<mfrac>")?;
+                self.write_present(f, *numerator)?;
+                self.write_present(f, *denominator)?;
+                f.write_str("</mfrac>")
+            }
+            Atom::Derivative {
+                variable,
+                order,
+                inner,
+            } => {
+                f.write_str("<mrow><mfrac>")?;
+                match order {
+                    1 => f.write_str("<mo>&#x2146;</mo><mrow><mo>&#x2146;</mo>")?,
+                    n => write!(
+                        f,
+                        "<msup><mo>&#x2146;</mo><mn>{n}</mn></msup><mrow><mo>&#x2146;</mo><msup>"
+                    )?,
+                }
+                self.present_symbol(f, self.symbols.name(*variable))?;
+                if *order != 1 {
+                    write!(f, "<mn>{order}</mn></msup>")?;
+                }
+                f.write_str("</mrow></mfrac>")?;
+                self.present_prec(f, *inner, Prec::Power)?;
+                f.write_str("</mrow>")
+            }
+            Atom::Limit {
+                variable,
+                point,
+                direction,
+                inner,
+            } => {
+                f.write_str("<mrow><munder><mo>lim</mo><mrow>")?;
+                self.present_symbol(f, self.symbols.name(*variable))?;
+                f.write_str("<mo>→</mo>")?;
+                match direction {
+                    Some(direction) => {
+                        f.write_str("<msup>")?;
+                        self.present_prec(f, *point, Prec::Atom)?;
+                        f.write_str(match direction {
+                            Direction::Below => "<mo>−</mo>",
+                            Direction::Above => "<mo>+</mo>",
+                        })?;
+                        f.write_str("</msup>")?;
+                    }
+                    None => self.write_present(f, *point)?,
+                }
+                f.write_str("</mrow></munder>")?;
+                self.present_prec(f, *inner, Prec::Product)?;
+                f.write_str("</mrow>")
+            }
+            Atom::Matrix { columns, entries } => {
+                f.write_str("<mrow><mo>(</mo><mtable>")?;
+                for row in entries.chunks(*columns as usize) {
+                    f.write_str("<mtr>")?;
+                    for entry in row {
+                        f.write_str("<mtd>")?;
+                        self.write_present(f, *entry)?;
+                        f.write_str("</mtd>")?;
+                    }
+                    f.write_str("</mtr>")?;
+                }
+                f.write_str("</mtable><mo>)</mo></mrow>")
+            }
+        }
+    }
+
+    fn content_symbol(&self, f: &mut dyn Write, name: &str) -> fmt::Result {
+        match name {
+            "π" => f.write_str("<pi/>"),
+            "e" => f.write_str("<exponentiale/>"),
+            "i" => f.write_str("<imaginaryi/>"),
+            "∞" => f.write_str("<infinity/>"),
+            name => {
+                f.write_str("<ci>")?;
+                write_escaped(f, name)?;
+                f.write_str("</ci>")
+            }
+        }
+    }
+
+    /// Write an `<apply>` element of the given operator element to all arguments
+    fn content_apply(&self, f: &mut dyn Write, op: &str, args: &[AtomId]) -> fmt::Result {
+        write!(f, "<apply>{op}")?;
+        for arg in args {
+            self.write_content(f, *arg)?;
+        }
+        f.write_str("</apply>")
+    }
+
+    fn write_content(&self, f: &mut dyn Write, id: AtomId) -> fmt::Result {
+        match self.atom(id) {
+            Atom::Integer(i) => write!(f, "<cn type=\"integer\">{i}</cn>"),
+            Atom::Rational(r) => write!(
+                f,
+                "<cn type=\"rational\">{}<sep/>{}</cn>",
+                r.numerator(),
+                r.denominator()
+            ),
+            Atom::Float(v) if v.value().is_infinite() => match v.value() < 0. {
+                true => f.write_str("<apply><minus/><infinity/></apply>"),
+                false => f.write_str("<infinity/>"),
+            },
+            Atom::Float(v) if v.value().is_nan() => f.write_str("<notanumber/>"),
+            Atom::Float(v) => write!(f, "<cn type=\"real\">{:?}</cn>", v.value()),
+            Atom::Complex { re, im } => match (self.atom(*re), self.atom(*im)) {
+                (Atom::Integer(a), Atom::Integer(b)) => {
+                    write!(f, "<cn type=\"complex-cartesian\">{a}<sep/>{b}</cn>")
+                }
+                _ => {
+                    f.write_str("<apply><plus/>")?;
+                    self.write_content(f, *re)?;
+                    f.write_str("<apply><times/>")?;
+                    self.write_content(f, *im)?;
+                    f.write_str("<imaginaryi/></apply></apply>")
+                }
+            },
+            Atom::Symbol(sym) => self.content_symbol(f, self.symbols.name(*sym)),
+            Atom::Unit(unit) => write!(f, "<csymbol cd=\"units\">{unit}</csymbol>"),
+            Atom::Sum(terms) => self.content_apply(f, "<plus/>", terms),
+            Atom::Product(factors) => self.content_apply(f, "<times/>", factors),
+            Atom::Power { base, exponent } => {
+                self.content_apply(f, "<power/>", &[*base, *exponent])
+            }
+            Atom::Neg(inner) => self.content_apply(f, "<minus/>", &[*inner]),
+            Atom::Order(term) => {
+                self.content_apply(f, "<csymbol cd=\"asymp\">big_O</csymbol>", &[*term])
+            }
+            Atom::Apply { func, args } => {
+                let op = match func {
+                    Function::Sin => "<sin/>",
+                    Function::Cos => "<cos/>",
+                    Function::Tan => "<tan/>",
+                    Function::Asin => "<arcsin/>",
+                    Function::Acos => "<arccos/>",
+                    Function::Atan => "<arctan/>",
+                    Function::Sinh => "<sinh/>",
+                    Function::Cosh => "<cosh/>",
+                    Function::Tanh => "<tanh/>",
+                    Function::Exp => "<exp/>",
+                    Function::Ln => "<ln/>",
+                    Function::Log => "<log/>",
+                    Function::Sqrt => "<root/>",
+                    Function::Abs => "<abs/>",
+                    Function::Re => "<real/>",
+                    Function::Im => "<imaginary/>",
+                    Function::Conj => "<conjugate/>",
+                    Function::Arg => "<arg/>",
+                    Function::User(sym) => {
+                        f.write_str("<apply><ci type=\"function\">")?;
+                        write_escaped(f, self.symbols.name(*sym))?;
+                        f.write_str("</ci>")?;
+                        for arg in args.iter() {
+                            self.write_content(f, *arg)?;
+                        }
+                        return f.write_str("</apply>");
+                    }
+                };
+                self.content_apply(f, op, args)
+            }
+            Atom::Equals { lhs, rhs } => self.content_apply(f, "<eq/>", &[*lhs, *rhs]),
+            Atom::Relation { op, lhs, rhs } => {
+                let op = match op {
+                    Relation::NotEqual => "<neq/>",
+                    Relation::Less => "<lt/>",
+                    Relation::LessEq => "<leq/>",
+                    Relation::Greater => "<gt/>",
+                    Relation::GreaterEq => "<geq/>",
+                };
+                self.content_apply(f, op, &[*lhs, *rhs])
+            }
+            Atom::Integral {
+                variable,
+                upper,
+                lower,
+                integrand,
+            } => {
+                f.write_str("<apply><int/><bvar>")?;
+                self.content_symbol(f, self.symbols.name(*variable))?;
+                f.write_str("</bvar>")?;
+                if let Some(lower) = lower {
+                    f.write_str("<lowlimit>")?;
+                    self.write_content(f, *lower)?;
+                    f.write_str("</lowlimit>")?;
+                }
+                if let Some(upper) = upper {
+                    f.write_str("<uplimit>")?;
+                    self.write_content(f, *upper)?;
+                    f.write_str("</uplimit>")?;
+                }
+                self.write_content(f, *integrand)?;
+                f.write_str("</apply>")
+            }
+            Atom::BigOperator {
+                op,
+                index,
+                lower,
+                upper,
+                term,
+            } => {
+                f.write_str(match op {
+                    BigOperator::Sum => "<apply><sum/><bvar>",
+                    BigOperator::Product => "<apply><product/><bvar>",
+                })?;
+                self.content_symbol(f, self.symbols.name(*index))?;
+                f.write_str("</bvar><lowlimit>")?;
+                self.write_content(f, *lower)?;
+                f.write_str("</lowlimit><uplimit>")?;
+                self.write_content(f, *upper)?;
+                f.write_str("</uplimit>")?;
+                self.write_content(f, *term)?;
+                f.write_str("</apply>")
+            }
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => self.content_apply(f, "<divide/>", &[*numerator, *denominator]),
+            Atom::Derivative {
+                variable,
+                order,
+                inner,
+            } => {
+                f.write_str("<apply><diff/><bvar>")?;
+                self.content_symbol(f, self.symbols.name(*variable))?;
+                if *order != 1 {
+                    write!(f, "<degree><cn>{order}</cn></degree>")?;
+                }
+                f.write_str("</bvar>")?;
+                self.write_content(f, *inner)?;
+                f.write_str("</apply>")
+            }
+            Atom::Limit {
+                variable,
+                point,
+                direction,
+                inner,
+            } => {
+                f.write_str("<apply><limit/><bvar>")?;
+                self.content_symbol(f, self.symbols.name(*variable))?;
+                f.write_str("</bvar>")?;
+                match direction {
+                    Some(direction) => {
+                        let side = match direction {
+                            Direction::Below => "below",
+                            Direction::Above => "above",
+                        };
+                        write!(f, "<condition><apply><tendsto type=\"{side}\"/>")?;
+                        self.content_symbol(f, self.symbols.name(*variable))?;
+                        self.write_content(f, *point)?;
+                        f.write_str("</apply></condition>")?;
+                    }
+                    None => {
+                        f.write_str("<lowlimit>")?;
+                        self.write_content(f, *point)?;
+                        f.write_str("</lowlimit>")?;
+                    }
+                }
+                self.write_content(f, *inner)?;
+                f.write_str("</apply>")
+            }
+            Atom::Matrix { columns, entries } => {
+                f.write_str("<matrix>")?;
+                for row in entries.chunks(*columns as usize) {
+                    f.write_str("<matrixrow>")?;
+                    for entry in row {
+                        self.write_content(f, *entry)?;
+                    }
+                    f.write_str("</matrixrow>")?;
+                }
+                f.write_str("</matrix>")
+            }
+        }
+    }
+}
+
+/// Write the given text with XML special characters escaped
+fn write_escaped(f: &mut dyn Write, text: &str) -> fmt::Result {
+    for ch in text.chars() {
+        match ch {
+            '&' => f.write_str("&amp;")?,
+            '<' => f.write_str("&lt;")?,
+            '>' => f.write_str("&gt;")?,
+            ch => f.write_char(ch)?,
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for MathMl<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">")?;
+        match self.content {
+            true => self.write_content(f, self.root)?,
+            false => self.write_present(f, self.root)?,
+        }
+        f.write_str("</math>")
+    }
+}
@@ -0,0 +1,370 @@
+use crate::{
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, ExprPool,
+    },
+    eval::{Env, EvalError, EvalErrorKind},
+    poly::{Polynomial, Quotient},
+    solve::{Solution, SolveError},
+};
+
+mod numeric;
+
+pub use numeric::NumericMatrix;
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum MatrixError {
+    #[error("Expression is not a matrix")]
+    NotMatrix,
+    #[error("Matrix is not square")]
+    NotSquare,
+    #[error("Cannot combine a {}×{} matrix with a {}×{} matrix", .lhs.0, .lhs.1, .rhs.0, .rhs.1)]
+    Shape {
+        lhs: (usize, usize),
+        rhs: (usize, usize),
+    },
+    #[error("Matrix is singular")]
+    Singular,
+    #[error(transparent)]
+    Solve(#[from] SolveError),
+}
+
+/// Entries of a matrix atom with its shape
+struct Parts {
+    rows: usize,
+    columns: usize,
+    entries: Box<[AtomId]>,
+}
+
+impl ExprPool {
+    /// Get the number of rows and columns of a matrix, or `None` if `id` is not a matrix
+    pub fn dimensions(&self, id: AtomId) -> Option<(usize, usize)> {
+        match self.get(id) {
+            Atom::Matrix { columns, entries } => {
+                let columns = *columns as usize;
+                Some((entries.len() / columns, columns))
+            }
+            _ => None,
+        }
+    }
+
+    fn parts(&self, id: AtomId) -> Result<Parts, MatrixError> {
+        match self.get(id) {
+            Atom::Matrix { columns, entries } => Ok(Parts {
+                rows: entries.len() / *columns as usize,
+                columns: *columns as usize,
+                entries: entries.clone(),
+            }),
+            _ => Err(MatrixError::NotMatrix),
+        }
+    }
+
+    fn square(&self, id: AtomId) -> Result<Parts, MatrixError> {
+        let parts = self.parts(id)?;
+        match parts.rows == parts.columns {
+            true => Ok(parts),
+            false => Err(MatrixError::NotSquare),
+        }
+    }
+
+    /// Get the transpose of a matrix, turning a column vector into a row vector
+    pub fn transpose(&mut self, id: AtomId) -> Result<AtomId, MatrixError> {
+        let Parts {
+            rows,
+            columns,
+            entries,
+        } = self.parts(id)?;
+        let transposed = (0..columns)
+            .map(|j| (0..rows).map(|i| entries[i * columns + j]).collect())
+            .collect::<Vec<_>>();
+        Ok(self.matrix(transposed).expect("Matrix is not empty"))
+    }
+
+    /// Add two matrices of the same shape and simplify the result
+    pub fn matrix_add(&mut self, lhs: AtomId, rhs: AtomId) -> Result<AtomId, MatrixError> {
+        let (a, b) = (self.parts(lhs)?, self.parts(rhs)?);
+        if (a.rows, a.columns) != (b.rows, b.columns) {
+            return Err(MatrixError::Shape {
+                lhs: (a.rows, a.columns),
+                rhs: (b.rows, b.columns),
+            });
+        }
+
+        let sum = self.sum([lhs, rhs]);
+        Ok(self.simplify(sum))
+    }
+
+    /// Multiply two matrices, where `lhs` has as many columns as `rhs` has rows, and simplify the
+    /// result
+    pub fn matrix_mul(&mut self, lhs: AtomId, rhs: AtomId) -> Result<AtomId, MatrixError> {
+        let (a, b) = (self.parts(lhs)?, self.parts(rhs)?);
+        if a.columns != b.rows {
+            return Err(MatrixError::Shape {
+                lhs: (a.rows, a.columns),
+                rhs: (b.rows, b.columns),
+            });
+        }
+
+        let product = self.product([lhs, rhs]);
+        Ok(self.simplify(product))
+    }
+
+    /// Get the entries of a matrix of numeric literals as a [NumericMatrix], or `None` if any entry
+    /// is not a literal or they are all exact, in which case exact arithmetic is preferred
+    fn floating(&self, parts: &Parts) -> Option<NumericMatrix> {
+        let mut inexact = false;
+        let values = parts
+            .entries
+            .iter()
+            .map(|entry| match self.get(*entry) {
+                Atom::Float(f) => {
+                    inexact = true;
+                    Some(f.value())
+                }
+                atom => atom.to_rational().map(|r| r.to_f64()),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        match inexact {
+            true => NumericMatrix::new(parts.rows, parts.columns, values),
+            false => None,
+        }
+    }
+
+    /// Insert a [NumericMatrix] as a matrix of floating point literals
+    fn insert_numeric(&mut self, matrix: &NumericMatrix) -> AtomId {
+        let rows = matrix
+            .entries()
+            .chunks(matrix.columns())
+            .map(|row| row.iter().map(|value| self.float(*value)).collect())
+            .collect::<Vec<_>>();
+        self.matrix(rows).expect("Matrix is not empty")
+    }
+
+    /// Convert the entries of a matrix into quotients of polynomials, row by row
+    fn quotients(&mut self, parts: &Parts) -> Vec<Vec<Quotient>> {
+        parts
+            .entries
+            .chunks(parts.columns)
+            .map(|row| {
+                row.iter()
+                    .map(|entry| {
+                        let normal = self.normal_form(*entry);
+                        Quotient::of(self, normal)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn insert_quotients(&mut self, rows: &[Vec<Quotient>]) -> AtomId {
+        let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|entry| entry.to_atom(self)).collect())
+            .collect::<Vec<_>>();
+        self.matrix(rows).expect("Matrix is not empty")
+    }
+
+    /// Get the determinant of a square matrix by Gaussian elimination over quotients of
+    /// polynomials, so that symbolic entries give an exact result
+    pub fn determinant(&mut self, id: AtomId) -> Result<AtomId, MatrixError> {
+        let parts = self.square(id)?;
+        if let Some(numeric) = self.floating(&parts) {
+            let determinant = numeric.determinant().expect("Matrix is square");
+            return Ok(self.float(determinant));
+        }
+
+        let mut rows = self.quotients(&parts);
+        let (pivots, determinant) = reduce(&mut rows, parts.columns);
+        Ok(match pivots.len() == parts.rows {
+            true => determinant.to_atom(self),
+            false => self.integer(0),
+        })
+    }
+
+    /// Get the inverse of a square matrix, which for symbolic entries is valid wherever the
+    /// determinant is nonzero
+    pub fn inverse(&mut self, id: AtomId) -> Result<AtomId, MatrixError> {
+        let parts = self.square(id)?;
+        if let Some(numeric) = self.floating(&parts) {
+            let inverse = numeric.inverse().ok_or(MatrixError::Singular)?;
+            return Ok(self.insert_numeric(&inverse));
+        }
+
+        // Reduce the matrix augmented with the identity
+        let n = parts.rows;
+        let mut rows = self.quotients(&parts);
+        for (i, row) in rows.iter_mut().enumerate() {
+            row.extend((0..n).map(|j| {
+                Quotient::polynomial(match i == j {
+                    true => Polynomial::one(),
+                    false => Polynomial::zero(),
+                })
+            }));
+        }
+        if reduce(&mut rows, n).0.len() < n {
+            return Err(MatrixError::Singular);
+        }
+
+        let inverse = rows
+            .into_iter()
+            .map(|row| row[n..].to_vec())
+            .collect::<Vec<_>>();
+        Ok(self.insert_quotients(&inverse))
+    }
+
+    /// Get the rank of a matrix. Symbolic entries are treated as independent, so the rank is the
+    /// one for all but special values of the symbols they contain
+    pub fn rank(&mut self, id: AtomId) -> Result<usize, MatrixError> {
+        let parts = self.parts(id)?;
+        if let Some(numeric) = self.floating(&parts) {
+            return Ok(numeric.rank());
+        }
+
+        let mut rows = self.quotients(&parts);
+        Ok(reduce(&mut rows, parts.columns).0.len())
+    }
+
+    /// Get the reduced row echelon form of a matrix. Like [ExprPool::rank], pivots are chosen as
+    /// if the symbols in the entries took generic values
+    pub fn rref(&mut self, id: AtomId) -> Result<AtomId, MatrixError> {
+        let parts = self.parts(id)?;
+        if let Some(numeric) = self.floating(&parts) {
+            return Ok(self.insert_numeric(&numeric.rref()));
+        }
+
+        let mut rows = self.quotients(&parts);
+        reduce(&mut rows, parts.columns);
+        Ok(self.insert_quotients(&rows))
+    }
+
+    /// Get the characteristic polynomial `det(λI - A)` of a square matrix in the given variable,
+    /// which should not appear in the matrix
+    pub fn characteristic_polynomial(
+        &mut self,
+        id: AtomId,
+        variable: Symbol,
+    ) -> Result<AtomId, MatrixError> {
+        let parts = self.square(id)?;
+        let lambda = self.symbol(variable);
+        let lambda = Quotient::polynomial(Polynomial::generator(lambda));
+        let mut rows = self.quotients(&parts);
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, entry) in row.iter_mut().enumerate() {
+                *entry = match i == j {
+                    true => lambda.sub(entry),
+                    false => entry.neg(),
+                };
+            }
+        }
+
+        let (pivots, determinant) = reduce(&mut rows, parts.columns);
+        Ok(match pivots.len() == parts.rows {
+            true => determinant.to_atom(self),
+            false => self.integer(0),
+        })
+    }
+
+    /// Get the eigenvalues of a square matrix as the real and complex solutions of its
+    /// characteristic equation, in the closed forms found by [ExprPool::solve] with complex
+    /// eigenvalues written in terms of `i`. Each eigenvalue is repeated by its algebraic
+    /// multiplicity, so `[[1, 1], [0, 1]]` has the eigenvalues `1` and `1`.
+    ///
+    /// Eigenvalues of matrices with floating point entries are better found with
+    /// [NumericMatrix::eigenvalues]
+    pub fn eigenvalues(
+        &mut self,
+        symbols: &mut SymbolStore,
+        id: AtomId,
+    ) -> Result<Vec<Solution>, MatrixError> {
+        self.square(id)?;
+        let lambda = (0..)
+            .map(|i| match i {
+                0 => String::from("λ"),
+                i => format!("λ_{i}"),
+            })
+            .map(|name| symbols.intern(&name))
+            .find(|sym| !self.depends_on(id, *sym))
+            .expect("There are infinitely many names");
+
+        let characteristic = self.characteristic_polynomial(id, lambda)?;
+        let polynomial = self.polynomial(characteristic);
+        if polynomial.is_zero() {
+            return Ok(self.solve_complex(symbols, characteristic, lambda)?);
+        }
+
+        let generator = self.symbol(lambda);
+        let mut eigenvalues = Vec::new();
+        for (factor, multiplicity) in polynomial.factor().factors {
+            if factor.degree(generator) == 0 {
+                continue;
+            }
+            let factor = factor.to_atom(self);
+            for solution in self.solve_complex(symbols, factor, lambda)? {
+                eigenvalues.extend(std::iter::repeat_n(solution, multiplicity as usize));
+            }
+        }
+        Ok(eigenvalues)
+    }
+
+    /// Numerically evaluate every entry of a matrix, looking up the values of symbols in `env`
+    pub fn eval_matrix(
+        &self,
+        id: AtomId,
+        symbols: &SymbolStore,
+        env: &Env,
+    ) -> Result<NumericMatrix, EvalError> {
+        let parts = self.parts(id).map_err(|_| EvalError {
+            kind: EvalErrorKind::NotNumeric,
+            atom: id,
+        })?;
+        let values = parts
+            .entries
+            .iter()
+            .map(|entry| self.eval(*entry, symbols, env))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(NumericMatrix::new(parts.rows, parts.columns, values).expect("Shape matches"))
+    }
+}
+
+/// Reduce rows of quotients to reduced row echelon form in the first `limit` columns by
+/// Gauss–Jordan elimination, returning the pivot columns and the determinant of the reduced part,
+/// which is only meaningful if every column up to `limit` has a pivot.
+///
+/// Constant pivots are preferred, and symbolic pivots are assumed to be nonzero
+fn reduce(rows: &mut [Vec<Quotient>], limit: usize) -> (Vec<usize>, Quotient) {
+    let mut pivots = Vec::new();
+    let mut determinant = Quotient::polynomial(Polynomial::one());
+    for column in 0..limit {
+        let row = pivots.len();
+        let mut candidates = (row..rows.len()).filter(|r| !rows[*r][column].is_zero());
+        let Some(first) = candidates.next() else {
+            continue;
+        };
+        let pivot = std::iter::once(first)
+            .chain(candidates)
+            .find(|r| rows[*r][column].is_constant())
+            .unwrap_or(first);
+        if pivot != row {
+            rows.swap(row, pivot);
+            determinant = determinant.neg();
+        }
+
+        let divisor = rows[row][column].clone();
+        determinant = determinant.mul(&divisor);
+        rows[row] = rows[row].iter().map(|entry| entry.div(&divisor)).collect();
+
+        for other in 0..rows.len() {
+            let factor = rows[other][column].clone();
+            if other == row || factor.is_zero() {
+                continue;
+            }
+            rows[other] = rows[other]
+                .iter()
+                .zip(&rows[row])
+                .map(|(entry, pivot)| entry.sub(&pivot.mul(&factor)))
+                .collect();
+        }
+        pivots.push(column);
+    }
+    (pivots, determinant)
+}
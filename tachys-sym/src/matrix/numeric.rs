@@ -0,0 +1,287 @@
+/// Largest number of Durand–Kerner iterations made when finding eigenvalues
+const MAX_ITERATIONS: usize = 1000;
+
+/// Dense matrix of floating point numbers, stored row by row
+#[derive(Clone, PartialEq, Debug)]
+pub struct NumericMatrix {
+    rows: usize,
+    columns: usize,
+    entries: Vec<f64>,
+}
+
+impl NumericMatrix {
+    /// Create a matrix from its entries row by row, or return `None` if the number of entries
+    /// does not match the shape
+    pub fn new(rows: usize, columns: usize, entries: Vec<f64>) -> Option<Self> {
+        (entries.len() == rows * columns).then_some(Self {
+            rows,
+            columns,
+            entries,
+        })
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut out = Self::zero(size, size);
+        for i in 0..size {
+            out.entries[i * size + i] = 1.;
+        }
+        out
+    }
+
+    fn zero(rows: usize, columns: usize) -> Self {
+        Self {
+            rows,
+            columns,
+            entries: vec![0.; rows * columns],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Get the entries row by row
+    pub fn entries(&self) -> &[f64] {
+        &self.entries
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> f64 {
+        self.entries[row * self.columns + column]
+    }
+
+    fn is_square(&self) -> bool {
+        self.rows == self.columns
+    }
+
+    /// Magnitude below which an entry produced by elimination is taken to be zero
+    fn tolerance(&self) -> f64 {
+        let largest = self.entries.iter().fold(0., |max: f64, x| max.max(x.abs()));
+        self.rows.max(self.columns) as f64 * f64::EPSILON * largest
+    }
+
+    pub fn transpose(&self) -> Self {
+        let entries = (0..self.entries.len())
+            .map(|i| self.get(i % self.rows, i / self.rows))
+            .collect();
+        Self {
+            rows: self.columns,
+            columns: self.rows,
+            entries,
+        }
+    }
+
+    /// Multiply by `rhs`, returning `None` if the number of columns does not match its number of
+    /// rows
+    pub fn mul(&self, rhs: &Self) -> Option<Self> {
+        if self.columns != rhs.rows {
+            return None;
+        }
+
+        let mut out = Self::zero(self.rows, rhs.columns);
+        for i in 0..self.rows {
+            for j in 0..rhs.columns {
+                out.entries[i * rhs.columns + j] = (0..self.columns)
+                    .map(|k| self.get(i, k) * rhs.get(k, j))
+                    .sum();
+            }
+        }
+        Some(out)
+    }
+
+    /// Reduce to reduced row echelon form with partial pivoting, returning the columns of the
+    /// pivots and the determinant of the row operations applied
+    fn reduce(&mut self, limit: usize) -> (Vec<usize>, f64) {
+        let tolerance = self.tolerance();
+        let mut pivots = Vec::new();
+        let mut determinant = 1.;
+        for column in 0..limit {
+            let row = pivots.len();
+            if row == self.rows {
+                break;
+            }
+
+            let (pivot, magnitude) = (row..self.rows)
+                .map(|r| (r, self.get(r, column).abs()))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .expect("There is at least one row left");
+            if magnitude <= tolerance {
+                continue;
+            }
+            if pivot != row {
+                for j in 0..self.columns {
+                    self.entries
+                        .swap(row * self.columns + j, pivot * self.columns + j);
+                }
+                determinant = -determinant;
+            }
+
+            let divisor = self.get(row, column);
+            determinant *= divisor;
+            for j in 0..self.columns {
+                self.entries[row * self.columns + j] /= divisor;
+            }
+            for other in (0..self.rows).filter(|r| *r != row) {
+                let factor = self.get(other, column);
+                for j in 0..self.columns {
+                    self.entries[other * self.columns + j] -= factor * self.get(row, j);
+                }
+            }
+            pivots.push(column);
+        }
+        (pivots, determinant)
+    }
+
+    /// Get the reduced row echelon form, treating entries that are negligible relative to the
+    /// largest entry as zero
+    pub fn rref(&self) -> Self {
+        let mut out = self.clone();
+        out.reduce(self.columns);
+        out
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().reduce(self.columns).0.len()
+    }
+
+    /// Get the determinant, or `None` if the matrix is not square
+    pub fn determinant(&self) -> Option<f64> {
+        if !self.is_square() {
+            return None;
+        }
+
+        let (pivots, determinant) = self.clone().reduce(self.columns);
+        Some(match pivots.len() == self.rows {
+            true => determinant,
+            false => 0.,
+        })
+    }
+
+    /// Get the inverse, or `None` if the matrix is not square or is singular
+    pub fn inverse(&self) -> Option<Self> {
+        if !self.is_square() {
+            return None;
+        }
+
+        // Reduce the matrix augmented with the identity
+        let n = self.rows;
+        let mut augmented = Self::zero(n, 2 * n);
+        for i in 0..n {
+            for j in 0..n {
+                augmented.entries[i * 2 * n + j] = self.get(i, j);
+            }
+            augmented.entries[i * 2 * n + n + i] = 1.;
+        }
+        if augmented.reduce(n).0.len() < n {
+            return None;
+        }
+
+        let entries = (0..n * n)
+            .map(|i| augmented.get(i / n, n + i % n))
+            .collect();
+        Some(Self {
+            rows: n,
+            columns: n,
+            entries,
+        })
+    }
+
+    /// Get the coefficients of the characteristic polynomial `det(λI - A)` from the constant term
+    /// up by the Faddeev–LeVerrier algorithm, or `None` if the matrix is not square
+    pub fn characteristic_polynomial(&self) -> Option<Vec<f64>> {
+        if !self.is_square() {
+            return None;
+        }
+
+        let n = self.rows;
+        let mut coefficients = vec![0.; n + 1];
+        coefficients[n] = 1.;
+        let mut m = Self::zero(n, n);
+        for k in 1..=n {
+            // M_k = A M_(k-1) + c_(n-k+1) I and c_(n-k) = -tr(A M_k) / k
+            m = self.mul(&m).expect("Matrices are square");
+            for i in 0..n {
+                m.entries[i * n + i] += coefficients[n - k + 1];
+            }
+            let product = self.mul(&m).expect("Matrices are square");
+            let trace = (0..n).map(|i| product.get(i, i)).sum::<f64>();
+            coefficients[n - k] = -trace / k as f64;
+        }
+        Some(coefficients)
+    }
+
+    /// Get the eigenvalues with multiplicity as pairs of real and imaginary parts, sorted by real
+    /// part, or `None` if the matrix is not square.
+    ///
+    /// Eigenvalues are found as roots of the characteristic polynomial with the Durand–Kerner
+    /// method, so repeated eigenvalues are less accurate than simple ones
+    pub fn eigenvalues(&self) -> Option<Vec<(f64, f64)>> {
+        let coefficients = self.characteristic_polynomial()?;
+        let mut roots = polynomial_roots(&coefficients);
+        for (re, im) in &mut roots {
+            if im.abs() <= 1e-9 * (1. + re.abs()) {
+                *im = 0.;
+            }
+        }
+        roots.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        Some(roots)
+    }
+}
+
+fn mul((a, b): (f64, f64), (c, d): (f64, f64)) -> (f64, f64) {
+    (a * c - b * d, a * d + b * c)
+}
+
+fn div((a, b): (f64, f64), (c, d): (f64, f64)) -> (f64, f64) {
+    let norm = c * c + d * d;
+    ((a * c + b * d) / norm, (b * c - a * d) / norm)
+}
+
+/// Find every complex root of a monic polynomial, given by its coefficients from the constant
+/// term up, with the Durand–Kerner method
+fn polynomial_roots(coefficients: &[f64]) -> Vec<(f64, f64)> {
+    let n = coefficients.len() - 1;
+    let evaluate = |z: (f64, f64)| {
+        coefficients.iter().rev().fold((0., 0.), |acc, c| {
+            let (re, im) = mul(acc, z);
+            (re + c, im)
+        })
+    };
+
+    // Start from points spread around a circle enclosing every root
+    let radius = 1.
+        + coefficients[..n]
+            .iter()
+            .fold(0., |max: f64, c| max.max(c.abs()));
+    let mut roots = (0..n)
+        .map(|k| {
+            let angle = 2. * std::f64::consts::PI * k as f64 / n as f64 + 0.4;
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect::<Vec<_>>();
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut change = 0.;
+        for k in 0..n {
+            let denominator = (0..n).filter(|j| *j != k).fold((1., 0.), |acc, j| {
+                mul(acc, (roots[k].0 - roots[j].0, roots[k].1 - roots[j].1))
+            });
+            let step = div(evaluate(roots[k]), denominator);
+            if !step.0.is_finite() || !step.1.is_finite() {
+                continue;
+            }
+            roots[k] = (roots[k].0 - step.0, roots[k].1 - step.1);
+            change = f64::max(
+                change,
+                step.0.hypot(step.1) / (1. + roots[k].0.hypot(roots[k].1)),
+            );
+        }
+        if change < f64::EPSILON {
+            break;
+        }
+    }
+    roots
+}
@@ -0,0 +1,762 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+
+use lex::{Token, TokenKind};
+
+use crate::{
+    atom::{
+        symbol::{self, Symbol, SymbolStore},
+        Atom, AtomId, BigInt, ExprPool, Function, Unit,
+    },
+    rewrite::{Constraint, Pattern},
+    units::UnitErrorKind,
+};
+
+mod lex;
+
+/// Parser for linear infix math input such as `x^2 + 3x/(y-1) = sin(θ)`, inserting the parsed
+/// expression into an [ExprPool].
+///
+/// Juxtaposed operands are multiplied, so `3x` and `2(x+1)` are products. An identifier followed
+/// by parentheses is only treated as a function call if it names a builtin [Function] or a
+/// function registered with [Parser::define_function]. Derivatives are written `d/dx f` or
+/// `d^2/dx^2 f` and series remainders `O(x^3)`
+pub struct Parser<'a> {
+    pool: &'a mut ExprPool,
+    symbols: &'a mut SymbolStore,
+    functions: HashSet<String>,
+    units: bool,
+}
+
+/// Error produced when parsing fails, with the byte range of the source that caused it
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+#[error("{kind}")]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum ParseErrorKind {
+    #[error("Unknown character '{0}'")]
+    UnknownCharacter(char),
+    #[error("Invalid number literal")]
+    InvalidNumber,
+    #[error("Expected {expected}, found {found}")]
+    Unexpected {
+        expected: &'static str,
+        found: String,
+    },
+    #[error("Unclosed '{0}'")]
+    Unclosed(char),
+    #[error("Integral is missing a differential such as 'dx'")]
+    ExpectedDifferential,
+    #[error("Function '{name}' expects {expected} argument(s) but was given {found}")]
+    Arity {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Unsupported LaTeX macro '\\{0}'")]
+    UnsupportedMacro(String),
+    #[error("Macro '\\{0}' expands recursively without end")]
+    MacroRecursion(String),
+    #[error("Unknown wildcard constraint '{0}'")]
+    UnknownConstraint(String),
+    #[error("Matrix rows must all have the same number of entries")]
+    RaggedMatrix,
+    #[error(transparent)]
+    Units(UnitErrorKind),
+}
+
+/// Parse the given source into `pool`, interning any symbols into `symbols`
+pub fn parse(
+    pool: &mut ExprPool,
+    symbols: &mut SymbolStore,
+    src: &str,
+) -> Result<AtomId, ParseError> {
+    Parser::new(pool, symbols).parse(src)
+}
+
+impl<'a> Parser<'a> {
+    /// Create a parser that inserts atoms into the given pool with no user functions defined
+    pub fn new(pool: &'a mut ExprPool, symbols: &'a mut SymbolStore) -> Self {
+        Self {
+            pool,
+            symbols,
+            functions: HashSet::new(),
+            units: false,
+        }
+    }
+
+    /// Treat the given name as a user function when it is followed by parentheses, instead of
+    /// multiplying it by the parenthesized expression
+    pub fn define_function(&mut self, name: &str) -> &mut Self {
+        self.functions.insert(name.to_owned());
+        self
+    }
+
+    /// Read identifiers naming a [Unit] such as `km` or `s` as units instead of symbols, and
+    /// allow an expression to end in `to` followed by the units it should be converted into, as
+    /// in `9.81 m/s^2 to km/h^2`
+    pub fn enable_units(&mut self) -> &mut Self {
+        self.units = true;
+        self
+    }
+
+    /// Parse a complete expression, equation or relation from the given source
+    pub fn parse(&mut self, src: &str) -> Result<AtomId, ParseError> {
+        self.parse_with(src, None).map(|(expr, _)| expr)
+    }
+
+    /// Parse a [Pattern] in which `?name` is a wildcard, optionally constrained as in
+    /// `?n: integer`. Wildcards are interned as symbols with the question mark included
+    pub fn parse_pattern(&mut self, src: &str) -> Result<Pattern, ParseError> {
+        let (root, wildcards) = self.parse_with(src, Some(HashMap::new()))?;
+        Ok(wildcards
+            .unwrap_or_default()
+            .into_iter()
+            .fold(Pattern::new(root), |pattern, (sym, constraint)| {
+                pattern.wildcard(sym, constraint)
+            }))
+    }
+
+    fn parse_with(
+        &mut self,
+        src: &str,
+        wildcards: Option<HashMap<Symbol, Constraint>>,
+    ) -> Result<(AtomId, Option<HashMap<Symbol, Constraint>>), ParseError> {
+        let tokens = lex::tokenize(src)?;
+        let mut state = State {
+            parser: self,
+            tokens,
+            pos: 0,
+            integral_depth: 0,
+            wildcards,
+        };
+
+        let mut expr = state.relation()?;
+        if state.at_conversion() {
+            let to = state.next();
+            let target = state.multiplicative()?;
+            expr = state
+                .parser
+                .pool
+                .convert(expr, target)
+                .map_err(|error| ParseError {
+                    kind: ParseErrorKind::Units(error.kind),
+                    span: to.span,
+                })?;
+        }
+        state.expect(TokenKind::Eof, "an operator or end of input")?;
+        Ok((expr, state.wildcards))
+    }
+}
+
+struct State<'p, 'a, 's> {
+    parser: &'p mut Parser<'a>,
+    tokens: Vec<Token<'s>>,
+    pos: usize,
+    integral_depth: usize,
+    /// Constraints of the wildcards seen so far, or `None` if wildcards are not allowed
+    wildcards: Option<HashMap<Symbol, Constraint>>,
+}
+
+impl<'s> State<'_, '_, 's> {
+    fn peek(&self) -> &Token<'s> {
+        &self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> Token<'s> {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, kind: TokenKind<'_>) -> bool {
+        let matches = self.peek().kind == kind;
+        if matches {
+            self.next();
+        }
+        matches
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        let token = self.peek();
+        // The end of input has no text to point at, so point at the token before it instead
+        let span = match (&token.kind, self.pos.checked_sub(1)) {
+            (TokenKind::Eof, Some(last)) => self.tokens[last].span.clone(),
+            _ => token.span.clone(),
+        };
+        ParseError {
+            kind: ParseErrorKind::Unexpected {
+                expected,
+                found: token.kind.to_string(),
+            },
+            span,
+        }
+    }
+
+    fn expect(
+        &mut self,
+        kind: TokenKind<'_>,
+        expected: &'static str,
+    ) -> Result<Token<'s>, ParseError> {
+        match self.peek().kind == kind {
+            true => Ok(self.next()),
+            false => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn close(&mut self, close: TokenKind<'_>, open: &Token<'_>) -> Result<(), ParseError> {
+        if self.eat(close.clone()) {
+            return Ok(());
+        }
+
+        match self.peek().kind {
+            TokenKind::Eof => Err(ParseError {
+                kind: ParseErrorKind::Unclosed(match open.kind {
+                    TokenKind::LBrace => '{',
+                    TokenKind::LBracket => '[',
+                    _ => '(',
+                }),
+                span: open.span.clone(),
+            }),
+            _ => Err(self.unexpected(match close {
+                TokenKind::RBrace => "'}'",
+                TokenKind::RBracket => "']'",
+                _ => "')'",
+            })),
+        }
+    }
+
+    /// Check if the current token is the `to` introducing a unit conversion
+    fn at_conversion(&self) -> bool {
+        self.parser.units && self.peek().kind == TokenKind::Ident("to")
+    }
+
+    /// Get the variable of the differential at the current token and the number of tokens it
+    /// spans, if parsing an integrand and the differential ends it. The differentials of
+    /// enclosing integrals may follow it, as in `∫∫ x y dx dy`
+    fn differential(&self) -> Option<(&'s str, usize)> {
+        if self.integral_depth == 0 {
+            return None;
+        }
+
+        let (variable, len) = self.differential_at(0)?;
+        let mut end = len;
+        for _ in 1..self.integral_depth {
+            match self.differential_at(end) {
+                Some((_, len)) => end += len,
+                None => break,
+            }
+        }
+
+        let end = match self.peek_nth(end) {
+            TokenKind::Ident("to") => self.parser.units,
+            TokenKind::Number(_)
+            | TokenKind::Ident(_)
+            | TokenKind::Wildcard(_)
+            | TokenKind::Superscript(_)
+            | TokenKind::Caret
+            | TokenKind::Underscore
+            | TokenKind::LParen
+            | TokenKind::LBrace
+            | TokenKind::LBracket
+            | TokenKind::Sqrt
+            | TokenKind::Integral => false,
+            _ => true,
+        };
+        end.then_some((variable, len))
+    }
+
+    /// Get the variable of the differential `offset` tokens after the current one and the number
+    /// of tokens it spans, whether or not it ends an integrand
+    ///
+    /// A differential is either `d` followed by a single letter such as `dx` or `dθ`, or `d` and
+    /// the variable written as separate words such as `d density`, so that `delta dx` is read as
+    /// `δ` integrated over `x`
+    fn differential_at(&self, offset: usize) -> Option<(&'s str, usize)> {
+        match *self.peek_nth(offset) {
+            TokenKind::Ident("d") => match *self.peek_nth(offset + 1) {
+                TokenKind::Ident(variable) => Some((variable, 2)),
+                _ => None,
+            },
+            TokenKind::Ident(name) => {
+                let variable = name.strip_prefix('d')?;
+                let (base, _) = symbol::split_subscript(variable);
+                let single = base.chars().count() == 1 || symbol::greek_letter(base).is_some();
+                single.then_some((variable, 1))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the kind of the token `offset` tokens after the current one, which is
+    /// [TokenKind::Eof] past the end of input
+    fn peek_nth(&self, offset: usize) -> &TokenKind<'s> {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.pos + offset).min(last)].kind
+    }
+
+    /// Get the order written as an exponent such as `^2` or `²` at `offset` tokens after the
+    /// current one and the number of tokens it spans
+    fn order_at(&self, offset: usize) -> Option<(u32, usize)> {
+        let (digits, len) = match self.peek_nth(offset) {
+            TokenKind::Caret => match self.peek_nth(offset + 1) {
+                TokenKind::Number(digits) => (*digits, 2),
+                _ => return None,
+            },
+            TokenKind::Superscript(digits) => (digits.as_str(), 1),
+            _ => return None,
+        };
+        digits
+            .parse::<u32>()
+            .ok()
+            .filter(|n| *n > 0)
+            .map(|n| (n, len))
+    }
+
+    /// Get the variable and order of the derivative operator at the current token and the number
+    /// of tokens it spans, if the operator is followed by an operand.
+    ///
+    /// The operator is written `d/dx` or `d^n/dx^n`, where the variable may also be separated
+    /// from its `d` as in `d/d θ`. Without an operand `d/dx` is the fraction it looks like
+    fn derivative(&self) -> Option<(&'s str, u32, usize)> {
+        if *self.peek_nth(0) != TokenKind::Ident("d") {
+            return None;
+        }
+
+        let (order, mut len) = match self.order_at(1) {
+            Some((order, len)) => (order, len + 1),
+            None => (1, 1),
+        };
+        if *self.peek_nth(len) != TokenKind::Slash {
+            return None;
+        }
+        let variable = match *self.peek_nth(len + 1) {
+            TokenKind::Ident("d") => match *self.peek_nth(len + 2) {
+                TokenKind::Ident(variable) => {
+                    len += 3;
+                    variable
+                }
+                _ => return None,
+            },
+            TokenKind::Ident(name) => {
+                len += 2;
+                name.strip_prefix('d')?
+            }
+            _ => return None,
+        };
+        if order != 1 {
+            let (exponent, exponent_len) = self.order_at(len)?;
+            if exponent != order {
+                return None;
+            }
+            len += exponent_len;
+        }
+
+        let operand = matches!(
+            self.peek_nth(len),
+            TokenKind::Number(_)
+                | TokenKind::Ident(_)
+                | TokenKind::Wildcard(_)
+                | TokenKind::LParen
+                | TokenKind::LBrace
+                | TokenKind::LBracket
+                | TokenKind::Sqrt
+                | TokenKind::Integral
+        );
+        operand.then_some((variable, order, len))
+    }
+
+    fn relation(&mut self) -> Result<AtomId, ParseError> {
+        let lhs = self.additive()?;
+
+        match self.peek().kind {
+            TokenKind::Equals => {
+                self.next();
+                let rhs = self.additive()?;
+                Ok(self.parser.pool.equals(lhs, rhs))
+            }
+            TokenKind::Relation(op) => {
+                self.next();
+                let rhs = self.additive()?;
+                Ok(self.parser.pool.relation(op, lhs, rhs))
+            }
+            _ => Ok(lhs),
+        }
+    }
+
+    fn additive(&mut self) -> Result<AtomId, ParseError> {
+        let mut terms = vec![self.multiplicative()?];
+
+        loop {
+            match self.peek().kind {
+                TokenKind::Plus => {
+                    self.next();
+                    terms.push(self.multiplicative()?);
+                }
+                TokenKind::Minus => {
+                    self.next();
+                    let term = self.multiplicative()?;
+                    terms.push(self.negate(term));
+                }
+                _ => break Ok(self.parser.pool.sum(terms)),
+            }
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<AtomId, ParseError> {
+        let mut factors = vec![self.unary()?];
+
+        loop {
+            match self.peek().kind {
+                TokenKind::Star => {
+                    self.next();
+                    factors.push(self.unary()?);
+                }
+                TokenKind::Slash => {
+                    self.next();
+                    let denominator = self.unary()?;
+                    let numerator = self.parser.pool.product(factors.drain(..));
+                    factors.push(self.parser.pool.fraction(numerator, denominator));
+                }
+                TokenKind::Number(_)
+                | TokenKind::Ident(_)
+                | TokenKind::Wildcard(_)
+                | TokenKind::LParen
+                | TokenKind::LBrace
+                | TokenKind::LBracket
+                | TokenKind::Sqrt
+                | TokenKind::Integral
+                    if self.differential().is_none() && !self.at_conversion() =>
+                {
+                    factors.push(self.power()?)
+                }
+                _ => break Ok(self.parser.pool.product(factors)),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<AtomId, ParseError> {
+        match self.peek().kind {
+            TokenKind::Minus => {
+                self.next();
+                let operand = self.unary()?;
+                Ok(self.negate(operand))
+            }
+            TokenKind::Plus => {
+                self.next();
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    /// Negate the given expression, folding the sign into numeric literals
+    fn negate(&mut self, operand: AtomId) -> AtomId {
+        match self.parser.pool.get(operand) {
+            Atom::Integer(i) => self.parser.pool.integer(-i),
+            Atom::Float(f) => self.parser.pool.float(-f.value()),
+            _ => self.parser.pool.neg(operand),
+        }
+    }
+
+    fn power(&mut self) -> Result<AtomId, ParseError> {
+        let base = self.primary()?;
+
+        match self.peek().kind.clone() {
+            TokenKind::Caret => {
+                self.next();
+                let exponent = self.unary()?;
+                Ok(self.parser.pool.power(base, exponent))
+            }
+            TokenKind::Superscript(digits) => {
+                let token = self.next();
+                let exponent = self.integer(&digits, token.span)?;
+                Ok(self.parser.pool.power(base, exponent))
+            }
+            _ => Ok(base),
+        }
+    }
+
+    fn integer(&mut self, digits: &str, span: Range<usize>) -> Result<AtomId, ParseError> {
+        digits
+            .parse::<BigInt>()
+            .map(|i| self.parser.pool.integer(i))
+            .map_err(|_| ParseError {
+                kind: ParseErrorKind::InvalidNumber,
+                span,
+            })
+    }
+
+    fn primary(&mut self) -> Result<AtomId, ParseError> {
+        let token = self.peek().clone();
+
+        match token.kind {
+            TokenKind::Number(text) => {
+                self.next();
+                match text.bytes().all(|b| b.is_ascii_digit()) {
+                    true => self.integer(text, token.span),
+                    false => text
+                        .parse::<f64>()
+                        .map(|f| self.parser.pool.float(f))
+                        .map_err(|_| ParseError {
+                            kind: ParseErrorKind::InvalidNumber,
+                            span: token.span,
+                        }),
+                }
+            }
+            TokenKind::Ident(name) => match self.derivative() {
+                Some((variable, order, len)) => {
+                    for _ in 0..len {
+                        self.next();
+                    }
+                    let inner = self.power()?;
+                    let variable = self.parser.symbols.intern(&canonical_name(variable));
+                    Ok(self.parser.pool.derivative(variable, order, inner))
+                }
+                None => {
+                    self.next();
+                    self.ident(name, token.span)
+                }
+            },
+            TokenKind::LParen | TokenKind::LBrace => {
+                self.next();
+                let saved = std::mem::take(&mut self.integral_depth);
+                let inner = self.relation();
+                self.integral_depth = saved;
+                let inner = inner?;
+                self.close(
+                    match token.kind {
+                        TokenKind::LParen => TokenKind::RParen,
+                        _ => TokenKind::RBrace,
+                    },
+                    &token,
+                )?;
+                Ok(inner)
+            }
+            TokenKind::LBracket => {
+                self.next();
+                let saved = std::mem::take(&mut self.integral_depth);
+                let matrix = self.matrix(&token);
+                self.integral_depth = saved;
+                matrix
+            }
+            TokenKind::Wildcard(name) if self.wildcards.is_some() => {
+                self.next();
+                self.wildcard(name)
+            }
+            TokenKind::Sqrt => {
+                self.next();
+                let arg = self.power()?;
+                Ok(self.parser.pool.apply(Function::Sqrt, [arg]))
+            }
+            TokenKind::Integral => {
+                self.next();
+                self.integral(token.span)
+            }
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+
+    /// Parse the remainder of a matrix opened by `open`, written as a list of rows such as
+    /// `[[a, b], [c, d]]` or as the list of entries of a column vector such as `[x, y]`
+    fn matrix(&mut self, open: &Token<'_>) -> Result<AtomId, ParseError> {
+        let rows = match self.peek().kind == TokenKind::LBracket {
+            true => {
+                let mut rows = Vec::new();
+                loop {
+                    let row = self.expect(TokenKind::LBracket, "'['")?;
+                    rows.push(self.entries()?);
+                    self.close(TokenKind::RBracket, &row)?;
+                    if !self.eat(TokenKind::Comma) {
+                        break rows;
+                    }
+                }
+            }
+            false => self.entries()?.into_iter().map(|e| vec![e]).collect(),
+        };
+        self.close(TokenKind::RBracket, open)?;
+
+        let end = self.tokens[self.pos - 1].span.end;
+        self.parser.pool.matrix(rows).ok_or(ParseError {
+            kind: ParseErrorKind::RaggedMatrix,
+            span: open.span.start..end,
+        })
+    }
+
+    /// Parse a comma separated list of expressions
+    fn entries(&mut self) -> Result<Vec<AtomId>, ParseError> {
+        let mut entries = vec![self.relation()?];
+        while self.eat(TokenKind::Comma) {
+            entries.push(self.relation()?);
+        }
+        Ok(entries)
+    }
+
+    fn ident(&mut self, name: &'s str, span: Range<usize>) -> Result<AtomId, ParseError> {
+        let builtin = Function::builtin(name);
+        let user = self.parser.functions.contains(name);
+        if let Some(unit) = Unit::from_symbol(name).filter(|_| self.parser.units && !user) {
+            return Ok(self.parser.pool.unit(unit));
+        }
+        if name == "O" && !user && self.peek().kind == TokenKind::LParen {
+            let open = self.next();
+            let saved = std::mem::take(&mut self.integral_depth);
+            let term = self.additive();
+            self.integral_depth = saved;
+            let term = term?;
+            self.close(TokenKind::RParen, &open)?;
+            return Ok(self.parser.pool.order(term));
+        }
+        if builtin.is_none() && !user {
+            let sym = self.parser.symbols.intern(&canonical_name(name));
+            return Ok(self.parser.pool.symbol(sym));
+        }
+
+        let func = builtin.unwrap_or_else(|| Function::User(self.parser.symbols.intern(name)));
+
+        let args = match self.peek().kind {
+            TokenKind::LParen => {
+                let open = self.next();
+                let mut args = vec![self.relation()?];
+                while self.eat(TokenKind::Comma) {
+                    args.push(self.relation()?);
+                }
+                self.close(TokenKind::RParen, &open)?;
+                args
+            }
+            _ if user => return Err(self.unexpected("'(' after function name")),
+            _ => vec![self.power()?],
+        };
+
+        if let Some(expected) = func.arity().filter(|n| *n != args.len()) {
+            return Err(ParseError {
+                kind: ParseErrorKind::Arity {
+                    name: name.to_owned(),
+                    expected,
+                    found: args.len(),
+                },
+                span,
+            });
+        }
+
+        Ok(self.parser.pool.apply(func, args))
+    }
+
+    fn wildcard(&mut self, name: &str) -> Result<AtomId, ParseError> {
+        let sym = self.parser.symbols.intern(&format!("?{name}"));
+        let constraint = match self.eat(TokenKind::Colon) {
+            true => {
+                let token = self.next();
+                let TokenKind::Ident(name) = token.kind else {
+                    return Err(ParseError {
+                        kind: ParseErrorKind::Unexpected {
+                            expected: "a constraint such as 'integer'",
+                            found: token.kind.to_string(),
+                        },
+                        span: token.span,
+                    });
+                };
+                Constraint::from_name(name).ok_or_else(|| ParseError {
+                    kind: ParseErrorKind::UnknownConstraint(name.to_owned()),
+                    span: token.span,
+                })?
+            }
+            false => Constraint::Any,
+        };
+
+        let wildcards = self.wildcards.as_mut().expect("Wildcards are allowed");
+        let existing = wildcards.entry(sym).or_insert(constraint);
+        if constraint != Constraint::Any {
+            *existing = constraint;
+        }
+        Ok(self.parser.pool.symbol(sym))
+    }
+
+    /// Parse a bound of an integral, which may be signed as in `∫_-1^1` but does not take an
+    /// exponent since the `^` introduces the upper bound
+    fn bound(&mut self) -> Result<AtomId, ParseError> {
+        match self.peek().kind {
+            TokenKind::Minus => {
+                self.next();
+                let operand = self.bound()?;
+                Ok(self.negate(operand))
+            }
+            TokenKind::Plus => {
+                self.next();
+                self.bound()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn integral(&mut self, span: Range<usize>) -> Result<AtomId, ParseError> {
+        let lower = match self.eat(TokenKind::Underscore) {
+            true => Some(self.bound()?),
+            false => None,
+        };
+        let upper = match self.peek().kind.clone() {
+            TokenKind::Caret => {
+                self.next();
+                Some(self.bound()?)
+            }
+            TokenKind::Superscript(digits) => {
+                let token = self.next();
+                Some(self.integer(&digits, token.span)?)
+            }
+            _ => None,
+        };
+
+        self.integral_depth += 1;
+        let integrand = match self.differential() {
+            Some(_) => Ok(self.parser.pool.integer(1)),
+            None => self.additive(),
+        };
+        self.integral_depth -= 1;
+        let integrand = integrand?;
+
+        self.integral_depth += 1;
+        let variable = self.differential();
+        self.integral_depth -= 1;
+
+        let Some((variable, len)) = variable else {
+            return Err(ParseError {
+                kind: ParseErrorKind::ExpectedDifferential,
+                span: span.start..self.peek().span.start.max(span.end),
+            });
+        };
+        for _ in 0..len {
+            self.next();
+        }
+
+        let variable = self.parser.symbols.intern(&canonical_name(variable));
+        Ok(self.parser.pool.insert(Atom::Integral {
+            variable,
+            upper,
+            lower,
+            integrand,
+        }))
+    }
+}
+
+/// Get the name a symbol written as `name` is interned as, with braces removed from the subscript
+/// and spelled out Greek letters such as `alpha` replaced by their character
+fn canonical_name(name: &str) -> String {
+    let (base, sub) = symbol::split_subscript(name);
+    let mut canonical = match symbol::greek_letter(base) {
+        Some(letter) => letter.to_string(),
+        None => base.to_owned(),
+    };
+    if let Some(sub) = sub {
+        canonical.push('_');
+        canonical.push_str(sub);
+    }
+    canonical
+}
@@ -0,0 +1,306 @@
+use std::ops::Range;
+
+use crate::atom::{symbol, Relation, Unit};
+
+use super::{ParseError, ParseErrorKind};
+
+/// A single lexical element of linear math input and the byte range it was read from
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token<'s> {
+    pub kind: TokenKind<'s>,
+    pub span: Range<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind<'s> {
+    /// Integer or decimal literal, including any exponent
+    Number(&'s str),
+    /// Symbol or function name, including any subscript
+    Ident(&'s str),
+    /// Name of a pattern wildcard written as `?name`, excluding the question mark
+    Wildcard(&'s str),
+    /// Exponent written with superscript characters, already converted to ASCII digits
+    Superscript(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    Underscore,
+    Comma,
+    Colon,
+    Equals,
+    Relation(Relation),
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Sqrt,
+    Integral,
+    Eof,
+}
+
+/// Split the given source into tokens, ending with a single [TokenKind::Eof]
+pub fn tokenize(src: &str) -> Result<Vec<Token<'_>>, ParseError> {
+    let mut lexer = Lexer { src, pos: 0 };
+    let mut tokens = Vec::new();
+
+    loop {
+        let token = lexer.next_token()?;
+        let eof = token.kind == TokenKind::Eof;
+        tokens.push(token);
+        if eof {
+            break Ok(tokens);
+        }
+    }
+}
+
+struct Lexer<'s> {
+    src: &'s str,
+    pos: usize,
+}
+
+impl<'s> Lexer<'s> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.src[self.pos..].chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn eat_while<F: Fn(char) -> bool>(&mut self, f: F) {
+        while self.peek().is_some_and(&f) {
+            self.bump();
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Token<'s>, ParseError> {
+        self.eat_while(char::is_whitespace);
+
+        let start = self.pos;
+        let Some(ch) = self.bump() else {
+            return Ok(Token {
+                kind: TokenKind::Eof,
+                span: start..start,
+            });
+        };
+
+        let kind = match ch {
+            '+' => TokenKind::Plus,
+            '-' | '−' => TokenKind::Minus,
+            '*' if self.peek() == Some('*') => {
+                self.bump();
+                TokenKind::Caret
+            }
+            '*' | '×' | '·' | '⋅' => TokenKind::Star,
+            '/' | '÷' => TokenKind::Slash,
+            '^' => TokenKind::Caret,
+            '_' => TokenKind::Underscore,
+            ',' => TokenKind::Comma,
+            ':' => TokenKind::Colon,
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            '{' => TokenKind::LBrace,
+            '}' => TokenKind::RBrace,
+            '[' => TokenKind::LBracket,
+            ']' => TokenKind::RBracket,
+            '√' => TokenKind::Sqrt,
+            '∫' => TokenKind::Integral,
+            '=' => TokenKind::Equals,
+            '≠' => TokenKind::Relation(Relation::NotEqual),
+            '≤' => TokenKind::Relation(Relation::LessEq),
+            '≥' => TokenKind::Relation(Relation::GreaterEq),
+            '!' if self.peek() == Some('=') => {
+                self.bump();
+                TokenKind::Relation(Relation::NotEqual)
+            }
+            '<' | '>' => {
+                let eq = self.peek() == Some('=');
+                if eq {
+                    self.bump();
+                }
+                TokenKind::Relation(match (ch, eq) {
+                    ('<', false) => Relation::Less,
+                    ('<', true) => Relation::LessEq,
+                    (_, false) => Relation::Greater,
+                    (_, true) => Relation::GreaterEq,
+                })
+            }
+            '∞' => TokenKind::Ident(&self.src[start..self.pos]),
+            '?' if self.peek().is_some_and(char::is_alphabetic) => {
+                let name = self.pos;
+                self.bump();
+                TokenKind::Wildcard(self.ident(name))
+            }
+            '0'..='9' | '.' => self.number(start)?,
+            ch if superscript_digit(ch).is_some() || ch == '⁻' => self.superscript(ch),
+            ch if ch.is_alphabetic() => TokenKind::Ident(self.ident(start)),
+            other => {
+                return Err(ParseError {
+                    kind: ParseErrorKind::UnknownCharacter(other),
+                    span: start..self.pos,
+                })
+            }
+        };
+
+        Ok(Token {
+            kind,
+            span: start..self.pos,
+        })
+    }
+
+    fn number(&mut self, start: usize) -> Result<TokenKind<'s>, ParseError> {
+        self.eat_while(|c| c.is_ascii_digit());
+        if self.src[start..self.pos].starts_with('.') || self.peek() == Some('.') {
+            if self.peek() == Some('.') {
+                self.bump();
+            }
+            self.eat_while(|c| c.is_ascii_digit());
+        }
+
+        // A second decimal point such as in `1.2.3` is a typo rather than two numbers
+        if self.peek() == Some('.') {
+            self.eat_while(|c| c.is_ascii_digit() || c == '.');
+            return Err(ParseError {
+                kind: ParseErrorKind::InvalidNumber,
+                span: start..self.pos,
+            });
+        }
+
+        let digits = &self.src[start..self.pos];
+        if !digits.bytes().any(|b| b.is_ascii_digit()) {
+            return Err(ParseError {
+                kind: ParseErrorKind::InvalidNumber,
+                span: start..self.pos,
+            });
+        }
+
+        // Only treat `e` as an exponent if digits follow, so that `2e` is still read as `2 * e`
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let digit_at = match self.peek_nth(1) {
+                Some('+' | '-') => 2,
+                _ => 1,
+            };
+            if self.peek_nth(digit_at).is_some_and(|c| c.is_ascii_digit()) {
+                for _ in 0..digit_at {
+                    self.bump();
+                }
+                self.eat_while(|c| c.is_ascii_digit());
+            }
+        }
+
+        Ok(TokenKind::Number(&self.src[start..self.pos]))
+    }
+
+    fn superscript(&mut self, first: char) -> TokenKind<'s> {
+        let mut digits = String::new();
+        let mut push = |ch: char| match ch {
+            '⁻' => digits.push('-'),
+            ch => digits.extend(superscript_digit(ch)),
+        };
+
+        push(first);
+        while let Some(ch) = self.peek().filter(|c| superscript_digit(*c).is_some()) {
+            self.bump();
+            push(ch);
+        }
+
+        TokenKind::Superscript(digits)
+    }
+
+    /// Read the rest of a name and its subscript, where a Greek letter such as `π` is always a
+    /// name of its own, so that `iπ` and `πr` are products of two symbols, unless the whole word
+    /// is a unit such as `kΩ` or `μs`
+    fn ident(&mut self, start: usize) -> &'s str {
+        let greek = |c: char| symbol::greek_name(c).is_some();
+        let letter = |c: char| c.is_alphanumeric() && superscript_digit(c).is_none();
+        let end = self.src[start..]
+            .find(|c| !letter(c))
+            .map_or(self.src.len(), |end| start + end);
+        let word = &self.src[start..end];
+        if word.chars().any(greek) && Unit::from_symbol(word).is_some() {
+            self.pos = end;
+        } else if !self.src[start..self.pos].chars().any(greek) {
+            self.eat_while(|c| letter(c) && !greek(c));
+        }
+
+        if self.peek() == Some('_') {
+            match self.peek_nth(1) {
+                Some('{') => {
+                    let save = self.pos;
+                    self.bump();
+                    self.bump();
+                    self.eat_while(|c| c.is_alphanumeric());
+                    if self.peek() == Some('}') {
+                        self.bump();
+                    } else {
+                        self.pos = save;
+                    }
+                }
+                Some(c) if c.is_alphanumeric() => {
+                    self.bump();
+                    self.eat_while(|c| c.is_alphanumeric() && superscript_digit(c).is_none());
+                }
+                _ => (),
+            }
+        }
+
+        &self.src[start..self.pos]
+    }
+}
+
+/// Get the ASCII digit represented by the given superscript digit character
+pub fn superscript_digit(ch: char) -> Option<char> {
+    Some(match ch {
+        '⁰' => '0',
+        '¹' => '1',
+        '²' => '2',
+        '³' => '3',
+        '⁴' => '4',
+        '⁵' => '5',
+        '⁶' => '6',
+        '⁷' => '7',
+        '⁸' => '8',
+        '⁹' => '9',
+        _ => return None,
+    })
+}
+
+impl std::fmt::Display for TokenKind<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "number '{n}'"),
+            Self::Ident(i) => write!(f, "'{i}'"),
+            Self::Wildcard(w) => write!(f, "wildcard '?{w}'"),
+            Self::Superscript(s) => write!(f, "exponent '{s}'"),
+            Self::Plus => write!(f, "'+'"),
+            Self::Minus => write!(f, "'-'"),
+            Self::Star => write!(f, "'*'"),
+            Self::Slash => write!(f, "'/'"),
+            Self::Caret => write!(f, "'^'"),
+            Self::Underscore => write!(f, "'_'"),
+            Self::Comma => write!(f, "','"),
+            Self::Colon => write!(f, "':'"),
+            Self::Equals => write!(f, "'='"),
+            Self::Relation(r) => write!(f, "'{}'", r.ascii()),
+            Self::LParen => write!(f, "'('"),
+            Self::RParen => write!(f, "')'"),
+            Self::LBrace => write!(f, "'{{'"),
+            Self::RBrace => write!(f, "'}}'"),
+            Self::LBracket => write!(f, "'['"),
+            Self::RBracket => write!(f, "']'"),
+            Self::Sqrt => write!(f, "'√'"),
+            Self::Integral => write!(f, "'∫'"),
+            Self::Eof => write!(f, "end of input"),
+        }
+    }
+}
@@ -0,0 +1,652 @@
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use num_integer::Integer;
+use num_traits::{Signed, ToPrimitive};
+
+use crate::atom::{Atom, AtomId, BigInt, ExprPool, Rational};
+
+mod factor;
+mod modular;
+mod quotient;
+
+pub use factor::Factorization;
+pub(crate) use quotient::Quotient;
+
+/// Largest integer power of a polynomial expanded when converting an expression, above which the
+/// power is kept as a generator
+const MAX_EXPONENT: u32 = 256;
+
+/// A product of generators raised to positive integer powers, sorted by generator.
+///
+/// Monomials are ordered lexicographically, with generators of lower [AtomId] more significant
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Monomial(Vec<(AtomId, u32)>);
+
+/// A sparse multivariate polynomial with rational coefficients.
+///
+/// The variables of the polynomial, called generators, may be any expression that is not itself a
+/// polynomial, such as a symbol, a function application or a fractional power. Generators are
+/// treated as algebraically independent, so `sin(x)` and `cos(x)` are unrelated
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Polynomial {
+    terms: BTreeMap<Monomial, Rational>,
+}
+
+impl Monomial {
+    /// Get the empty monomial, equal to one
+    pub fn one() -> Self {
+        Self::default()
+    }
+
+    /// Get the monomial `generator ^ power`
+    pub fn generator(generator: AtomId, power: u32) -> Self {
+        match power {
+            0 => Self::one(),
+            power => Self(vec![(generator, power)]),
+        }
+    }
+
+    /// Get the generators of this monomial with their positive powers
+    pub fn powers(&self) -> &[(AtomId, u32)] {
+        &self.0
+    }
+
+    /// Get the power of `generator` in this monomial
+    pub fn degree(&self, generator: AtomId) -> u32 {
+        self.0
+            .iter()
+            .find(|(g, _)| *g == generator)
+            .map_or(0, |(_, power)| *power)
+    }
+
+    /// Get the sum of the powers of every generator in this monomial
+    pub fn total_degree(&self) -> u32 {
+        self.0.iter().map(|(_, power)| power).sum()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Combine the powers of two monomials with `op`, dropping generators with a power of zero
+    fn zip(&self, rhs: &Self, mut op: impl FnMut(u32, u32) -> Option<u32>) -> Option<Self> {
+        let mut out = Vec::with_capacity(self.0.len() + rhs.0.len());
+        let (mut lhs, mut rhs) = (self.0.iter().peekable(), rhs.0.iter().peekable());
+        loop {
+            let (generator, power) = match (lhs.peek(), rhs.peek()) {
+                (None, None) => break,
+                (Some((a, x)), Some((b, y))) if a == b => {
+                    lhs.next();
+                    rhs.next();
+                    (*a, op(*x, *y)?)
+                }
+                (Some((a, x)), Some((b, _))) if a < b => {
+                    lhs.next();
+                    (*a, op(*x, 0)?)
+                }
+                (Some((a, x)), None) => {
+                    lhs.next();
+                    (*a, op(*x, 0)?)
+                }
+                (_, Some((b, y))) => {
+                    rhs.next();
+                    (*b, op(0, *y)?)
+                }
+            };
+            if power != 0 {
+                out.push((generator, power));
+            }
+        }
+        Some(Self(out))
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        self.zip(rhs, |a, b| Some(a + b))
+            .expect("Multiplication always succeeds")
+    }
+
+    /// Divide this monomial by `rhs`, returning `None` if `rhs` does not divide it
+    pub fn div(&self, rhs: &Self) -> Option<Self> {
+        self.zip(rhs, |a, b| a.checked_sub(b))
+    }
+
+    /// Get the greatest monomial dividing both monomials
+    pub fn gcd(&self, rhs: &Self) -> Self {
+        self.zip(rhs, |a, b| Some(a.min(b)))
+            .expect("Minimum always succeeds")
+    }
+
+    /// Remove `generator` from this monomial
+    fn without(&self, generator: AtomId) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter(|(g, _)| *g != generator)
+                .copied()
+                .collect(),
+        )
+    }
+}
+
+impl Ord for Monomial {
+    fn cmp(&self, other: &Self) -> Ordering {
+        for (a, b) in self.0.iter().zip(&other.0) {
+            let order = match a.0.cmp(&b.0) {
+                // A generator missing from the other monomial has power zero there
+                Ordering::Less => Ordering::Greater,
+                Ordering::Greater => Ordering::Less,
+                Ordering::Equal => a.1.cmp(&b.1),
+            };
+            if order.is_ne() {
+                return order;
+            }
+        }
+        self.0.len().cmp(&other.0.len())
+    }
+}
+
+impl PartialOrd for Monomial {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Polynomial {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::constant(Rational::integer(1))
+    }
+
+    pub fn constant(value: Rational) -> Self {
+        Self::term(Monomial::one(), value)
+    }
+
+    /// Get the polynomial consisting of a single generator
+    pub fn generator(generator: AtomId) -> Self {
+        Self::term(Monomial::generator(generator, 1), Rational::integer(1))
+    }
+
+    /// Get the polynomial with a single term
+    pub fn term(monomial: Monomial, coefficient: Rational) -> Self {
+        let mut out = Self::zero();
+        out.add_term(monomial, coefficient);
+        out
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Get the value of this polynomial if it has no generators
+    pub fn as_constant(&self) -> Option<Rational> {
+        match self.terms.iter().next() {
+            None => Some(Rational::integer(0)),
+            Some((monomial, c)) if monomial.is_one() && self.terms.len() == 1 => Some(c.clone()),
+            _ => None,
+        }
+    }
+
+    /// Iterate over the nonzero terms of this polynomial in increasing monomial order
+    pub fn terms(&self) -> impl DoubleEndedIterator<Item = (&Monomial, &Rational)> {
+        self.terms.iter()
+    }
+
+    /// Get the generators appearing in this polynomial in increasing order
+    pub fn generators(&self) -> Vec<AtomId> {
+        let mut generators = self
+            .terms
+            .keys()
+            .flat_map(|monomial| monomial.0.iter().map(|(g, _)| *g))
+            .collect::<Vec<_>>();
+        generators.sort();
+        generators.dedup();
+        generators
+    }
+
+    /// Get the highest power of `generator` in this polynomial, taking the degree of zero to be
+    /// zero
+    pub fn degree(&self, generator: AtomId) -> u32 {
+        self.terms
+            .keys()
+            .map(|monomial| monomial.degree(generator))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the term with the greatest monomial
+    pub fn leading(&self) -> Option<(&Monomial, &Rational)> {
+        self.terms.last_key_value()
+    }
+
+    fn add_term(&mut self, monomial: Monomial, coefficient: Rational) {
+        let sum = match self.terms.remove(&monomial) {
+            Some(existing) => &existing + &coefficient,
+            None => coefficient,
+        };
+        if !sum.is_zero() {
+            self.terms.insert(monomial, sum);
+        }
+    }
+
+    pub fn scale(&self, factor: &Rational) -> Self {
+        match factor.is_zero() {
+            true => Self::zero(),
+            false => Self {
+                terms: self
+                    .terms
+                    .iter()
+                    .map(|(monomial, c)| (monomial.clone(), c * factor))
+                    .collect(),
+            },
+        }
+    }
+
+    pub fn pow(&self, exponent: u32) -> Self {
+        let mut out = Self::one();
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                out = &out * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        out
+    }
+
+    /// View this polynomial as a polynomial in `generator` whose coefficients are polynomials in
+    /// the remaining generators, returning the nonzero coefficient of each power
+    pub fn coefficients(&self, generator: AtomId) -> BTreeMap<u32, Self> {
+        let mut out = BTreeMap::<u32, Self>::new();
+        for (monomial, c) in &self.terms {
+            out.entry(monomial.degree(generator))
+                .or_default()
+                .add_term(monomial.without(generator), c.clone());
+        }
+        out
+    }
+
+    /// Get the coefficient of `generator ^ power`, a polynomial in the remaining generators
+    pub fn coefficient(&self, generator: AtomId, power: u32) -> Self {
+        let mut out = Self::zero();
+        for (monomial, c) in &self.terms {
+            if monomial.degree(generator) == power {
+                out.add_term(monomial.without(generator), c.clone());
+            }
+        }
+        out
+    }
+
+    /// Differentiate this polynomial with respect to `generator`
+    pub fn derivative(&self, generator: AtomId) -> Self {
+        let mut out = Self::zero();
+        for (monomial, c) in &self.terms {
+            let power = monomial.degree(generator);
+            if power > 0 {
+                let lowered = monomial
+                    .without(generator)
+                    .mul(&Monomial::generator(generator, power - 1));
+                out.add_term(lowered, c * &Rational::integer(power));
+            }
+        }
+        out
+    }
+
+    /// Divide this polynomial by a nonzero `divisor`, returning the quotient and remainder.
+    ///
+    /// Terms are divided by the leading term of `divisor` in monomial order, so the remainder is
+    /// zero whenever `divisor` divides this polynomial exactly
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let (lead, lead_coefficient) = divisor.leading().expect("divisor must be nonzero");
+        let mut quotient = Self::zero();
+        let mut remainder = Self::zero();
+        let mut rest = self.clone();
+        while let Some((monomial, c)) = rest.terms.pop_last() {
+            match monomial.div(lead) {
+                Some(shift) => {
+                    let factor = c
+                        .checked_div(lead_coefficient)
+                        .expect("Nonzero leading term");
+                    for (m, d) in divisor.terms.iter().rev().skip(1) {
+                        rest.add_term(m.mul(&shift), -&(d * &factor));
+                    }
+                    quotient.add_term(shift, factor);
+                }
+                None => remainder.add_term(monomial, c),
+            }
+        }
+        (quotient, remainder)
+    }
+
+    /// Divide this polynomial by `divisor`, returning `None` unless the division is exact
+    pub fn divide(&self, divisor: &Self) -> Option<Self> {
+        if divisor.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = self.div_rem(divisor);
+        remainder.is_zero().then_some(quotient)
+    }
+
+    /// Get the positive rational number whose quotient with this polynomial has coprime integer
+    /// coefficients
+    pub fn content(&self) -> Rational {
+        let (numerator, denominator) = self.terms.values().fold(
+            (BigInt::from(0), BigInt::from(1)),
+            |(numerator, denominator), c| {
+                (
+                    numerator.gcd(c.numerator()),
+                    denominator.lcm(c.denominator()),
+                )
+            },
+        );
+        Rational::new(numerator, denominator).unwrap_or_else(|| Rational::integer(0))
+    }
+
+    /// Split this polynomial into a rational constant and a primitive polynomial with coprime
+    /// integer coefficients and a positive leading coefficient
+    pub fn primitive(&self) -> (Rational, Self) {
+        let content = self.content();
+        let Some((_, lead)) = self.leading() else {
+            return (content, Self::zero());
+        };
+
+        let content = match lead.is_negative() {
+            true => -&content,
+            false => content,
+        };
+        let inverse = content.recip().expect("Content of nonzero polynomial");
+        (content, self.scale(&inverse))
+    }
+
+    /// Get the greatest common divisor of the coefficients of this polynomial viewed as a
+    /// polynomial in `generator`, itself a primitive polynomial in the other generators
+    pub fn content_in(&self, generator: AtomId) -> Self {
+        self.coefficients(generator)
+            .values()
+            .fold(Self::zero(), |content, c| content.gcd(c))
+    }
+
+    /// Split this polynomial into its content in `generator` and the quotient by that content
+    fn split_content(&self, generator: AtomId) -> (Self, Self) {
+        let content = self.content_in(generator);
+        let primitive = self
+            .divide(&content)
+            .expect("Content divides the polynomial");
+        (content, primitive)
+    }
+
+    /// Get the remainder of `l^k` times this polynomial divided by `divisor` as polynomials in
+    /// `generator`, where `l` is the leading coefficient of `divisor`. Constant factors of the
+    /// remainder are dropped to keep its coefficients small
+    fn pseudo_remainder(&self, divisor: &Self, generator: AtomId) -> Self {
+        let degree = divisor.degree(generator);
+        let lead = divisor.coefficient(generator, degree);
+        let mut remainder = self.clone();
+        while !remainder.is_zero() && remainder.degree(generator) >= degree {
+            let power = remainder.degree(generator);
+            let shift = Self::term(
+                Monomial::generator(generator, power - degree),
+                Rational::integer(1),
+            );
+            let shift = &shift * &remainder.coefficient(generator, power);
+            remainder = &(&lead * &remainder) - &(&shift * divisor);
+            remainder = remainder.primitive().1;
+        }
+        remainder
+    }
+
+    /// Get the greatest common divisor of two polynomials, scaled to be primitive with a positive
+    /// leading coefficient. The GCD of two constants is one, and the GCD of zero and zero is zero
+    pub fn gcd(&self, other: &Self) -> Self {
+        if self.is_zero() {
+            return other.primitive().1;
+        }
+        if other.is_zero() {
+            return self.primitive().1;
+        }
+
+        let generators = self.generators().into_iter().chain(other.generators());
+        let Some(generator) = generators.min() else {
+            return Self::one();
+        };
+
+        match (self.degree(generator), other.degree(generator)) {
+            (0, _) => self.gcd(&other.content_in(generator)),
+            (_, 0) => other.gcd(&self.content_in(generator)),
+            (m, n) => {
+                let (lhs_content, lhs) = self.split_content(generator);
+                let (rhs_content, rhs) = other.split_content(generator);
+                let content = lhs_content.gcd(&rhs_content);
+
+                // Euclid's algorithm over primitive pseudo-remainders
+                let (mut a, mut b) = match m >= n {
+                    true => (lhs, rhs),
+                    false => (rhs, lhs),
+                };
+                while !b.is_zero() {
+                    let remainder = a.pseudo_remainder(&b, generator);
+                    a = b;
+                    b = match remainder.is_zero() {
+                        true => remainder,
+                        false => remainder.split_content(generator).1,
+                    };
+                }
+
+                let (_, a) = a.split_content(generator);
+                (&content * &a).primitive().1
+            }
+        }
+    }
+
+    /// Decompose this polynomial into square-free primitive factors with their multiplicities,
+    /// so that it is a constant times the product of each factor raised to its multiplicity.
+    /// Factors sharing a multiplicity are not necessarily combined
+    pub fn square_free(&self) -> Vec<(Self, u32)> {
+        let Some(generator) = self.generators().first().copied() else {
+            return Vec::new();
+        };
+
+        let (content, f) = self.split_content(generator);
+        let mut factors = content.square_free();
+
+        // Yun's algorithm
+        let derivative = f.derivative(generator);
+        let common = f.gcd(&derivative);
+        let mut b = f.divide(&common).expect("GCD divides");
+        let mut c = derivative.divide(&common).expect("GCD divides");
+        let mut multiplicity = 1;
+        while b.degree(generator) > 0 {
+            let d = &c - &b.derivative(generator);
+            let a = b.gcd(&d);
+            if a.degree(generator) > 0 {
+                factors.push((a.clone(), multiplicity));
+            }
+            b = b.divide(&a).expect("GCD divides");
+            c = d.divide(&a).expect("GCD divides");
+            multiplicity += 1;
+        }
+        factors
+    }
+
+    /// Insert this polynomial as an unsimplified sum of terms in decreasing monomial order
+    pub fn to_atom(&self, pool: &mut ExprPool) -> AtomId {
+        let mut terms = Vec::new();
+        for (monomial, c) in self.terms.iter().rev() {
+            let mut factors = Vec::new();
+            if !c.is_one() || monomial.is_one() {
+                factors.push(pool.number(c.clone()));
+            }
+            for (generator, power) in monomial.powers() {
+                factors.push(match power {
+                    1 => *generator,
+                    power => {
+                        let power = pool.integer(*power);
+                        pool.power(*generator, power)
+                    }
+                });
+            }
+            terms.push(pool.product(factors));
+        }
+        pool.sum(terms)
+    }
+}
+
+impl Add for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, rhs: Self) -> Polynomial {
+        let mut out = self.clone();
+        for (monomial, c) in &rhs.terms {
+            out.add_term(monomial.clone(), c.clone());
+        }
+        out
+    }
+}
+
+impl Sub for &Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, rhs: Self) -> Polynomial {
+        self + &-rhs
+    }
+}
+
+impl Mul for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: Self) -> Polynomial {
+        let mut out = Polynomial::zero();
+        for (a, x) in &self.terms {
+            for (b, y) in &rhs.terms {
+                out.add_term(a.mul(b), x * y);
+            }
+        }
+        out
+    }
+}
+
+impl Neg for &Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Polynomial {
+        self.scale(&Rational::integer(-1))
+    }
+}
+
+impl ExprPool {
+    /// Convert the expression rooted at `id` into a polynomial, taking every subexpression that
+    /// is not a sum, product, exact number or nonnegative integer power as a generator
+    pub fn polynomial(&mut self, id: AtomId) -> Polynomial {
+        let normal = self.normal_form(id);
+        convert(self, normal, &mut |_, generator| generator)
+    }
+
+    /// Expand products and integer powers of sums throughout the expression rooted at `id`,
+    /// including inside function arguments and denominators, and simplify the result
+    pub fn expand(&mut self, id: AtomId) -> AtomId {
+        let normal = self.normal_form(id);
+        let expanded = expand_normal(self, normal);
+        self.simplify(expanded)
+    }
+
+    /// Factor every polynomial in the expression rooted at `id` into irreducible factors over the
+    /// integers and simplify the result. Numerators and denominators are factored separately
+    pub fn factor(&mut self, id: AtomId) -> AtomId {
+        let normal = self.normal_form(id);
+        let factored = factor_normal(self, normal);
+        self.simplify(factored)
+    }
+}
+
+/// Convert a normal-form expression into a polynomial, mapping each generator with `generator`
+/// before it is added
+fn convert(
+    pool: &mut ExprPool,
+    id: AtomId,
+    generator: &mut dyn FnMut(&mut ExprPool, AtomId) -> AtomId,
+) -> Polynomial {
+    match pool.get(id).clone() {
+        atom @ (Atom::Integer(_) | Atom::Rational(_)) => {
+            Polynomial::constant(atom.to_rational().expect("Exact number"))
+        }
+        Atom::Sum(terms) => terms.iter().fold(Polynomial::zero(), |sum, term| {
+            &sum + &convert(pool, *term, generator)
+        }),
+        Atom::Product(factors) => factors.iter().fold(Polynomial::one(), |product, factor| {
+            &product * &convert(pool, *factor, generator)
+        }),
+        Atom::Neg(inner) => -&convert(pool, inner, generator),
+        Atom::Power { base, exponent } => {
+            let power = pool
+                .get(exponent)
+                .to_rational()
+                .filter(Rational::is_integer)
+                .and_then(|e| e.numerator().to_u32())
+                .filter(|power| *power <= MAX_EXPONENT);
+            match power {
+                Some(power) => convert(pool, base, generator).pow(power),
+                None => Polynomial::generator(generator(pool, id)),
+            }
+        }
+        _ => Polynomial::generator(generator(pool, id)),
+    }
+}
+
+/// Expand a normal-form expression, expanding the children of every generator first and the
+/// denominators of negative integer powers as in `(x + 1)^-2 = (x^2 + 2x + 1)^-1`
+fn expand_normal(pool: &mut ExprPool, id: AtomId) -> AtomId {
+    let polynomial = convert(pool, id, &mut |pool, generator| {
+        let atom = pool.get(generator).clone();
+        if let Atom::Power { base, exponent } = atom {
+            if let Atom::Integer(power) = pool.get(exponent) {
+                if power.is_negative() {
+                    let power = pool.integer(-power.clone());
+                    let denominator = pool.power(base, power);
+                    let denominator = expand_normal(pool, denominator);
+                    let minus_one = pool.integer(-1);
+                    return pool.power(denominator, minus_one);
+                }
+            }
+        }
+        let atom = atom.map_children(|child| expand_normal(pool, child));
+        pool.insert(atom)
+    });
+    polynomial.to_atom(pool)
+}
+
+/// Factor a normal-form expression, factoring the bases of powers and the factors of products
+/// separately so that denominators are factored on their own
+fn factor_normal(pool: &mut ExprPool, id: AtomId) -> AtomId {
+    match pool.get(id).clone() {
+        Atom::Sum(_) => {
+            let polynomial = convert(pool, id, &mut |pool, generator| {
+                let atom = pool.get(generator).clone();
+                let atom = atom.map_children(|child| factor_normal(pool, child));
+                pool.insert(atom)
+            });
+            polynomial.factor().to_atom(pool)
+        }
+        Atom::Product(factors) => {
+            let factors = factors
+                .iter()
+                .map(|factor| factor_normal(pool, *factor))
+                .collect::<Vec<_>>();
+            pool.product(factors)
+        }
+        atom => {
+            let atom = atom.map_children(|child| factor_normal(pool, child));
+            pool.insert(atom)
+        }
+    }
+}
@@ -0,0 +1,560 @@
+use num_integer::Integer;
+use num_traits::{One, Pow, Signed, Zero};
+
+use super::{modular::Field, Monomial, Polynomial};
+use crate::atom::{AtomId, BigInt, ExprPool, Rational};
+
+/// Largest degree of the univariate image of a multivariate polynomial under Kronecker
+/// substitution, above which the polynomial is left unfactored
+const MAX_KRONECKER_DEGREE: u64 = 256;
+
+/// Largest number of candidate factors tried when recombining modular or substituted factors
+const MAX_COMBINATIONS: usize = 1 << 14;
+
+/// Number of suitable primes tried when looking for the modular factorization with the fewest
+/// factors
+const PRIME_ATTEMPTS: usize = 5;
+
+/// Largest prime tried when reducing a polynomial modulo a prime
+const MAX_PRIME: u64 = 1 << 15;
+
+/// A polynomial written as a rational constant times powers of distinct irreducible polynomials
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Factorization {
+    pub unit: Rational,
+    /// Primitive irreducible factors with integer coefficients and positive leading coefficients,
+    /// with their multiplicities
+    pub factors: Vec<(Polynomial, u32)>,
+}
+
+impl Factorization {
+    /// Multiply the factorization back out into a polynomial
+    pub fn expand(&self) -> Polynomial {
+        self.factors.iter().fold(
+            Polynomial::constant(self.unit.clone()),
+            |product, (f, m)| &product * &f.pow(*m),
+        )
+    }
+
+    /// Insert this factorization as an unsimplified product of powers of factors
+    pub fn to_atom(&self, pool: &mut ExprPool) -> AtomId {
+        let mut factors = Vec::new();
+        if !self.unit.is_one() || self.factors.is_empty() {
+            factors.push(pool.number(self.unit.clone()));
+        }
+        for (factor, multiplicity) in &self.factors {
+            let factor = factor.to_atom(pool);
+            factors.push(match multiplicity {
+                1 => factor,
+                m => {
+                    let m = pool.integer(*m);
+                    pool.power(factor, m)
+                }
+            });
+        }
+        pool.product(factors)
+    }
+}
+
+impl Polynomial {
+    /// Factor this polynomial into irreducible factors over the integers.
+    ///
+    /// Polynomials in one generator are factored completely by the Zassenhaus algorithm. Those in
+    /// several generators are split by their contents and then factored by Kronecker substitution,
+    /// leaving factors whose substituted degree is too large unfactored
+    pub fn factor(&self) -> Factorization {
+        let (unit, primitive) = self.primitive();
+        let Some(common) = primitive
+            .terms
+            .keys()
+            .cloned()
+            .reduce(|common, monomial| common.gcd(&monomial))
+            .filter(|_| primitive.as_constant().is_none())
+        else {
+            return Factorization {
+                unit,
+                factors: Vec::new(),
+            };
+        };
+
+        let mut factors = common
+            .powers()
+            .iter()
+            .map(|(g, power)| (Self::generator(*g), *power))
+            .collect::<Vec<_>>();
+        let rest = primitive
+            .divide(&Self::term(common, Rational::integer(1)))
+            .expect("Common monomial divides every term");
+
+        for (part, multiplicity) in rest.square_free() {
+            for factor in irreducible(&part) {
+                match factors.iter_mut().find(|(f, _)| *f == factor) {
+                    Some((_, m)) => *m += multiplicity,
+                    None => factors.push((factor, multiplicity)),
+                }
+            }
+        }
+
+        // The leading term of a product is the product of leading terms
+        let lead = factors.iter().fold(Rational::integer(1), |lead, (f, m)| {
+            let (_, c) = f.leading().expect("Factors are nonzero");
+            &lead * &c.pow(*m as i32).expect("Leading coefficient is nonzero")
+        });
+        let (_, self_lead) = self.leading().expect("Polynomial is nonconstant");
+        Factorization {
+            unit: self_lead
+                .checked_div(&lead)
+                .expect("Leading coefficient is nonzero"),
+            factors,
+        }
+    }
+}
+
+/// Split a primitive square-free polynomial with no monomial factor into irreducible factors
+fn irreducible(f: &Polynomial) -> Vec<Polynomial> {
+    let generators = f.generators();
+    match *generators {
+        [] => Vec::new(),
+        [generator] => zassenhaus(&dense(f, generator))
+            .iter()
+            .map(|factor| sparse(factor, generator).primitive().1)
+            .collect(),
+        _ => {
+            for generator in &generators {
+                let content = f.content_in(*generator);
+                if content.as_constant().is_none() {
+                    let rest = f.divide(&content).expect("Content divides");
+                    let mut factors = irreducible(&content);
+                    factors.extend(irreducible(&rest));
+                    return factors;
+                }
+            }
+            homogeneous(f, &generators).unwrap_or_else(|| kronecker(f, &generators))
+        }
+    }
+}
+
+/// Factor a homogeneous polynomial by setting its last generator to one, factoring the result in
+/// the remaining generators and homogenizing each factor again
+fn homogeneous(f: &Polynomial, generators: &[AtomId]) -> Option<Vec<Polynomial>> {
+    let degree = f.terms().map(|(m, _)| m.total_degree()).max()?;
+    if f.terms().any(|(m, _)| m.total_degree() != degree) {
+        return None;
+    }
+
+    let last = *generators.last()?;
+    let mut affine = Polynomial::zero();
+    for (monomial, c) in f.terms() {
+        affine.add_term(monomial.without(last), c.clone());
+    }
+
+    let factors = irreducible(&affine).into_iter().map(|factor| {
+        let degree = factor.terms().map(|(m, _)| m.total_degree()).max();
+        let mut out = Polynomial::zero();
+        for (monomial, c) in factor.terms() {
+            let missing = degree.unwrap_or(0) - monomial.total_degree();
+            out.add_term(monomial.mul(&Monomial::generator(last, missing)), c.clone());
+        }
+        out.primitive().1
+    });
+    Some(factors.collect())
+}
+
+/// Factor a multivariate polynomial by substituting powers of the first generator for the others,
+/// factoring the univariate image and searching for products of its factors that map back to
+/// factors of the original polynomial
+fn kronecker(f: &Polynomial, generators: &[AtomId]) -> Vec<Polynomial> {
+    // Every factor has a lower degree than `base` in each generator, so substitution is injective
+    let base = generators.iter().map(|g| f.degree(*g)).max().unwrap_or(0) as u64 + 1;
+    let mut weights = Vec::with_capacity(generators.len());
+    let mut weight = 1u64;
+    for _ in generators {
+        weights.push(weight);
+        weight = match weight.checked_mul(base) {
+            Some(next) => next,
+            None => return vec![f.clone()],
+        };
+    }
+    // The image has a lower degree than the next unused weight
+    if weight > MAX_KRONECKER_DEGREE {
+        return vec![f.clone()];
+    }
+
+    let x = generators[0];
+    let mut image = Polynomial::zero();
+    for (monomial, c) in f.terms() {
+        let power = generators
+            .iter()
+            .zip(&weights)
+            .map(|(g, w)| monomial.degree(*g) as u64 * w)
+            .sum::<u64>();
+        image.add_term(Monomial::generator(x, power as u32), c.clone());
+    }
+
+    let unsubstitute = |image: &[BigInt]| {
+        let mut out = Polynomial::zero();
+        for (power, c) in image.iter().enumerate() {
+            let monomial = generators
+                .iter()
+                .zip(&weights)
+                .map(|(g, w)| Monomial::generator(*g, (power as u64 / w % base) as u32))
+                .fold(Monomial::one(), |m, g| m.mul(&g));
+            out.add_term(monomial, Rational::integer(c.clone()));
+        }
+        out
+    };
+
+    // The image need not be square-free, so every repeated factor is a separate piece
+    let mut pieces = Vec::new();
+    let common = image.terms().map(|(m, _)| m.degree(x)).min().unwrap_or(0);
+    pieces.extend((0..common).map(|_| vec![BigInt::zero(), BigInt::one()]));
+    let image = image
+        .divide(&Polynomial::term(
+            Monomial::generator(x, common),
+            Rational::integer(1),
+        ))
+        .expect("Common power divides");
+    for (part, multiplicity) in image.square_free() {
+        for factor in zassenhaus(&dense(&part, x)) {
+            pieces.extend((0..multiplicity).map(|_| factor.clone()));
+        }
+    }
+
+    let mut remaining = f.clone();
+    let mut factors = Vec::new();
+    recombine(pieces, |subset| {
+        let product = subset
+            .iter()
+            .fold(vec![BigInt::one()], |product, piece| mul(&product, piece));
+        let candidate = unsubstitute(&product).primitive().1;
+        if candidate.as_constant().is_some() {
+            return false;
+        }
+
+        match remaining.divide(&candidate) {
+            Some(quotient) => {
+                remaining = quotient;
+                factors.push(candidate);
+                true
+            }
+            None => false,
+        }
+    });
+
+    if remaining.as_constant().is_none() {
+        factors.push(remaining.primitive().1);
+    }
+    factors
+}
+
+/// Factor a primitive square-free univariate polynomial with integer coefficients by factoring it
+/// modulo a prime, Hensel lifting the modular factors and recombining them into true factors
+fn zassenhaus(f: &[BigInt]) -> Vec<Vec<BigInt>> {
+    let degree = f.len() - 1;
+    if degree <= 1 {
+        return vec![f.to_vec()];
+    }
+
+    let lead = f[degree].clone();
+    let mut best: Option<(Field, Vec<Vec<u64>>)> = None;
+    let mut attempts = 0;
+    for p in (3..MAX_PRIME).step_by(2).filter(|p| is_prime(*p)) {
+        if (&lead % p).is_zero() {
+            continue;
+        }
+
+        let field = Field::new(p);
+        let reduced = field.reduce(f);
+        if !field.is_square_free(&reduced) {
+            continue;
+        }
+
+        let factors = field.factor(&reduced);
+        if best
+            .as_ref()
+            .is_none_or(|(_, best)| factors.len() < best.len())
+        {
+            best = Some((field, factors));
+        }
+        attempts += 1;
+        if attempts == PRIME_ATTEMPTS {
+            break;
+        }
+    }
+
+    let Some((field, factors)) = best.filter(|(_, factors)| factors.len() > 1) else {
+        return vec![f.to_vec()];
+    };
+
+    // Lift until the modulus exceeds twice the Mignotte bound on the coefficients of a factor
+    // multiplied by the leading coefficient
+    let norm = f.iter().map(BigInt::abs).max().unwrap_or_default();
+    let bound = (lead.abs() * norm * (degree + 1)) << (degree + 1);
+    let prime = BigInt::from(field.prime());
+    let mut modulus = prime.clone();
+    let mut exponent = 1;
+    while modulus <= bound {
+        modulus *= &prime;
+        exponent += 1;
+    }
+
+    let lifted = hensel_lift(field, f, &factors, exponent);
+    let mut remaining = f.to_vec();
+    let mut out = Vec::new();
+    recombine(lifted, |subset| {
+        let lead = remaining
+            .last()
+            .expect("Remaining factor is nonzero")
+            .clone();
+        let product = subset.iter().fold(vec![lead], |product, piece| {
+            mul(&product, piece)
+                .iter()
+                .map(|c| c.mod_floor(&modulus))
+                .collect()
+        });
+        let candidate = primitive(&symmetric(&product, &modulus));
+        match divide(&remaining, &candidate) {
+            Some(quotient) => {
+                remaining = quotient;
+                out.push(candidate);
+                true
+            }
+            None => false,
+        }
+    });
+
+    if remaining.len() > 1 {
+        out.push(primitive(&remaining));
+    }
+    out
+}
+
+/// Lift the factorization of `f` into its leading coefficient times monic `factors` modulo a
+/// prime into monic factors modulo the prime raised to `exponent`
+fn hensel_lift(
+    field: Field,
+    f: &[BigInt],
+    factors: &[Vec<u64>],
+    exponent: u32,
+) -> Vec<Vec<BigInt>> {
+    let modulus = Pow::pow(BigInt::from(field.prime()), exponent);
+    let mut target = f.to_vec();
+    let mut out = Vec::new();
+    for (i, factor) in factors[..factors.len() - 1].iter().enumerate() {
+        let lead = field.reduce(&target[target.len() - 1..]);
+        let cofactor = factors[i + 1..]
+            .iter()
+            .fold(lead, |product, f| field.mul(&product, f));
+        let (g, h) = lift_pair(field, &target, factor, &cofactor, exponent);
+        out.push(g.iter().map(|c| c.mod_floor(&modulus)).collect());
+        target = h.iter().map(|c| c.mod_floor(&modulus)).collect();
+    }
+
+    // The last cofactor is the final factor times the leading coefficient
+    let lead = target.last().expect("Cofactor is nonzero");
+    let inverse = lead.extended_gcd(&modulus).x;
+    out.push(
+        target
+            .iter()
+            .map(|c| (c * &inverse).mod_floor(&modulus))
+            .collect(),
+    );
+    out
+}
+
+/// Lift `f = gh (mod p)`, with `g` monic and `g` and `h` coprime, to `f = GH (mod p^exponent)` by
+/// linear Hensel lifting
+fn lift_pair(
+    field: Field,
+    f: &[BigInt],
+    g: &[u64],
+    h: &[u64],
+    exponent: u32,
+) -> (Vec<BigInt>, Vec<BigInt>) {
+    let (_, t) = field.bezout(g, h);
+    let prime = BigInt::from(field.prime());
+    let lift = |v: &[u64]| v.iter().map(|c| BigInt::from(*c)).collect::<Vec<_>>();
+    let (mut big_g, mut big_h) = (lift(g), lift(h));
+    let mut modulus = prime.clone();
+    for _ in 1..exponent {
+        // With e = (f - GH) / p^i, find a and b with ha + gb = e (mod p) and deg a < deg g
+        let error = sub(f, &mul(&big_g, &big_h))
+            .iter()
+            .map(|c| c / &modulus)
+            .collect::<Vec<_>>();
+        let e = field.reduce(&error);
+        let a = field.div_rem(&field.mul(&t, &e), g).1;
+        let b = field.div_rem(&field.sub(&e, &field.mul(&a, h)), g).0;
+
+        big_g = add(&big_g, &scale(&lift(&a), &modulus));
+        big_h = add(&big_h, &scale(&lift(&b), &modulus));
+        modulus *= &prime;
+    }
+    (big_g, big_h)
+}
+
+/// Search subsets of `pieces` in increasing size for ones whose product is a true factor, as
+/// decided by `accept`, which also removes that factor from the polynomial being factored. Gives
+/// up once [MAX_COMBINATIONS] subsets have been tried
+fn recombine<T>(mut pieces: Vec<T>, mut accept: impl FnMut(&[&T]) -> bool) {
+    let mut size = 1;
+    let mut attempts = 0;
+    'search: while 2 * size <= pieces.len() {
+        let mut indices = (0..size).collect::<Vec<_>>();
+        loop {
+            attempts += 1;
+            if attempts > MAX_COMBINATIONS {
+                return;
+            }
+
+            let subset = indices.iter().map(|i| &pieces[*i]).collect::<Vec<_>>();
+            if accept(&subset) {
+                for i in indices.iter().rev() {
+                    pieces.remove(*i);
+                }
+                continue 'search;
+            }
+            if !next_combination(&mut indices, pieces.len()) {
+                break;
+            }
+        }
+        size += 1;
+    }
+}
+
+/// Advance `indices` to the next subset of `0..n` of the same size in lexicographic order,
+/// returning `false` if it was the last
+fn next_combination(indices: &mut [usize], n: usize) -> bool {
+    let k = indices.len();
+    for i in (0..k).rev() {
+        if indices[i] < n - k + i {
+            indices[i] += 1;
+            for j in i + 1..k {
+                indices[j] = indices[j - 1] + 1;
+            }
+            return true;
+        }
+    }
+    false
+}
+
+fn is_prime(n: u64) -> bool {
+    n >= 2
+        && (2..)
+            .take_while(|d| d * d <= n)
+            .all(|d| !n.is_multiple_of(d))
+}
+
+/// Get the dense integer coefficients of a polynomial in the single generator `generator`
+fn dense(f: &Polynomial, generator: AtomId) -> Vec<BigInt> {
+    let mut out = vec![BigInt::zero(); f.degree(generator) as usize + 1];
+    for (monomial, c) in f.terms() {
+        out[monomial.degree(generator) as usize] = c.numerator().clone();
+    }
+    out
+}
+
+fn sparse(f: &[BigInt], generator: AtomId) -> Polynomial {
+    let mut out = Polynomial::zero();
+    for (power, c) in f.iter().enumerate() {
+        out.add_term(
+            Monomial::generator(generator, power as u32),
+            Rational::integer(c.clone()),
+        );
+    }
+    out
+}
+
+fn trim(mut f: Vec<BigInt>) -> Vec<BigInt> {
+    while f.last().is_some_and(Zero::is_zero) {
+        f.pop();
+    }
+    f
+}
+
+fn add(a: &[BigInt], b: &[BigInt]) -> Vec<BigInt> {
+    let zero = BigInt::zero();
+    let len = a.len().max(b.len());
+    trim(
+        (0..len)
+            .map(|i| a.get(i).unwrap_or(&zero) + b.get(i).unwrap_or(&zero))
+            .collect(),
+    )
+}
+
+fn sub(a: &[BigInt], b: &[BigInt]) -> Vec<BigInt> {
+    add(a, &scale(b, &BigInt::from(-1)))
+}
+
+fn mul(a: &[BigInt], b: &[BigInt]) -> Vec<BigInt> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut out = vec![BigInt::zero(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            out[i + j] += x * y;
+        }
+    }
+    trim(out)
+}
+
+fn scale(a: &[BigInt], factor: &BigInt) -> Vec<BigInt> {
+    trim(a.iter().map(|c| c * factor).collect())
+}
+
+/// Map coefficients modulo `modulus` into the symmetric range around zero
+fn symmetric(f: &[BigInt], modulus: &BigInt) -> Vec<BigInt> {
+    let half = modulus / 2;
+    trim(
+        f.iter()
+            .map(|c| {
+                let c = c.mod_floor(modulus);
+                match c > half {
+                    true => c - modulus,
+                    false => c,
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Divide out the content of a polynomial, making its leading coefficient positive
+fn primitive(f: &[BigInt]) -> Vec<BigInt> {
+    let content = f.iter().fold(BigInt::zero(), |content, c| content.gcd(c));
+    let content = match f.last().is_some_and(Signed::is_negative) {
+        true => -content,
+        false => content,
+    };
+    match content.is_zero() {
+        true => f.to_vec(),
+        false => f.iter().map(|c| c / &content).collect(),
+    }
+}
+
+/// Divide `f` by `g` over the integers, returning `None` unless the division is exact
+fn divide(f: &[BigInt], g: &[BigInt]) -> Option<Vec<BigInt>> {
+    if g.len() > f.len() {
+        return None;
+    }
+    // A factor's constant term divides the constant term of the product
+    if !g[0].is_zero() && !f[0].is_multiple_of(&g[0]) {
+        return None;
+    }
+
+    let lead = g.last()?;
+    let mut remainder = f.to_vec();
+    let mut quotient = vec![BigInt::zero(); f.len() - g.len() + 1];
+    for i in (0..quotient.len()).rev() {
+        let (factor, rest) = remainder[i + g.len() - 1].div_rem(lead);
+        if !rest.is_zero() {
+            return None;
+        }
+        for (j, c) in g.iter().enumerate() {
+            remainder[i + j] -= &factor * c;
+        }
+        quotient[i] = factor;
+    }
+    remainder.iter().all(Zero::is_zero).then(|| trim(quotient))
+}
@@ -0,0 +1,243 @@
+use num_integer::Integer;
+use num_traits::{Pow, ToPrimitive};
+
+use crate::atom::BigInt;
+
+/// Arithmetic on dense polynomials with coefficients in the integers modulo a small odd prime,
+/// stored from the constant term up with no trailing zero coefficients
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Field {
+    p: u64,
+}
+
+fn trim(mut coefficients: Vec<u64>) -> Vec<u64> {
+    while coefficients.last() == Some(&0) {
+        coefficients.pop();
+    }
+    coefficients
+}
+
+/// Get the degree of a polynomial, taking the degree of zero to be zero
+fn degree(f: &[u64]) -> usize {
+    f.len().saturating_sub(1)
+}
+
+/// Advance a xorshift generator, used to pick deterministic pseudo-random splitting polynomials
+fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+impl Field {
+    pub fn new(p: u64) -> Self {
+        Self { p }
+    }
+
+    pub fn prime(&self) -> u64 {
+        self.p
+    }
+
+    /// Reduce a polynomial with integer coefficients modulo the prime
+    pub fn reduce(&self, f: &[BigInt]) -> Vec<u64> {
+        let p = BigInt::from(self.p);
+        trim(
+            f.iter()
+                .map(|c| c.mod_floor(&p).to_u64().expect("Reduced below the prime"))
+                .collect(),
+        )
+    }
+
+    fn pow_scalar(&self, mut base: u64, mut exponent: u64) -> u64 {
+        let mut out = 1;
+        base %= self.p;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                out = out * base % self.p;
+            }
+            base = base * base % self.p;
+            exponent >>= 1;
+        }
+        out
+    }
+
+    /// Get the multiplicative inverse of a nonzero element
+    pub fn inverse(&self, value: u64) -> u64 {
+        self.pow_scalar(value, self.p - 2)
+    }
+
+    pub fn add(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let len = a.len().max(b.len());
+        trim(
+            (0..len)
+                .map(|i| (a.get(i).unwrap_or(&0) + b.get(i).unwrap_or(&0)) % self.p)
+                .collect(),
+        )
+    }
+
+    pub fn sub(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        self.add(a, &self.scale(b, self.p - 1))
+    }
+
+    pub fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        if a.is_empty() || b.is_empty() {
+            return Vec::new();
+        }
+
+        let mut out = vec![0; a.len() + b.len() - 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                out[i + j] = (out[i + j] + x * y) % self.p;
+            }
+        }
+        trim(out)
+    }
+
+    pub fn scale(&self, a: &[u64], factor: u64) -> Vec<u64> {
+        trim(a.iter().map(|c| c * factor % self.p).collect())
+    }
+
+    /// Divide `a` by a nonzero `b`, returning the quotient and remainder
+    pub fn div_rem(&self, a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+        let inverse = self.inverse(*b.last().expect("divisor must be nonzero"));
+        if a.len() < b.len() {
+            return (Vec::new(), a.to_vec());
+        }
+
+        let mut remainder = a.to_vec();
+        let mut quotient = vec![0; a.len() - b.len() + 1];
+        for i in (0..quotient.len()).rev() {
+            let factor = remainder[i + b.len() - 1] * inverse % self.p;
+            quotient[i] = factor;
+            for (j, c) in b.iter().enumerate() {
+                remainder[i + j] = (remainder[i + j] + self.p - factor * c % self.p) % self.p;
+            }
+        }
+        remainder.truncate(b.len() - 1);
+        (trim(quotient), trim(remainder))
+    }
+
+    /// Scale a polynomial so that its leading coefficient is one
+    pub fn monic(&self, a: &[u64]) -> Vec<u64> {
+        match a.last() {
+            Some(lead) => self.scale(a, self.inverse(*lead)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the monic greatest common divisor of two polynomials
+    pub fn gcd(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let (mut a, mut b) = (a.to_vec(), b.to_vec());
+        while !b.is_empty() {
+            let remainder = self.div_rem(&a, &b).1;
+            a = std::mem::replace(&mut b, remainder);
+        }
+        self.monic(&a)
+    }
+
+    /// Get `s` and `t` such that `sa + tb = 1` for coprime polynomials `a` and `b`
+    pub fn bezout(&self, a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+        let (mut r0, mut r1) = (a.to_vec(), b.to_vec());
+        let (mut s0, mut s1) = (vec![1], Vec::new());
+        let (mut t0, mut t1) = (Vec::new(), vec![1]);
+        while !r1.is_empty() {
+            let (quotient, remainder) = self.div_rem(&r0, &r1);
+            r0 = std::mem::replace(&mut r1, remainder);
+            let s = self.sub(&s0, &self.mul(&quotient, &s1));
+            s0 = std::mem::replace(&mut s1, s);
+            let t = self.sub(&t0, &self.mul(&quotient, &t1));
+            t0 = std::mem::replace(&mut t1, t);
+        }
+
+        let inverse = self.inverse(r0[0]);
+        (self.scale(&s0, inverse), self.scale(&t0, inverse))
+    }
+
+    fn derivative(&self, a: &[u64]) -> Vec<u64> {
+        trim(
+            a.iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, c)| i as u64 % self.p * c % self.p)
+                .collect(),
+        )
+    }
+
+    /// Raise `base` to a nonnegative power modulo `modulus`
+    fn pow_mod(&self, base: &[u64], exponent: &BigInt, modulus: &[u64]) -> Vec<u64> {
+        let base = self.div_rem(base, modulus).1;
+        let mut out = vec![1];
+        for bit in (0..exponent.bits()).rev() {
+            out = self.div_rem(&self.mul(&out, &out), modulus).1;
+            if exponent.bit(bit) {
+                out = self.div_rem(&self.mul(&out, &base), modulus).1;
+            }
+        }
+        out
+    }
+
+    /// Check if a nonconstant polynomial has no repeated factors
+    pub fn is_square_free(&self, f: &[u64]) -> bool {
+        let derivative = self.derivative(f);
+        !derivative.is_empty() && degree(&self.gcd(f, &derivative)) == 0
+    }
+
+    /// Factor a square-free polynomial into monic irreducible factors by distinct-degree
+    /// factorization followed by Cantor–Zassenhaus equal-degree splitting
+    pub fn factor(&self, f: &[u64]) -> Vec<Vec<u64>> {
+        let mut f = self.monic(f);
+        let x = vec![0, 1];
+        let p = BigInt::from(self.p);
+        let mut state = 0x9E37_79B9_7F4A_7C15;
+        let mut out = Vec::new();
+
+        // `h` is `x^(p^d)` modulo the unfactored part of `f`
+        let mut h = x.clone();
+        let mut d = 0;
+        while degree(&f) >= 2 * (d + 1) {
+            d += 1;
+            h = self.pow_mod(&h, &p, &f);
+            let product = self.gcd(&self.sub(&h, &x), &f);
+            if degree(&product) > 0 {
+                self.split(&product, d, &mut state, &mut out);
+                f = self.div_rem(&f, &product).0;
+                h = self.div_rem(&h, &f).1;
+            }
+        }
+
+        if degree(&f) > 0 {
+            out.push(f);
+        }
+        out
+    }
+
+    /// Split a product of distinct irreducible factors of degree `d` into its factors
+    fn split(&self, f: &[u64], d: usize, state: &mut u64, out: &mut Vec<Vec<u64>>) {
+        if degree(f) == d {
+            out.push(f.to_vec());
+            return;
+        }
+
+        let exponent = (Pow::pow(BigInt::from(self.p), d) - 1) / 2;
+        loop {
+            let a = trim(
+                (0..degree(f))
+                    .map(|_| next_random(state) % self.p)
+                    .collect(),
+            );
+            if degree(&a) == 0 {
+                continue;
+            }
+
+            let b = self.sub(&self.pow_mod(&a, &exponent, f), &[1]);
+            let factor = self.gcd(&b, f);
+            if degree(&factor) > 0 && degree(&factor) < degree(f) {
+                let cofactor = self.div_rem(f, &factor).0;
+                self.split(&factor, d, state, out);
+                self.split(&cofactor, d, state, out);
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use num_traits::ToPrimitive;
+
+use crate::atom::{Atom, AtomId, ExprPool, Rational};
+
+use super::{Polynomial, MAX_EXPONENT};
+
+/// A quotient of polynomials in lowest terms with a primitive denominator
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Quotient {
+    pub numerator: Polynomial,
+    pub denominator: Polynomial,
+}
+
+impl Quotient {
+    pub fn new(numerator: Polynomial, denominator: Polynomial) -> Self {
+        let common = numerator.gcd(&denominator);
+        let numerator = numerator.divide(&common).expect("GCD divides");
+        let (scale, denominator) = denominator
+            .divide(&common)
+            .expect("GCD divides")
+            .primitive();
+        Self {
+            numerator: numerator.scale(&scale.recip().expect("Denominator is nonzero")),
+            denominator,
+        }
+    }
+
+    pub fn polynomial(numerator: Polynomial) -> Self {
+        Self {
+            numerator,
+            denominator: Polynomial::one(),
+        }
+    }
+
+    /// Convert a normal-form expression into a quotient of polynomials, taking every subexpression
+    /// that is not a sum, product, exact number or integer power as a generator
+    pub fn of(pool: &ExprPool, id: AtomId) -> Self {
+        match pool.get(id) {
+            atom @ (Atom::Integer(_) | Atom::Rational(_)) => Self::polynomial(
+                Polynomial::constant(atom.to_rational().expect("Exact number")),
+            ),
+            Atom::Sum(terms) => terms
+                .iter()
+                .fold(Self::polynomial(Polynomial::zero()), |sum, term| {
+                    sum.add(&Self::of(pool, *term))
+                }),
+            Atom::Product(factors) => factors
+                .iter()
+                .fold(Self::polynomial(Polynomial::one()), |product, factor| {
+                    product.mul(&Self::of(pool, *factor))
+                }),
+            Atom::Power { base, exponent } => {
+                let power = pool
+                    .get(*exponent)
+                    .to_rational()
+                    .filter(Rational::is_integer)
+                    .and_then(|e| e.numerator().to_i64())
+                    .filter(|power| power.unsigned_abs() <= MAX_EXPONENT as u64);
+                let base = Self::of(pool, *base);
+                match power {
+                    Some(power) if power >= 0 => base.pow(power as u32),
+                    Some(power) if !base.numerator.is_zero() => {
+                        base.recip().pow(power.unsigned_abs() as u32)
+                    }
+                    _ => Self::polynomial(Polynomial::generator(id)),
+                }
+            }
+            _ => Self::polynomial(Polynomial::generator(id)),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+
+    pub fn add(&self, rhs: &Self) -> Self {
+        let lhs = &self.numerator * &rhs.denominator;
+        let rhs_numerator = &rhs.numerator * &self.denominator;
+        Self::new(&lhs + &rhs_numerator, &self.denominator * &rhs.denominator)
+    }
+
+    pub fn sub(&self, rhs: &Self) -> Self {
+        self.add(&rhs.neg())
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            numerator: -&self.numerator,
+            denominator: self.denominator.clone(),
+        }
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        Self::new(
+            &self.numerator * &rhs.numerator,
+            &self.denominator * &rhs.denominator,
+        )
+    }
+
+    /// Get the reciprocal of this nonzero quotient
+    pub fn recip(&self) -> Self {
+        Self::new(self.denominator.clone(), self.numerator.clone())
+    }
+
+    pub fn pow(&self, exponent: u32) -> Self {
+        Self {
+            numerator: self.numerator.pow(exponent),
+            denominator: self.denominator.pow(exponent),
+        }
+    }
+
+    pub fn to_atom(&self, pool: &mut ExprPool) -> AtomId {
+        let numerator = self.numerator.to_atom(pool);
+        let denominator = self.denominator.to_atom(pool);
+        let quotient = pool.fraction(numerator, denominator);
+        pool.simplify(quotient)
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.numerator.as_constant().is_some() && self.denominator.as_constant().is_some()
+    }
+
+    pub fn div(&self, rhs: &Self) -> Self {
+        self.mul(&rhs.recip())
+    }
+}
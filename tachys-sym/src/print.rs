@@ -0,0 +1,514 @@
+use std::fmt::{self, Write};
+
+use num_traits::{One, Signed};
+
+use crate::atom::{
+    symbol::{self, SymbolStore},
+    Atom, AtomId, BigInt, BigOperator, Direction, ExprPool, Function,
+};
+
+/// [Display](fmt::Display) adapter rendering an expression as linear text with the minimum number
+/// of parentheses required for the output to parse back into an equal expression.
+///
+/// The parsed output is equal in value but not always made of the same atoms: rationals such as
+/// `1/3` are read back as fractions and products with a coefficient of `-1` as negations, which
+/// [simplify](ExprPool::simplify) folds back into the original form. Derivatives are written as
+/// `d/dx f(x)` and order terms as `O(x^3)`, which the parser reads back. Big operators and limits
+/// have no linear syntax, so they are written in a notation such as `sum_(k=1)^n k` that the
+/// parser rejects rather than misreads. `NaN` is the one exception that does not round trip.
+///
+/// By default only ASCII is written, with the exception of `∫` and `∞` which have no ASCII
+/// spelling the parser reads back, and Greek letters are spelled out as in `alpha`.
+/// [Print::unicode] enables superscript exponents, `√`, `·`, `−`, relation symbols such as `≤`
+/// and Greek characters
+#[derive(Clone, Copy)]
+pub struct Print<'a> {
+    pool: &'a ExprPool,
+    symbols: &'a SymbolStore,
+    root: AtomId,
+    unicode: bool,
+}
+
+/// Binding strength of printed operators, where higher levels bind more tightly
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Prec {
+    Relation,
+    Sum,
+    Product,
+    Neg,
+    Power,
+    Atom,
+}
+
+impl ExprPool {
+    /// Get a [Display](fmt::Display) adapter for the expression rooted at `id`
+    pub fn display<'a>(&'a self, id: AtomId, symbols: &'a SymbolStore) -> Print<'a> {
+        Print {
+            pool: self,
+            symbols,
+            root: id,
+            unicode: false,
+        }
+    }
+}
+
+impl<'a> Print<'a> {
+    /// Render using Unicode operators and superscripts instead of their ASCII spellings
+    pub fn unicode(self) -> Self {
+        Self {
+            unicode: true,
+            ..self
+        }
+    }
+
+    fn atom(&self, id: AtomId) -> &'a Atom {
+        self.pool.get(id)
+    }
+
+    /// Check if the printed form of the given expression starts with a minus sign
+    fn leading_negative(&self, id: AtomId) -> bool {
+        match self.atom(id) {
+            Atom::Integer(i) => i.is_negative(),
+            Atom::Rational(r) => r.is_negative(),
+            Atom::Float(f) => f.value().is_sign_negative(),
+            Atom::Complex { re, im } => match self.atom(*re).is_zero() {
+                true => self.leading_negative(*im),
+                false => self.leading_negative(*re),
+            },
+            Atom::Neg(_) => true,
+            Atom::Product(factors) => self.leading_negative(factors[0]),
+            Atom::Fraction { numerator, .. } => self.leading_negative(*numerator),
+            Atom::Sum(terms) => self.leading_negative(terms[0]),
+            _ => false,
+        }
+    }
+
+    fn prec(&self, id: AtomId) -> Prec {
+        let prec = match self.atom(id) {
+            Atom::Integer(_)
+            | Atom::Float(_)
+            | Atom::Symbol(_)
+            | Atom::Unit(_)
+            | Atom::Apply { .. }
+            | Atom::Matrix { .. }
+            | Atom::Order(_) => Prec::Atom,
+            Atom::Rational(_) | Atom::Product(_) | Atom::Fraction { .. } => Prec::Product,
+            Atom::Complex { re, im } => match (self.atom(*re).is_zero(), self.atom(*im)) {
+                (true, Atom::Integer(i)) if i.magnitude().is_one() => Prec::Atom,
+                (true, _) => Prec::Product,
+                (false, _) => Prec::Sum,
+            },
+            Atom::Sum(_) => Prec::Sum,
+            Atom::Power { .. }
+            | Atom::Integral { .. }
+            | Atom::BigOperator { .. }
+            | Atom::Derivative { .. }
+            | Atom::Limit { .. } => Prec::Power,
+            Atom::Neg(_) => Prec::Neg,
+            Atom::Equals { .. } | Atom::Relation { .. } => Prec::Relation,
+        };
+
+        match self.leading_negative(id) {
+            true => prec.min(Prec::Neg),
+            false => prec,
+        }
+    }
+
+    /// Write the given expression, surrounding it in parentheses if it binds less tightly than
+    /// `min`
+    fn write_prec(&self, f: &mut dyn Write, id: AtomId, min: Prec) -> fmt::Result {
+        match self.prec(id) < min {
+            true => {
+                f.write_char('(')?;
+                self.write(f, id)?;
+                f.write_char(')')
+            }
+            false => self.write(f, id),
+        }
+    }
+
+    fn write_symbol(&self, f: &mut dyn Write, name: &str) -> fmt::Result {
+        let (base, sub) = symbol::split_subscript(name);
+        let mut chars = base.chars();
+        match (chars.next(), chars.next()) {
+            (Some(letter), None) if !self.unicode => {
+                f.write_str(symbol::greek_name(letter).unwrap_or(base))?
+            }
+            _ if self.unicode => match symbol::greek_letter(base) {
+                Some(letter) => f.write_char(letter)?,
+                None => f.write_str(base)?,
+            },
+            _ => f.write_str(base)?,
+        }
+
+        match sub {
+            Some(sub) => write!(f, "_{sub}"),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the character used for unary and binary minus signs
+    fn minus(&self) -> char {
+        match self.unicode {
+            true => '−',
+            false => '-',
+        }
+    }
+
+    fn write_float(&self, f: &mut dyn Write, value: f64) -> fmt::Result {
+        if value.is_sign_negative() && !value.is_nan() {
+            f.write_char(self.minus())?;
+        }
+
+        match value.is_infinite() {
+            true => f.write_char('∞'),
+            false => write!(f, "{:?}", value.abs()),
+        }
+    }
+
+    fn write_integer(&self, f: &mut dyn Write, value: &BigInt) -> fmt::Result {
+        if value.is_negative() {
+            f.write_char(self.minus())?;
+        }
+        write!(f, "{}", value.magnitude())
+    }
+
+    /// Write a complex literal as in `3 - 2i` or `i/2`
+    fn write_complex(&self, f: &mut dyn Write, re: AtomId, im: AtomId) -> fmt::Result {
+        let negative = self.atom(im).is_number() && self.leading_negative(im);
+        if !self.atom(re).is_zero() {
+            self.write(f, re)?;
+            match negative {
+                true => write!(f, " {} ", self.minus())?,
+                false => f.write_str(" + ")?,
+            }
+        } else if negative {
+            f.write_char(self.minus())?;
+        }
+
+        match self.atom(im) {
+            Atom::Integer(i) if i.magnitude().is_one() => f.write_char('i'),
+            Atom::Integer(i) => write!(f, "{}i", i.magnitude()),
+            Atom::Rational(r) => {
+                if !r.numerator().magnitude().is_one() {
+                    write!(f, "{}", r.numerator().magnitude())?;
+                }
+                write!(f, "i/{}", r.denominator())
+            }
+            Atom::Float(v) => write!(f, "{:?}i", v.value().abs()),
+            _ => {
+                self.write_prec(f, im, Prec::Power)?;
+                f.write_str("*i")
+            }
+        }
+    }
+
+    fn write_sum(&self, f: &mut dyn Write, terms: &[AtomId]) -> fmt::Result {
+        for (i, term) in terms.iter().copied().enumerate() {
+            if i == 0 {
+                match self.atom(term) {
+                    Atom::Sum(_) => self.write_prec(f, term, Prec::Neg)?,
+                    _ => self.write_prec(f, term, Prec::Product)?,
+                }
+                continue;
+            }
+
+            let mut positive = String::new();
+            let negative = match self.atom(term) {
+                Atom::Neg(inner) => {
+                    self.write_prec(&mut positive, *inner, Prec::Product)?;
+                    true
+                }
+                _ if self.leading_negative(term) => {
+                    self.write_prec(&mut positive, term, Prec::Product)?;
+                    positive.remove(0);
+                    true
+                }
+                _ => {
+                    self.write_prec(&mut positive, term, Prec::Product)?;
+                    false
+                }
+            };
+
+            match negative {
+                true => write!(f, " {} ", self.minus())?,
+                false => f.write_str(" + ")?,
+            }
+            f.write_str(&positive)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_product(&self, f: &mut dyn Write, factors: &[AtomId]) -> fmt::Result {
+        let Some((first, rest)) = factors.split_first() else {
+            return Ok(());
+        };
+
+        let mut separate = true;
+        let mut coefficient = false;
+        match self.atom(*first) {
+            atom if atom.is_minus_one() && !rest.is_empty() => {
+                f.write_char(self.minus())?;
+                separate = false;
+            }
+            Atom::Integer(i) => {
+                self.write_integer(f, i)?;
+                coefficient = true;
+            }
+            Atom::Float(v) => {
+                self.write_float(f, v.value())?;
+                coefficient = true;
+            }
+            Atom::Product(_) => self.write_prec(f, *first, Prec::Power)?,
+            _ => self.write_prec(f, *first, Prec::Product)?,
+        }
+
+        for factor in rest.iter().copied() {
+            let mut rendered = String::new();
+            self.write_prec(&mut rendered, factor, Prec::Power)?;
+
+            // Numeric coefficients are juxtaposed with the next factor as in `3x`, unless it would
+            // lex as the exponent of the number or as the differential of an integral
+            let mut chars = rendered.chars();
+            let juxtapose = coefficient
+                && match (chars.next(), chars.next()) {
+                    (Some('e' | 'E'), Some(c)) => !c.is_ascii_digit(),
+                    (Some('d'), Some(_)) => false,
+                    (Some(c), _) => c.is_alphabetic() || matches!(c, '(' | '[' | '√'),
+                    (None, _) => false,
+                };
+
+            // Units are set apart from the magnitude they follow, as in `9.81 m/s^2`
+            let unit = match self.atom(factor) {
+                Atom::Power { base, .. } => matches!(self.atom(*base), Atom::Unit(_)),
+                atom => matches!(atom, Atom::Unit(_)),
+            };
+            match (separate && !juxtapose, self.unicode) {
+                _ if separate && unit => f.write_char(' ')?,
+                (true, true) => f.write_char('·')?,
+                (true, false) => f.write_char('*')?,
+                (false, _) => (),
+            }
+            f.write_str(&rendered)?;
+
+            separate = true;
+            coefficient = false;
+        }
+
+        Ok(())
+    }
+
+    fn write_power(&self, f: &mut dyn Write, base: AtomId, exponent: AtomId) -> fmt::Result {
+        self.write_prec(f, base, Prec::Atom)?;
+
+        match self.atom(exponent) {
+            Atom::Integer(i) if self.unicode => {
+                for ch in i.to_string().chars() {
+                    f.write_char(match ch {
+                        '-' => '⁻',
+                        '0' => '⁰',
+                        '1' => '¹',
+                        '2' => '²',
+                        '3' => '³',
+                        '4' => '⁴',
+                        '5' => '⁵',
+                        '6' => '⁶',
+                        '7' => '⁷',
+                        '8' => '⁸',
+                        _ => '⁹',
+                    })?;
+                }
+                Ok(())
+            }
+            _ => {
+                f.write_char('^')?;
+                self.write_prec(f, exponent, Prec::Neg)
+            }
+        }
+    }
+
+    fn write_apply(&self, f: &mut dyn Write, func: Function, args: &[AtomId]) -> fmt::Result {
+        match (func, args) {
+            (Function::Sqrt, [arg]) if self.unicode => {
+                f.write_char('√')?;
+                return self.write_prec(f, *arg, Prec::Atom);
+            }
+            (Function::User(sym), _) => self.write_symbol(f, self.symbols.name(sym))?,
+            (func, _) => f.write_str(func.builtin_name().unwrap_or_default())?,
+        }
+
+        f.write_char('(')?;
+        for (i, arg) in args.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            self.write(f, *arg)?;
+        }
+        f.write_char(')')
+    }
+
+    fn write(&self, f: &mut dyn Write, id: AtomId) -> fmt::Result {
+        match self.atom(id) {
+            Atom::Integer(i) => self.write_integer(f, i),
+            Atom::Rational(r) => {
+                self.write_integer(f, r.numerator())?;
+                write!(f, "/{}", r.denominator())
+            }
+            Atom::Float(v) => self.write_float(f, v.value()),
+            Atom::Complex { re, im } => self.write_complex(f, *re, *im),
+            Atom::Symbol(sym) => self.write_symbol(f, self.symbols.name(*sym)),
+            Atom::Unit(unit) => write!(f, "{unit}"),
+            Atom::Sum(terms) => self.write_sum(f, terms),
+            Atom::Product(factors) => self.write_product(f, factors),
+            Atom::Power { base, exponent } => self.write_power(f, *base, *exponent),
+            Atom::Neg(inner) => {
+                f.write_char(self.minus())?;
+                match self.leading_negative(*inner) {
+                    true => self.write_prec(f, *inner, Prec::Atom),
+                    false => self.write_prec(f, *inner, Prec::Product),
+                }
+            }
+            Atom::Apply { func, args } => self.write_apply(f, *func, args),
+            Atom::Order(term) => {
+                f.write_str("O(")?;
+                self.write(f, *term)?;
+                f.write_char(')')
+            }
+            Atom::Equals { lhs, rhs } => {
+                self.write_prec(f, *lhs, Prec::Sum)?;
+                f.write_str(" = ")?;
+                self.write_prec(f, *rhs, Prec::Sum)
+            }
+            Atom::Relation { op, lhs, rhs } => {
+                self.write_prec(f, *lhs, Prec::Sum)?;
+                match self.unicode {
+                    true => write!(f, " {} ", op.unicode())?,
+                    false => write!(f, " {} ", op.ascii())?,
+                }
+                self.write_prec(f, *rhs, Prec::Sum)
+            }
+            Atom::Integral {
+                variable,
+                upper,
+                lower,
+                integrand,
+            } => {
+                f.write_char('∫')?;
+                if let Some(lower) = lower {
+                    f.write_char('_')?;
+                    self.write_prec(f, *lower, Prec::Atom)?;
+                }
+                if let Some(upper) = upper {
+                    f.write_char('^')?;
+                    self.write_prec(f, *upper, Prec::Atom)?;
+                }
+                f.write_char(' ')?;
+                self.write_prec(f, *integrand, Prec::Sum)?;
+                f.write_str(" d")?;
+                self.write_symbol(f, self.symbols.name(*variable))
+            }
+            Atom::BigOperator {
+                op,
+                index,
+                lower,
+                upper,
+                term,
+            } => {
+                f.write_str(match (op, self.unicode) {
+                    (BigOperator::Sum, true) => "∑_(",
+                    (BigOperator::Product, true) => "∏_(",
+                    (BigOperator::Sum, false) => "sum_(",
+                    (BigOperator::Product, false) => "prod_(",
+                })?;
+                self.write_symbol(f, self.symbols.name(*index))?;
+                f.write_char('=')?;
+                self.write(f, *lower)?;
+                f.write_str(")^")?;
+                self.write_prec(f, *upper, Prec::Atom)?;
+                f.write_char(' ')?;
+                self.write_prec(f, *term, Prec::Product)
+            }
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => {
+                self.write_prec(f, *numerator, Prec::Product)?;
+                f.write_char('/')?;
+                self.write_prec(f, *denominator, Prec::Power)
+            }
+            Atom::Derivative {
+                variable,
+                order,
+                inner,
+            } => {
+                let name = self.symbols.name(*variable);
+                match order {
+                    1 => f.write_str("d/d")?,
+                    n => write!(f, "d^{n}/d")?,
+                }
+                self.write_symbol(f, name)?;
+                if *order != 1 {
+                    write!(f, "^{order}")?;
+                }
+                f.write_char(' ')?;
+                self.write_prec(f, *inner, Prec::Atom)
+            }
+            Atom::Limit {
+                variable,
+                point,
+                direction,
+                inner,
+            } => {
+                f.write_str("lim_(")?;
+                self.write_symbol(f, self.symbols.name(*variable))?;
+                f.write_str(if self.unicode { "→" } else { "->" })?;
+                self.write(f, *point)?;
+                match (direction, self.unicode) {
+                    (Some(Direction::Below), true) => f.write_char('⁻')?,
+                    (Some(Direction::Above), true) => f.write_char('⁺')?,
+                    (Some(Direction::Below), false) => f.write_char('-')?,
+                    (Some(Direction::Above), false) => f.write_char('+')?,
+                    (None, _) => (),
+                }
+                f.write_str(") ")?;
+                self.write_prec(f, *inner, Prec::Product)
+            }
+            Atom::Matrix { columns, entries } => self.write_matrix(f, *columns as usize, entries),
+        }
+    }
+
+    /// Write a matrix as a list of rows such as `[[a, b], [c, d]]`, or a column vector as the
+    /// list of its entries
+    fn write_matrix(&self, f: &mut dyn Write, columns: usize, entries: &[AtomId]) -> fmt::Result {
+        let write_list = |f: &mut dyn Write, items: &[AtomId]| {
+            f.write_char('[')?;
+            for (i, item) in items.iter().enumerate() {
+                if i != 0 {
+                    f.write_str(", ")?;
+                }
+                self.write(f, *item)?;
+            }
+            f.write_char(']')
+        };
+
+        if columns == 1 {
+            return write_list(f, entries);
+        }
+
+        f.write_char('[')?;
+        for (i, row) in entries.chunks(columns).enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write_list(f, row)?;
+        }
+        f.write_char(']')
+    }
+}
+
+impl fmt::Display for Print<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, self.root)
+    }
+}
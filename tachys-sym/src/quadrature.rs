@@ -0,0 +1,345 @@
+use std::f64::consts::FRAC_PI_2;
+
+/// Accuracy and cost limits for numeric integration
+#[derive(Clone, Copy, Debug)]
+pub struct QuadratureOptions {
+    /// Largest acceptable error relative to the magnitude of the result
+    pub relative: f64,
+    /// Largest acceptable absolute error, used when the result is close to zero
+    pub absolute: f64,
+    /// Largest number of evaluations of the integrand made by each method attempted
+    pub max_evaluations: usize,
+}
+
+/// Numeric value of a definite integral with an estimate of its absolute error
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quadrature {
+    pub value: f64,
+    pub error: f64,
+    /// If the error estimate is within the requested tolerance
+    pub converged: bool,
+    pub evaluations: usize,
+}
+
+impl Default for QuadratureOptions {
+    fn default() -> Self {
+        Self {
+            relative: 1e-10,
+            absolute: 1e-12,
+            max_evaluations: 20_000,
+        }
+    }
+}
+
+impl QuadratureOptions {
+    fn tolerance(&self, value: f64) -> f64 {
+        self.absolute.max(self.relative * value.abs())
+    }
+}
+
+/// Abscissae of the 15 point Kronrod rule on `[-1, 1]`, the odd-indexed ones shared with the 7
+/// point Gauss rule
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.,
+];
+
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_22,
+    0.063_092_092_629_978_55,
+    0.104_790_010_322_250_2,
+    0.140_653_259_715_525_9,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_8,
+];
+
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_7,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+/// Deepest level of tanh-sinh refinement, halving the step size each level
+const MAX_TANH_SINH_LEVEL: u32 = 12;
+
+/// Numerically integrate `f` from `a` to `b`, either of which may be infinite.
+///
+/// Adaptive Gauss–Kronrod quadrature is tried first, falling back to tanh-sinh quadrature, which
+/// copes better with singularities at the endpoints, if it does not converge. Infinite intervals
+/// are mapped onto finite ones before integrating
+pub fn integrate<F: FnMut(f64) -> f64>(
+    mut f: F,
+    a: f64,
+    b: f64,
+    options: &QuadratureOptions,
+) -> Quadrature {
+    if a.is_nan() || b.is_nan() {
+        return Quadrature {
+            value: f64::NAN,
+            error: f64::INFINITY,
+            converged: false,
+            evaluations: 0,
+        };
+    }
+    if a == b {
+        return Quadrature {
+            value: 0.,
+            error: 0.,
+            converged: true,
+            evaluations: 0,
+        };
+    }
+    if a > b {
+        let reversed = integrate(f, b, a, options);
+        return Quadrature {
+            value: -reversed.value,
+            ..reversed
+        };
+    }
+
+    // The integrand must vanish at infinity for the integral to converge, so samples where the
+    // substitution overflows are taken to be zero
+    match (a.is_finite(), b.is_finite()) {
+        (true, true) => finite(&mut f, a, b, options),
+        // x = a + t / (1 - t)
+        (true, false) => finite(
+            &mut |t| {
+                let s = 1. - t;
+                match s * s {
+                    0. => 0.,
+                    s2 => f(a + t / s) / s2,
+                }
+            },
+            0.,
+            1.,
+            options,
+        ),
+        // x = b - t / (1 - t)
+        (false, true) => finite(
+            &mut |t| {
+                let s = 1. - t;
+                match s * s {
+                    0. => 0.,
+                    s2 => f(b - t / s) / s2,
+                }
+            },
+            0.,
+            1.,
+            options,
+        ),
+        // x = t / (1 - t^2)
+        (false, false) => finite(
+            &mut |t| {
+                let s = 1. - t * t;
+                match s * s {
+                    0. => 0.,
+                    s2 => f(t / s) * (1. + t * t) / s2,
+                }
+            },
+            -1.,
+            1.,
+            options,
+        ),
+    }
+}
+
+fn finite(
+    f: &mut dyn FnMut(f64) -> f64,
+    a: f64,
+    b: f64,
+    options: &QuadratureOptions,
+) -> Quadrature {
+    let kronrod = gauss_kronrod(f, a, b, options);
+    if kronrod.converged {
+        return kronrod;
+    }
+
+    let tanh_sinh = tanh_sinh(f, a, b, options);
+    let evaluations = kronrod.evaluations + tanh_sinh.evaluations;
+    let best = match tanh_sinh.converged || tanh_sinh.error < kronrod.error {
+        true => tanh_sinh,
+        false => kronrod,
+    };
+    Quadrature {
+        evaluations,
+        ..best
+    }
+}
+
+/// Interval of an adaptive Gauss–Kronrod integration with its estimated integral and error
+struct Segment {
+    a: f64,
+    b: f64,
+    value: f64,
+    error: f64,
+}
+
+/// Apply the 15 point Kronrod rule to `[a, b]`, estimating the error from the embedded 7 point
+/// Gauss rule as in QUADPACK
+fn kronrod_segment(f: &mut dyn FnMut(f64) -> f64, a: f64, b: f64) -> Segment {
+    let center = (a + b) / 2.;
+    let half = (b - a) / 2.;
+
+    let fc = f(center);
+    let mut kronrod = fc * KRONROD_WEIGHTS[7];
+    let mut gauss = fc * GAUSS_WEIGHTS[3];
+    let mut absolute = kronrod.abs();
+    let mut samples = [(0., 0.); 7];
+    for (i, node) in KRONROD_NODES[..7].iter().enumerate() {
+        let (lo, hi) = (f(center - half * node), f(center + half * node));
+        samples[i] = (lo, hi);
+        kronrod += KRONROD_WEIGHTS[i] * (lo + hi);
+        absolute += KRONROD_WEIGHTS[i] * (lo.abs() + hi.abs());
+        if i % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[i / 2] * (lo + hi);
+        }
+    }
+
+    let mean = kronrod / 2.;
+    let deviation = KRONROD_WEIGHTS[7] * (fc - mean).abs()
+        + samples
+            .iter()
+            .zip(KRONROD_WEIGHTS)
+            .map(|((lo, hi), w)| w * ((lo - mean).abs() + (hi - mean).abs()))
+            .sum::<f64>();
+
+    let (deviation, absolute) = (deviation * half.abs(), absolute * half.abs());
+    let mut error = ((kronrod - gauss) * half).abs();
+    if deviation != 0. && error != 0. {
+        error = deviation * (200. * error / deviation).powf(1.5).min(1.);
+    }
+    if absolute > f64::MIN_POSITIVE / (50. * f64::EPSILON) {
+        error = error.max(50. * f64::EPSILON * absolute);
+    }
+
+    Segment {
+        a,
+        b,
+        value: kronrod * half,
+        error,
+    }
+}
+
+/// Integrate by repeatedly bisecting the interval with the largest estimated error
+fn gauss_kronrod(
+    f: &mut dyn FnMut(f64) -> f64,
+    a: f64,
+    b: f64,
+    options: &QuadratureOptions,
+) -> Quadrature {
+    let mut segments = vec![kronrod_segment(f, a, b)];
+    let mut evaluations = 15;
+    loop {
+        let value = segments.iter().map(|s| s.value).sum::<f64>();
+        let error = segments.iter().map(|s| s.error).sum::<f64>();
+        let result = |converged| Quadrature {
+            value,
+            error,
+            converged,
+            evaluations,
+        };
+
+        if !value.is_finite() || !error.is_finite() {
+            return Quadrature {
+                error: f64::INFINITY,
+                ..result(false)
+            };
+        }
+        if error <= options.tolerance(value) {
+            return result(true);
+        }
+        if evaluations + 30 > options.max_evaluations {
+            return result(false);
+        }
+
+        let (worst, _) = segments
+            .iter()
+            .enumerate()
+            .max_by(|(_, x), (_, y)| x.error.total_cmp(&y.error))
+            .expect("there is always at least one segment");
+        let segment = segments.swap_remove(worst);
+        let mid = (segment.a + segment.b) / 2.;
+        if mid <= segment.a || mid >= segment.b {
+            segments.push(segment);
+            return result(false);
+        }
+
+        segments.push(kronrod_segment(f, segment.a, mid));
+        segments.push(kronrod_segment(f, mid, segment.b));
+        evaluations += 30;
+    }
+}
+
+/// Integrate using the double exponential substitution `x = tanh(π/2 sinh(t))`, which clusters
+/// samples at the endpoints so that singularities there are integrated accurately
+fn tanh_sinh(
+    f: &mut dyn FnMut(f64) -> f64,
+    a: f64,
+    b: f64,
+    options: &QuadratureOptions,
+) -> Quadrature {
+    let center = (a + b) / 2.;
+    let half = (b - a) / 2.;
+    let mut evaluations = 1;
+
+    // Sum of weighted samples at the nodes `t = kh` added by each level, ignoring the step size
+    let mut sum = FRAC_PI_2 * f(center);
+    let mut previous = f64::NAN;
+    let mut step = 1.;
+    for level in 0..=MAX_TANH_SINH_LEVEL {
+        let stride = if level == 0 { 1 } else { 2 };
+        let mut k = 1;
+        loop {
+            let t = k as f64 * step;
+            let u = FRAC_PI_2 * t.sinh();
+            let cosh = u.cosh();
+            let weight = FRAC_PI_2 * t.cosh() / (cosh * cosh);
+
+            // Distance of the node from the nearest endpoint, computed without cancellation
+            let distance = half * 2. / ((2. * u).exp() + 1.);
+            if weight == 0. || distance == 0. || a + distance == a || b - distance == b {
+                break;
+            }
+
+            sum += weight * (f(a + distance) + f(b - distance));
+            evaluations += 2;
+            k += stride;
+        }
+
+        let value = sum * step * half;
+        let error = (value - previous).abs();
+        let result = |converged| Quadrature {
+            value,
+            error: if level == 0 { f64::INFINITY } else { error },
+            converged,
+            evaluations,
+        };
+
+        if !value.is_finite() {
+            return Quadrature {
+                error: f64::INFINITY,
+                ..result(false)
+            };
+        }
+        if level > 0 && error <= options.tolerance(value) {
+            return result(true);
+        }
+        if level == MAX_TANH_SINH_LEVEL || evaluations * 2 > options.max_evaluations {
+            return result(false);
+        }
+
+        previous = value;
+        step /= 2.;
+    }
+
+    unreachable!("the last level always returns")
+}
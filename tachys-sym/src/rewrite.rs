@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+
+use crate::{
+    assume::Assumptions,
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, ExprPool,
+    },
+    parse::{ParseError, Parser},
+};
+
+/// Values bound to the wildcards of a [Pattern] by a successful match
+pub type Bindings = HashMap<Symbol, AtomId>;
+
+/// An expression in which some symbols are wildcards that match any subexpression satisfying
+/// their [Constraint].
+///
+/// Sums and products match commutatively, with each term of the pattern matching one term of the
+/// expression except for the last wildcard term, which matches all remaining terms. For example
+/// `?a * (?b + ?c)` matches `2x(y + z + 1)` with `?a = 2x`, `?b = y` and `?c = z + 1`
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    root: AtomId,
+    wildcards: HashMap<Symbol, Constraint>,
+}
+
+/// Condition an expression must satisfy to be bound to a wildcard
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Constraint {
+    #[default]
+    Any,
+    Integer,
+    /// An integer or rational literal
+    Rational,
+    /// Any numeric literal, including floats
+    Number,
+    Symbol,
+    /// An expression containing no symbols other than the imaginary unit
+    Constant,
+    /// An expression known to be real, see [ExprPool::is_real]
+    Real,
+    /// An expression known to be positive, see [ExprPool::is_positive]
+    Positive,
+    /// An expression known to be nonzero, see [ExprPool::is_nonzero]
+    Nonzero,
+}
+
+/// A pattern and the expression it is rewritten to, which may refer to the pattern's wildcards
+#[derive(Clone, Debug)]
+pub struct Rule {
+    pattern: Pattern,
+    replacement: AtomId,
+}
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum RewriteError {
+    #[error("Rewriting did not reach a fixed point after {0} passes")]
+    Diverged(usize),
+}
+
+impl Constraint {
+    /// Get the constraint written with the given name in a pattern, as in `?n: integer`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "any" => Self::Any,
+            "integer" => Self::Integer,
+            "rational" => Self::Rational,
+            "number" => Self::Number,
+            "symbol" => Self::Symbol,
+            "constant" => Self::Constant,
+            "real" => Self::Real,
+            "positive" => Self::Positive,
+            "nonzero" => Self::Nonzero,
+            _ => return None,
+        })
+    }
+
+    /// Check if the expression rooted at `id` satisfies this constraint
+    pub fn check(&self, pool: &ExprPool, id: AtomId) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Integer => matches!(pool.get(id), Atom::Integer(_)),
+            Self::Rational => matches!(pool.get(id), Atom::Integer(_) | Atom::Rational(_)),
+            Self::Number => pool.get(id).is_number(),
+            Self::Symbol => matches!(pool.get(id), Atom::Symbol(_)),
+            Self::Constant => !pool.descendants(id).any(
+                |id| matches!(pool.get(id), Atom::Symbol(sym) if *sym != Symbol::IMAGINARY_UNIT),
+            ),
+            Self::Real => pool.is_real(id, &Assumptions::new()) == Some(true),
+            Self::Positive => pool.is_positive(id, &Assumptions::new()) == Some(true),
+            Self::Nonzero => pool.is_nonzero(id, &Assumptions::new()) == Some(true),
+        }
+    }
+}
+
+impl Pattern {
+    /// Create a pattern with no wildcards, matching only the expression `root` itself
+    pub fn new(root: AtomId) -> Self {
+        Self {
+            root,
+            wildcards: HashMap::new(),
+        }
+    }
+
+    /// Treat every occurrence of `symbol` in the pattern as a wildcard with the given constraint
+    pub fn wildcard(mut self, symbol: Symbol, constraint: Constraint) -> Self {
+        self.wildcards.insert(symbol, constraint);
+        self
+    }
+
+    /// Parse a pattern from linear text, see [Parser::parse_pattern]
+    pub fn parse(
+        pool: &mut ExprPool,
+        symbols: &mut SymbolStore,
+        src: &str,
+    ) -> Result<Self, ParseError> {
+        Parser::new(pool, symbols).parse_pattern(src)
+    }
+
+    /// Get the ID of the expression this pattern was built from
+    pub const fn root(&self) -> AtomId {
+        self.root
+    }
+
+    /// Get the constraint of the given symbol if it is a wildcard of this pattern
+    pub fn constraint(&self, symbol: Symbol) -> Option<Constraint> {
+        self.wildcards.get(&symbol).copied()
+    }
+}
+
+impl Rule {
+    /// Create a rule rewriting expressions matching `pattern` to `replacement`, with wildcards in
+    /// the replacement substituted by the values they were bound to
+    pub fn new(pattern: Pattern, replacement: AtomId) -> Self {
+        Self {
+            pattern,
+            replacement,
+        }
+    }
+
+    /// Parse a rule from linear text patterns, as in `Rule::parse(.., "?a * (?b + ?c)", "?a ?b +
+    /// ?a ?c")`
+    pub fn parse(
+        pool: &mut ExprPool,
+        symbols: &mut SymbolStore,
+        pattern: &str,
+        replacement: &str,
+    ) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(pool, symbols);
+        let pattern = parser.parse_pattern(pattern)?;
+        let replacement = parser.parse_pattern(replacement)?.root();
+        Ok(Self::new(pattern, replacement))
+    }
+
+    /// Get the pattern matched by this rule
+    pub const fn pattern(&self) -> &Pattern {
+        &self.pattern
+    }
+
+    /// Get the template that matching expressions are rewritten to
+    pub const fn replacement(&self) -> AtomId {
+        self.replacement
+    }
+}
+
+impl ExprPool {
+    /// Find every way in which `pattern` matches the expression rooted at `id`
+    pub fn match_all(&mut self, pattern: &Pattern, id: AtomId) -> Vec<Bindings> {
+        let mut matcher = Matcher {
+            pool: self,
+            pattern,
+        };
+        matcher.matches(pattern.root, id, Bindings::new())
+    }
+
+    /// Match `pattern` against the expression rooted at `id`, returning the first set of bindings
+    /// found
+    pub fn match_pattern(&mut self, pattern: &Pattern, id: AtomId) -> Option<Bindings> {
+        self.match_all(pattern, id).into_iter().next()
+    }
+
+    /// Replace every bound wildcard in the expression rooted at `template` with its value
+    pub fn instantiate(&mut self, template: AtomId, bindings: &Bindings) -> AtomId {
+        if let Atom::Symbol(sym) = self.get(template) {
+            return bindings.get(sym).copied().unwrap_or(template);
+        }
+
+        let atom = self.get(template).clone();
+        let atom = atom.map_children(|child| self.instantiate(child, bindings));
+        self.insert_flattened(atom)
+    }
+
+    /// Insert the given atom, first merging any sums (or products) among the terms of a sum (or
+    /// product) into it
+    pub(crate) fn insert_flattened(&mut self, atom: Atom) -> AtomId {
+        let flatten = |items: &[AtomId], sum: bool| {
+            items
+                .iter()
+                .flat_map(|id| match self.get(*id) {
+                    Atom::Sum(terms) if sum => terms.to_vec(),
+                    Atom::Product(factors) if !sum => factors.to_vec(),
+                    _ => vec![*id],
+                })
+                .collect()
+        };
+
+        let atom = match atom {
+            Atom::Sum(terms) => Atom::Sum(flatten(&terms, true)),
+            Atom::Product(factors) => Atom::Product(flatten(&factors, false)),
+            atom => atom,
+        };
+        self.insert(atom)
+    }
+
+    /// Rewrite the expression rooted at `id` from the leaves up, replacing each subexpression with
+    /// the replacement of the first rule matching it once its children have been rewritten.
+    /// The output of a rule is not rewritten again in the same pass
+    pub fn rewrite(&mut self, id: AtomId, rules: &[Rule]) -> AtomId {
+        let mut memo = HashMap::new();
+        self.rewrite_memo(id, rules, &mut memo)
+    }
+
+    /// Repeatedly [rewrite](ExprPool::rewrite) the expression rooted at `id` until no rule
+    /// applies, failing if that does not happen within `max_passes` passes
+    pub fn rewrite_fixpoint(
+        &mut self,
+        id: AtomId,
+        rules: &[Rule],
+        max_passes: usize,
+    ) -> Result<AtomId, RewriteError> {
+        let mut current = id;
+        for _ in 0..max_passes {
+            let next = self.rewrite(current, rules);
+            if next == current {
+                return Ok(current);
+            }
+            current = next;
+        }
+
+        Err(RewriteError::Diverged(max_passes))
+    }
+
+    fn rewrite_memo(
+        &mut self,
+        id: AtomId,
+        rules: &[Rule],
+        memo: &mut HashMap<AtomId, AtomId>,
+    ) -> AtomId {
+        if let Some(done) = memo.get(&id) {
+            return *done;
+        }
+
+        let atom = self.get(id).clone();
+        let atom = atom.map_children(|child| self.rewrite_memo(child, rules, memo));
+        let rewritten = self.insert_flattened(atom);
+        let out = rules
+            .iter()
+            .find_map(|rule| {
+                self.match_pattern(&rule.pattern, rewritten)
+                    .map(|bindings| (rule, bindings))
+            })
+            .map(|(rule, bindings)| self.instantiate(rule.replacement, &bindings))
+            .unwrap_or(rewritten);
+
+        memo.insert(id, out);
+        out
+    }
+}
+
+struct Matcher<'a> {
+    pool: &'a mut ExprPool,
+    pattern: &'a Pattern,
+}
+
+impl Matcher<'_> {
+    fn wildcard(&self, id: AtomId) -> Option<(Symbol, Constraint)> {
+        match self.pool.get(id) {
+            Atom::Symbol(sym) => self.pattern.constraint(*sym).map(|c| (*sym, c)),
+            _ => None,
+        }
+    }
+
+    fn has_wildcards(&self, id: AtomId) -> bool {
+        self.pool
+            .descendants(id)
+            .any(|id| self.wildcard(id).is_some())
+    }
+
+    fn matches(&mut self, pattern: AtomId, id: AtomId, bindings: Bindings) -> Vec<Bindings> {
+        if let Some((sym, constraint)) = self.wildcard(pattern) {
+            return match bindings.get(&sym) {
+                Some(bound) if *bound != id => vec![],
+                Some(_) => vec![bindings],
+                None if constraint.check(self.pool, id) => {
+                    let mut bindings = bindings;
+                    bindings.insert(sym, id);
+                    vec![bindings]
+                }
+                None => vec![],
+            };
+        }
+
+        if !self.has_wildcards(pattern) {
+            return match pattern == id {
+                true => vec![bindings],
+                false => vec![],
+            };
+        }
+
+        match (self.pool.get(pattern), self.pool.get(id)) {
+            (Atom::Sum(patterns), Atom::Sum(terms)) => {
+                let (patterns, terms) = (patterns.to_vec(), terms.to_vec());
+                self.commutative(patterns, terms, true, bindings)
+            }
+            (Atom::Product(patterns), Atom::Product(factors)) => {
+                let (patterns, factors) = (patterns.to_vec(), factors.to_vec());
+                self.commutative(patterns, factors, false, bindings)
+            }
+            (p, a) if same_head(p, a) => {
+                let pairs = p.children().zip(a.children()).collect::<Vec<_>>();
+                pairs
+                    .into_iter()
+                    .fold(vec![bindings], |candidates, (pattern, id)| {
+                        candidates
+                            .into_iter()
+                            .flat_map(|bindings| self.matches(pattern, id, bindings))
+                            .collect()
+                    })
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Match the terms of a sum or product pattern against the terms of an expression in any order
+    fn commutative(
+        &mut self,
+        mut patterns: Vec<AtomId>,
+        terms: Vec<AtomId>,
+        sum: bool,
+        bindings: Bindings,
+    ) -> Vec<Bindings> {
+        // Match the most specific terms first, leaving a wildcard last to absorb the rest
+        patterns.sort_by_key(|p| self.wildcard(*p).map(|(_, c)| c == Constraint::Any));
+
+        let mut out = Vec::new();
+        self.assign(&patterns, terms, sum, bindings, &mut out);
+        out
+    }
+
+    fn assign(
+        &mut self,
+        patterns: &[AtomId],
+        terms: Vec<AtomId>,
+        sum: bool,
+        bindings: Bindings,
+        out: &mut Vec<Bindings>,
+    ) {
+        let Some((first, rest)) = patterns.split_first() else {
+            if terms.is_empty() {
+                out.push(bindings);
+            }
+            return;
+        };
+
+        if rest.is_empty() && terms.len() > 1 && self.wildcard(*first).is_some() {
+            let combined = match sum {
+                true => self.pool.sum(terms),
+                false => self.pool.product(terms),
+            };
+            out.extend(self.matches(*first, combined, bindings));
+            return;
+        }
+
+        for i in 0..terms.len() {
+            for bindings in self.matches(*first, terms[i], bindings.clone()) {
+                let mut remaining = terms.clone();
+                remaining.remove(i);
+                self.assign(rest, remaining, sum, bindings, out);
+            }
+        }
+    }
+}
+
+/// Check if two atoms are the same kind of node with the same number of children, so that their
+/// children can be matched pairwise
+pub(crate) fn same_head(a: &Atom, b: &Atom) -> bool {
+    match (a, b) {
+        (Atom::Power { .. }, Atom::Power { .. })
+        | (Atom::Complex { .. }, Atom::Complex { .. })
+        | (Atom::Neg(_), Atom::Neg(_))
+        | (Atom::Order(_), Atom::Order(_))
+        | (Atom::Equals { .. }, Atom::Equals { .. })
+        | (Atom::Fraction { .. }, Atom::Fraction { .. }) => true,
+        (Atom::Apply { func: f, args: x }, Atom::Apply { func: g, args: y }) => {
+            f == g && x.len() == y.len()
+        }
+        (Atom::Relation { op: p, .. }, Atom::Relation { op: q, .. }) => p == q,
+        (
+            Atom::Integral {
+                variable: v,
+                upper: u,
+                lower: l,
+                ..
+            },
+            Atom::Integral {
+                variable: w,
+                upper: u2,
+                lower: l2,
+                ..
+            },
+        ) => v == w && u.is_some() == u2.is_some() && l.is_some() == l2.is_some(),
+        (
+            Atom::BigOperator {
+                op: p, index: v, ..
+            },
+            Atom::BigOperator {
+                op: q, index: w, ..
+            },
+        ) => p == q && v == w,
+        (
+            Atom::Limit {
+                variable: v,
+                direction: d,
+                ..
+            },
+            Atom::Limit {
+                variable: w,
+                direction: e,
+                ..
+            },
+        ) => v == w && d == e,
+        (
+            Atom::Derivative {
+                variable: v,
+                order: n,
+                ..
+            },
+            Atom::Derivative {
+                variable: w,
+                order: m,
+                ..
+            },
+        ) => v == w && n == m,
+        (
+            Atom::Matrix {
+                columns: c,
+                entries: x,
+            },
+            Atom::Matrix {
+                columns: d,
+                entries: y,
+            },
+        ) => c == d && x.len() == y.len(),
+        _ => false,
+    }
+}
@@ -0,0 +1,924 @@
+use std::collections::HashMap;
+
+use num_traits::ToPrimitive;
+
+use crate::{
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, BigInt, ExprPool, Function, Rational,
+    },
+    eval::Env,
+};
+
+/// Number of times the working precision is raised when cancellation leaves fewer terms than
+/// requested
+const MAX_RETRIES: usize = 4;
+
+/// Magnitude below which the value of a function at the expansion point is taken to be exactly
+/// zero, as for `cos(π/2)`
+const EPSILON: f64 = 1e-12;
+
+/// Error produced when an expression has no series at the expansion point, with the ID of the
+/// sub-expression that caused it
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("{kind}")]
+pub struct SeriesError {
+    pub kind: SeriesErrorKind,
+    pub atom: AtomId,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum SeriesErrorKind {
+    #[error("Expansion point must not depend on the variable")]
+    Point,
+    #[error("Expression has an essential singularity at the expansion point")]
+    EssentialSingularity,
+    #[error("Expansion point is a branch point of the expression")]
+    Branch,
+    #[error("No series is known for the function '{0}'")]
+    UnknownFunction(String),
+    #[error("Cannot find the leading term of the series")]
+    LeadingTerm,
+    #[error("Division by zero")]
+    DivisionByZero,
+    #[error("Cannot expand this expression into a series")]
+    Unsupported,
+}
+
+/// Point around which a series is expanded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Point {
+    Finite(AtomId),
+    Infinity { negative: bool },
+}
+
+impl Point {
+    /// Recognize `point` as `∞`, `-∞` or a finite value that does not depend on `variable`
+    pub(crate) fn of(
+        pool: &mut ExprPool,
+        symbols: &SymbolStore,
+        point: AtomId,
+        variable: Symbol,
+    ) -> Result<Self, SeriesError> {
+        let infinity = |id| match pool.get(id) {
+            Atom::Symbol(sym) => symbols.name(*sym) == "∞",
+            Atom::Float(v) => v.value() == f64::INFINITY,
+            _ => false,
+        };
+        let negative_infinity = |id| match pool.get(id) {
+            Atom::Float(v) => v.value() == f64::NEG_INFINITY,
+            Atom::Neg(inner) => infinity(*inner),
+            Atom::Product(factors) => match **factors {
+                [minus_one, inner] => pool.get(minus_one).is_minus_one() && infinity(inner),
+                _ => false,
+            },
+            _ => false,
+        };
+
+        Ok(match point {
+            _ if infinity(point) => Self::Infinity { negative: false },
+            _ if negative_infinity(point) => Self::Infinity { negative: true },
+            _ if pool.depends_on(point, variable) => {
+                return Err(SeriesError {
+                    kind: SeriesErrorKind::Point,
+                    atom: point,
+                })
+            }
+            _ => Self::Finite(pool.simplify(point)),
+        })
+    }
+}
+
+/// Truncated Laurent series `Σ c_k t^(valuation + k) + O(t^precision)` in the local variable
+/// `t`, which is `x - a` around a finite point `a`, `1/x` around `∞` and `-1/x` around `-∞`.
+///
+/// Coefficients are simplified and the first one is never zero. A series with no coefficients
+/// is zero, and its valuation is then its precision
+#[derive(Clone, Debug)]
+pub(crate) struct Series {
+    pub(crate) valuation: i32,
+    pub(crate) coefficients: Vec<AtomId>,
+    /// Exponent of the remainder, or `None` if the series is exact
+    pub(crate) precision: Option<i32>,
+}
+
+impl Series {
+    fn zero(precision: Option<i32>) -> Self {
+        Self {
+            valuation: precision.unwrap_or(0),
+            coefficients: Vec::new(),
+            precision,
+        }
+    }
+
+    fn is_exact_zero(&self) -> bool {
+        self.coefficients.is_empty() && self.precision.is_none()
+    }
+
+    /// Exponent one past the last coefficient
+    fn end(&self) -> i32 {
+        self.valuation + self.coefficients.len() as i32
+    }
+
+    /// Shift every exponent by `by`, multiplying the series by `t^by`
+    fn shift(mut self, by: i32) -> Self {
+        self.valuation += by;
+        self.precision = self.precision.map(|p| p + by);
+        self
+    }
+}
+
+/// Lowest of two precisions, where `None` is exact
+fn min_precision(a: Option<i32>, b: Option<i32>) -> Option<i32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+impl ExprPool {
+    /// Expand the expression rooted at `id` into a Taylor or Laurent series in `variable` around
+    /// `point`, keeping the terms below `(x - a)^order` and writing the rest as an [Atom::Order]
+    /// remainder, as in `x - x^3/6 + O(x^5)` for `sin(x)` around zero with order five.
+    ///
+    /// The point may be `∞` or `-∞`, in which case the series is in powers of `1/x`. Sums,
+    /// products, quotients, powers and compositions of elementary functions are expanded, raising
+    /// the working precision when cancellation loses terms, so the remainder may only be of lower
+    /// order than requested if the retries run out. No remainder is written when the expansion is
+    /// exact, as for polynomials
+    pub fn series(
+        &mut self,
+        symbols: &mut SymbolStore,
+        id: AtomId,
+        variable: Symbol,
+        point: AtomId,
+        order: i32,
+    ) -> Result<AtomId, SeriesError> {
+        let point = Point::of(self, symbols, point, variable)?;
+        let series = self.local_series(symbols, id, variable, point, order)?;
+        Ok(self.series_atom(&series, variable, point))
+    }
+
+    /// Expand the expression rooted at `id` into a series in the local variable at `point`, with
+    /// every term below `order` known unless cancellation could not be overcome
+    pub(crate) fn local_series(
+        &mut self,
+        symbols: &mut SymbolStore,
+        id: AtomId,
+        variable: Symbol,
+        point: Point,
+        order: i32,
+    ) -> Result<Series, SeriesError> {
+        let normal = self.normal_form(id);
+        let mut precision = order;
+
+        for attempt in 0..=MAX_RETRIES {
+            let mut expander = Expander {
+                pool: self,
+                symbols,
+                variable,
+                point,
+                precision,
+                memo: HashMap::new(),
+            };
+            let last = attempt == MAX_RETRIES;
+            match expander.expand(normal) {
+                Ok(series) => match series.precision {
+                    Some(p) if p < order && !last => precision += order - p,
+                    _ => {
+                        expander.precision = order;
+                        return Ok(expander.normalize(series));
+                    }
+                },
+                Err(e) if e.kind == SeriesErrorKind::LeadingTerm && !last => {
+                    precision += (precision - order).max(2)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!("Last attempt always returns")
+    }
+
+    /// Write a series as a sum of terms in ascending order followed by its remainder
+    fn series_atom(&mut self, series: &Series, variable: Symbol, point: Point) -> AtomId {
+        let x = self.symbol(variable);
+        let (flip, negative) = match point {
+            Point::Finite(_) => (1, false),
+            Point::Infinity { negative } => (-1, negative),
+        };
+
+        let mut terms = Vec::new();
+        for (k, coefficient) in series.coefficients.iter().enumerate() {
+            let exponent = series.valuation + k as i32;
+            if self.get(*coefficient).is_zero() {
+                continue;
+            }
+            let exponent_atom = self.integer(flip * exponent);
+            let power = self.power(x, exponent_atom);
+            let term = match negative && exponent % 2 != 0 {
+                true => {
+                    let minus_one = self.integer(-1);
+                    self.product([minus_one, *coefficient, power])
+                }
+                false => self.product([*coefficient, power]),
+            };
+            terms.push(self.simplify(term));
+        }
+
+        if let Some(precision) = series.precision {
+            let exponent = self.integer(flip * precision);
+            let power = self.power(x, exponent);
+            let power = self.simplify(power);
+            terms.push(self.order(power));
+        }
+        let series = self.sum(terms);
+
+        // Terms are built in powers of `x` and only then written in powers of `x - a`, which
+        // simplifying would otherwise expand or factor, as in `(2x - π)^2/4`
+        match point {
+            Point::Finite(a) if !self.get(a).is_zero() => {
+                let a = self.neg(a);
+                let shifted = self.sum([x, a]);
+                let shifted = self.simplify(shifted);
+                self.substitute(series, x, shifted)
+            }
+            _ => series,
+        }
+    }
+}
+
+/// State of a single expansion at a fixed working precision, operating on expressions in the
+/// simplifier's normal form
+struct Expander<'a> {
+    pool: &'a mut ExprPool,
+    symbols: &'a mut SymbolStore,
+    variable: Symbol,
+    point: Point,
+    /// Exponent at which every intermediate series is truncated
+    precision: i32,
+    memo: HashMap<AtomId, Series>,
+}
+
+impl Expander<'_> {
+    fn simplify_sum<I: IntoIterator<Item = AtomId>>(&mut self, terms: I) -> AtomId {
+        let sum = self.pool.sum(terms);
+        self.pool.simplify(sum)
+    }
+
+    fn simplify_product<I: IntoIterator<Item = AtomId>>(&mut self, factors: I) -> AtomId {
+        let product = self.pool.product(factors);
+        self.pool.simplify(product)
+    }
+
+    fn simplify_apply(&mut self, func: Function, arg: AtomId) -> AtomId {
+        let applied = self.pool.apply(func, [arg]);
+        let applied = self.pool.simplify(applied);
+        match self.pool.eval(applied, self.symbols, &Env::new()) {
+            Ok(value) if value.abs() < EPSILON => self.pool.integer(0),
+            _ => applied,
+        }
+    }
+
+    fn recip(&mut self, value: AtomId) -> AtomId {
+        let minus_one = self.pool.integer(-1);
+        let power = self.pool.power(value, minus_one);
+        self.pool.simplify(power)
+    }
+
+    fn constant(&mut self, value: AtomId) -> Series {
+        let value = self.pool.simplify(value);
+        match self.pool.get(value).is_zero() {
+            true => Series::zero(None),
+            false => Series {
+                valuation: 0,
+                coefficients: vec![value],
+                precision: None,
+            },
+        }
+    }
+
+    fn integer(&mut self, value: i64) -> Series {
+        let value = self.pool.integer(value);
+        self.constant(value)
+    }
+
+    /// Get the series of the expansion variable `x` in the local variable
+    fn variable(&mut self) -> Series {
+        let one = self.pool.integer(1);
+        match self.point {
+            Point::Finite(a) if self.pool.get(a).is_zero() => Series {
+                valuation: 1,
+                coefficients: vec![one],
+                precision: None,
+            },
+            Point::Finite(a) => Series {
+                valuation: 0,
+                coefficients: vec![a, one],
+                precision: None,
+            },
+            Point::Infinity { negative } => Series {
+                valuation: -1,
+                coefficients: vec![self.pool.integer(if negative { -1 } else { 1 })],
+                precision: None,
+            },
+        }
+    }
+
+    /// Truncate a series at the working precision and strip zero coefficients from both ends
+    fn normalize(&self, mut series: Series) -> Series {
+        let limit = series.precision.unwrap_or(i32::MAX).min(self.precision);
+        if series.end() > limit {
+            let keep = (limit - series.valuation).max(0) as usize;
+            series.coefficients.truncate(keep);
+            series.precision = Some(limit);
+        }
+        series.precision = series.precision.map(|p| p.min(self.precision));
+
+        while series
+            .coefficients
+            .last()
+            .is_some_and(|c| self.pool.get(*c).is_zero())
+        {
+            series.coefficients.pop();
+        }
+        let zeros = series
+            .coefficients
+            .iter()
+            .take_while(|c| self.pool.get(**c).is_zero())
+            .count();
+        series.coefficients.drain(..zeros);
+        series.valuation += zeros as i32;
+
+        match series.coefficients.is_empty() {
+            true => Series::zero(series.precision),
+            false => series,
+        }
+    }
+
+    fn add(&mut self, a: &Series, b: &Series) -> Series {
+        if a.is_exact_zero() {
+            return b.clone();
+        }
+        if b.is_exact_zero() {
+            return a.clone();
+        }
+
+        let valuation = a.valuation.min(b.valuation);
+        let mut coefficients = Vec::new();
+        for exponent in valuation..a.end().max(b.end()) {
+            let terms = [a, b].into_iter().filter_map(|s| {
+                let k = usize::try_from(exponent - s.valuation).ok()?;
+                s.coefficients.get(k).copied()
+            });
+            let terms = terms.collect::<Vec<_>>();
+            coefficients.push(self.simplify_sum(terms));
+        }
+        self.normalize(Series {
+            valuation,
+            coefficients,
+            precision: min_precision(a.precision, b.precision),
+        })
+    }
+
+    fn mul(&mut self, a: &Series, b: &Series) -> Series {
+        if a.is_exact_zero() || b.is_exact_zero() {
+            return Series::zero(None);
+        }
+
+        let valuation = a.valuation + b.valuation;
+        let precision = min_precision(
+            a.precision.map(|p| p + b.valuation),
+            b.precision.map(|p| p + a.valuation),
+        );
+        let length = match a.coefficients.is_empty() || b.coefficients.is_empty() {
+            true => 0,
+            false => a.coefficients.len() + b.coefficients.len() - 1,
+        };
+
+        // Skip the terms that would be truncated anyway
+        let limit = precision.unwrap_or(i32::MAX).min(self.precision);
+        let kept = (limit - valuation).max(0) as usize;
+        let precision = match length > kept {
+            true => Some(limit),
+            false => precision,
+        };
+        let length = length.min(kept);
+
+        let mut coefficients = Vec::with_capacity(length);
+        for k in 0..length {
+            let terms = (0..=k)
+                .filter_map(|i| Some((*a.coefficients.get(i)?, *b.coefficients.get(k - i)?)))
+                .map(|(x, y)| self.pool.product([x, y]))
+                .collect::<Vec<_>>();
+            coefficients.push(self.simplify_sum(terms));
+        }
+        self.normalize(Series {
+            valuation,
+            coefficients,
+            precision,
+        })
+    }
+
+    fn reciprocal(&mut self, a: &Series, id: AtomId) -> Result<Series, SeriesError> {
+        let error = |kind| SeriesError { kind, atom: id };
+        if a.is_exact_zero() {
+            return Err(error(SeriesErrorKind::DivisionByZero));
+        }
+        let Some(&first) = a.coefficients.first() else {
+            return Err(error(SeriesErrorKind::LeadingTerm));
+        };
+
+        let valuation = -a.valuation;
+        let inverse = self.recip(first);
+        if a.precision.is_none() && a.coefficients.len() == 1 {
+            return Ok(Series {
+                valuation,
+                coefficients: vec![inverse],
+                precision: None,
+            });
+        }
+
+        // Known coefficients of `a` determine as many of its reciprocal
+        let known = a.precision.map_or(i32::MAX, |p| p - a.valuation);
+        let length = known.min(self.precision - valuation).max(0) as usize;
+        let mut coefficients = vec![inverse];
+        for k in 1..length {
+            let terms = (1..=k.min(a.coefficients.len() - 1))
+                .map(|j| self.pool.product([a.coefficients[j], coefficients[k - j]]))
+                .collect::<Vec<_>>();
+            let sum = self.pool.sum(terms);
+            let minus_one = self.pool.integer(-1);
+            coefficients.push(self.simplify_product([minus_one, inverse, sum]));
+        }
+        coefficients.truncate(length);
+
+        Ok(self.normalize(Series {
+            valuation,
+            coefficients,
+            precision: Some(valuation + length as i32),
+        }))
+    }
+
+    fn pow_integer(&mut self, a: &Series, n: i64, id: AtomId) -> Result<Series, SeriesError> {
+        if n < 0 {
+            let power = self.pow_integer(a, -n, id)?;
+            return self.reciprocal(&power, id);
+        }
+
+        let mut result = self.integer(1);
+        let mut square = a.clone();
+        let mut n = n;
+        while n > 0 {
+            if n % 2 == 1 {
+                result = self.mul(&result, &square);
+            }
+            n /= 2;
+            if n > 0 {
+                square = self.mul(&square, &square);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Get the number of terms of a composition `f(u)` needed to reach the working precision,
+    /// where `u` vanishes at the expansion point
+    fn composition_terms(&self, u: &Series) -> usize {
+        match u.coefficients.is_empty() || self.precision <= 0 {
+            true => 1,
+            false => ((self.precision - 1) / u.valuation) as usize + 1,
+        }
+    }
+
+    /// Substitute a series `u` vanishing at the expansion point into the power series with the
+    /// given coefficients, which is a polynomial if `polynomial` is set
+    fn compose(&mut self, coefficients: &[AtomId], u: &Series, polynomial: bool) -> Series {
+        let mut result = self.constant(coefficients[0]);
+        let mut power = self.integer(1);
+        for coefficient in &coefficients[1..] {
+            power = self.mul(&power, u);
+            let coefficient = self.constant(*coefficient);
+            let term = self.mul(&coefficient, &power);
+            result = self.add(&result, &term);
+        }
+
+        if !polynomial && !u.is_exact_zero() {
+            let truncation = coefficients.len() as i32 * u.valuation;
+            result.precision = min_precision(result.precision, Some(truncation));
+            result = self.normalize(result);
+        }
+        result
+    }
+
+    /// Split a series with no negative powers into its constant term and the rest
+    fn split_constant(
+        &mut self,
+        a: &Series,
+        id: AtomId,
+        negative: SeriesErrorKind,
+    ) -> Result<(AtomId, Series), SeriesError> {
+        if !a.coefficients.is_empty() && a.valuation < 0 {
+            return Err(SeriesError {
+                kind: negative,
+                atom: id,
+            });
+        }
+        if a.precision.is_some_and(|p| p <= 0) {
+            return Err(SeriesError {
+                kind: SeriesErrorKind::LeadingTerm,
+                atom: id,
+            });
+        }
+
+        let constant = match a.valuation == 0 && !a.coefficients.is_empty() {
+            true => a.coefficients[0],
+            false => self.pool.integer(0),
+        };
+        let minus = self.pool.neg(constant);
+        let minus = self.constant(minus);
+        Ok((constant, self.add(a, &minus)))
+    }
+
+    /// Expand `f(a)` from the values at the constant term of `a` of `f` and its derivatives,
+    /// which repeat with the period of `derivatives`
+    fn taylor(
+        &mut self,
+        a: &Series,
+        id: AtomId,
+        derivatives: &[AtomId],
+    ) -> Result<Series, SeriesError> {
+        let (_, u) = self.split_constant(a, id, SeriesErrorKind::EssentialSingularity)?;
+        let terms = self.composition_terms(&u);
+
+        let mut factorial = BigInt::from(1);
+        let mut coefficients = Vec::with_capacity(terms);
+        for k in 0..terms {
+            if k > 0 {
+                factorial *= k;
+            }
+            let factorial = self.pool.integer(factorial.clone());
+            let factorial = self.recip(factorial);
+            let derivative = derivatives[k % derivatives.len()];
+            coefficients.push(self.simplify_product([derivative, factorial]));
+        }
+        Ok(self.compose(&coefficients, &u, false))
+    }
+
+    fn exp(&mut self, a: &Series, id: AtomId) -> Result<Series, SeriesError> {
+        let (constant, _) = self.split_constant(a, id, SeriesErrorKind::EssentialSingularity)?;
+        let value = self.simplify_apply(Function::Exp, constant);
+        self.taylor(a, id, &[value])
+    }
+
+    /// Expand `sin`, `cos`, `sinh` or `cosh`, whose derivatives at the constant term `c` cycle
+    /// through the function and its partner at `c`, negated in turn for `sin` and `cos`
+    fn periodic(&mut self, a: &Series, id: AtomId, func: Function) -> Result<Series, SeriesError> {
+        let (constant, _) = self.split_constant(a, id, SeriesErrorKind::EssentialSingularity)?;
+        let partner = match func {
+            Function::Sin => Function::Cos,
+            Function::Cos => Function::Sin,
+            Function::Sinh => Function::Cosh,
+            _ => Function::Sinh,
+        };
+        let f = self.simplify_apply(func, constant);
+        let g = self.simplify_apply(partner, constant);
+
+        let minus_one = self.pool.integer(-1);
+        let neg_f = self.simplify_product([minus_one, f]);
+        let neg_g = self.simplify_product([minus_one, g]);
+        match func {
+            Function::Sin => self.taylor(a, id, &[f, g, neg_f, neg_g]),
+            Function::Cos => self.taylor(a, id, &[f, neg_g, neg_f, g]),
+            _ => self.taylor(a, id, &[f, g]),
+        }
+    }
+
+    /// Factor a nonzero series into `c t^v (1 + u)`, returning `c` and `u`
+    fn factor_leading(&mut self, a: &Series, id: AtomId) -> Result<(AtomId, Series), SeriesError> {
+        let Some(&first) = a.coefficients.first() else {
+            let kind = match a.precision {
+                Some(_) => SeriesErrorKind::LeadingTerm,
+                None => SeriesErrorKind::Branch,
+            };
+            return Err(SeriesError { kind, atom: id });
+        };
+        let inverse = self.recip(first);
+        let inverse = self.constant(inverse);
+        let normalized = self.mul(a, &inverse).shift(-a.valuation);
+        let minus_one = self.integer(-1);
+        Ok((first, self.add(&normalized, &minus_one)))
+    }
+
+    fn ln(&mut self, a: &Series, id: AtomId) -> Result<Series, SeriesError> {
+        let (first, u) = self.factor_leading(a, id)?;
+        if a.valuation != 0 {
+            return Err(SeriesError {
+                kind: SeriesErrorKind::Branch,
+                atom: id,
+            });
+        }
+
+        let terms = self.composition_terms(&u);
+        let mut coefficients = vec![self.simplify_apply(Function::Ln, first)];
+        for k in 1..terms {
+            let sign = if k % 2 == 1 { 1 } else { -1 };
+            let coefficient = Rational::new(sign, k as i64).expect("Nonzero");
+            coefficients.push(self.pool.number(coefficient));
+        }
+        Ok(self.compose(&coefficients, &u, false))
+    }
+
+    /// Expand `a^exponent` for an exponent that does not depend on the variable
+    fn power(&mut self, a: &Series, exponent: AtomId, id: AtomId) -> Result<Series, SeriesError> {
+        let error = |kind| SeriesError { kind, atom: id };
+        let rational = self.pool.get(exponent).to_rational();
+        if let Some(n) = rational.as_ref().filter(|r| r.is_integer()) {
+            let n = n
+                .numerator()
+                .to_i64()
+                .ok_or(error(SeriesErrorKind::Unsupported))?;
+            return self.pow_integer(a, n, id);
+        }
+        if a.is_exact_zero() {
+            return match rational {
+                Some(r) if !r.is_negative() => Ok(Series::zero(None)),
+                _ => Err(error(SeriesErrorKind::DivisionByZero)),
+            };
+        }
+
+        // The leading power `t^(v α)` must remain a power series in `t`
+        let shift = match a.valuation {
+            0 => 0,
+            v => {
+                let shift = rational
+                    .map(|r| &r * &Rational::integer(v))
+                    .filter(|r| r.is_integer())
+                    .and_then(|r| r.numerator().to_i32());
+                shift.ok_or(error(SeriesErrorKind::Branch))?
+            }
+        };
+        let (first, u) = self.factor_leading(a, id)?;
+
+        // Binomial series of `(1 + u)^α`
+        let terms = self.composition_terms(&u);
+        let mut coefficients = vec![self.pool.integer(1)];
+        for k in 1..terms {
+            let offset = self.pool.integer(1 - k as i64);
+            let factor = self.pool.sum([exponent, offset]);
+            let k = self.pool.integer(k as i64);
+            let k = self.recip(k);
+            let previous = coefficients[coefficients.len() - 1];
+            coefficients.push(self.simplify_product([previous, factor, k]));
+        }
+        let binomial = self.compose(&coefficients, &u, false);
+
+        let leading = self.pool.power(first, exponent);
+        let leading = self.constant(leading);
+        let series = self.mul(&leading, &binomial).shift(shift);
+        Ok(self.normalize(series))
+    }
+
+    fn derivative(&mut self, a: &Series) -> Series {
+        let coefficients = (a.valuation..)
+            .zip(a.coefficients.clone())
+            .map(|(exponent, c)| {
+                let exponent = self.pool.integer(exponent);
+                self.simplify_product([exponent, c])
+            })
+            .collect();
+        let series = Series {
+            valuation: a.valuation - 1,
+            coefficients,
+            precision: a.precision.map(|p| p - 1),
+        };
+        self.normalize(series)
+    }
+
+    fn antiderivative(&mut self, a: &Series, id: AtomId) -> Result<Series, SeriesError> {
+        let mut coefficients = Vec::with_capacity(a.coefficients.len());
+        for (exponent, c) in (a.valuation..).zip(a.coefficients.clone()) {
+            // Integrating `1/t` would give a logarithm
+            if exponent == -1 {
+                return Err(SeriesError {
+                    kind: SeriesErrorKind::Branch,
+                    atom: id,
+                });
+            }
+            let exponent = self.pool.integer(exponent + 1);
+            let exponent = self.recip(exponent);
+            coefficients.push(self.simplify_product([exponent, c]));
+        }
+        let series = Series {
+            valuation: a.valuation + 1,
+            coefficients,
+            precision: a.precision.map(|p| p + 1),
+        };
+        Ok(self.normalize(series))
+    }
+
+    /// Expand `f(a)` as `f(c) + ∫ f'(a) a' dt` given the constant term `c` of `a` and the series
+    /// of `f'(a)`
+    fn integral(
+        &mut self,
+        a: &Series,
+        id: AtomId,
+        func: Function,
+        derivative: &Series,
+    ) -> Result<Series, SeriesError> {
+        let (constant, _) = self.split_constant(a, id, SeriesErrorKind::Branch)?;
+        let da = self.derivative(a);
+        let integrand = self.mul(derivative, &da);
+        let integral = self.antiderivative(&integrand, id)?;
+        let value = self.simplify_apply(func, constant);
+        let value = self.constant(value);
+        Ok(self.add(&value, &integral))
+    }
+
+    /// Get the sign of a series at points near the expansion point, if it is the same on both
+    /// sides of a finite point
+    fn sign(&mut self, a: &Series, id: AtomId) -> Result<f64, SeriesError> {
+        let error = |kind| SeriesError { kind, atom: id };
+        let Some(&first) = a.coefficients.first() else {
+            return Err(error(SeriesErrorKind::LeadingTerm));
+        };
+        if matches!(self.point, Point::Finite(_)) && a.valuation % 2 != 0 {
+            return Err(error(SeriesErrorKind::Branch));
+        }
+
+        let value = self.pool.eval(first, self.symbols, &Env::new());
+        match value {
+            Ok(value) if value != 0. && value.is_finite() => Ok(value.signum()),
+            _ => Err(error(SeriesErrorKind::Unsupported)),
+        }
+    }
+
+    fn pi(&mut self) -> AtomId {
+        let pi = self.symbols.intern("π");
+        self.pool.symbol(pi)
+    }
+
+    fn atan(&mut self, a: &Series, id: AtomId) -> Result<Series, SeriesError> {
+        // Far from zero, `atan(a) = ±π/2 - atan(1/a)`
+        if !a.coefficients.is_empty() && a.valuation < 0 {
+            let sign = self.sign(a, id)?;
+            let reciprocal = self.reciprocal(a, id)?;
+            let inner = self.atan(&reciprocal, id)?;
+            let pi = self.pi();
+            let half = Rational::new(sign as i64, 2).expect("Nonzero");
+            let half = self.pool.number(half);
+            let limit = self.simplify_product([half, pi]);
+            let limit = self.constant(limit);
+            let minus_one = self.integer(-1);
+            let inner = self.mul(&minus_one, &inner);
+            return Ok(self.add(&limit, &inner));
+        }
+
+        let square = self.mul(a, a);
+        let one = self.integer(1);
+        let denominator = self.add(&one, &square);
+        let derivative = self.reciprocal(&denominator, id)?;
+        self.integral(a, id, Function::Atan, &derivative)
+    }
+
+    /// Expand `asin` or `acos`, whose derivatives are `±1/sqrt(1 - a^2)`
+    fn arcsine(&mut self, a: &Series, id: AtomId, func: Function) -> Result<Series, SeriesError> {
+        let square = self.mul(a, a);
+        let minus_one = self.integer(-1);
+        let square = self.mul(&minus_one, &square);
+        let one = self.integer(1);
+        let difference = self.add(&one, &square);
+        let exponent = self.pool.number(Rational::new(-1, 2).expect("Nonzero"));
+        let mut derivative = self.power(&difference, exponent, id)?;
+        if func == Function::Acos {
+            derivative = self.mul(&minus_one, &derivative);
+        }
+        self.integral(a, id, func, &derivative)
+    }
+
+    fn expand(&mut self, id: AtomId) -> Result<Series, SeriesError> {
+        if let Some(done) = self.memo.get(&id) {
+            return Ok(done.clone());
+        }
+
+        let out = match self.pool.depends_on(id, self.variable) {
+            true => self.expand_atom(id)?,
+            false => self.constant(id),
+        };
+        self.memo.insert(id, out.clone());
+        Ok(out)
+    }
+
+    fn expand_atom(&mut self, id: AtomId) -> Result<Series, SeriesError> {
+        let error = |kind| SeriesError { kind, atom: id };
+
+        Ok(match self.pool.get(id).clone() {
+            Atom::Symbol(_) => self.variable(),
+            Atom::Sum(terms) => {
+                let mut sum = Series::zero(None);
+                for term in terms.iter() {
+                    let term = self.expand(*term)?;
+                    sum = self.add(&sum, &term);
+                }
+                sum
+            }
+            Atom::Product(factors) => {
+                let mut product = self.integer(1);
+                for factor in factors.iter() {
+                    let factor = self.expand(*factor)?;
+                    product = self.mul(&product, &factor);
+                }
+                product
+            }
+            Atom::Neg(inner) => {
+                let inner = self.expand(inner)?;
+                let minus_one = self.integer(-1);
+                self.mul(&minus_one, &inner)
+            }
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => {
+                let numerator = self.expand(numerator)?;
+                let denominator = self.expand(denominator)?;
+                let reciprocal = self.reciprocal(&denominator, id)?;
+                self.mul(&numerator, &reciprocal)
+            }
+            Atom::Power { base, exponent } if !self.pool.depends_on(exponent, self.variable) => {
+                let base = self.expand(base)?;
+                let exponent = self.pool.simplify(exponent);
+                self.power(&base, exponent, id)?
+            }
+            Atom::Power { base, exponent } => {
+                // `b^e = exp(e ln(b))`
+                let exponent = match self.pool.get(base) {
+                    Atom::Symbol(sym) if self.symbols.name(*sym) == "e" => exponent,
+                    _ => {
+                        let ln = self.pool.apply(Function::Ln, [base]);
+                        self.pool.product([exponent, ln])
+                    }
+                };
+                let exponent = self.expand(exponent)?;
+                self.exp(&exponent, id)?
+            }
+            Atom::Apply { func, args } => {
+                let [arg] = *args else {
+                    return Err(error(SeriesErrorKind::Unsupported));
+                };
+                let a = self.expand(arg)?;
+                match func {
+                    Function::Exp => self.exp(&a, id)?,
+                    Function::Sin | Function::Cos | Function::Sinh | Function::Cosh => {
+                        self.periodic(&a, id, func)?
+                    }
+                    Function::Tan | Function::Tanh => {
+                        let (f, g) = match func {
+                            Function::Tan => (Function::Sin, Function::Cos),
+                            _ => (Function::Sinh, Function::Cosh),
+                        };
+                        let numerator = self.periodic(&a, id, f)?;
+                        let denominator = self.periodic(&a, id, g)?;
+                        let reciprocal = self.reciprocal(&denominator, id)?;
+                        self.mul(&numerator, &reciprocal)
+                    }
+                    Function::Ln => self.ln(&a, id)?,
+                    Function::Log => {
+                        let ln = self.ln(&a, id)?;
+                        let ten = self.pool.integer(10);
+                        let ln_ten = self.simplify_apply(Function::Ln, ten);
+                        let scale = self.recip(ln_ten);
+                        let scale = self.constant(scale);
+                        self.mul(&scale, &ln)
+                    }
+                    Function::Sqrt => {
+                        let half = self.pool.number(Rational::new(1, 2).expect("Nonzero"));
+                        self.power(&a, half, id)?
+                    }
+                    Function::Atan => self.atan(&a, id)?,
+                    Function::Asin | Function::Acos => self.arcsine(&a, id, func)?,
+                    Function::Abs if a.is_exact_zero() => a,
+                    Function::Abs => {
+                        let sign = self.sign(&a, id)?;
+                        let sign = self.integer(sign as i64);
+                        self.mul(&sign, &a)
+                    }
+                    Function::User(sym) => {
+                        let name = self.symbols.name(sym).to_owned();
+                        return Err(error(SeriesErrorKind::UnknownFunction(name)));
+                    }
+                    func => {
+                        let name = func.builtin_name().unwrap_or_default().to_owned();
+                        return Err(error(SeriesErrorKind::UnknownFunction(name)));
+                    }
+                }
+            }
+            // A remainder vanishes at least as fast as the leading power of its term
+            Atom::Order(term) => {
+                let term = self.expand(term)?;
+                match term.is_exact_zero() {
+                    true => term,
+                    false => self.normalize(Series::zero(Some(term.valuation))),
+                }
+            }
+            _ => return Err(error(SeriesErrorKind::Unsupported)),
+        })
+    }
+}
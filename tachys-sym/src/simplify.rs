@@ -0,0 +1,1588 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, HashMap},
+};
+
+use num_integer::Integer;
+use num_traits::{Pow, ToPrimitive};
+
+use crate::{
+    assume::Assumptions,
+    atom::{
+        symbol::Symbol, Atom, AtomId, BigInt, Dimension, ExprPool, Function, Rational, Relation,
+        Unit,
+    },
+    eval,
+};
+
+/// Largest number of bits allowed in the numerator or denominator of an exact power folded into a
+/// constant, above which the power is left unevaluated
+const MAX_POWER_BITS: u64 = 1 << 16;
+
+/// Largest integer power of a matrix computed by repeated multiplication
+const MAX_MATRIX_POWER: u32 = 1 << 10;
+
+impl ExprPool {
+    /// Simplify the expression rooted at `id` into a canonical form, so that expressions differing
+    /// only in the order of terms, the nesting of sums and products or in unreduced constants
+    /// simplify to the same [AtomId].
+    ///
+    /// Like terms and powers of a common base are combined, with quantities in units of the same
+    /// dimension first written in the smallest of those units and units whose dimensions cancel
+    /// in a product replaced by their size, as in `1 Hz s = 1`, constants are folded exactly,
+    /// including functions at special points like `cos(π) = -1`, and quotients are written as a
+    /// single [Atom::Fraction] with common factors cancelled. A numeric coefficient of a lone sum
+    /// is distributed over its terms, while sums multiplied by other factors have their common
+    /// numeric factor taken out, as in `2(x + y) = 2x + 2y` and `z(2x + 2y) = 2z(x + y)`. Every
+    /// subexpression is visited once and rules are never re-applied to their own output, so the
+    /// pass always terminates. Sums are not expanded, leaving `(x + 1)^2` as written
+    pub fn simplify(&mut self, id: AtomId) -> AtomId {
+        self.simplify_assuming(id, &Assumptions::new())
+    }
+
+    /// Simplify the expression rooted at `id` like [ExprPool::simplify], also applying the rules
+    /// that only hold under `assumptions`, such as `sqrt(x^2) = x` for nonnegative `x` or
+    /// `(-1)^(2n) = 1` for integer `n`
+    pub fn simplify_assuming(&mut self, id: AtomId, assumptions: &Assumptions) -> AtomId {
+        let mut simplifier = Simplifier::new(self, assumptions);
+        let normal = simplifier.normalize(id);
+        simplifier.present(normal)
+    }
+
+    /// Bring the expression rooted at `id` into the simplifier's normal form, in which negations,
+    /// fractions and square roots are all written as products and powers
+    pub(crate) fn normal_form(&mut self, id: AtomId) -> AtomId {
+        self.normal_form_assuming(id, &Assumptions::new())
+    }
+
+    /// Bring the expression rooted at `id` into the simplifier's normal form under `assumptions`
+    pub(crate) fn normal_form_assuming(&mut self, id: AtomId, assumptions: &Assumptions) -> AtomId {
+        Simplifier::new(self, assumptions).normalize(id)
+    }
+}
+
+/// A numeric literal collected while combining terms and factors
+#[derive(Clone, Debug)]
+enum Number {
+    Exact(Rational),
+    Float(f64),
+    /// Complex number whose real and imaginary parts are real numbers, with a nonzero imaginary
+    /// part
+    Complex(Box<(Number, Number)>),
+}
+
+impl Number {
+    fn of(pool: &ExprPool, id: AtomId) -> Option<Self> {
+        match pool.get(id) {
+            Atom::Float(f) => Some(Self::Float(f.value())),
+            Atom::Complex { re, im } => {
+                let (re, im) = (Self::of(pool, *re)?, Self::of(pool, *im)?);
+                match re.is_complex() || im.is_complex() {
+                    true => None,
+                    false => Some(Self::complex(re, im)),
+                }
+            }
+            atom => atom.to_rational().map(Self::Exact),
+        }
+    }
+
+    fn integer(value: i64) -> Self {
+        Self::Exact(Rational::integer(value))
+    }
+
+    fn imaginary_unit() -> Self {
+        Self::complex(Self::integer(0), Self::integer(1))
+    }
+
+    /// Build `re + im·i` from real parts, which is just `re` if `im` is zero
+    fn complex(re: Self, im: Self) -> Self {
+        match im.is_zero() {
+            true => re,
+            false => Self::Complex(Box::new((re, im))),
+        }
+    }
+
+    /// Split into real and imaginary parts
+    fn parts(&self) -> (Self, Self) {
+        match self {
+            Self::Complex(parts) => (**parts).clone(),
+            real => (real.clone(), Self::integer(0)),
+        }
+    }
+
+    fn is_complex(&self) -> bool {
+        matches!(self, Self::Complex(_))
+    }
+
+    /// Get the value of a real number, which is NaN for a complex number
+    fn to_f64(&self) -> f64 {
+        match self {
+            Self::Exact(r) => r.to_f64(),
+            Self::Float(f) => *f,
+            Self::Complex(_) => f64::NAN,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        match self {
+            Self::Exact(r) => r.is_zero(),
+            Self::Float(f) => *f == 0.,
+            Self::Complex(_) => false,
+        }
+    }
+
+    fn is_one(&self) -> bool {
+        match self {
+            Self::Exact(r) => r.is_one(),
+            Self::Float(f) => *f == 1.,
+            Self::Complex(_) => false,
+        }
+    }
+
+    fn is_negative(&self) -> bool {
+        match self {
+            Self::Exact(r) => r.is_negative(),
+            Self::Float(f) => *f < 0.,
+            Self::Complex(_) => false,
+        }
+    }
+
+    /// Get the largest number of bits in the numerator or denominator of an exact part
+    fn bits(&self) -> u64 {
+        match self {
+            Self::Exact(r) => r.numerator().bits().max(r.denominator().bits()),
+            Self::Float(_) => 0,
+            Self::Complex(parts) => parts.0.bits().max(parts.1.bits()),
+        }
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        match (self, rhs) {
+            (Self::Exact(a), Self::Exact(b)) => Self::Exact(a + b),
+            (Self::Complex(_), _) | (_, Self::Complex(_)) => {
+                let ((a, b), (c, d)) = (self.parts(), rhs.parts());
+                Self::complex(a.add(&c), b.add(&d))
+            }
+            _ => Self::Float(self.to_f64() + rhs.to_f64()),
+        }
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        match (self, rhs) {
+            (Self::Exact(a), Self::Exact(b)) => Self::Exact(a * b),
+            (Self::Complex(_), _) | (_, Self::Complex(_)) => {
+                let ((a, b), (c, d)) = (self.parts(), rhs.parts());
+                Self::complex(a.mul(&c).add(&b.mul(&d).neg()), a.mul(&d).add(&b.mul(&c)))
+            }
+            _ => Self::Float(self.to_f64() * rhs.to_f64()),
+        }
+    }
+
+    fn neg(&self) -> Self {
+        match self {
+            Self::Exact(r) => Self::Exact(-r),
+            Self::Float(f) => Self::Float(-f),
+            Self::Complex(parts) => Self::complex(parts.0.neg(), parts.1.neg()),
+        }
+    }
+
+    fn conj(&self) -> Self {
+        let (re, im) = self.parts();
+        Self::complex(re, im.neg())
+    }
+
+    /// Get the square of the modulus `re² + im²`
+    fn norm(&self) -> Self {
+        let (re, im) = self.parts();
+        re.mul(&re).add(&im.mul(&im))
+    }
+
+    /// Get the absolute value, or `None` for a complex number whose modulus is not a rational or
+    /// floating point number
+    fn abs(&self) -> Option<Self> {
+        match self {
+            Self::Exact(r) => Some(Self::Exact(r.abs())),
+            Self::Float(f) => Some(Self::Float(f.abs())),
+            Self::Complex(_) => self.norm().pow(&Self::Exact(
+                Rational::new(1, 2).expect("Denominator is not zero"),
+            )),
+        }
+    }
+
+    /// Get `1 / self`, or `None` if this number is zero
+    fn recip(&self) -> Option<Self> {
+        match self {
+            Self::Exact(r) => r.recip().map(Self::Exact),
+            Self::Float(f) => Some(Self::Float(1. / f)),
+            Self::Complex(_) => {
+                let scale = self.norm().recip()?;
+                Some(self.conj().mul(&scale))
+            }
+        }
+    }
+
+    /// Raise this number to the power of `exponent`, or get `None` if the result is not a
+    /// rational, floating point or complex number, is infinite or would be too large to represent
+    /// exactly. Complex numbers are only raised to integer powers
+    fn pow(&self, exponent: &Self) -> Option<Self> {
+        match (self, exponent) {
+            (_, Self::Complex(_)) => None,
+            (Self::Complex(_), Self::Exact(exponent)) if exponent.is_integer() => {
+                let exponent = exponent.numerator().to_i32()?;
+                if self.bits().saturating_mul(exponent.unsigned_abs().into()) > MAX_POWER_BITS {
+                    return None;
+                }
+
+                let mut out = Self::integer(1);
+                let mut square = self.clone();
+                let mut power = exponent.unsigned_abs();
+                while power > 0 {
+                    if power & 1 == 1 {
+                        out = out.mul(&square);
+                    }
+                    power >>= 1;
+                    if power > 0 {
+                        square = square.mul(&square);
+                    }
+                }
+                match exponent < 0 {
+                    true => out.recip(),
+                    false => Some(out),
+                }
+            }
+            (Self::Complex(_), _) => None,
+            (Self::Exact(base), Self::Exact(exponent)) if exponent.is_integer() => {
+                let exponent = exponent.numerator().to_i32()?;
+                match self.bits().saturating_mul(exponent.unsigned_abs().into()) > MAX_POWER_BITS {
+                    true => None,
+                    false => base.pow(exponent).map(Self::Exact),
+                }
+            }
+            // Only fold roots that are exact, as in `4^(1/2) = 2`
+            (Self::Exact(base), Self::Exact(exponent)) if !base.is_negative() => {
+                let degree = exponent.denominator().to_u32()?;
+                let root = Rational::new(
+                    exact_root(base.numerator(), degree)?,
+                    exact_root(base.denominator(), degree)?,
+                )?;
+                Self::Exact(root).pow(&Self::Exact(Rational::integer(
+                    exponent.numerator().clone(),
+                )))
+            }
+            (Self::Exact(_), Self::Exact(_)) => None,
+            _ => {
+                let (base, exponent) = (self.to_f64(), exponent.to_f64());
+                let value = base.powf(exponent);
+                match (base >= 0. || exponent.fract() == 0.) && value.is_finite() {
+                    true => Some(Self::Float(value)),
+                    false => None,
+                }
+            }
+        }
+    }
+
+    /// Total order on numbers by value, placing exact numbers before equal floats and real
+    /// numbers before complex numbers, which are ordered by their real then imaginary parts
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Exact(a), Self::Exact(b)) => a.cmp(b),
+            (Self::Complex(a), Self::Complex(b)) => {
+                a.0.compare(&b.0).then_with(|| a.1.compare(&b.1))
+            }
+            (Self::Complex(_), _) => Ordering::Greater,
+            (_, Self::Complex(_)) => Ordering::Less,
+            _ => self
+                .to_f64()
+                .total_cmp(&other.to_f64())
+                .then_with(|| matches!(self, Self::Float(_)).cmp(&matches!(other, Self::Float(_)))),
+        }
+    }
+
+    fn insert(&self, pool: &mut ExprPool) -> AtomId {
+        match self {
+            Self::Exact(r) => pool.number(r.clone()),
+            Self::Float(f) => pool.float(*f),
+            Self::Complex(parts) => {
+                let re = parts.0.insert(pool);
+                let im = parts.1.insert(pool);
+                pool.complex(re, im)
+            }
+        }
+    }
+}
+
+/// A term of a sum split into the units it is measured in and its other factors
+struct QuantityParts {
+    /// Unit factors, each a unit or an integer power of one
+    units: Vec<AtomId>,
+    rest: Vec<AtomId>,
+    /// Size of the product of the units in coherent SI units
+    scale: Rational,
+    dimension: Dimension,
+}
+
+/// Get the `degree`th root of `value` if it is an integer
+fn exact_root(value: &BigInt, degree: u32) -> Option<BigInt> {
+    let root = value.nth_root(degree);
+    match Pow::pow(&root, degree) == *value {
+        true => Some(root),
+        false => None,
+    }
+}
+
+/// State of a single simplification pass.
+///
+/// Expressions are first brought into a normal form where negations, fractions and square roots
+/// are all written as products and powers, making cancellation a matter of adding exponents. The
+/// normal form is then presented with fractions and square roots restored
+struct Simplifier<'a> {
+    pool: &'a mut ExprPool,
+    assumptions: &'a Assumptions,
+    normal: HashMap<AtomId, AtomId>,
+    presented: HashMap<AtomId, AtomId>,
+}
+
+impl<'a> Simplifier<'a> {
+    fn new(pool: &'a mut ExprPool, assumptions: &'a Assumptions) -> Self {
+        Self {
+            pool,
+            assumptions,
+            normal: HashMap::new(),
+            presented: HashMap::new(),
+        }
+    }
+
+    fn number(&self, id: AtomId) -> Option<Number> {
+        Number::of(self.pool, id)
+    }
+
+    fn normalize(&mut self, id: AtomId) -> AtomId {
+        if let Some(done) = self.normal.get(&id) {
+            return *done;
+        }
+
+        let out = match self.pool.get(id).clone() {
+            Atom::Symbol(Symbol::IMAGINARY_UNIT) => Number::imaginary_unit().insert(self.pool),
+            Atom::Complex { .. } => match self.number(id) {
+                Some(value) => value.insert(self.pool),
+                None => id,
+            },
+            Atom::Integer(_) | Atom::Rational(_) | Atom::Float(_) | Atom::Symbol(_) => id,
+            Atom::Sum(terms) => {
+                let terms = terms.iter().map(|term| self.normalize(*term)).collect();
+                self.sum(terms)
+            }
+            Atom::Product(factors) => {
+                let factors = factors
+                    .iter()
+                    .map(|factor| self.normalize(*factor))
+                    .collect();
+                self.product(factors)
+            }
+            Atom::Power { base, exponent } => {
+                let base = self.normalize(base);
+                let exponent = self.normalize(exponent);
+                self.power(base, exponent)
+            }
+            Atom::Neg(inner) => {
+                let inner = self.normalize(inner);
+                let minus_one = self.pool.integer(-1);
+                self.product(vec![minus_one, inner])
+            }
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => {
+                let numerator = self.normalize(numerator);
+                let denominator = self.normalize(denominator);
+                let minus_one = self.pool.integer(-1);
+                let reciprocal = self.power(denominator, minus_one);
+                self.product(vec![numerator, reciprocal])
+            }
+            Atom::Apply { func, args } => {
+                let args = args.iter().map(|arg| self.normalize(*arg)).collect();
+                self.apply(func, args)
+            }
+            // Constant factors do not change the order of a remainder, as in `O(2x^3) = O(x^3)`
+            Atom::Order(term) => {
+                let term = self.normalize(term);
+                match self.number(term) {
+                    Some(value) if value.is_zero() => term,
+                    Some(_) => {
+                        let one = self.pool.integer(1);
+                        self.pool.order(one)
+                    }
+                    None => {
+                        let (_, rest) = self.split_coefficient(term);
+                        self.pool.order(rest)
+                    }
+                }
+            }
+            // An index named `i` shadows the imaginary unit, so the term is left as written
+            Atom::BigOperator {
+                op,
+                index: Symbol::IMAGINARY_UNIT,
+                lower,
+                upper,
+                term,
+            } => {
+                let lower = self.normalize(lower);
+                let upper = self.normalize(upper);
+                self.pool
+                    .big_operator(op, Symbol::IMAGINARY_UNIT, lower, upper, term)
+            }
+            atom => {
+                let atom = atom.map_children(|child| self.normalize(child));
+                self.pool.insert(atom)
+            }
+        };
+
+        self.normal.insert(id, out);
+        out
+    }
+
+    /// Split a term into its base and exponent, which is `None` if the term is not a power
+    fn base_exponent(&self, id: AtomId) -> (AtomId, Option<AtomId>) {
+        match self.pool.get(id) {
+            Atom::Power { base, exponent } => (*base, Some(*exponent)),
+            _ => (id, None),
+        }
+    }
+
+    /// Split a term into its numeric coefficient and the product of its remaining factors
+    fn split_coefficient(&mut self, term: AtomId) -> (Number, AtomId) {
+        let Atom::Product(factors) = self.pool.get(term) else {
+            return (Number::integer(1), term);
+        };
+        let Some(coefficient) = self.number(factors[0]) else {
+            return (Number::integer(1), term);
+        };
+
+        let rest = match **factors {
+            [_, rest] => rest,
+            _ => {
+                let rest = factors[1..].into();
+                self.pool.insert(Atom::Product(rest))
+            }
+        };
+        (coefficient, rest)
+    }
+
+    /// Get the non-numeric factors of a term
+    fn rest<'b>(&'b self, term: &'b AtomId) -> &'b [AtomId] {
+        match self.pool.get(*term) {
+            Atom::Product(factors) if self.pool.get(factors[0]).is_number() => &factors[1..],
+            Atom::Product(factors) => factors,
+            _ => std::slice::from_ref(term),
+        }
+    }
+
+    /// Expand every sum (or product) in `items` into its terms (or factors)
+    fn flatten(&self, items: Vec<AtomId>, sum: bool) -> Vec<AtomId> {
+        items
+            .into_iter()
+            .flat_map(|id| match self.pool.get(id) {
+                Atom::Sum(terms) if sum => terms.to_vec(),
+                Atom::Product(factors) if !sum => factors.to_vec(),
+                _ => vec![id],
+            })
+            .collect()
+    }
+
+    /// Build the normalized sum of normalized terms
+    fn sum(&mut self, terms: Vec<AtomId>) -> AtomId {
+        let terms = self.flatten(terms, true);
+        let terms = self.common_units(terms);
+        if terms
+            .iter()
+            .any(|term| matches!(self.pool.get(*term), Atom::Matrix { .. }))
+        {
+            return self.matrix_sum(terms);
+        }
+
+        let mut constant = Number::integer(0);
+        let mut like = Vec::<(AtomId, Number)>::new();
+        let mut index = HashMap::<AtomId, usize>::new();
+        let mut remainders = Vec::new();
+
+        for term in terms {
+            if let Some(value) = self.number(term) {
+                constant = constant.add(&value);
+                continue;
+            }
+            // Remainders of series absorb repeats of themselves, as in `O(x^3) + O(x^3) = O(x^3)`
+            if let Atom::Order(_) = self.pool.get(term) {
+                if !remainders.contains(&term) {
+                    remainders.push(term);
+                }
+                continue;
+            }
+
+            let (coefficient, rest) = self.split_coefficient(term);
+            match index.entry(rest) {
+                Entry::Occupied(entry) => {
+                    let sum = &mut like[*entry.get()].1;
+                    *sum = sum.add(&coefficient);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(like.len());
+                    like.push((rest, coefficient));
+                }
+            }
+        }
+
+        let mut terms = Vec::with_capacity(like.len() + 1);
+        for (rest, coefficient) in like {
+            if !coefficient.is_zero() {
+                let coefficient = coefficient.insert(self.pool);
+                terms.push(self.product(vec![coefficient, rest]));
+            }
+        }
+        terms.sort_by(|a, b| self.compare_terms(a, b));
+
+        if !constant.is_zero() || (terms.is_empty() && remainders.is_empty()) {
+            terms.push(constant.insert(self.pool));
+        }
+        terms.extend(remainders);
+        match *terms {
+            [term] => term,
+            _ => self.pool.insert(Atom::Sum(terms.into())),
+        }
+    }
+
+    /// Split a sum with exact coefficients into their positive common factor and the remaining
+    /// sum, as in `2x + 4 = 2 (x + 2)`, if that factor is not one
+    fn split_content(&mut self, id: AtomId) -> Option<(Rational, AtomId)> {
+        let Atom::Sum(terms) = self.pool.get(id).clone() else {
+            return None;
+        };
+        let mut numerator = BigInt::from(0);
+        let mut denominator = BigInt::from(1);
+        for term in terms.iter() {
+            if let Atom::Order(_) = self.pool.get(*term) {
+                return None;
+            }
+            let coefficient = match self.number(*term) {
+                Some(value) => value,
+                None => self.split_coefficient(*term).0,
+            };
+            let Number::Exact(coefficient) = coefficient else {
+                return None;
+            };
+            numerator = numerator.gcd(coefficient.numerator());
+            denominator = denominator.lcm(coefficient.denominator());
+        }
+
+        let content = Rational::new(numerator, denominator)?;
+        if content.is_one() {
+            return None;
+        }
+        let scale = self.pool.number(content.recip()?);
+        let terms = terms
+            .iter()
+            .map(|term| self.product(vec![scale, *term]))
+            .collect();
+        Some((content, self.sum(terms)))
+    }
+
+    /// Write terms whose units have the same dimension in the smallest of those units so that they
+    /// combine, as in `1 m + 1 km = 1001 m`
+    fn common_units(&mut self, mut terms: Vec<AtomId>) -> Vec<AtomId> {
+        let split = terms
+            .iter()
+            .map(|term| self.split_units(*term))
+            .collect::<Vec<_>>();
+        let mut smallest = HashMap::<Dimension, &QuantityParts>::new();
+        for parts in split.iter().flatten() {
+            smallest
+                .entry(parts.dimension)
+                .and_modify(|target| {
+                    if parts
+                        .scale
+                        .checked_div(&target.scale)
+                        .is_some_and(|ratio| ratio.numerator() < ratio.denominator())
+                    {
+                        *target = parts;
+                    }
+                })
+                .or_insert(parts);
+        }
+
+        for (term, parts) in terms.iter_mut().zip(&split) {
+            let Some(parts) = parts else {
+                continue;
+            };
+            let target = smallest[&parts.dimension];
+            if target.units == parts.units {
+                continue;
+            }
+            let ratio = parts
+                .scale
+                .checked_div(&target.scale)
+                .expect("Units have a nonzero scale");
+            let mut factors = vec![self.pool.number(ratio)];
+            factors.extend(parts.rest.iter().chain(&target.units).copied());
+            *term = self.product(factors);
+        }
+        terms
+    }
+
+    /// Split a term into the units it is measured in and its other factors, if it has units of a
+    /// dimension other than a pure number
+    fn split_units(&self, term: AtomId) -> Option<QuantityParts> {
+        let factors = match self.pool.get(term) {
+            Atom::Product(factors) => factors.to_vec(),
+            _ => vec![term],
+        };
+        let mut parts = QuantityParts {
+            units: Vec::new(),
+            rest: Vec::new(),
+            scale: Rational::integer(1),
+            dimension: Dimension::NONE,
+        };
+        for factor in factors {
+            let (base, exponent) = self.base_exponent(factor);
+            let exponent = match exponent.map(|e| self.pool.get(e)) {
+                None => Some(1),
+                Some(Atom::Integer(n)) => n.to_i8(),
+                Some(_) => None,
+            };
+            match (self.pool.get(base), exponent) {
+                (Atom::Unit(unit), Some(exponent)) => {
+                    parts.units.push(factor);
+                    parts.scale = &parts.scale * &unit.scale().pow(exponent.into())?;
+                    parts.dimension = parts.dimension.mul(&unit.dimension().pow(exponent, 1)?);
+                }
+                _ => parts.rest.push(factor),
+            }
+        }
+        (!parts.dimension.is_none()).then_some(parts)
+    }
+
+    /// Build the normalized product of normalized factors
+    fn product(&mut self, factors: Vec<AtomId>) -> AtomId {
+        let factors = self.flatten(factors, false);
+        if factors
+            .iter()
+            .any(|factor| matches!(self.pool.get(*factor), Atom::Matrix { .. }))
+        {
+            return self.matrix_product(factors);
+        }
+
+        let mut coefficient = Number::integer(1);
+        let mut bases = Vec::<(AtomId, Vec<AtomId>)>::new();
+        let mut index = HashMap::<AtomId, usize>::new();
+
+        for factor in factors {
+            if let Some(value) = self.number(factor) {
+                coefficient = coefficient.mul(&value);
+                continue;
+            }
+
+            let (mut base, exponent) = self.base_exponent(factor);
+            let exponent = exponent.unwrap_or_else(|| self.pool.integer(1));
+            // Sums are matched up to a constant factor, as in `(2x + 2y)/(x + y) = 2`
+            if let Some(power) = self.pool.get(exponent).to_rational() {
+                if let Some((content, primitive)) = power
+                    .is_integer()
+                    .then(|| self.split_content(base))
+                    .flatten()
+                {
+                    let power = power.numerator().to_i32().and_then(|p| content.pow(p));
+                    if let Some(scale) = power {
+                        coefficient = coefficient.mul(&Number::Exact(scale));
+                        base = primitive;
+                    }
+                }
+            }
+            match index.entry(base) {
+                Entry::Occupied(entry) => bases[*entry.get()].1.push(exponent),
+                Entry::Vacant(entry) => {
+                    entry.insert(bases.len());
+                    bases.push((base, vec![exponent]));
+                }
+            }
+        }
+
+        if coefficient.is_zero() {
+            return coefficient.insert(self.pool);
+        }
+
+        // Combining exponents may produce numbers or products that need to be merged with the
+        // other factors, as in `2^(1/2) * 2^(1/2) = 2`
+        let mut regroup = false;
+        let mut powers = Vec::with_capacity(bases.len() + 1);
+        for (base, exponents) in bases {
+            let exponent = self.sum(exponents);
+            let power = self.power(base, exponent);
+            regroup |= matches!(self.pool.get(power), Atom::Product(_))
+                || self.pool.get(power).is_number()
+                || self.base_exponent(power).0 != base;
+            powers.push(power);
+        }
+
+        if regroup {
+            powers.push(coefficient.insert(self.pool));
+            return self.product(powers);
+        }
+        if let Some(mut factors) = self.reduce_units(&powers) {
+            factors.push(coefficient.insert(self.pool));
+            return self.product(factors);
+        }
+
+        powers.sort_by(|a, b| self.compare(*a, *b));
+        // Numeric coefficients are always distributed over a lone sum so that its terms combine
+        // with others, as in `2(x + 1) - 2x = 2`
+        if let ([power], false) = (&*powers, coefficient.is_one()) {
+            if let Atom::Sum(terms) = self.pool.get(*power).clone() {
+                let coefficient = coefficient.insert(self.pool);
+                let terms = terms
+                    .iter()
+                    .map(|term| self.product(vec![coefficient, *term]))
+                    .collect();
+                return self.sum(terms);
+            }
+        }
+        if !coefficient.is_one() || powers.is_empty() {
+            powers.insert(0, coefficient.insert(self.pool));
+        }
+        match *powers {
+            [factor] => factor,
+            _ => self.pool.insert(Atom::Product(powers.into())),
+        }
+    }
+
+    /// Reduce the units among the factors of a product to SI base dimensions, writing units of the
+    /// same dimension in the smallest of them and replacing units whose dimensions cancel by their
+    /// exact size, as in `1 km/m = 1000` and `1 Hz s = 1`. Get the reduced factors, or `None` if
+    /// the units are already reduced
+    fn reduce_units(&mut self, powers: &[AtomId]) -> Option<Vec<AtomId>> {
+        let mut units = Vec::<(Unit, i8)>::new();
+        let mut factors = Vec::with_capacity(powers.len());
+        for power in powers {
+            let (base, exponent) = self.base_exponent(*power);
+            let exponent = match exponent.map(|e| self.pool.get(e)) {
+                None => Some(1),
+                Some(Atom::Integer(n)) => n.to_i8(),
+                Some(_) => None,
+            };
+            match (self.pool.get(base), exponent) {
+                (Atom::Unit(unit), Some(exponent)) => units.push((*unit, exponent)),
+                _ => factors.push(*power),
+            }
+        }
+
+        // Units are written in terms of an earlier unit of the same or the reciprocal dimension,
+        // and the smaller of two units of the same dimension is kept
+        let count = units.len();
+        let mut scale = Rational::integer(1);
+        let mut reduced = Vec::<(Unit, i8)>::with_capacity(count);
+        for (unit, exponent) in units {
+            let dimension = unit.dimension();
+            let Some((target, total)) = reduced.iter_mut().find(|(target, _)| {
+                let target = target.dimension();
+                target == dimension || target.pow(-1, 1) == Some(dimension)
+            }) else {
+                reduced.push((unit, exponent));
+                continue;
+            };
+            if target.dimension() != dimension {
+                let ratio = &unit.scale() * &target.scale();
+                scale = &scale * &ratio.pow(exponent.into())?;
+                *total = total.checked_sub(exponent)?;
+                continue;
+            }
+            let ratio = unit.scale().checked_div(&target.scale())?;
+            match ratio.numerator() < ratio.denominator() {
+                true => {
+                    scale = &scale * &ratio.recip()?.pow((*total).into())?;
+                    *target = unit;
+                }
+                false => scale = &scale * &ratio.pow(exponent.into())?,
+            }
+            *total = total.checked_add(exponent)?;
+        }
+
+        // Units cancel once their exponent is zero, or together once their dimensions do
+        let dimension = |(unit, exponent): &(Unit, i8)| unit.dimension().pow(*exponent, 1);
+        let mut cancelled = reduced.iter().map(|(_, e)| *e == 0).collect::<Vec<_>>();
+        let total = reduced.iter().try_fold(Dimension::NONE, |total, unit| {
+            Some(total.mul(&dimension(unit)?))
+        })?;
+        if total.is_none() {
+            cancelled.fill(true);
+        }
+        if reduced.len() == count && !cancelled.contains(&true) {
+            return None;
+        }
+
+        for ((unit, exponent), cancelled) in reduced.into_iter().zip(cancelled) {
+            match cancelled {
+                true => scale = &scale * &unit.scale().pow(exponent.into())?,
+                false => {
+                    let unit = self.pool.insert(Atom::Unit(unit));
+                    let exponent = self.pool.integer(exponent);
+                    factors.push(self.power(unit, exponent));
+                }
+            }
+        }
+        factors.push(self.pool.number(scale));
+        Some(factors)
+    }
+
+    /// Build the normalized power of a normalized base and exponent
+    fn power(&mut self, base: AtomId, exponent: AtomId) -> AtomId {
+        if matches!(self.pool.get(base), Atom::Matrix { .. }) {
+            return self.matrix_power(base, exponent);
+        }
+
+        match (self.number(base), self.number(exponent)) {
+            (_, Some(Number::Exact(e))) if e.is_zero() => return self.pool.integer(1),
+            (_, Some(Number::Exact(e))) if e.is_one() => return base,
+            (Some(Number::Exact(b)), _) if b.is_one() => return self.pool.integer(1),
+            (Some(Number::Exact(b)), Some(e)) if b.is_zero() && !e.is_negative() => {
+                return self.pool.integer(0)
+            }
+            (Some(b), Some(e)) => {
+                if let Some(value) = b.pow(&e) {
+                    return value.insert(self.pool);
+                }
+                // Take square roots of negative numbers on the principal branch, as in
+                // `(-2)^(3/2) = i^3 2^(3/2)`
+                if let (Number::Exact(b), Number::Exact(e)) = (&b, &e) {
+                    if b.is_negative() && e.denominator() == &BigInt::from(2) {
+                        let unit = Number::imaginary_unit().insert(self.pool);
+                        let turns = self.pool.integer(e.numerator().clone());
+                        let unit = self.power(unit, turns);
+                        let magnitude = self.pool.number(-b);
+                        let magnitude = self.power(magnitude, exponent);
+                        return self.product(vec![unit, magnitude]);
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        let integer = self.pool.is_integer(exponent, self.assumptions) == Some(true);
+        if integer && self.number(base).is_some_and(|b| b.neg().is_one()) {
+            if let Some(power) = self.sign_power(exponent) {
+                return power;
+            }
+        }
+        // Integer powers of sums keep their numeric factor outside, as in `(2x + 2)^2 = 4(x + 1)^2`
+        if let Atom::Integer(power) = self.pool.get(exponent) {
+            if let Some(power) = power.to_i32() {
+                if let Some((content, primitive)) = self.split_content(base) {
+                    if let Some(scale) = content.pow(power) {
+                        let scale = self.pool.number(scale);
+                        let power = self.power(primitive, exponent);
+                        return self.product(vec![scale, power]);
+                    }
+                }
+            }
+        }
+
+        // Powers only distribute over products and nested powers for integer exponents, since
+        // `((-1)^2)^(1/2) != -1`, or when the signs of the factors are known
+        match self.pool.get(base).clone() {
+            Atom::Power {
+                base: inner,
+                exponent: inner_exponent,
+            } if integer || (self.is_nonnegative(inner) && self.is_real(inner_exponent)) => {
+                let exponent = self.product(vec![inner_exponent, exponent]);
+                return self.power(inner, exponent);
+            }
+            // Even powers of real numbers are powers of their absolute value, as in
+            // `sqrt(x^2) = |x|` for real `x`
+            Atom::Power {
+                base: inner,
+                exponent: inner_exponent,
+            } if self.is_even(inner_exponent) && self.is_real(inner) => {
+                let abs = self.apply(Function::Abs, vec![inner]);
+                let exponent = self.product(vec![inner_exponent, exponent]);
+                return self.power(abs, exponent);
+            }
+            Atom::Product(factors)
+                if integer || factors.iter().all(|factor| self.is_nonnegative(*factor)) =>
+            {
+                let factors = factors
+                    .iter()
+                    .map(|factor| self.power(*factor, exponent))
+                    .collect();
+                return self.product(factors);
+            }
+            _ => (),
+        }
+
+        self.pool.insert(Atom::Power { base, exponent })
+    }
+
+    /// Reduce a power of `-1` with exponent `c·n`, for integers `c` and `n`, to `1` or `(-1)^n` by
+    /// the parity of `c`, as in `(-1)^(2n) = 1` and `(-1)^(3n) = (-1)^n`
+    fn sign_power(&mut self, exponent: AtomId) -> Option<AtomId> {
+        let (coefficient, rest) = self.split_coefficient(exponent);
+        let Number::Exact(c) = coefficient else {
+            return None;
+        };
+        if !c.is_integer()
+            || c.abs().is_one()
+            || self.pool.is_integer(rest, self.assumptions) != Some(true)
+        {
+            return None;
+        }
+
+        let minus_one = self.pool.integer(-1);
+        match c.numerator().is_even() {
+            true => Some(self.pool.integer(1)),
+            false => Some(self.power(minus_one, rest)),
+        }
+    }
+
+    fn is_nonnegative(&self, id: AtomId) -> bool {
+        self.pool.is_nonnegative(id, self.assumptions) == Some(true)
+    }
+
+    fn is_even(&self, id: AtomId) -> bool {
+        matches!(self.pool.get(id), Atom::Integer(i) if i.is_even())
+    }
+
+    /// Get the number of columns and the entries of a matrix
+    fn matrix(&self, id: AtomId) -> Option<(usize, Box<[AtomId]>)> {
+        match self.pool.get(id) {
+            Atom::Matrix { columns, entries } => Some((*columns as usize, entries.clone())),
+            _ => None,
+        }
+    }
+
+    /// Add matrices entry by entry, leaving the sum unevaluated if any term is not a matrix of
+    /// the same shape as the others
+    fn matrix_sum(&mut self, terms: Vec<AtomId>) -> AtomId {
+        let matrices = terms
+            .iter()
+            .map(|term| self.matrix(*term))
+            .collect::<Option<Vec<_>>>();
+        let Some(matrices) = matrices.filter(|matrices| {
+            let (columns, entries) = &matrices[0];
+            matrices
+                .iter()
+                .all(|(c, e)| c == columns && e.len() == entries.len())
+        }) else {
+            return self.pool.insert(Atom::Sum(terms.into()));
+        };
+
+        let columns = matrices[0].0;
+        let entries = (0..matrices[0].1.len())
+            .map(|i| {
+                let terms = matrices.iter().map(|(_, entries)| entries[i]).collect();
+                self.sum(terms)
+            })
+            .collect();
+        self.pool.insert(Atom::Matrix {
+            columns: columns as u32,
+            entries,
+        })
+    }
+
+    /// Multiply matrices in order and scale the result by the product of the other factors,
+    /// leaving the product unevaluated if the shapes of adjacent matrices do not match
+    fn matrix_product(&mut self, factors: Vec<AtomId>) -> AtomId {
+        let (matrices, scalars): (Vec<_>, Vec<_>) = factors
+            .iter()
+            .partition(|factor| matches!(self.pool.get(**factor), Atom::Matrix { .. }));
+        let scale = self.product(scalars);
+
+        let mut matrices = matrices.into_iter();
+        let first = matrices.next().expect("There is at least one matrix");
+        let mut out = self.matrix(first).expect("Factor is a matrix");
+        for matrix in matrices {
+            let (columns, entries) = self.matrix(matrix).expect("Factor is a matrix");
+            if out.0 != entries.len() / columns {
+                return self.pool.insert(Atom::Product(factors.into()));
+            }
+            out = self.multiply(&out, &(columns, entries));
+        }
+
+        let (columns, entries) = out;
+        let entries = entries
+            .iter()
+            .map(|entry| self.product(vec![scale, *entry]))
+            .collect();
+        self.pool.insert(Atom::Matrix {
+            columns: columns as u32,
+            entries,
+        })
+    }
+
+    /// Multiply two matrices with compatible shapes, given by their columns and entries
+    fn multiply(
+        &mut self,
+        (n, a): &(usize, Box<[AtomId]>),
+        (m, b): &(usize, Box<[AtomId]>),
+    ) -> (usize, Box<[AtomId]>) {
+        let rows = a.len() / n;
+        let entries = (0..rows * m)
+            .map(|index| {
+                let (i, j) = (index / m, index % m);
+                let terms = (0..*n)
+                    .map(|k| self.product(vec![a[i * n + k], b[k * m + j]]))
+                    .collect();
+                self.sum(terms)
+            })
+            .collect();
+        (*m, entries)
+    }
+
+    /// Raise a square matrix to an integer power by repeated squaring, inverting it first for a
+    /// negative power, and leave any other power of a matrix unevaluated
+    fn matrix_power(&mut self, base: AtomId, exponent: AtomId) -> AtomId {
+        let (columns, mut entries) = self.matrix(base).expect("Base is a matrix");
+        let power = match self.pool.get(exponent) {
+            Atom::Integer(i) => i
+                .to_i64()
+                .filter(|p| p.unsigned_abs() <= MAX_MATRIX_POWER as u64),
+            _ => None,
+        };
+        let Some(power) = power.filter(|_| entries.len() == columns * columns) else {
+            return self.pool.insert(Atom::Power { base, exponent });
+        };
+        if power < 0 {
+            let Ok(inverse) = self.pool.inverse(base) else {
+                return self.pool.insert(Atom::Power { base, exponent });
+            };
+            let inverse = self.normalize(inverse);
+            entries = self.matrix(inverse).expect("Inverse is a matrix").1;
+        }
+        let mut power = power.unsigned_abs();
+
+        let identity = (0..entries.len())
+            .map(|i| self.pool.integer((i / columns == i % columns) as u8))
+            .collect();
+        let mut out = (columns, identity);
+        let mut square = (columns, entries);
+        while power > 0 {
+            if power & 1 == 1 {
+                out = self.multiply(&out, &square);
+            }
+            power >>= 1;
+            if power > 0 {
+                square = self.multiply(&square, &square);
+            }
+        }
+        self.pool.insert(Atom::Matrix {
+            columns: columns as u32,
+            entries: out.1,
+        })
+    }
+
+    /// Build the normalized application of a function to normalized arguments
+    fn apply(&mut self, func: Function, args: Vec<AtomId>) -> AtomId {
+        let [arg] = *args else {
+            return self.pool.apply(func, args);
+        };
+        if func == Function::Sqrt {
+            let half = self.pool.rational(1, 2).expect("Denominator is not zero");
+            return self.power(arg, half);
+        }
+
+        if let Some(value) = self.number(arg) {
+            if let Some(folded) = self.fold_apply(func, &value) {
+                return folded;
+            }
+        }
+        if let Some(folded) = self.fold_constant(func, arg) {
+            return folded;
+        }
+
+        match (func, self.pool.get(arg)) {
+            (
+                Function::Exp,
+                Atom::Apply {
+                    func: Function::Ln,
+                    args,
+                },
+            ) if args.len() == 1 => return args[0],
+            // The logarithm only inverts the exponential on the real line, as `ln(exp(2πi)) = 0`
+            (
+                Function::Ln,
+                Atom::Apply {
+                    func: Function::Exp,
+                    args,
+                },
+            ) if args.len() == 1 && self.is_real(args[0]) => return args[0],
+            (Function::Abs, Atom::Apply { func, .. }) if *func == Function::Abs => return arg,
+            _ => (),
+        }
+        if let Some(part) = self.complex_part(func, arg) {
+            return part;
+        }
+        // Absolute values of real numbers with a known sign are dropped, as in `|x| = x` for
+        // nonnegative `x`
+        if let (Function::Abs, Some(interval)) = (func, self.pool.interval(arg, self.assumptions)) {
+            match (
+                interval.decide(Relation::GreaterEq),
+                interval.decide(Relation::LessEq),
+            ) {
+                (Some(true), _) => return arg,
+                (_, Some(true)) => return self.negate(Number::integer(1), arg),
+                _ => (),
+            }
+        }
+
+        // Move numeric coefficients out of functions with symmetry, as in `sin(-x) = -sin(x)`
+        let (coefficient, rest) = self.split_coefficient(arg);
+        if coefficient.is_one() {
+            return self.pool.apply(func, [arg]);
+        }
+        let (outer, inner) = match func {
+            Function::Abs => match coefficient.abs() {
+                Some(outer) => (outer, rest),
+                None => return self.pool.apply(func, [arg]),
+            },
+            _ if !coefficient.is_negative() => return self.pool.apply(func, [arg]),
+            Function::Sin
+            | Function::Tan
+            | Function::Asin
+            | Function::Atan
+            | Function::Sinh
+            | Function::Tanh => (Number::integer(-1), self.negate(coefficient, rest)),
+            Function::Cos | Function::Cosh => (Number::integer(1), self.negate(coefficient, rest)),
+            _ => return self.pool.apply(func, [arg]),
+        };
+
+        let outer = outer.insert(self.pool);
+        let inner = self.apply(func, vec![inner]);
+        self.product(vec![outer, inner])
+    }
+
+    /// Build the normalized product of the negation of `coefficient` with `rest`
+    fn negate(&mut self, coefficient: Number, rest: AtomId) -> AtomId {
+        let coefficient = coefficient.neg().insert(self.pool);
+        self.product(vec![coefficient, rest])
+    }
+
+    /// Evaluate a function at a number if the result is exact or the number is already
+    /// approximate
+    fn fold_apply(&mut self, func: Function, value: &Number) -> Option<AtomId> {
+        let name = func.builtin_name()?;
+        let value = match value {
+            Number::Complex(parts) => return self.fold_complex(func, name, parts),
+            Number::Exact(r) if func == Function::Abs => return Some(self.pool.number(r.abs())),
+            Number::Exact(r) => match func {
+                Function::Re | Function::Conj => return Some(self.pool.number(r.clone())),
+                Function::Im => 0,
+                Function::Arg if !r.is_negative() => 0,
+                Function::Sin
+                | Function::Tan
+                | Function::Asin
+                | Function::Atan
+                | Function::Sinh
+                | Function::Tanh
+                    if r.is_zero() =>
+                {
+                    0
+                }
+                Function::Cos | Function::Cosh | Function::Exp if r.is_zero() => 1,
+                Function::Acos | Function::Ln | Function::Log if r.is_one() => 0,
+                Function::Log if *r == Rational::integer(10) => 1,
+                _ => return None,
+            },
+            Number::Float(x) => {
+                let value = eval::apply(func, name, *x).ok()?;
+                return value.is_finite().then(|| self.pool.float(value));
+            }
+        };
+
+        Some(self.pool.integer(value))
+    }
+
+    /// Evaluate a function exactly at the special points of `e` and `π`, as in `ln(e) = 1`,
+    /// `exp(1) = e` and `cos(π/3) = 1/2`
+    fn fold_constant(&mut self, func: Function, arg: AtomId) -> Option<AtomId> {
+        let e = self.pool.symbol(Symbol::E);
+        match (func, self.pool.get(arg)) {
+            (Function::Ln, _) if arg == e => return Some(self.pool.integer(1)),
+            (Function::Exp, _) if self.number(arg).is_some_and(|x| x.is_one()) => return Some(e),
+            (Function::Ln, Atom::Power { base, exponent })
+                if *base == e && self.is_real(*exponent) =>
+            {
+                return Some(*exponent)
+            }
+            (Function::Sin | Function::Cos | Function::Tan, _) => (),
+            _ => return None,
+        }
+
+        // Angles are measured in twelfths of a half turn, covering multiples of π/4 and π/6
+        let (coefficient, rest) = self.split_coefficient(arg);
+        let Number::Exact(turns) = coefficient else {
+            return None;
+        };
+        let twelfths = (&turns * &Rational::integer(12)).into_parts();
+        if *self.pool.get(rest) != Atom::Symbol(Symbol::PI) || twelfths.1 != BigInt::from(1) {
+            return None;
+        }
+        let twelfths = twelfths.0.mod_floor(&BigInt::from(24)).to_i64()?;
+        let sin = self.exact_sin(twelfths)?;
+        let cos = self.exact_sin(twelfths + 6)?;
+        match func {
+            Function::Sin => Some(sin),
+            Function::Cos => Some(cos),
+            _ if self.number(cos).is_some_and(|cos| cos.is_zero()) => None,
+            _ => {
+                let minus_one = self.pool.integer(-1);
+                let cos = self.power(cos, minus_one);
+                Some(self.product(vec![sin, cos]))
+            }
+        }
+    }
+
+    /// Get the exact sine of an angle given in twelfths of a half turn, if it is a multiple of
+    /// π/4 or π/6
+    fn exact_sin(&mut self, twelfths: i64) -> Option<AtomId> {
+        let twelfths = twelfths.rem_euclid(24);
+        let (sign, twelfths) = match twelfths < 12 {
+            true => (1, twelfths),
+            false => (-1, twelfths - 12),
+        };
+        let (numerator, root) = match twelfths.min(12 - twelfths) {
+            0 => (0, 1),
+            2 => (1, 1),
+            3 => (1, 2),
+            4 => (1, 3),
+            6 => (2, 1),
+            _ => return None,
+        };
+        let half = self.pool.rational(sign * numerator, 2).expect("Nonzero");
+        let root = self.pool.integer(root);
+        let exponent = self.pool.rational(1, 2).expect("Nonzero");
+        let root = self.power(root, exponent);
+        Some(self.product(vec![half, root]))
+    }
+
+    /// Evaluate a function at a complex number, exactly for the functions taking it apart and
+    /// numerically for the others when either part is a float
+    fn fold_complex(
+        &mut self,
+        func: Function,
+        name: &'static str,
+        (re, im): &(Number, Number),
+    ) -> Option<AtomId> {
+        match func {
+            Function::Re => return Some(re.insert(self.pool)),
+            Function::Im => return Some(im.insert(self.pool)),
+            Function::Conj => return Some(Number::complex(re.clone(), im.neg()).insert(self.pool)),
+            Function::Abs => {
+                let norm = re.mul(re).add(&im.mul(im)).insert(self.pool);
+                let half = self.pool.rational(1, 2).expect("Denominator is not zero");
+                return Some(self.power(norm, half));
+            }
+            _ => (),
+        }
+
+        if let (Number::Exact(_), Number::Exact(_)) = (re, im) {
+            return None;
+        }
+        let z = eval::Complex64::new(re.to_f64(), im.to_f64());
+        let value = eval::apply_complex(func, name, z).ok()?;
+        (value.re.is_finite() && value.im.is_finite()).then(|| {
+            Number::complex(Number::Float(value.re), Number::Float(value.im)).insert(self.pool)
+        })
+    }
+
+    /// Check if a normalized expression is known to be real, either from its structure or from
+    /// the assumptions about its symbols
+    fn is_real(&self, id: AtomId) -> bool {
+        let structural = match self.pool.get(id) {
+            Atom::Integer(_) | Atom::Rational(_) | Atom::Float(_) => true,
+            Atom::Apply {
+                func: Function::Re | Function::Im | Function::Abs | Function::Arg,
+                ..
+            } => true,
+            Atom::Apply {
+                func:
+                    Function::Sin
+                    | Function::Cos
+                    | Function::Tan
+                    | Function::Atan
+                    | Function::Sinh
+                    | Function::Cosh
+                    | Function::Tanh
+                    | Function::Exp,
+                args,
+            } => args.iter().all(|arg| self.is_real(*arg)),
+            Atom::Sum(items) | Atom::Product(items) => items.iter().all(|item| self.is_real(*item)),
+            Atom::Power { base, exponent } => {
+                self.is_real(*base) && matches!(self.pool.get(*exponent), Atom::Integer(_))
+            }
+            Atom::Symbol(symbol) => self.assumptions.interval(*symbol).is_some(),
+            _ => false,
+        };
+        structural || self.pool.is_real(id, self.assumptions) == Some(true)
+    }
+
+    /// Apply the rules for real parts, imaginary parts and conjugates, which leave real
+    /// expressions fixed and are linear over real coefficients
+    fn complex_part(&mut self, func: Function, arg: AtomId) -> Option<AtomId> {
+        if !matches!(
+            func,
+            Function::Re | Function::Im | Function::Conj | Function::Abs
+        ) {
+            return None;
+        }
+        if self.is_real(arg) {
+            return match func {
+                Function::Re | Function::Conj => Some(arg),
+                Function::Im => Some(self.pool.integer(0)),
+                _ => None,
+            };
+        }
+
+        match (func, self.pool.get(arg).clone()) {
+            (
+                Function::Conj,
+                Atom::Apply {
+                    func: Function::Conj,
+                    args,
+                },
+            ) => Some(args[0]),
+            (
+                Function::Re | Function::Im | Function::Abs,
+                Atom::Apply {
+                    func: Function::Conj,
+                    args,
+                },
+            ) => {
+                let part = self.apply(func, args.to_vec());
+                match func {
+                    Function::Im => {
+                        let minus_one = self.pool.integer(-1);
+                        Some(self.product(vec![minus_one, part]))
+                    }
+                    _ => Some(part),
+                }
+            }
+            (Function::Re | Function::Im | Function::Conj, Atom::Sum(terms)) => {
+                let terms = terms
+                    .iter()
+                    .map(|term| self.apply(func, vec![*term]))
+                    .collect();
+                Some(self.sum(terms))
+            }
+            (Function::Conj, Atom::Product(factors)) => {
+                let factors = factors
+                    .iter()
+                    .map(|factor| self.apply(func, vec![*factor]))
+                    .collect();
+                Some(self.product(factors))
+            }
+            (Function::Conj, Atom::Power { base, exponent })
+                if matches!(self.pool.get(exponent), Atom::Integer(_)) =>
+            {
+                let base = self.apply(func, vec![base]);
+                Some(self.power(base, exponent))
+            }
+            // re((a + bi) z) = a re(z) - b im(z) and im((a + bi) z) = b re(z) + a im(z)
+            (Function::Re | Function::Im, Atom::Product(_)) => {
+                let (coefficient, rest) = self.split_coefficient(arg);
+                if coefficient.is_one() {
+                    return None;
+                }
+                let (a, b) = coefficient.parts();
+                let (a, b) = match func {
+                    Function::Re => (a, b.neg()),
+                    _ => (b, a),
+                };
+                let re = self.apply(Function::Re, vec![rest]);
+                let im = self.apply(Function::Im, vec![rest]);
+                let (a, b) = (a.insert(self.pool), b.insert(self.pool));
+                let terms = vec![self.product(vec![a, re]), self.product(vec![b, im])];
+                Some(self.sum(terms))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the total degree of a term in its symbols, used to order polynomials from the highest
+    /// degree term
+    fn degree(&self, id: AtomId) -> f64 {
+        match self.pool.get(id) {
+            Atom::Symbol(_) => 1.,
+            Atom::Power { base, exponent } => match self.number(*exponent) {
+                Some(exponent) if !exponent.is_complex() => exponent.to_f64() * self.degree(*base),
+                _ => 0.,
+            },
+            Atom::Product(factors) => factors.iter().map(|factor| self.degree(*factor)).sum(),
+            _ => 0.,
+        }
+    }
+
+    /// Order the terms of a sum from highest to lowest degree, then lexicographically by their
+    /// factors with higher powers first as in `x^2 + xy + y^2`
+    fn compare_terms(&self, a: &AtomId, b: &AtomId) -> Ordering {
+        let (ra, rb) = (self.rest(a), self.rest(b));
+        let degree = |factors: &[AtomId]| factors.iter().map(|f| self.degree(*f)).sum::<f64>();
+
+        degree(rb)
+            .total_cmp(&degree(ra))
+            .then_with(|| {
+                for (fa, fb) in ra.iter().zip(rb) {
+                    let (base_a, exp_a) = self.base_exponent(*fa);
+                    let (base_b, exp_b) = self.base_exponent(*fb);
+                    let order = self
+                        .compare(base_a, base_b)
+                        .then_with(|| self.compare_exponents(exp_b, exp_a));
+                    if order.is_ne() {
+                        return order;
+                    }
+                }
+                ra.len().cmp(&rb.len())
+            })
+            .then_with(|| self.compare(*a, *b))
+    }
+
+    /// Total order on expressions used to sort factors, placing numbers first and powers next to
+    /// their base
+    fn compare(&self, a: AtomId, b: AtomId) -> Ordering {
+        if a == b {
+            return Ordering::Equal;
+        }
+
+        match (self.number(a), self.number(b)) {
+            (Some(m), Some(n)) => return m.compare(&n).then(a.cmp(&b)),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => (),
+        }
+
+        let (x, y) = (self.pool.get(a), self.pool.get(b));
+        if matches!(x, Atom::Power { .. }) || matches!(y, Atom::Power { .. }) {
+            let (base_a, exp_a) = self.base_exponent(a);
+            let (base_b, exp_b) = self.base_exponent(b);
+            return self
+                .compare(base_a, base_b)
+                .then_with(|| self.compare_exponents(exp_a, exp_b))
+                .then(a.cmp(&b));
+        }
+
+        let rank = |atom: &Atom| match atom {
+            Atom::Symbol(_) => 0,
+            Atom::Apply { .. } => 1,
+            Atom::Sum(_) => 2,
+            Atom::Product(_) => 3,
+            // Units come last, so quantities print as `2x m`
+            Atom::Unit(_) => 5,
+            _ => 4,
+        };
+        rank(x)
+            .cmp(&rank(y))
+            .then_with(|| match (x, y) {
+                (Atom::Symbol(s), Atom::Symbol(t)) => s.cmp(t),
+                (Atom::Unit(u), Atom::Unit(v)) => u.cmp(v),
+                (Atom::Apply { func: f, args: p }, Atom::Apply { func: g, args: q }) => {
+                    f.cmp(g).then_with(|| self.compare_all(p, q))
+                }
+                _ => self.compare_all(
+                    &x.children().collect::<Vec<_>>(),
+                    &y.children().collect::<Vec<_>>(),
+                ),
+            })
+            .then(a.cmp(&b))
+    }
+
+    /// Compare two exponents, where `None` is an implicit exponent of one
+    fn compare_exponents(&self, a: Option<AtomId>, b: Option<AtomId>) -> Ordering {
+        let with_one = |e: AtomId| match self.number(e) {
+            Some(value) => value.compare(&Number::integer(1)).then(Ordering::Greater),
+            None => Ordering::Greater,
+        };
+
+        match (a, b) {
+            (None, None) => Ordering::Equal,
+            (Some(a), Some(b)) => self.compare(a, b),
+            (Some(a), None) => with_one(a),
+            (None, Some(b)) => with_one(b).reverse(),
+        }
+    }
+
+    /// Lexicographically compare two lists of expressions
+    fn compare_all(&self, a: &[AtomId], b: &[AtomId]) -> Ordering {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| self.compare(*a, *b))
+            .find(|order| order.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len()))
+    }
+
+    /// Rewrite a normalized expression with negative powers written as fractions and square roots
+    /// as [Function::Sqrt]
+    fn present(&mut self, id: AtomId) -> AtomId {
+        if let Some(done) = self.presented.get(&id) {
+            return *done;
+        }
+
+        let out = match self.pool.get(id).clone() {
+            Atom::Power { base, exponent } => match self.number(exponent) {
+                Some(e) if e.is_negative() => {
+                    let one = self.pool.integer(1);
+                    let denominator = self.reciprocal(base, e);
+                    self.pool.fraction(one, denominator)
+                }
+                Some(Number::Exact(e)) if e == Rational::new(1, 2).expect("Nonzero") => {
+                    let base = self.present(base);
+                    self.pool.apply(Function::Sqrt, [base])
+                }
+                _ => {
+                    let base = self.present(base);
+                    let exponent = self.present(exponent);
+                    self.pool.power(base, exponent)
+                }
+            },
+            Atom::Product(factors) => {
+                let mut numerator = Vec::new();
+                let mut denominator = Vec::new();
+                for factor in factors.iter().copied() {
+                    match self.number(factor) {
+                        Some(Number::Exact(r)) => {
+                            let (p, q) = r.into_parts();
+                            if p != BigInt::from(1) {
+                                numerator.push(self.pool.integer(p));
+                            }
+                            if q != BigInt::from(1) {
+                                denominator.push(self.pool.integer(q));
+                            }
+                        }
+                        _ => match self.base_exponent(factor) {
+                            (base, Some(e)) if self.number(e).is_some_and(|e| e.is_negative()) => {
+                                let e = self.number(e).expect("Exponent is a number");
+                                denominator.push(self.reciprocal(base, e))
+                            }
+                            _ => numerator.push(self.present(factor)),
+                        },
+                    }
+                }
+
+                let numerator = self.pool.product(numerator);
+                match denominator.is_empty() {
+                    true => numerator,
+                    false => {
+                        let denominator = self.pool.product(denominator);
+                        self.pool.fraction(numerator, denominator)
+                    }
+                }
+            }
+            atom => {
+                let atom = atom.map_children(|child| self.present(child));
+                self.pool.insert(atom)
+            }
+        };
+
+        self.presented.insert(id, out);
+        out
+    }
+
+    /// Present `base` raised to the negation of the negative exponent `exponent`
+    fn reciprocal(&mut self, base: AtomId, exponent: Number) -> AtomId {
+        let exponent = exponent.neg().insert(self.pool);
+        let power = self.power(base, exponent);
+        self.present(power)
+    }
+}
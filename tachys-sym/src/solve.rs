@@ -0,0 +1,939 @@
+use crate::{
+    assume::Assumptions,
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, BigInt, ExprPool, Function, Rational, Relation,
+    },
+    eval::Env,
+    poly::{Polynomial, Quotient},
+};
+
+mod linear;
+
+pub use linear::SystemSolution;
+
+/// Magnitude below which an approximately evaluated condition is taken to be exactly zero
+const EPSILON: f64 = 1e-12;
+
+/// A solution of an equation, valid whenever all of its conditions hold
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Solution {
+    pub value: AtomId,
+    /// [Atom::Relation]s and [Atom::Equals] between other symbols that must hold for the solution
+    /// to be valid, such as a nonzero leading coefficient or the logarithm of a positive number
+    pub conditions: Vec<AtomId>,
+    /// Symbols introduced for the branches of periodic functions, each standing for any integer
+    pub parameters: Vec<Symbol>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum SolveError {
+    #[error("Only equations can be solved")]
+    NotEquation,
+    #[error("Equation is not linear in the unknowns")]
+    NonLinear,
+    #[error("No method is known to solve this equation")]
+    Unsupported,
+}
+
+impl Solution {
+    fn new(value: AtomId) -> Self {
+        Self {
+            value,
+            conditions: Vec::new(),
+            parameters: Vec::new(),
+        }
+    }
+}
+
+impl ExprPool {
+    /// Solve an equation for `variable`, returning every real solution along with the conditions
+    /// under which it holds. An expression that is not an [Atom::Equals] is taken to equal zero.
+    ///
+    /// Polynomial equations are factored and solved in closed form up to degree four, where cubics
+    /// and quartics must have rational coefficients. Equations in a single function or power of an
+    /// expression containing `variable`, such as `exp(2x) = 3`, are solved by inverting it, with
+    /// periodic solutions written in terms of fresh integer parameters. If the equation holds for
+    /// every value of `variable`, its only solution is `variable` itself
+    pub fn solve(
+        &mut self,
+        symbols: &mut SymbolStore,
+        equation: AtomId,
+        variable: Symbol,
+    ) -> Result<Vec<Solution>, SolveError> {
+        self.solve_assuming(symbols, equation, variable, &Assumptions::new())
+    }
+
+    /// Solve an equation for `variable` like [ExprPool::solve] under `assumptions`, which decide
+    /// conditions on other symbols and discard solutions outside the values assumed for
+    /// `variable`, so that `x^2 = 4` only has the solution `2` for positive `x`
+    pub fn solve_assuming(
+        &mut self,
+        symbols: &mut SymbolStore,
+        equation: AtomId,
+        variable: Symbol,
+        assumptions: &Assumptions,
+    ) -> Result<Vec<Solution>, SolveError> {
+        self.solve_over(symbols, equation, variable, assumptions, false)
+    }
+
+    /// Solve an equation for `variable` like [ExprPool::solve], also returning the complex roots
+    /// of polynomials
+    pub(crate) fn solve_complex(
+        &mut self,
+        symbols: &mut SymbolStore,
+        equation: AtomId,
+        variable: Symbol,
+    ) -> Result<Vec<Solution>, SolveError> {
+        self.solve_over(symbols, equation, variable, &Assumptions::new(), true)
+    }
+
+    fn solve_over(
+        &mut self,
+        symbols: &mut SymbolStore,
+        equation: AtomId,
+        variable: Symbol,
+        assumptions: &Assumptions,
+        complex: bool,
+    ) -> Result<Vec<Solution>, SolveError> {
+        let (lhs, rhs) = match *self.get(equation) {
+            Atom::Equals { lhs, rhs } => (lhs, rhs),
+            Atom::Relation { .. } => return Err(SolveError::NotEquation),
+            _ => (equation, self.integer(0)),
+        };
+
+        let mut solver = Solver {
+            pool: self,
+            symbols,
+            assumptions,
+            variable,
+            equation,
+            parameters: Vec::new(),
+            complex,
+        };
+        let mut solutions = solver.solve(lhs, rhs)?;
+        solutions.retain_mut(|solution| solver.admissible(solution));
+        Ok(solutions)
+    }
+}
+
+/// Decide whether `lhs op rhs` holds, returning `None` if either side depends on a symbol with no
+/// known value and the assumptions about its symbols do not settle it
+fn decide(
+    pool: &mut ExprPool,
+    symbols: &SymbolStore,
+    assumptions: &Assumptions,
+    op: Relation,
+    lhs: AtomId,
+    rhs: AtomId,
+) -> Option<bool> {
+    let rhs = pool.neg(rhs);
+    let difference = pool.sum([lhs, rhs]);
+    let difference = pool.simplify_assuming(difference, assumptions);
+    if let Some(holds) = pool
+        .interval(difference, assumptions)
+        .and_then(|interval| interval.decide(op))
+    {
+        return Some(holds);
+    }
+    let value = match pool.get(difference).to_rational() {
+        Some(exact) => exact.to_f64(),
+        None => match pool.eval(difference, symbols, &Env::new()).ok()? {
+            value if value.abs() < EPSILON => 0.,
+            value => value,
+        },
+    };
+
+    Some(match op {
+        Relation::NotEqual => value != 0.,
+        Relation::Less => value < 0.,
+        Relation::LessEq => value <= 0.,
+        Relation::Greater => value > 0.,
+        Relation::GreaterEq => value >= 0.,
+    })
+}
+
+/// Record in `conditions` that `lhs op rhs` must hold, returning `false` if it is known not to
+fn require(
+    pool: &mut ExprPool,
+    symbols: &SymbolStore,
+    assumptions: &Assumptions,
+    conditions: &mut Vec<AtomId>,
+    op: Relation,
+    lhs: AtomId,
+    rhs: AtomId,
+) -> bool {
+    match decide(pool, symbols, assumptions, op, lhs, rhs) {
+        Some(holds) => holds,
+        None => {
+            let lhs = pool.simplify(lhs);
+            let rhs = pool.simplify(rhs);
+            let condition = pool.relation(op, lhs, rhs);
+            if !conditions.contains(&condition) {
+                conditions.push(condition);
+            }
+            true
+        }
+    }
+}
+
+/// Values of a function's argument at which it takes a given value, with the integer parameter
+/// they are written in terms of
+type Branches = (Vec<AtomId>, Option<Symbol>);
+
+/// Solves a single equation for one variable
+struct Solver<'a> {
+    pool: &'a mut ExprPool,
+    symbols: &'a mut SymbolStore,
+    assumptions: &'a Assumptions,
+    variable: Symbol,
+    /// Equation being solved, whose symbols are avoided when naming integer parameters
+    equation: AtomId,
+    parameters: Vec<Symbol>,
+    /// Whether polynomials also get their complex roots, written with square roots of negative
+    /// numbers rather than requiring a nonnegative radicand
+    complex: bool,
+}
+
+impl Solver<'_> {
+    fn depends(&self, id: AtomId) -> bool {
+        self.pool.depends_on(id, self.variable)
+    }
+
+    fn require(
+        &mut self,
+        conditions: &mut Vec<AtomId>,
+        op: Relation,
+        lhs: AtomId,
+        rhs: AtomId,
+    ) -> bool {
+        require(
+            self.pool,
+            self.symbols,
+            self.assumptions,
+            conditions,
+            op,
+            lhs,
+            rhs,
+        )
+    }
+
+    /// Simplify a solution under the assumptions and record the conditions for it to lie among
+    /// the values assumed for the variable, returning `false` if it is known not to
+    fn admissible(&mut self, solution: &mut Solution) -> bool {
+        let (assumptions, value) = (self.assumptions, solution.value);
+        let value = self.pool.simplify_assuming(value, assumptions);
+        solution.value = value;
+        if assumptions.is_integer(self.variable)
+            && self.pool.is_integer(value, assumptions) == Some(false)
+        {
+            return false;
+        }
+        // The bounds of an interval excluding zero already require a nonzero solution
+        let interval = assumptions.interval(self.variable);
+        let zero = self.pool.integer(0);
+        if assumptions.is_nonzero(self.variable)
+            && interval.is_none_or(|interval| interval.contains(0.))
+            && !self.require(&mut solution.conditions, Relation::NotEqual, value, zero)
+        {
+            return false;
+        }
+
+        let Some(interval) = interval else {
+            return true;
+        };
+        if self.pool.is_real(value, assumptions) == Some(false) {
+            return false;
+        }
+        let bounds = [
+            (interval.lower, interval.lower_open, Relation::Greater),
+            (interval.upper, interval.upper_open, Relation::Less),
+        ];
+        for (bound, open, op) in bounds {
+            if bound.is_infinite() {
+                continue;
+            }
+            let op = match (open, op) {
+                (false, Relation::Greater) => Relation::GreaterEq,
+                (false, _) => Relation::LessEq,
+                (true, op) => op,
+            };
+            let bound = match bound.fract() == 0. && bound.abs() < 2f64.powi(53) {
+                true => self.pool.integer(bound as i64),
+                false => self.pool.float(bound),
+            };
+            if !self.require(&mut solution.conditions, op, value, bound) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Expand an expression into a quotient of polynomials
+    fn expand(&mut self, id: AtomId) -> AtomId {
+        let normal = self.pool.normal_form(id);
+        Quotient::of(self.pool, normal).to_atom(self.pool)
+    }
+
+    /// Solve `lhs = rhs`, discarding solutions at which a denominator of the equation vanishes
+    fn solve(&mut self, lhs: AtomId, rhs: AtomId) -> Result<Vec<Solution>, SolveError> {
+        let rhs = self.pool.neg(rhs);
+        let difference = self.pool.sum([lhs, rhs]);
+        let normal = self.pool.normal_form(difference);
+        let quotient = Quotient::of(self.pool, normal);
+        let x = self.pool.symbol(self.variable);
+        if quotient.is_zero() {
+            return Ok(vec![Solution::new(x)]);
+        }
+
+        let denominator = quotient.denominator.to_atom(self.pool);
+        let zero = self.pool.integer(0);
+        let mut out = Vec::<Solution>::new();
+        for mut solution in self.zeros(&quotient.numerator)? {
+            let at = self.pool.substitute(denominator, x, solution.value);
+            if self.require(&mut solution.conditions, Relation::NotEqual, at, zero)
+                && out.iter().all(|s| s.value != solution.value)
+            {
+                out.push(solution);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Find the zeros of a polynomial whose generators include at most one expression containing
+    /// the variable
+    fn zeros(&mut self, polynomial: &Polynomial) -> Result<Vec<Solution>, SolveError> {
+        let x = self.pool.symbol(self.variable);
+        let dependent = polynomial
+            .generators()
+            .into_iter()
+            .filter(|g| self.depends(*g))
+            .collect::<Vec<_>>();
+
+        match *dependent {
+            [] => Ok(Vec::new()),
+            [generator] if generator == x => self.roots(polynomial, x),
+            [generator] => {
+                let mut out = Vec::new();
+                for root in self.roots(polynomial, generator)? {
+                    for mut solution in self.invert(generator, root.value)? {
+                        solution
+                            .conditions
+                            .splice(0..0, root.conditions.iter().copied());
+                        out.push(solution);
+                    }
+                }
+                Ok(out)
+            }
+            _ => Err(SolveError::Unsupported),
+        }
+    }
+
+    /// Find the values of `generator` at which a polynomial vanishes by solving each of its
+    /// irreducible factors
+    fn roots(
+        &mut self,
+        polynomial: &Polynomial,
+        generator: AtomId,
+    ) -> Result<Vec<Solution>, SolveError> {
+        let mut out = Vec::new();
+        for (factor, _) in polynomial.factor().factors {
+            let degree = factor.degree(generator);
+            if degree == 0 {
+                continue;
+            }
+
+            let coefficients = (0..=degree)
+                .map(|power| factor.coefficient(generator, power))
+                .collect::<Vec<_>>();
+            let exact = coefficients
+                .iter()
+                .map(Polynomial::as_constant)
+                .collect::<Option<Vec<_>>>();
+            let mut atoms = coefficients
+                .iter()
+                .map(|c| {
+                    let atom = c.to_atom(self.pool);
+                    self.pool.simplify(atom)
+                })
+                .collect::<Vec<_>>();
+
+            out.extend(match (degree, exact) {
+                (1, _) => {
+                    let minus_b = (-&coefficients[0]).to_atom(self.pool);
+                    let minus_b = self.pool.simplify(minus_b);
+                    self.linear(atoms[1], minus_b)
+                }
+                (2, _) => {
+                    let (a, b, c) = (atoms.pop(), atoms.pop(), atoms.pop());
+                    self.quadratic(
+                        a.expect("Degree 2"),
+                        b.expect("Degree 2"),
+                        c.expect("Degree 2"),
+                    )
+                }
+                (3, Some(exact)) => self.cubic(&exact),
+                (4, Some(exact)) => self.quartic(&exact)?,
+                _ => return Err(SolveError::Unsupported),
+            });
+        }
+        Ok(out)
+    }
+
+    /// Solve `ax = c`
+    fn linear(&mut self, a: AtomId, c: AtomId) -> Vec<Solution> {
+        let mut conditions = Vec::new();
+        let zero = self.pool.integer(0);
+        if !self.require(&mut conditions, Relation::NotEqual, a, zero) {
+            return Vec::new();
+        }
+
+        let value = self.pool.fraction(c, a);
+        vec![Solution {
+            value: self.expand(value),
+            conditions,
+            parameters: Vec::new(),
+        }]
+    }
+
+    /// Solve `ax^2 + bx + c = 0` by the quadratic formula
+    fn quadratic(&mut self, a: AtomId, b: AtomId, c: AtomId) -> Vec<Solution> {
+        let mut conditions = Vec::new();
+        let zero = self.pool.integer(0);
+        let minus_c = self.pool.neg(c);
+        let minus_c = self.pool.simplify(minus_c);
+        if !self.require(&mut conditions, Relation::NotEqual, a, zero) {
+            return self.linear(b, minus_c);
+        }
+
+        let two = self.pool.integer(2);
+        let minus_four = self.pool.integer(-4);
+        let b_squared = self.pool.power(b, two);
+        let four_ac = self.pool.product([minus_four, a, c]);
+        let discriminant = self.pool.sum([b_squared, four_ac]);
+        let discriminant = self.expand(discriminant);
+
+        // Solve ax^2 = -c directly to avoid a square root of a multiple of four
+        if self.pool.get(b).to_rational().is_some_and(|b| b.is_zero()) {
+            let square = self.pool.fraction(minus_c, a);
+            let square = self.pool.simplify(square);
+            if !self.complex && !self.require(&mut conditions, Relation::GreaterEq, square, zero) {
+                return Vec::new();
+            }
+            let root = self.pool.apply(Function::Sqrt, [square]);
+            let root = self.pool.simplify(root);
+            let mut roots = vec![root];
+            if square != zero {
+                let negative = self.pool.neg(root);
+                roots.push(self.pool.simplify(negative));
+            }
+            return roots
+                .into_iter()
+                .map(|value| Solution {
+                    value,
+                    conditions: conditions.clone(),
+                    parameters: Vec::new(),
+                })
+                .collect();
+        }
+
+        let minus_b = self.pool.neg(b);
+        let minus_b = self.expand(minus_b);
+        let two_a = self.pool.product([two, a]);
+        let (roots, rational) = match self.pool.get(discriminant).to_rational() {
+            Some(d) if d.is_zero() => (vec![minus_b], true),
+            _ if !self.complex
+                && !self.require(&mut conditions, Relation::GreaterEq, discriminant, zero) =>
+            {
+                return Vec::new()
+            }
+            _ => {
+                let root = self.pool.apply(Function::Sqrt, [discriminant]);
+                let root = self.pool.simplify(root);
+                let negative = self.pool.neg(root);
+                let roots = vec![
+                    self.pool.sum([minus_b, root]),
+                    self.pool.sum([minus_b, negative]),
+                ];
+                (roots, self.pool.get(root).to_rational().is_some())
+            }
+        };
+
+        roots
+            .into_iter()
+            .map(|numerator| {
+                let value = self.pool.fraction(numerator, two_a);
+                // Without a square root left the roots are rational functions of the coefficients
+                let value = match rational {
+                    true => self.expand(value),
+                    false => self.pool.simplify(value),
+                };
+                Solution {
+                    value,
+                    conditions: conditions.clone(),
+                    parameters: Vec::new(),
+                }
+            })
+            .collect()
+    }
+
+    /// Solve a cubic with rational coefficients, given from the constant term up, by Cardano's
+    /// formula when it has one real root, along with its complex roots if asked for, and the
+    /// trigonometric method when it has three
+    fn cubic(&mut self, coefficients: &[Rational]) -> Vec<Solution> {
+        let [d, c, b, a] = coefficients else {
+            unreachable!("Cubic has four coefficients")
+        };
+        let r = |n: i64| Rational::integer(n);
+        let div = |x: &Rational, y: &Rational| x.checked_div(y).expect("Nonzero divisor");
+
+        // Substitute x = t - b/3a to get t^3 + pt + q = 0
+        let a2 = a * a;
+        let p = div(&(&(&r(3) * &(a * c)) - &(b * b)), &(&r(3) * &a2));
+        let q = div(
+            &(&(&(&r(2) * &(&(b * b) * b)) - &(&r(9) * &(&(a * b) * c))) + &(&r(27) * &(&a2 * d))),
+            &(&r(27) * &(&a2 * a)),
+        );
+        let shift = div(&-b, &(&r(3) * a));
+
+        let half_q = div(&q, &r(2));
+        let third_p = div(&p, &r(3));
+        let discriminant = &(&half_q * &half_q) + &(&(&third_p * &third_p) * &third_p);
+
+        let roots = if discriminant.is_zero() {
+            match p.is_zero() {
+                true => vec![self.pool.integer(0)],
+                false => vec![
+                    self.pool.number(div(&(&r(3) * &q), &p)),
+                    self.pool.number(div(&(&r(-3) * &q), &(&r(2) * &p))),
+                ],
+            }
+        } else if !discriminant.is_negative() {
+            // Take real cube roots of -q/2 ± sqrt(D), whose sign is that of the larger term since
+            // D - (q/2)^2 = (p/3)^3
+            let third = self.pool.rational(1, 3).expect("Nonzero denominator");
+            let discriminant = self.pool.number(discriminant);
+            let root = self.pool.apply(Function::Sqrt, [discriminant]);
+            let rational_sign = match (half_q.is_zero(), half_q.is_negative()) {
+                (true, _) => 0,
+                (false, true) => 1,
+                (false, false) => -1,
+            };
+            let [u, v] = [1, -1].map(|direction| {
+                let sign = match (p.is_negative(), p.is_zero()) {
+                    _ if rational_sign == 0 || rational_sign == direction => direction,
+                    (true, _) => rational_sign,
+                    (_, true) => return None,
+                    _ => direction,
+                };
+                // Write a negative radicand as the negative cube root of its negation
+                let half_q = self.pool.number(&Rational::integer(-sign) * &half_q);
+                let root = match sign * direction {
+                    1 => root,
+                    _ => self.pool.neg(root),
+                };
+                let cube = self.pool.sum([half_q, root]);
+                let cube_root = self.pool.power(cube, third);
+                Some(match sign {
+                    1 => cube_root,
+                    _ => self.pool.neg(cube_root),
+                })
+            });
+            let zero = self.pool.integer(0);
+            let (u, v) = (u.unwrap_or(zero), v.unwrap_or(zero));
+            let mut roots = vec![self.pool.sum([u, v])];
+            if self.complex {
+                // The other roots are -(u + v)/2 ± i sqrt(3)/2 (u - v)
+                let minus_half = self.pool.rational(-1, 2).expect("Nonzero denominator");
+                let middle = self.pool.product([minus_half, roots[0]]);
+                let minus_three = self.pool.integer(-3);
+                let width = self.pool.apply(Function::Sqrt, [minus_three]);
+                let half = self.pool.rational(1, 2).expect("Nonzero denominator");
+                let minus_v = self.pool.neg(v);
+                let difference = self.pool.sum([u, minus_v]);
+                let offset = self.pool.product([half, width, difference]);
+                let negative = self.pool.neg(offset);
+                roots.push(self.pool.sum([middle, offset]));
+                roots.push(self.pool.sum([middle, negative]));
+            }
+            roots
+        } else {
+            // t = 2 sqrt(-p/3) cos(acos(3q/2p sqrt(-3/p)) / 3 - 2πk/3)
+            let scale = self.pool.number(-&third_p);
+            let scale = self.pool.apply(Function::Sqrt, [scale]);
+            let two = self.pool.integer(2);
+            let scale = self.pool.product([two, scale]);
+            let ratio = self.pool.number(div(&(&r(3) * &q), &(&r(2) * &p)));
+            let root = self.pool.number(div(&r(-3), &p));
+            let root = self.pool.apply(Function::Sqrt, [root]);
+            let argument = self.pool.product([ratio, root]);
+            let angle = self.pool.apply(Function::Acos, [argument]);
+            let third = self.pool.rational(1, 3).expect("Nonzero denominator");
+            let angle = self.pool.product([third, angle]);
+            let pi = self.pi();
+            (0..3)
+                .map(|k| {
+                    let offset = self.pool.rational(-2 * k, 3).expect("Nonzero denominator");
+                    let offset = self.pool.product([offset, pi]);
+                    let angle = self.pool.sum([angle, offset]);
+                    let cos = self.pool.apply(Function::Cos, [angle]);
+                    self.pool.product([scale, cos])
+                })
+                .collect()
+        };
+
+        let shift = self.pool.number(shift);
+        roots
+            .into_iter()
+            .map(|t| {
+                let value = self.pool.sum([t, shift]);
+                Solution::new(self.pool.simplify(value))
+            })
+            .collect()
+    }
+
+    /// Solve a quartic with rational coefficients, given from the constant term up, as a
+    /// quadratic in the square when it is biquadratic and by Ferrari's method otherwise
+    fn quartic(&mut self, coefficients: &[Rational]) -> Result<Vec<Solution>, SolveError> {
+        let [e, d, c, b, a] = coefficients else {
+            unreachable!("Quartic has five coefficients")
+        };
+        let r = |n: i64| Rational::integer(n);
+        let div = |x: &Rational, y: &Rational| x.checked_div(y).expect("Nonzero divisor");
+
+        // Substitute x = y - b/4a to get y^4 + py^2 + qy + r = 0
+        let (a2, b2) = (a * a, b * b);
+        let p = div(&(&(&r(8) * &(a * c)) - &(&r(3) * &b2)), &(&r(8) * &a2));
+        let q = div(
+            &(&(&(&b2 * b) - &(&r(4) * &(&(a * b) * c))) + &(&r(8) * &(&a2 * d))),
+            &(&r(8) * &(&a2 * a)),
+        );
+        let constant = div(
+            &(&(&(&(&r(-3) * &(&b2 * &b2)) + &(&r(256) * &(&(&a2 * a) * e)))
+                - &(&r(64) * &(&(&a2 * b) * d)))
+                + &(&r(16) * &(&(a * &b2) * c))),
+            &(&r(256) * &(&a2 * &a2)),
+        );
+        let shift = div(&-b, &(&r(4) * a));
+        let one = self.pool.integer(1);
+
+        let mut roots = Vec::new();
+        if q.is_zero() {
+            // Solve z^2 + pz + r = 0 for z = y^2
+            let p = self.pool.number(p);
+            let constant = self.pool.number(constant);
+            for z in self.quadratic(one, p, constant) {
+                let zero = self.pool.integer(0);
+                let mut conditions = Vec::new();
+                if !self.complex
+                    && !self.require(&mut conditions, Relation::GreaterEq, z.value, zero)
+                {
+                    continue;
+                }
+                let root = self.pool.apply(Function::Sqrt, [z.value]);
+                let root = self.pool.simplify(root);
+                roots.push(root);
+                if root != zero {
+                    roots.push(self.pool.neg(root));
+                }
+            }
+        } else {
+            // Find m > 0 with 8m^3 + 8pm^2 + (2p^2 - 8r)m - q^2 = 0, so that
+            // (y^2 + p/2 + m)^2 = (sqrt(2m) y - q / 2sqrt(2m))^2. The resolvent is negative at
+            // zero, so it has a positive root, which is rational when it can be
+            let resolvent = [
+                -&(&q * &q),
+                &(&r(2) * &(&p * &p)) - &(&r(8) * &constant),
+                &r(8) * &p,
+                r(8),
+            ];
+            let m = self.pool.symbol(self.variable);
+            let polynomial =
+                resolvent
+                    .iter()
+                    .enumerate()
+                    .fold(Polynomial::zero(), |sum, (power, c)| {
+                        let term = &Polynomial::generator(m).pow(power as u32)
+                            * &Polynomial::constant(c.clone());
+                        &sum + &term
+                    });
+            let rational = polynomial
+                .factor()
+                .factors
+                .into_iter()
+                .filter(|(factor, _)| factor.degree(m) == 1)
+                .map(|(factor, _)| {
+                    let c0 = factor.coefficient(m, 0).as_constant().expect("Rational");
+                    let c1 = factor.coefficient(m, 1).as_constant().expect("Rational");
+                    div(&-&c0, &c1)
+                })
+                .find(|m| !m.is_negative() && !m.is_zero());
+            let m = match rational {
+                Some(m) => self.pool.number(m),
+                None => {
+                    let roots = self.cubic(&resolvent);
+                    roots
+                        .into_iter()
+                        .filter_map(|root| {
+                            let value = self.pool.eval(root.value, self.symbols, &Env::new());
+                            Some((root.value, value.ok()?))
+                        })
+                        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                        .ok_or(SolveError::Unsupported)?
+                        .0
+                }
+            };
+
+            let two = self.pool.integer(2);
+            let two_m = self.pool.product([two, m]);
+            let two_m = self.pool.simplify(two_m);
+            let s = self.pool.apply(Function::Sqrt, [two_m]);
+            let half_q = self.pool.number(div(&q, &r(2)));
+            let half_p = self.pool.number(div(&p, &r(2)));
+            let base = self.pool.sum([half_p, m]);
+            for sign in [1, -1] {
+                let sign = self.pool.integer(sign);
+                let b = self.pool.product([sign, s]);
+                let b = self.pool.neg(b);
+                let ratio = self.pool.fraction(half_q, s);
+                let ratio = self.pool.product([sign, ratio]);
+                let c = self.pool.sum([base, ratio]);
+                let (b, c) = (self.pool.simplify(b), self.pool.simplify(c));
+                roots.extend(self.quadratic(one, b, c).into_iter().map(|s| s.value));
+            }
+        }
+
+        let shift = self.pool.number(shift);
+        Ok(roots
+            .into_iter()
+            .map(|y| {
+                let value = self.pool.sum([y, shift]);
+                Solution::new(self.pool.simplify(value))
+            })
+            .collect())
+    }
+
+    /// Solve `generator = value` for the variable by inverting the function or power at the root
+    /// of `generator`
+    fn invert(&mut self, generator: AtomId, value: AtomId) -> Result<Vec<Solution>, SolveError> {
+        let zero = self.pool.integer(0);
+        let mut conditions = Vec::new();
+        let (argument, values, parameter) = match self.pool.get(generator).clone() {
+            Atom::Apply { func, args } if args.len() == 1 => {
+                match self.inverse(func, value, &mut conditions)? {
+                    Some((values, parameter)) => (args[0], values, parameter),
+                    None => return Ok(Vec::new()),
+                }
+            }
+            Atom::Power { base, exponent } => {
+                match (self.depends(base), self.depends(exponent)) {
+                    (true, false) => {
+                        let (roots, nonnegative) = self.root(exponent, value);
+                        if nonnegative
+                            && !self.require(&mut conditions, Relation::GreaterEq, value, zero)
+                        {
+                            return Ok(Vec::new());
+                        }
+                        (base, roots, None)
+                    }
+                    // a^u = v gives u = ln(v) / ln(a)
+                    (false, true) => {
+                        if !self.require(&mut conditions, Relation::Greater, value, zero) {
+                            return Ok(Vec::new());
+                        }
+                        let ln_value = self.pool.apply(Function::Ln, [value]);
+                        let ln_base = self.pool.apply(Function::Ln, [base]);
+                        (exponent, vec![self.pool.fraction(ln_value, ln_base)], None)
+                    }
+                    _ => return Err(SolveError::Unsupported),
+                }
+            }
+            _ => return Err(SolveError::Unsupported),
+        };
+
+        let mut out = Vec::new();
+        for target in values {
+            for mut solution in self.solve(argument, target)? {
+                solution.conditions.splice(0..0, conditions.iter().copied());
+                solution.parameters.extend(parameter);
+                out.push(solution);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Get the values of the argument of `func` at which it takes `value`, with the integer
+    /// parameter they are written in terms of, or `None` if `value` is outside the range of `func`
+    fn inverse(
+        &mut self,
+        func: Function,
+        value: AtomId,
+        conditions: &mut Vec<AtomId>,
+    ) -> Result<Option<Branches>, SolveError> {
+        let zero = self.pool.integer(0);
+        let one = self.pool.integer(1);
+        let minus_one = self.pool.integer(-1);
+        let pi = self.pi();
+        let half = self.pool.rational(1, 2).expect("Nonzero denominator");
+        let half_pi = self.pool.product([half, pi]);
+        let minus_half_pi = self.pool.neg(half_pi);
+
+        let range = match func {
+            Function::Exp => vec![(Relation::Greater, zero)],
+            Function::Sqrt | Function::Abs => vec![(Relation::GreaterEq, zero)],
+            Function::Cosh => vec![(Relation::GreaterEq, one)],
+            Function::Sin | Function::Cos => {
+                vec![(Relation::GreaterEq, minus_one), (Relation::LessEq, one)]
+            }
+            Function::Tanh => vec![(Relation::Greater, minus_one), (Relation::Less, one)],
+            Function::Asin => vec![
+                (Relation::GreaterEq, minus_half_pi),
+                (Relation::LessEq, half_pi),
+            ],
+            Function::Acos => vec![(Relation::GreaterEq, zero), (Relation::LessEq, pi)],
+            Function::Atan => vec![
+                (Relation::Greater, minus_half_pi),
+                (Relation::Less, half_pi),
+            ],
+            _ => Vec::new(),
+        };
+        for (op, bound) in range {
+            if !self.require(conditions, op, value, bound) {
+                return Ok(None);
+            }
+        }
+
+        let two = self.pool.integer(2);
+        let values = match func {
+            Function::Exp => vec![self.pool.apply(Function::Ln, [value])],
+            Function::Ln => vec![self.pool.apply(Function::Exp, [value])],
+            Function::Log => {
+                let ten = self.pool.integer(10);
+                vec![self.pool.power(ten, value)]
+            }
+            Function::Sqrt => vec![self.pool.power(value, two)],
+            Function::Abs => vec![value, self.pool.neg(value)],
+            Function::Sin | Function::Cos | Function::Tan => {
+                let (values, parameter) = self.periodic(func, value);
+                return Ok(Some((values, Some(parameter))));
+            }
+            Function::Asin => vec![self.pool.apply(Function::Sin, [value])],
+            Function::Acos => vec![self.pool.apply(Function::Cos, [value])],
+            Function::Atan => vec![self.pool.apply(Function::Tan, [value])],
+            // asinh(v) = ln(v + sqrt(v^2 + 1)) and acosh(v) = ln(v + sqrt(v^2 - 1))
+            Function::Sinh | Function::Cosh => {
+                let square = self.pool.power(value, two);
+                let offset = match func {
+                    Function::Sinh => one,
+                    _ => minus_one,
+                };
+                let radicand = self.pool.sum([square, offset]);
+                let root = self.pool.apply(Function::Sqrt, [radicand]);
+                let sum = self.pool.sum([value, root]);
+                let ln = self.pool.apply(Function::Ln, [sum]);
+                match func {
+                    Function::Sinh => vec![ln],
+                    _ => vec![ln, self.pool.neg(ln)],
+                }
+            }
+            // atanh(v) = ln((1 + v) / (1 - v)) / 2
+            Function::Tanh => {
+                let numerator = self.pool.sum([one, value]);
+                let negative = self.pool.neg(value);
+                let denominator = self.pool.sum([one, negative]);
+                let ratio = self.pool.fraction(numerator, denominator);
+                let ln = self.pool.apply(Function::Ln, [ratio]);
+                vec![self.pool.product([half, ln])]
+            }
+            Function::Conj => vec![self.pool.apply(Function::Conj, [value])],
+            Function::Re | Function::Im | Function::Arg | Function::User(_) => {
+                return Err(SolveError::Unsupported)
+            }
+        };
+        Ok(Some((values, None)))
+    }
+
+    /// Get the values of `base` for which `base ^ exponent = value`, and whether `value` must be
+    /// nonnegative for them to be real
+    fn root(&mut self, exponent: AtomId, value: AtomId) -> (Vec<AtomId>, bool) {
+        match self.pool.get(exponent).to_rational() {
+            // base^(p/q) = v gives base = ±v^(q/p), requiring v ≥ 0 for even p or q
+            Some(exponent) => {
+                let (p, q) = exponent.into_parts();
+                let even = |n: &BigInt| !n.bit(0);
+                let inverse = self
+                    .pool
+                    .number(Rational::new(q.clone(), p.clone()).expect("Exponent is nonzero"));
+                let root = self.pool.power(value, inverse);
+                match even(&p) {
+                    true => (vec![root, self.pool.neg(root)], true),
+                    false => (vec![root], even(&q)),
+                }
+            }
+            None => {
+                let one = self.pool.integer(1);
+                let inverse = self.pool.fraction(one, exponent);
+                (vec![self.pool.power(value, inverse)], true)
+            }
+        }
+    }
+
+    /// Get the argument values at which a trigonometric function takes `value`, written with a
+    /// fresh integer parameter `n` over every period
+    fn periodic(&mut self, func: Function, value: AtomId) -> (Vec<AtomId>, Symbol) {
+        let n = self.parameter();
+        let n_atom = self.pool.symbol(n);
+        let pi = self.pi();
+        let period = match func {
+            Function::Tan => self.pool.product([n_atom, pi]),
+            _ => {
+                let two = self.pool.integer(2);
+                self.pool.product([two, n_atom, pi])
+            }
+        };
+
+        let inverse = match func {
+            Function::Sin => Function::Asin,
+            Function::Cos => Function::Acos,
+            _ => Function::Atan,
+        };
+        let principal = self.pool.apply(inverse, [value]);
+        let negative = self.pool.neg(principal);
+        let values = match func {
+            // sin(π - u) = sin(u)
+            Function::Sin => vec![principal, self.pool.sum([pi, negative])],
+            // cos(-u) = cos(u)
+            Function::Cos => vec![principal, negative],
+            _ => vec![principal],
+        };
+
+        let values = values
+            .into_iter()
+            .map(|value| self.pool.sum([value, period]))
+            .collect();
+        (values, n)
+    }
+
+    fn pi(&mut self) -> AtomId {
+        let pi = self.symbols.intern("π");
+        self.pool.symbol(pi)
+    }
+
+    /// Create a symbol standing for an arbitrary integer that does not appear in the equation
+    fn parameter(&mut self) -> Symbol {
+        let parameter = (0..)
+            .map(|i| match i {
+                0 => String::from("n"),
+                i => format!("n_{i}"),
+            })
+            .map(|name| self.symbols.intern(&name))
+            .find(|sym| {
+                *sym != self.variable
+                    && !self.pool.depends_on(self.equation, *sym)
+                    && !self.parameters.contains(sym)
+            })
+            .expect("There are infinitely many names");
+        self.parameters.push(parameter);
+        parameter
+    }
+}
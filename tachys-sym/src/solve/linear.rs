@@ -0,0 +1,181 @@
+use crate::{
+    assume::Assumptions,
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, ExprPool, Relation,
+    },
+    poly::{Monomial, Polynomial, Quotient},
+};
+
+use super::{require, SolveError};
+
+/// Solution of a system of linear equations, valid whenever all of its conditions hold
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SystemSolution {
+    /// Value of each unknown, in the order they were given. Unknowns that can take any value are
+    /// their own value, and the values of the others may depend on them
+    pub values: Vec<(Symbol, AtomId)>,
+    /// [Atom::Relation]s and [Atom::Equals] between other symbols that must hold for the solution
+    /// to be valid, such as a nonzero pivot
+    pub conditions: Vec<AtomId>,
+}
+
+impl ExprPool {
+    /// Solve a system of equations that are linear in `unknowns` by Gauss–Jordan elimination.
+    /// Expressions that are not [Atom::Equals] are taken to equal zero.
+    ///
+    /// Coefficients may depend on other symbols, in which case the solution is conditional on the
+    /// pivots chosen being nonzero. Returns `None` if the equations are inconsistent
+    pub fn solve_linear(
+        &mut self,
+        symbols: &SymbolStore,
+        equations: &[AtomId],
+        unknowns: &[Symbol],
+    ) -> Result<Option<SystemSolution>, SolveError> {
+        let generators = unknowns
+            .iter()
+            .map(|sym| self.symbol(*sym))
+            .collect::<Vec<_>>();
+        let zero = self.integer(0);
+        let mut conditions = Vec::new();
+
+        // Each row holds the coefficients of the unknowns followed by the right-hand side
+        let mut rows = Vec::new();
+        for equation in equations {
+            let (lhs, rhs) = match *self.get(*equation) {
+                Atom::Equals { lhs, rhs } => (lhs, rhs),
+                Atom::Relation { .. } => return Err(SolveError::NotEquation),
+                _ => (*equation, zero),
+            };
+            let rhs = self.neg(rhs);
+            let difference = self.sum([lhs, rhs]);
+            let normal = self.normal_form(difference);
+            let quotient = Quotient::of(self, normal);
+
+            let depends = |pool: &Self, id| unknowns.iter().any(|sym| pool.depends_on(id, *sym));
+            let denominator = quotient.denominator.to_atom(self);
+            if depends(self, denominator) {
+                return Err(SolveError::NonLinear);
+            }
+            // An equation whose denominator always vanishes is never satisfied
+            if !require(
+                self,
+                symbols,
+                &Assumptions::new(),
+                &mut conditions,
+                Relation::NotEqual,
+                denominator,
+                zero,
+            ) {
+                return Ok(None);
+            }
+
+            let mut row = vec![Polynomial::zero(); unknowns.len() + 1];
+            for (monomial, coefficient) in quotient.numerator.terms() {
+                let mut column = unknowns.len();
+                let mut rest = monomial.clone();
+                for (generator, power) in monomial.powers() {
+                    match generators.iter().position(|g| g == generator) {
+                        Some(index) if *power == 1 && column == unknowns.len() => {
+                            column = index;
+                            rest = rest
+                                .div(&Monomial::generator(*generator, 1))
+                                .expect("Monomial contains the generator");
+                        }
+                        Some(_) => return Err(SolveError::NonLinear),
+                        None if depends(self, *generator) => return Err(SolveError::NonLinear),
+                        None => (),
+                    }
+                }
+
+                // Constant terms move to the right-hand side
+                let term = Polynomial::term(rest, coefficient.clone());
+                row[column] = match column == unknowns.len() {
+                    true => &row[column] - &term,
+                    false => &row[column] + &term,
+                };
+            }
+            rows.push(
+                row.into_iter()
+                    .map(Quotient::polynomial)
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        // Reduce to reduced row echelon form, recording the pivot column of each nonzero row
+        let mut pivots = Vec::new();
+        for column in 0..unknowns.len() {
+            let row = pivots.len();
+            let mut candidates = (row..rows.len()).filter(|r| !rows[*r][column].is_zero());
+            let Some(first) = candidates.next() else {
+                continue;
+            };
+            let pivot = std::iter::once(first)
+                .chain(candidates)
+                .find(|r| rows[*r][column].is_constant())
+                .unwrap_or(first);
+            rows.swap(row, pivot);
+
+            let divisor = rows[row][column].clone();
+            // A pivot that is not identically zero may still evaluate to zero, as with sin(π)
+            let pivot = divisor.to_atom(self);
+            if !require(
+                self,
+                symbols,
+                &Assumptions::new(),
+                &mut conditions,
+                Relation::NotEqual,
+                pivot,
+                zero,
+            ) {
+                return Err(SolveError::Unsupported);
+            }
+            rows[row] = rows[row].iter().map(|entry| entry.div(&divisor)).collect();
+
+            for other in 0..rows.len() {
+                let factor = rows[other][column].clone();
+                if other == row || factor.is_zero() {
+                    continue;
+                }
+                rows[other] = rows[other]
+                    .iter()
+                    .zip(&rows[row])
+                    .map(|(entry, pivot)| entry.sub(&pivot.mul(&factor)))
+                    .collect();
+            }
+            pivots.push(column);
+        }
+
+        // Rows without a pivot read 0 = rhs
+        for row in &rows[pivots.len()..] {
+            let rhs = &row[unknowns.len()];
+            if rhs.is_zero() {
+                continue;
+            }
+            if rhs.is_constant() {
+                return Ok(None);
+            }
+            let rhs = rhs.to_atom(self);
+            let condition = self.equals(rhs, zero);
+            if !conditions.contains(&condition) {
+                conditions.push(condition);
+            }
+        }
+
+        let mut values = unknowns
+            .iter()
+            .zip(&generators)
+            .map(|(sym, generator)| (*sym, *generator))
+            .collect::<Vec<_>>();
+        for (row, column) in pivots.iter().enumerate() {
+            let mut value = rows[row][unknowns.len()].clone();
+            for free in (0..unknowns.len()).filter(|c| !pivots.contains(c)) {
+                let generator = Quotient::polynomial(Polynomial::generator(generators[free]));
+                value = value.sub(&rows[row][free].mul(&generator));
+            }
+            values[*column].1 = value.to_atom(self);
+        }
+
+        Ok(Some(SystemSolution { values, conditions }))
+    }
+}
@@ -0,0 +1,736 @@
+use std::collections::BTreeMap;
+
+use num_traits::ToPrimitive;
+
+use crate::{
+    assume::Assumptions,
+    atom::{
+        symbol::{Symbol, SymbolStore},
+        Atom, AtomId, BigInt, BigOperator, ExprPool, Function, Rational,
+    },
+    eval::Env,
+    integrate::rational::{self, UniPoly},
+    poly::Polynomial,
+    series::Point,
+};
+
+/// Largest number of terms written out when a sum or product with integer bounds has no closed
+/// form
+const MAX_TERMS: i64 = 64;
+
+/// Largest distance `m` between the terms `g(k)` and `g(k + m)` that cancel in a telescoping sum
+/// or product
+const MAX_SHIFT: i64 = 8;
+
+impl ExprPool {
+    /// Find a closed form for the sum of `term` over the integers `index` from `lower` to
+    /// `upper`, which may be `∞`, or `None` if none is found or the sum diverges.
+    ///
+    /// Polynomial terms are summed by Faulhaber's formula and geometric terms by their ratio.
+    /// Other terms, after splitting rational functions of the index into partial fractions, are
+    /// grouped into shifted copies `c·g(k + m)` of one another, whose sum telescopes if their
+    /// coefficients cancel. Sums with integer bounds and no closed form are written out if they
+    /// have at most [MAX_TERMS] terms. A sum whose upper bound is known to be below its lower
+    /// bound is empty and equal to `0`
+    pub fn sum_over(
+        &mut self,
+        symbols: &mut SymbolStore,
+        term: AtomId,
+        index: Symbol,
+        lower: AtomId,
+        upper: AtomId,
+    ) -> Option<AtomId> {
+        self.closed_form(symbols, BigOperator::Sum, term, index, [lower, upper])
+    }
+
+    /// Find a closed form for the product of `term` over the integers `index` from `lower` to
+    /// `upper`, which may be `∞`, or `None` if none is found or the product diverges.
+    ///
+    /// Powers of a constant base and exponentials become sums in the exponent. The remaining
+    /// factors, after splitting rational functions of the index into linear factors, are grouped
+    /// into shifted copies `g(k + m)^c` of one another, whose product telescopes if their
+    /// exponents cancel. Products with integer bounds and no closed form are written out if they
+    /// have at most [MAX_TERMS] factors. A product whose upper bound is known to be below its
+    /// lower bound is empty and equal to `1`, while one with a factor that is zero within the
+    /// range, like `k` at `k = 0`, is `0`
+    pub fn product_over(
+        &mut self,
+        symbols: &mut SymbolStore,
+        term: AtomId,
+        index: Symbol,
+        lower: AtomId,
+        upper: AtomId,
+    ) -> Option<AtomId> {
+        self.closed_form(symbols, BigOperator::Product, term, index, [lower, upper])
+    }
+
+    /// Evaluate every [Atom::BigOperator] in the expression rooted at `id` that has a closed
+    /// form, leaving the others in place
+    pub fn eval_big_operators(&mut self, symbols: &mut SymbolStore, id: AtomId) -> AtomId {
+        let atom = self.get(id).clone();
+        let atom = atom.map_children(|child| self.eval_big_operators(symbols, child));
+        let Atom::BigOperator {
+            op,
+            index,
+            lower,
+            upper,
+            term,
+        } = atom
+        else {
+            return self.insert(atom);
+        };
+
+        match self.closed_form(symbols, op, term, index, [lower, upper]) {
+            Some(value) => value,
+            None => self.insert(atom),
+        }
+    }
+
+    fn closed_form(
+        &mut self,
+        symbols: &mut SymbolStore,
+        op: BigOperator,
+        term: AtomId,
+        index: Symbol,
+        [lower, upper]: [AtomId; 2],
+    ) -> Option<AtomId> {
+        // An index named `i` shadows the imaginary unit, so it is renamed before simplifying
+        let (term, index) = match index == Symbol::IMAGINARY_UNIT {
+            true => {
+                let renamed = (1..)
+                    .map(|n| symbols.intern(&format!("i_{n}")))
+                    .find(|s| {
+                        [term, lower, upper]
+                            .iter()
+                            .all(|id| !self.depends_on(*id, *s))
+                    })
+                    .expect("Expressions have finitely many symbols");
+                let (from, to) = (self.symbol(index), self.symbol(renamed));
+                (self.substitute(term, from, to), renamed)
+            }
+            false => (term, index),
+        };
+
+        let Point::Finite(lower) = Point::of(self, symbols, lower, index).ok()? else {
+            return None;
+        };
+        let upper = match Point::of(self, symbols, upper, index).ok()? {
+            Point::Finite(upper) => Some(upper),
+            Point::Infinity { negative: false } => None,
+            Point::Infinity { negative: true } => return None,
+        };
+
+        // The closed forms continue past an empty range, as in Faulhaber's formula giving `-9`
+        // for the sum of `k` from 5 to 1
+        if let Some(upper) = upper {
+            let minus_lower = self.neg(lower);
+            let length = self.sum([upper, minus_lower]);
+            let length = self.simplify(length);
+            if self.is_negative(length, &Assumptions::new()) == Some(true) {
+                return Some(self.integer(match op {
+                    BigOperator::Sum => 0,
+                    BigOperator::Product => 1,
+                }));
+            }
+        }
+
+        let mut summation = Summation {
+            pool: self,
+            symbols,
+            index,
+            lower,
+            upper,
+        };
+        let value = match op {
+            BigOperator::Sum => summation.sum(term),
+            BigOperator::Product => summation.product(term),
+        };
+        let value = value.or_else(|| summation.write_out(op, term))?;
+        Some(self.simplify(value))
+    }
+}
+
+/// State of a single search for a closed form over the integers `index` from `lower` to `upper`
+struct Summation<'a> {
+    pool: &'a mut ExprPool,
+    symbols: &'a mut SymbolStore,
+    index: Symbol,
+    lower: AtomId,
+    /// Upper bound, or `None` if the index runs to infinity
+    upper: Option<AtomId>,
+}
+
+impl Summation<'_> {
+    fn k(&mut self) -> AtomId {
+        self.pool.symbol(self.index)
+    }
+
+    fn depends(&self, id: AtomId) -> bool {
+        self.pool.depends_on(id, self.index)
+    }
+
+    /// Substitute `value` for the index
+    fn at(&mut self, id: AtomId, value: AtomId) -> AtomId {
+        let k = self.k();
+        self.pool.substitute(id, k, value)
+    }
+
+    /// Substitute `k + m` for the index `k` and bring the result into normal form
+    fn shift(&mut self, id: AtomId, m: i64) -> AtomId {
+        let (k, m) = (self.k(), self.pool.integer(m));
+        let shifted = self.pool.sum([k, m]);
+        let shifted = self.at(id, shifted);
+        self.pool.normal_form(shifted)
+    }
+
+    /// Get `n + m` for an integer offset `m`
+    fn offset(&mut self, n: AtomId, m: i64) -> AtomId {
+        let m = self.pool.integer(m);
+        self.pool.sum([n, m])
+    }
+
+    /// Get the number of terms `upper - lower + 1`, or `None` if there are infinitely many
+    fn count(&mut self) -> Option<AtomId> {
+        let upper = self.upper?;
+        let lower = self.pool.neg(self.lower);
+        let one = self.pool.integer(1);
+        Some(self.pool.sum([upper, lower, one]))
+    }
+
+    fn sum(&mut self, term: AtomId) -> Option<AtomId> {
+        let term = self.pool.normal_form(term);
+        if !self.depends(term) {
+            return match self.count() {
+                Some(count) => Some(self.pool.product([count, term])),
+                None => self.pool.get(term).is_zero().then_some(term),
+            };
+        }
+
+        let mut powers = BTreeMap::<u32, Vec<AtomId>>::new();
+        let mut shifted = Vec::new();
+        let mut values = Vec::new();
+        for piece in self.pieces(term) {
+            if let Some((coefficient, power)) = self.monomial(piece) {
+                powers.entry(power).or_default().push(coefficient);
+            } else if let Some(ratio) = self.ratio(piece) {
+                values.push(self.geometric(piece, ratio)?);
+            } else {
+                let (coefficient, rest) = self.split_constant(piece);
+                shifted.push((rest, coefficient));
+            }
+        }
+
+        // The sums of powers are factored together, so `2k + 3` gives `(n + 1)(n + 3)`
+        if !powers.is_empty() {
+            let mut polynomial = Vec::new();
+            for (power, coefficients) in powers {
+                let coefficient = self.pool.sum(coefficients);
+                let sum = self.power_sum(power)?;
+                polynomial.push(self.pool.product([coefficient, sum]));
+            }
+            let polynomial = self.pool.sum(polynomial);
+            values.push(self.pool.factor(polynomial));
+        }
+        if !shifted.is_empty() {
+            values.push(self.telescope(BigOperator::Sum, shifted)?);
+        }
+        Some(self.pool.sum(values))
+    }
+
+    fn product(&mut self, term: AtomId) -> Option<AtomId> {
+        let term = self.pool.normal_form(term);
+        if self.has_zero_factor(term) {
+            return Some(self.pool.integer(0));
+        }
+        let mut constants = Vec::new();
+        let mut shifted = Vec::new();
+        let mut values = Vec::new();
+        for (base, exponent) in self.factors(term) {
+            match (self.depends(base), self.depends(exponent)) {
+                (false, false) => constants.push(self.pool.power(base, exponent)),
+                (false, true) => {
+                    let sum = self.sum(exponent)?;
+                    values.push(self.pool.power(base, sum));
+                }
+                (true, false) => match self.pool.get(base).clone() {
+                    Atom::Apply {
+                        func: Function::Exp,
+                        args,
+                    } => {
+                        let arg = self.pool.product([args[0], exponent]);
+                        let sum = self.sum(arg)?;
+                        values.push(self.pool.apply(Function::Exp, [sum]));
+                    }
+                    _ => shifted.push((base, exponent)),
+                },
+                (true, true) => return None,
+            }
+        }
+
+        if !constants.is_empty() {
+            let constant = self.pool.product(constants);
+            match self.count() {
+                Some(count) => values.push(self.pool.power(constant, count)),
+                None => {
+                    let constant = self.pool.simplify(constant);
+                    if !matches!(self.pool.get(constant), Atom::Integer(i) if *i == BigInt::from(1))
+                    {
+                        return None;
+                    }
+                }
+            }
+        }
+        if !shifted.is_empty() {
+            values.push(self.telescope(BigOperator::Product, shifted)?);
+        }
+        Some(self.pool.product(values))
+    }
+
+    /// Check if one of the factors of a product is zero, as `k` is at `k = 0`. A factor at the
+    /// lower bound is in every nonempty range, while a root of the term past it must be known to
+    /// be at most the upper bound
+    fn has_zero_factor(&mut self, term: AtomId) -> bool {
+        let Some((numerator, denominator)) =
+            rational::rational_function(self.pool, term, self.index)
+        else {
+            return false;
+        };
+        numerator
+            .rational_roots()
+            .into_iter()
+            .filter(|root| root.is_integer() && !denominator.eval(root).is_zero())
+            .any(|root| {
+                let root = self.pool.number(root);
+                let lower = self.pool.neg(self.lower);
+                let offset = self.pool.sum([root, lower]);
+                let offset = self.pool.simplify(offset);
+                if self.pool.get(offset).is_zero() {
+                    return true;
+                }
+                let Some(upper) = self.upper else {
+                    return self.pool.is_positive(offset, &Assumptions::new()) == Some(true);
+                };
+                let root = self.pool.neg(root);
+                let remaining = self.pool.sum([upper, root]);
+                let remaining = self.pool.simplify(remaining);
+                [offset, remaining]
+                    .iter()
+                    .all(|id| self.pool.is_nonnegative(*id, &Assumptions::new()) == Some(true))
+            })
+    }
+
+    /// Split a normalized term into pieces to be summed separately, expanding products and
+    /// powers of sums and writing rational functions of the index as partial fractions
+    fn pieces(&mut self, term: AtomId) -> Vec<AtomId> {
+        if let Some((numerator, denominator)) =
+            rational::rational_function(self.pool, term, self.index)
+        {
+            if denominator.degree() > 0 {
+                if let Some(pieces) = self.partial_fractions(&numerator, &denominator) {
+                    return pieces;
+                }
+            }
+        }
+
+        let polynomial = self.pool.polynomial(term);
+        let monomials = polynomial
+            .terms()
+            .map(|(monomial, c)| Polynomial::term(monomial.clone(), c.clone()))
+            .collect::<Vec<_>>();
+        monomials
+            .into_iter()
+            .map(|monomial| {
+                let piece = monomial.to_atom(self.pool);
+                self.pool.normal_form(piece)
+            })
+            .collect()
+    }
+
+    /// Split a quotient of polynomials in the index into the terms of its polynomial part and
+    /// fractions `a/(k - r)^j`, or `None` if the denominator does not split into linear factors
+    /// over the rationals
+    fn partial_fractions(
+        &mut self,
+        numerator: &UniPoly,
+        denominator: &UniPoly,
+    ) -> Option<Vec<AtomId>> {
+        let k = self.k();
+        let (quotient, remainder) = numerator.div_rem(denominator);
+        let mut pieces = Vec::new();
+        for power in 0..=quotient.degree() {
+            let c = quotient.coefficient(power);
+            if c.is_zero() {
+                continue;
+            }
+            let (c, power) = (self.pool.number(c), self.pool.integer(power as i64));
+            let monomial = self.pool.power(k, power);
+            let piece = self.pool.product([c, monomial]);
+            pieces.push(self.pool.normal_form(piece));
+        }
+        if remainder.is_zero() {
+            return Some(pieces);
+        }
+
+        // Solve for the numerators of each fraction by equating coefficients
+        let (_, linear) = linear_factors(denominator)?;
+        let mut basis = Vec::new();
+        for (root, multiplicity) in &linear {
+            for j in 1..=*multiplicity {
+                let power = UniPoly::linear(root).pow(j);
+                basis.push(denominator.div_rem(&power).0);
+            }
+        }
+        let n = denominator.degree();
+        let matrix = (0..n)
+            .map(|row| basis.iter().map(|b| b.coefficient(row)).collect())
+            .collect();
+        let rhs = (0..n).map(|row| remainder.coefficient(row)).collect();
+        let mut coefficients = rational::solve(matrix, rhs)?.into_iter();
+
+        for (root, multiplicity) in &linear {
+            let shifted = UniPoly::linear(root).to_atom(self.pool, k);
+            for j in 1..=*multiplicity {
+                let a = coefficients.next()?;
+                if a.is_zero() {
+                    continue;
+                }
+                let (a, exponent) = (self.pool.number(a), self.pool.integer(-(j as i64)));
+                let power = self.pool.power(shifted, exponent);
+                let piece = self.pool.product([a, power]);
+                pieces.push(self.pool.normal_form(piece));
+            }
+        }
+        Some(pieces)
+    }
+
+    /// Split a normalized term into factors `base^exponent`, writing rational functions of the
+    /// index as products of linear factors
+    fn factors(&mut self, term: AtomId) -> Vec<(AtomId, AtomId)> {
+        let one = self.pool.integer(1);
+        let split = rational::rational_function(self.pool, term, self.index).and_then(|(n, d)| {
+            let ((a, top), (b, bottom)) = (linear_factors(&n)?, linear_factors(&d)?);
+            Some((a.checked_div(&b)?, top, bottom))
+        });
+        if let Some((lead, top, bottom)) = split {
+            let k = self.k();
+            let lead = self.pool.number(lead);
+            let mut factors = vec![(lead, one)];
+            for (roots, sign) in [(top, 1), (bottom, -1)] {
+                for (root, multiplicity) in roots {
+                    let base = UniPoly::linear(&root).to_atom(self.pool, k);
+                    let base = self.pool.normal_form(base);
+                    let exponent = self.pool.integer(sign * multiplicity as i64);
+                    factors.push((base, exponent));
+                }
+            }
+            return factors;
+        }
+
+        let factors = match self.pool.get(term) {
+            Atom::Product(factors) => factors.to_vec(),
+            _ => vec![term],
+        };
+        factors
+            .into_iter()
+            .map(|factor| match *self.pool.get(factor) {
+                Atom::Power { base, exponent } => (base, exponent),
+                _ => (factor, one),
+            })
+            .collect()
+    }
+
+    /// Split a piece into the product of its factors that do not depend on the index and the
+    /// product of those that do
+    fn split_constant(&mut self, piece: AtomId) -> (AtomId, AtomId) {
+        let factors = match self.pool.get(piece) {
+            Atom::Product(factors) => factors.to_vec(),
+            _ => vec![piece],
+        };
+        let (rest, constant): (Vec<_>, Vec<_>) =
+            factors.into_iter().partition(|f| self.depends(*f));
+        (self.pool.product(constant), self.pool.product(rest))
+    }
+
+    /// Split a piece into a coefficient that does not depend on the index and a nonnegative
+    /// integer power of the index, if it is a monomial in the index
+    fn monomial(&mut self, piece: AtomId) -> Option<(AtomId, u32)> {
+        let (coefficient, rest) = self.split_constant(piece);
+        let power = match self.pool.get(rest) {
+            _ if !self.depends(rest) => 0,
+            Atom::Symbol(sym) if *sym == self.index => 1,
+            Atom::Power { base, exponent } if matches!(self.pool.get(*base), Atom::Symbol(sym) if *sym == self.index) => {
+                match self.pool.get(*exponent) {
+                    Atom::Integer(e) => e.to_u32()?,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        Some((coefficient, power))
+    }
+
+    /// Get the ratio between consecutive values of a geometric piece such as `3·2^k`, if it does
+    /// not depend on the index
+    fn ratio(&mut self, piece: AtomId) -> Option<AtomId> {
+        let next = self.shift(piece, 1);
+        let ratio = self.pool.fraction(next, piece);
+        // Expanding cancels exponents such as `-(k + 1) + k`
+        let ratio = self.pool.expand(ratio);
+        (!self.depends(ratio)).then_some(ratio)
+    }
+
+    /// Sum a geometric piece with the given ratio, which must be less than one in magnitude if
+    /// there are infinitely many terms
+    fn geometric(&mut self, piece: AtomId, ratio: AtomId) -> Option<AtomId> {
+        let first = self.at(piece, self.lower);
+        match self.count() {
+            // a (r^n - 1) / (r - 1)
+            Some(count) => {
+                let power = self.pool.power(ratio, count);
+                let minus_one = self.pool.integer(-1);
+                let numerator = self.pool.sum([power, minus_one]);
+                let numerator = self.pool.product([first, numerator]);
+                let denominator = self.pool.sum([ratio, minus_one]);
+                Some(self.pool.fraction(numerator, denominator))
+            }
+            // a / (1 - r) for |r| < 1
+            None => {
+                let magnitude = self.pool.eval_complex(ratio, self.symbols, &Env::new());
+                if !magnitude.is_ok_and(|r| r.norm() < 1.) {
+                    return None;
+                }
+                let one = self.pool.integer(1);
+                let minus_ratio = self.pool.neg(ratio);
+                let denominator = self.pool.sum([one, minus_ratio]);
+                Some(self.pool.fraction(first, denominator))
+            }
+        }
+    }
+
+    /// Sum `k^power` over the range by Faulhaber's formula, as `S(upper + 1) - S(lower)` where
+    /// `S(n)` is the sum for `k` from `0` to `n - 1`
+    fn power_sum(&mut self, power: u32) -> Option<AtomId> {
+        let upper = self.upper?;
+        let end = self.offset(upper, 1);
+        let end = faulhaber(self.pool, power, end);
+        let start = faulhaber(self.pool, power, self.lower);
+        let start = self.pool.neg(start);
+        Some(self.pool.sum([end, start]))
+    }
+
+    /// Combine pairs `(base, weight)` whose bases are shifted copies `g(k + m)` of one another,
+    /// as the sum of `weight·base` or the product of `base^weight`.
+    ///
+    /// Each group telescopes if its weights cancel, leaving the values of `g` at finitely many
+    /// points past each end of the range. An infinite range instead ends in the limit of the
+    /// values past its end, which must be finite, and nonzero for a product
+    fn telescope(&mut self, op: BigOperator, items: Vec<(AtomId, AtomId)>) -> Option<AtomId> {
+        let mut values = Vec::new();
+        for (g, members) in self.group(items) {
+            let weights = members.iter().map(|(_, weight)| *weight);
+            let total = self.pool.sum(weights.collect::<Vec<_>>());
+            let total = self.pool.simplify(total);
+            if !self.pool.get(total).is_zero() {
+                return None;
+            }
+
+            // Over an infinite range the terms at the end only converge together, as `(k + 1)/k`
+            // does for `1 - 1/k^2`, so they are combined at a symbolic end before the limit
+            let before = self.offset(self.lower, -1);
+            let last = self.upper.unwrap_or_else(|| self.k());
+            let (mut starts, mut ends) = (Vec::new(), Vec::new());
+            for (m, weight) in members {
+                if m == 0 {
+                    continue;
+                }
+                let start = self.boundary(op, g, before, m);
+                starts.push(self.weighted(op, start, weight));
+                let end = self.boundary(op, g, last, m);
+                ends.push(self.weighted(op, end, weight));
+            }
+            let start = self.combine(op, starts);
+            let mut end = self.combine(op, ends);
+            if self.upper.is_none() {
+                end = self.limit(op, end)?;
+            }
+            values.push(match op {
+                BigOperator::Sum => {
+                    let start = self.pool.neg(start);
+                    self.pool.sum([end, start])
+                }
+                BigOperator::Product => self.pool.fraction(end, start),
+            });
+        }
+
+        Some(self.combine(op, values))
+    }
+
+    /// Scale `value` by `weight` in a sum, or raise it to `weight` in a product
+    fn weighted(&mut self, op: BigOperator, value: AtomId, weight: AtomId) -> AtomId {
+        match op {
+            BigOperator::Sum => self.pool.product([weight, value]),
+            BigOperator::Product => self.pool.power(value, weight),
+        }
+    }
+
+    /// Add up `values` for a sum, or multiply them for a product
+    fn combine(&mut self, op: BigOperator, values: Vec<AtomId>) -> AtomId {
+        match op {
+            BigOperator::Sum => self.pool.sum(values),
+            BigOperator::Product => self.pool.product(values),
+        }
+    }
+
+    /// Group pairs `(base, weight)` by the first base `g` that their base is a shift `g(k + m)`
+    /// of, keeping the shift `m` with each weight
+    fn group(&mut self, items: Vec<(AtomId, AtomId)>) -> Vec<(AtomId, Vec<(i64, AtomId)>)> {
+        let mut groups: Vec<(AtomId, Vec<(i64, AtomId)>)> = Vec::new();
+        'items: for (base, weight) in items {
+            for (g, members) in groups.iter_mut() {
+                for m in -MAX_SHIFT..=MAX_SHIFT {
+                    if self.shift(*g, m) == base {
+                        members.push((m, weight));
+                        continue 'items;
+                    }
+                }
+            }
+            groups.push((base, vec![(0, weight)]));
+        }
+        groups
+    }
+
+    /// Combine the values of `g` between `n` and `n + m`, which is the sum or product over the
+    /// `m` integers after `n` for positive `m`, and the negated sum or reciprocal product over
+    /// the `-m` integers up to `n` for negative `m`
+    fn boundary(&mut self, op: BigOperator, g: AtomId, n: AtomId, m: i64) -> AtomId {
+        let points = match m > 0 {
+            true => (1..=m).collect::<Vec<_>>(),
+            false => (m + 1..=0).collect(),
+        };
+        let mut values = Vec::new();
+        for j in points {
+            let point = self.offset(n, j);
+            values.push(self.at(g, point));
+        }
+
+        let combined = self.combine(op, values);
+        match (m > 0, op) {
+            (true, _) => combined,
+            (false, BigOperator::Sum) => self.pool.neg(combined),
+            (false, BigOperator::Product) => {
+                let one = self.pool.integer(1);
+                self.pool.fraction(one, combined)
+            }
+        }
+    }
+
+    /// Get the limit of `g` as the index grows without bound, if it is finite, and nonzero for
+    /// a product
+    fn limit(&mut self, op: BigOperator, g: AtomId) -> Option<AtomId> {
+        let g = self.pool.simplify(g);
+        let infinity = self.symbols.intern("∞");
+        let point = self.pool.symbol(infinity);
+        let limit = self
+            .pool
+            .limit(self.symbols, g, self.index, point, None)
+            .ok()?;
+        let zero = self.pool.get(limit).is_zero();
+        match self.pool.depends_on(limit, infinity) || (zero && op == BigOperator::Product) {
+            true => None,
+            false => Some(limit),
+        }
+    }
+
+    /// Write out every term of a sum or product whose bounds are integers, if there are at most
+    /// [MAX_TERMS] terms
+    fn write_out(&mut self, op: BigOperator, term: AtomId) -> Option<AtomId> {
+        let (Atom::Integer(a), Atom::Integer(b)) =
+            (self.pool.get(self.lower), self.pool.get(self.upper?))
+        else {
+            return None;
+        };
+        let (a, b) = (a.to_i64()?, b.to_i64()?);
+        if b - a >= MAX_TERMS {
+            return None;
+        }
+
+        let mut terms = Vec::new();
+        for k in a..=b {
+            let value = self.pool.integer(k);
+            terms.push(self.at(term, value));
+        }
+        Some(self.combine(op, terms))
+    }
+}
+
+/// Split a polynomial into its leading coefficient and linear factors `x - r` with their
+/// multiplicities, or `None` if it has a factor that is irreducible over the rationals
+fn linear_factors(polynomial: &UniPoly) -> Option<(Rational, Vec<(Rational, u32)>)> {
+    let mut rest = polynomial.clone();
+    let mut factors = Vec::new();
+    for root in polynomial.rational_roots() {
+        let factor = UniPoly::linear(&root);
+        let mut multiplicity = 0;
+        loop {
+            let (quotient, remainder) = rest.div_rem(&factor);
+            if !remainder.is_zero() {
+                break;
+            }
+            rest = quotient;
+            multiplicity += 1;
+        }
+        factors.push((root, multiplicity));
+    }
+    (rest.degree() == 0).then(|| (rest.coefficient(0), factors))
+}
+
+/// Get the sum of `k^power` for `k` from `0` to `n - 1` as a polynomial in `n` by Faulhaber's
+/// formula `1/(p + 1) Σ_j C(p + 1, j) B_j n^(p + 1 - j)`
+fn faulhaber(pool: &mut ExprPool, power: u32, n: AtomId) -> AtomId {
+    let p = power as usize;
+    let bernoulli = bernoulli(p);
+    let binomials = binomials(p + 1);
+    let scale = Rational::new(1, p as i64 + 1).expect("Denominator is positive");
+
+    let mut terms = Vec::new();
+    for (j, (b, c)) in bernoulli.iter().zip(binomials).enumerate() {
+        let c = &(&Rational::from(c) * b) * &scale;
+        if c.is_zero() {
+            continue;
+        }
+        let (c, exponent) = (pool.number(c), pool.integer(p + 1 - j));
+        let power = pool.power(n, exponent);
+        terms.push(pool.product([c, power]));
+    }
+    pool.sum(terms)
+}
+
+/// Get the Bernoulli numbers `B_0` to `B_n`, with `B_1 = -1/2`
+fn bernoulli(n: usize) -> Vec<Rational> {
+    let mut numbers: Vec<Rational> = Vec::with_capacity(n + 1);
+    numbers.push(Rational::integer(1));
+    // Σ_{j ≤ m} C(m + 1, j) B_j = 0 for every m ≥ 1
+    for m in 1..=n {
+        let sum = binomials(m + 1)
+            .into_iter()
+            .zip(&numbers)
+            .fold(Rational::integer(0), |sum, (c, b)| {
+                &sum + &(&Rational::from(c) * b)
+            });
+        let b = sum
+            .checked_div(&Rational::integer(m as i64 + 1))
+            .expect("Divisor is positive");
+        numbers.push(-&b);
+    }
+    numbers
+}
+
+/// Get the binomial coefficients `C(n, 0)` to `C(n, n)`
+fn binomials(n: usize) -> Vec<BigInt> {
+    let mut row = vec![BigInt::from(1)];
+    for j in 0..n {
+        let next = &row[j] * BigInt::from(n - j) / BigInt::from(j + 1);
+        row.push(next);
+    }
+    row
+}
@@ -0,0 +1,278 @@
+use num_traits::ToPrimitive;
+
+use crate::atom::{Atom, AtomId, BigOperator, Dimension, ExprPool, Function, Rational, Unit};
+
+/// Error produced when an expression is not dimensionally consistent, with the ID of the
+/// sub-expression that caused it
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("{kind}")]
+pub struct UnitError {
+    pub kind: UnitErrorKind,
+    pub atom: AtomId,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum UnitErrorKind {
+    #[error("Cannot combine a quantity in {found} with one in {expected}")]
+    Mismatch {
+        expected: Dimension,
+        found: Dimension,
+    },
+    #[error("Argument of '{func}' must be dimensionless, but is in {found}")]
+    NotDimensionless {
+        func: &'static str,
+        found: Dimension,
+    },
+    #[error("Exponent must be dimensionless, but is in {0}")]
+    DimensionedExponent(Dimension),
+    #[error("Cannot raise a quantity in {0} to this power")]
+    Power(Dimension),
+    #[error("Cannot convert a quantity in {from} to {to}")]
+    Incompatible { from: Dimension, to: Dimension },
+    #[error("Target of a conversion must be a product of units")]
+    NotUnit,
+}
+
+impl ExprPool {
+    /// Check that the expression rooted at `id` is dimensionally consistent and get its
+    /// dimension.
+    ///
+    /// Symbols may stand for quantities of any dimension, so the dimension is `None` if it depends
+    /// on a symbol, and terms whose dimension is unknown are assumed to match the others.
+    /// Equations and relations have no dimension, but both sides are checked against each other
+    pub fn dimension(&self, id: AtomId) -> Result<Option<Dimension>, UnitError> {
+        let error = |kind| UnitError { kind, atom: id };
+
+        Ok(match self.get(id) {
+            Atom::Integer(_) | Atom::Rational(_) | Atom::Float(_) | Atom::Complex { .. } => {
+                Some(Dimension::NONE)
+            }
+            Atom::Unit(unit) => Some(unit.dimension()),
+            Atom::Symbol(_) => None,
+            Atom::Sum(terms) => self.common_dimension(terms)?,
+            Atom::Equals { lhs, rhs } | Atom::Relation { lhs, rhs, .. } => {
+                self.common_dimension(&[*lhs, *rhs])?;
+                None
+            }
+            Atom::Product(factors) => {
+                let mut product = Some(Dimension::NONE);
+                for factor in factors.iter() {
+                    product = match (product, self.dimension(*factor)?) {
+                        (Some(a), Some(b)) => Some(a.mul(&b)),
+                        _ => None,
+                    };
+                }
+                product
+            }
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => match (self.dimension(*numerator)?, self.dimension(*denominator)?) {
+                (Some(a), Some(b)) => Some(a.div(&b)),
+                _ => None,
+            },
+            Atom::Neg(inner) | Atom::Order(inner) | Atom::Limit { inner, .. } => {
+                self.dimension(*inner)?
+            }
+            Atom::Power { base, exponent } => {
+                if let Some(found) = self.dimension(*exponent)?.filter(|d| !d.is_none()) {
+                    return Err(UnitError {
+                        kind: UnitErrorKind::DimensionedExponent(found),
+                        atom: *exponent,
+                    });
+                }
+                match self.dimension(*base)? {
+                    Some(base) if !base.is_none() => {
+                        let power = self.exact_value(*exponent).and_then(|e| {
+                            base.pow(e.numerator().to_i8()?, e.denominator().to_i8()?)
+                        });
+                        Some(power.ok_or(error(UnitErrorKind::Power(base)))?)
+                    }
+                    base => base,
+                }
+            }
+            Atom::Apply { func, args } => {
+                let mut dimensions = Vec::with_capacity(args.len());
+                for arg in args.iter() {
+                    dimensions.push(self.dimension(*arg)?);
+                }
+                match (func, &*dimensions) {
+                    (Function::User(_), _) => None,
+                    (Function::Sqrt, [Some(found)]) => {
+                        Some(found.pow(1, 2).ok_or(error(UnitErrorKind::Power(*found)))?)
+                    }
+                    (Function::Abs | Function::Re | Function::Im | Function::Conj, [inner]) => {
+                        *inner
+                    }
+                    (_, [Some(found)]) if !found.is_none() => {
+                        return Err(error(UnitErrorKind::NotDimensionless {
+                            func: func.builtin_name().expect("Function is builtin"),
+                            found: *found,
+                        }))
+                    }
+                    (Function::Sqrt, _) => None,
+                    _ => Some(Dimension::NONE),
+                }
+            }
+            Atom::Integral {
+                upper,
+                lower,
+                integrand,
+                ..
+            } => {
+                let bounds = [*lower, *upper].into_iter().flatten().collect::<Vec<_>>();
+                self.common_dimension(&bounds)?;
+                self.dimension(*integrand)?;
+                None
+            }
+            Atom::BigOperator {
+                op,
+                lower,
+                upper,
+                term,
+                ..
+            } => {
+                self.common_dimension(&[*lower, *upper])?;
+                match op {
+                    BigOperator::Sum => self.dimension(*term)?,
+                    BigOperator::Product => self.dimension(*term)?.filter(|d| d.is_none()),
+                }
+            }
+            Atom::Derivative { inner, .. } => {
+                self.dimension(*inner)?;
+                None
+            }
+            Atom::Matrix { entries, .. } => {
+                let mut common = None;
+                for entry in entries.iter() {
+                    let dimension = self.dimension(*entry)?;
+                    if dimension.is_none() || (common.is_some() && common != dimension) {
+                        return Ok(None);
+                    }
+                    common = dimension;
+                }
+                common
+            }
+        })
+    }
+
+    /// Check that every known dimension among `items` is the same and get it, reporting the first
+    /// item whose dimension differs from the ones before it
+    fn common_dimension(&self, items: &[AtomId]) -> Result<Option<Dimension>, UnitError> {
+        let mut common = None;
+        for item in items {
+            let Some(found) = self.dimension(*item)? else {
+                continue;
+            };
+            match common {
+                None => common = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(UnitError {
+                        kind: UnitErrorKind::Mismatch { expected, found },
+                        atom: *item,
+                    })
+                }
+                Some(_) => (),
+            }
+        }
+        Ok(common)
+    }
+
+    /// Get the value of an exact numeric literal, which may be negated or written as a fraction
+    fn exact_value(&self, id: AtomId) -> Option<Rational> {
+        match self.get(id) {
+            Atom::Neg(inner) => Some(-&self.exact_value(*inner)?),
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => self
+                .exact_value(*numerator)?
+                .checked_div(&self.exact_value(*denominator)?),
+            atom => atom.to_rational(),
+        }
+    }
+
+    /// Convert a quantity into the given units, so that `60 km/h` converted to `m/s` is
+    /// `50/3 m/s`.
+    ///
+    /// `target` must be built from units and numbers by multiplication, division and numeric
+    /// powers, and have the same dimension as `id` unless that depends on a symbol. The magnitude
+    /// in front of the target units is simplified
+    pub fn convert(&mut self, id: AtomId, target: AtomId) -> Result<AtomId, UnitError> {
+        if !self.is_unit_product(target) {
+            return Err(UnitError {
+                kind: UnitErrorKind::NotUnit,
+                atom: target,
+            });
+        }
+        let to = self
+            .dimension(target)?
+            .expect("Units have a known dimension");
+        if let Some(from) = self.dimension(id)?.filter(|from| *from != to) {
+            return Err(UnitError {
+                kind: UnitErrorKind::Incompatible { from, to },
+                atom: id,
+            });
+        }
+
+        let magnitude = self.to_coherent(id);
+        let scale = self.to_coherent(target);
+        let ratio = self.fraction(magnitude, scale);
+        let ratio = self.simplify(ratio);
+
+        // Write the magnitude into the numerator of the target, as in `50/3 m/s`
+        let (numerator, denominator) = match self.get(target) {
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => (*numerator, Some(*denominator)),
+            _ => (target, None),
+        };
+        let mut factors = match self.get(ratio) {
+            Atom::Product(factors) => factors.to_vec(),
+            _ => vec![ratio],
+        };
+        factors.push(numerator);
+        let quantity = self.product(factors);
+        Ok(match denominator {
+            Some(denominator) => self.fraction(quantity, denominator),
+            None => quantity,
+        })
+    }
+
+    /// Check if an expression is built from units and numbers by multiplication, division and
+    /// numeric powers
+    fn is_unit_product(&self, id: AtomId) -> bool {
+        match self.get(id) {
+            Atom::Unit(_) => true,
+            Atom::Product(factors) => factors.iter().all(|f| self.is_unit_product(*f)),
+            Atom::Fraction {
+                numerator,
+                denominator,
+            } => self.is_unit_product(*numerator) && self.is_unit_product(*denominator),
+            Atom::Power { base, exponent } => {
+                self.is_unit_product(*base) && self.exact_value(*exponent).is_some()
+            }
+            atom => atom.is_number(),
+        }
+    }
+
+    /// Replace every unit in the expression rooted at `id` by its size in coherent SI units, so
+    /// that a quantity becomes its magnitude in SI base units
+    pub fn to_coherent(&mut self, id: AtomId) -> AtomId {
+        let mut units = self
+            .descendants(id)
+            .filter_map(|d| match self.get(d) {
+                Atom::Unit(unit) => Some((d, *unit)),
+                _ => None,
+            })
+            .collect::<Vec<(AtomId, Unit)>>();
+        units.sort();
+        units.dedup();
+
+        units.into_iter().fold(id, |expr, (from, unit)| {
+            let scale = self.number(unit.scale());
+            self.substitute(expr, from, scale)
+        })
+    }
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::Session;
+use tachys_sym::assume::{Assumption, Assumptions, Interval};
+
+/// Parse `src` and find the facts the assumption system can decide about it
+fn facts(session: &mut Session, src: &str, assumptions: &Assumptions) -> Facts {
+    let id = session.parse(src);
+    let pool = &session.pool;
+    Facts {
+        interval: pool.interval(id, assumptions),
+        positive: pool.is_positive(id, assumptions),
+        nonzero: pool.is_nonzero(id, assumptions),
+        integer: pool.is_integer(id, assumptions),
+    }
+}
+
+#[derive(Debug)]
+struct Facts {
+    interval: Option<Interval>,
+    positive: Option<bool>,
+    nonzero: Option<bool>,
+    integer: Option<bool>,
+}
+
+fn facts_of(src: &str) -> Facts {
+    facts(&mut Session::new(), src, &Assumptions::new())
+}
+
+#[test]
+fn rounding_error_does_not_decide_signs() {
+    for src in ["2^53 + 1 - 2^53", "sqrt(2)^2 - 2", "0.1 + 0.2 - 0.3"] {
+        let facts = facts_of(src);
+        assert_ne!(facts.positive, Some(true), "{src}: {facts:?}");
+        assert_ne!(facts.positive, Some(false), "{src}: {facts:?}");
+        assert_eq!(facts.nonzero, None, "{src}: {facts:?}");
+    }
+}
+
+#[test]
+fn overflow_does_not_decide_signs() {
+    let facts = facts_of("exp(1000) - exp(1000)");
+    assert_ne!(facts.positive, Some(true), "{facts:?}");
+    assert_ne!(facts.nonzero, Some(true), "{facts:?}");
+    if let Some(interval) = facts.interval {
+        assert!(
+            !interval.lower.is_nan() && !interval.upper.is_nan(),
+            "{facts:?}"
+        );
+    }
+
+    let facts = facts_of("10^400");
+    let interval = facts.interval.unwrap();
+    assert!(!interval.is_empty(), "{interval:?}");
+    assert_eq!(interval.lower, f64::MAX);
+    assert_eq!(facts.positive, Some(true));
+}
+
+#[test]
+fn rounding_error_does_not_decide_integrality() {
+    assert_eq!(facts_of("10^20 + 1/2").integer, None);
+    assert_eq!(facts_of("3/2").integer, Some(false));
+    assert_eq!(facts_of("4").integer, Some(true));
+
+    let mut session = Session::new();
+    let mut assumptions = Assumptions::new();
+    assumptions
+        .assume(session.symbol("n"), Assumption::Integer)
+        .unwrap();
+    assert_eq!(
+        facts(&mut session, "sqrt(2)", &assumptions).integer,
+        Some(false)
+    );
+}
+
+#[test]
+fn exact_values_are_still_decided() {
+    for src in ["sqrt(4) - 2", "exp(0) - 1", "2^10 - 1024"] {
+        let facts = facts_of(src);
+        assert_eq!(facts.nonzero, Some(false), "{src}: {facts:?}");
+        let interval = facts.interval.unwrap();
+        assert_eq!((interval.lower, interval.upper), (0., 0.), "{src}");
+    }
+    assert_eq!(facts_of("sqrt(2) - 1").positive, Some(true));
+    assert_eq!(facts_of("0.1 - 0.3").positive, Some(false));
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::Session;
+use tachys_sym::{
+    atom::{Float, Function},
+    Atom, ExprPool, Relation,
+};
+
+#[test]
+fn children_in_field_order() {
+    let mut session = Session::new();
+    let id = session.parse("∫_a^b x y dx");
+    let Atom::Integral {
+        upper,
+        lower,
+        integrand,
+        ..
+    } = *session.pool.get(id)
+    else {
+        panic!("expected an integral, got {}", session.display(id));
+    };
+    let children = session.pool.get(id).children().collect::<Vec<_>>();
+    assert_eq!(children, [upper.unwrap(), lower.unwrap(), integrand]);
+    assert_eq!(session.pool.get(integrand).children().count(), 2);
+
+    let x = session.parse("x");
+    assert!(session.pool.get(x).is_leaf());
+}
+
+#[test]
+fn map_children_keeps_structure() {
+    let mut session = Session::new();
+    let id = session.parse("x < sin(y)");
+    let two = session.pool.integer(2);
+    let mapped = session.pool.get(id).map_children(|_| two);
+    assert_eq!(
+        mapped,
+        Atom::Relation {
+            op: Relation::Less,
+            lhs: two,
+            rhs: two
+        }
+    );
+}
+
+#[test]
+fn floats_compare_by_bits() {
+    assert_eq!(Float(f64::NAN), Float(f64::NAN));
+    assert_ne!(Float(0.), Float(-0.));
+
+    let mut pool = ExprPool::new();
+    assert_eq!(pool.float(f64::NAN), pool.float(f64::NAN));
+    let (zero, negative_zero) = (pool.float(0.), pool.float(-0.));
+    assert_ne!(zero, negative_zero);
+    assert!(pool.get(zero).is_zero() && pool.get(negative_zero).is_zero());
+}
+
+#[test]
+fn numeric_predicates() {
+    let mut pool = ExprPool::new();
+    let minus_one = pool.integer(-1);
+    let half = pool.rational(1, 2).unwrap();
+    let zero = pool.rational(0, 5).unwrap();
+    assert!(pool.get(minus_one).is_minus_one());
+    assert!(!pool.get(half).is_minus_one());
+    assert!(pool.get(zero).is_zero());
+    assert!(pool.get(half).is_number());
+    assert_eq!(
+        pool.get(half).to_rational().map(|r| r.to_string()),
+        Some("1/2".to_owned())
+    );
+}
+
+#[test]
+fn functions_and_relations() {
+    for func in Function::BUILTIN {
+        let name = func.builtin_name().unwrap();
+        assert_eq!(Function::builtin(name), Some(func));
+        assert_eq!(func.arity(), Some(1));
+    }
+    assert_eq!(Function::builtin("f"), None);
+
+    for op in [
+        Relation::NotEqual,
+        Relation::Less,
+        Relation::LessEq,
+        Relation::Greater,
+        Relation::GreaterEq,
+    ] {
+        assert_eq!(op.flip().flip(), op);
+    }
+    assert_eq!(Relation::Less.flip(), Relation::Greater);
+    assert_eq!(Relation::LessEq.ascii(), "<=");
+    assert_eq!(Relation::LessEq.unicode(), "≤");
+}
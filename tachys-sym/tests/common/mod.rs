@@ -0,0 +1,74 @@
+//! Helpers shared by the integration tests. Each test binary uses a different subset of them
+
+#![allow(dead_code)]
+
+use tachys_sym::{
+    eval::Env,
+    latex::parse_latex,
+    parse::{parse, Parser},
+    AtomId, ExprPool, Symbol, SymbolStore,
+};
+
+/// Expression pool and symbol store that a test parses into and prints from
+#[derive(Default)]
+pub struct Session {
+    pub pool: ExprPool,
+    pub symbols: SymbolStore,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `src` as infix notation, panicking if it is invalid
+    pub fn parse(&mut self, src: &str) -> AtomId {
+        parse(&mut self.pool, &mut self.symbols, src).unwrap_or_else(|err| panic!("{src}: {err:?}"))
+    }
+
+    /// Parse `src` as infix notation with units enabled, panicking if it is invalid
+    pub fn parse_units(&mut self, src: &str) -> AtomId {
+        let mut parser = Parser::new(&mut self.pool, &mut self.symbols);
+        parser.enable_units();
+        parser
+            .parse(src)
+            .unwrap_or_else(|err| panic!("{src}: {err:?}"))
+    }
+
+    /// Parse `src` as LaTeX, panicking if it is invalid
+    pub fn latex(&mut self, src: &str) -> AtomId {
+        parse_latex(&mut self.pool, &mut self.symbols, src)
+            .unwrap_or_else(|err| panic!("{src}: {err:?}"))
+    }
+
+    pub fn symbol(&mut self, name: &str) -> Symbol {
+        self.symbols.intern(name)
+    }
+
+    /// Print `id` in plain notation
+    pub fn display(&self, id: AtomId) -> String {
+        self.pool.display(id, &self.symbols).to_string()
+    }
+
+    /// Evaluate `id` with no symbols bound, panicking if it has no real value
+    pub fn eval(&self, id: AtomId) -> f64 {
+        self.pool
+            .eval(id, &self.symbols, &Env::new())
+            .unwrap_or_else(|err| panic!("{}: {err}", self.display(id)))
+    }
+}
+
+/// Parse `src` and print it back
+pub fn print(src: &str) -> String {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    session.display(id)
+}
+
+/// Parse `src`, simplify it and print the result
+pub fn simplified(src: &str) -> String {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let id = session.pool.simplify(id);
+    session.display(id)
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use common::Session;
+
+/// Differentiate `src` with respect to `x` and display the simplified result
+fn derivative(src: &str) -> String {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let x = session.symbol("x");
+    let derivative = session.pool.diff(id, x);
+    session.display(derivative)
+}
+
+#[test]
+fn powers_of_e_are_exponentials() {
+    assert_eq!(derivative("e^x"), "e^x");
+    assert_eq!(derivative("e^(2x)"), "2e^(2x)");
+    assert_eq!(derivative("e^sin(x)"), "e^sin(x)*cos(x)");
+    assert_eq!(derivative("x e^x"), "e^x*x + e^x");
+    assert_eq!(derivative("exp(x)"), "exp(x)");
+    assert_eq!(derivative("2^x"), "2^x*ln(2)");
+}
+
+#[test]
+fn product_quotient_and_chain_rules() {
+    assert_eq!(derivative("sin(x)^2"), "2sin(x)*cos(x)");
+    assert_eq!(derivative("x^x"), "x^x*(ln(x) + 1)");
+    assert_eq!(derivative("ln(x)"), "1/x");
+    assert_eq!(derivative("3x^2 + y"), "6x");
+}
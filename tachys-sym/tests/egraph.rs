@@ -0,0 +1,169 @@
+mod common;
+
+use common::Session;
+use tachys_sym::{
+    egraph::{identities, Depth, EGraph, Limits, NodeCount, StopReason},
+    rewrite::{Constraint, Rule},
+    Atom, Function,
+};
+
+/// Find the smallest expression equal to `src` under the default identities
+fn saturated(src: &str) -> String {
+    let mut session = Session::new();
+    let rules = identities(&mut session.pool, &mut session.symbols);
+    let id = session.parse(src);
+    let best = session
+        .pool
+        .saturate(id, &rules, &Limits::default(), &mut NodeCount);
+    session.display(best)
+}
+
+/// Check if `a` and `b` are shown to be equal by saturating an e-graph containing both with the
+/// default identities
+fn proves(a: &str, b: &str) -> bool {
+    let mut session = Session::new();
+    let rules = identities(&mut session.pool, &mut session.symbols);
+    let a = session.parse(a);
+    let b = session.parse(b);
+    let mut graph = EGraph::new(&mut session.pool);
+    let (a, b) = (graph.add(a), graph.add(b));
+    graph.saturate(&rules, &Limits::default());
+    graph.equivalent(a, b)
+}
+
+#[test]
+fn identities_hold() {
+    assert_eq!(saturated("sin(x)^2 + cos(x)^2"), "1");
+    assert_eq!(saturated("ln(2) + ln(3)"), "ln(2*3)");
+    assert_eq!(saturated("exp(ln(2))"), "2");
+    assert_eq!(saturated("ln(exp(3))"), "3");
+    assert!(proves("(2 + 1)/(2 + 1)", "1"));
+}
+
+#[test]
+fn identities_respect_their_domain() {
+    assert!(!proves("0/0", "1"));
+    assert!(!proves("(x - x)/(x - x)", "1"));
+    assert!(!proves("x/x", "1"));
+    assert!(!proves("ln(exp(2pi*i))", "2pi*i"));
+    assert!(!proves("exp(ln(x))", "x"));
+    assert!(!proves("ln((-1)(-1))", "ln(-1) + ln(-1)"));
+    assert!(!proves("ln((-1)^2)", "2ln(-1)"));
+    assert!(!proves("ln(x y)", "ln(x) + ln(y)"));
+}
+
+#[test]
+fn constant_constraint_accepts_imaginary_unit() {
+    let mut session = Session::new();
+    let rules = [Rule::parse(
+        &mut session.pool,
+        &mut session.symbols,
+        "sin(?c: constant)",
+        "0",
+    )
+    .unwrap()];
+
+    for (src, expected) in [("sin(2i)", "0"), ("sin(i)", "0"), ("sin(2x)", "sin(2x)")] {
+        let id = session.parse(src);
+        let Atom::Apply { args, .. } = session.pool.get(id) else {
+            panic!("{src} is not a function application");
+        };
+        let arg = args[0];
+        assert_eq!(
+            Constraint::Constant.check(&session.pool, arg),
+            expected == "0",
+            "{src}"
+        );
+
+        let best = session
+            .pool
+            .saturate(id, &rules, &Limits::default(), &mut NodeCount);
+        assert_eq!(session.display(best), expected, "{src}");
+    }
+}
+
+#[test]
+fn union_merges_congruent_classes_on_rebuild() {
+    let mut session = Session::new();
+    let ids = ["a", "b", "sin(a) + 1", "sin(b) + 1"].map(|src| session.parse(src));
+    let mut graph = EGraph::new(&mut session.pool);
+    let [a, b, fa, fb] = ids.map(|id| graph.add(id));
+    assert_eq!((graph.node_count(), graph.class_count()), (7, 7));
+    assert!(!graph.equivalent(fa, fb));
+
+    assert!(graph.union(a, b));
+    assert!(!graph.union(b, a));
+    graph.rebuild();
+    assert!(graph.equivalent(fa, fb));
+    assert_eq!(graph.class_count(), 4);
+    assert_eq!(graph.lookup(ids[3]), Some(graph.find(fa)));
+}
+
+#[test]
+fn saturation_stops_at_its_limits() {
+    let mut session = Session::new();
+    let growing = [Rule::parse(
+        &mut session.pool,
+        &mut session.symbols,
+        "?x",
+        "(?x + 1) - 1",
+    )
+    .unwrap()];
+    let id = session.parse("y");
+
+    for (limits, reason) in [
+        (
+            Limits {
+                max_nodes: 50,
+                ..Limits::default()
+            },
+            StopReason::NodeLimit,
+        ),
+        (
+            Limits {
+                max_iterations: 2,
+                ..Limits::default()
+            },
+            StopReason::IterationLimit,
+        ),
+    ] {
+        let mut graph = EGraph::new(&mut session.pool);
+        graph.add(id);
+        assert_eq!(graph.saturate(&growing, &limits), reason);
+    }
+
+    let rules = identities(&mut session.pool, &mut session.symbols);
+    let mut graph = EGraph::new(&mut session.pool);
+    graph.add(id);
+    assert_eq!(
+        graph.saturate(&rules, &Limits::default()),
+        StopReason::Saturated
+    );
+}
+
+#[test]
+fn extraction_follows_the_cost_function() {
+    let mut session = Session::new();
+    let rules = identities(&mut session.pool, &mut session.symbols);
+    let limits = Limits::default();
+
+    let id = session.parse("tan(x)");
+    let best = session.pool.saturate(id, &rules, &limits, &mut NodeCount);
+    assert_eq!(session.display(best), "tan(x)");
+    let mut avoid_tan = |atom: &Atom, children: &[f64]| {
+        let cost = match atom {
+            Atom::Apply {
+                func: Function::Tan,
+                ..
+            } => 100.,
+            _ => 1.,
+        };
+        cost + children.iter().sum::<f64>()
+    };
+    let best = session.pool.saturate(id, &rules, &limits, &mut avoid_tan);
+    assert_eq!(session.display(best), "sin(x)/cos(x)");
+
+    let id = session.parse("sin(x)^2 + cos(x)^2 + ln(2) + ln(3)");
+    let best = session.pool.saturate(id, &rules, &limits, &mut Depth);
+    assert_eq!(session.display(best), "1 + ln(2) + ln(3)");
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::Session;
+use tachys_sym::eval::{Env, EvalError, EvalErrorKind};
+
+/// Evaluate `src` with `x` bound to 2, returning the error and the sub-expression it points at
+fn eval(src: &str) -> Result<f64, (EvalErrorKind, String)> {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let mut env = Env::new();
+    env.bind(session.symbol("x"), 2.);
+    session
+        .pool
+        .eval(id, &session.symbols, &env)
+        .map_err(|EvalError { kind, atom }| (kind, session.display(atom)))
+}
+
+#[test]
+fn bindings_and_constants() {
+    assert_eq!(eval("x^2 + 3x/(x - 1)"), Ok(10.));
+    assert_eq!(eval("2^-2"), Ok(0.25));
+    assert_eq!(eval("sin(pi/2)"), Ok(1.));
+    assert_eq!(eval("e^1"), Ok(std::f64::consts::E));
+    assert_eq!(eval("(-8)^(1/3)"), Ok(-2.));
+    assert_eq!(eval("(1 + i)(1 - i)"), Ok(2.));
+
+    let mut session = Session::new();
+    let id = session.parse("pi");
+    let pi = session.symbol("π");
+    let env = Env::from_iter([(pi, 3.)]);
+    assert_eq!(session.pool.eval(id, &session.symbols, &env), Ok(3.));
+}
+
+#[test]
+fn errors_point_at_their_cause() {
+    assert_eq!(
+        eval("y + 1"),
+        Err((EvalErrorKind::Unbound("y".to_owned()), "y".to_owned()))
+    );
+    assert_eq!(
+        eval("1 + 1/(x - 2)"),
+        Err((EvalErrorKind::DivisionByZero, "1/(x - 2)".to_owned()))
+    );
+    assert_eq!(
+        eval("ln(0)"),
+        Err((
+            EvalErrorKind::Domain {
+                func: "ln",
+                value: 0.
+            },
+            "ln(0)".to_owned()
+        ))
+    );
+    assert_eq!(
+        eval("(-8)^0.5").map_err(|e| e.0),
+        Err(EvalErrorKind::NegativeBase(-8.))
+    );
+    assert_eq!(
+        eval("x = 2").map_err(|e| e.0),
+        Err(EvalErrorKind::NotNumeric)
+    );
+}
+
+#[test]
+fn complex_values() {
+    let mut session = Session::new();
+    let id = session.parse("sqrt(-4) + e^(i*pi)");
+    assert_eq!(
+        session.pool.eval(id, &session.symbols, &Env::new()),
+        Err(EvalError {
+            kind: EvalErrorKind::Domain {
+                func: "sqrt",
+                value: -4.
+            },
+            atom: session.parse("sqrt(-4)")
+        })
+    );
+    let value = session
+        .pool
+        .eval_complex(id, &session.symbols, &Env::new())
+        .unwrap();
+    assert!((value.re + 1.).abs() < 1e-12 && (value.im - 2.).abs() < 1e-12);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::Session;
+use tachys_sym::{
+    assume::{Assumption, Assumptions},
+    Atom,
+};
+
+/// Evaluate the integrals in `src`, returning `None` if the outermost one is left in place
+fn definite(src: &str) -> Option<f64> {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let value = session.pool.eval_integrals(&mut session.symbols, id);
+    match session.pool.get(value) {
+        Atom::Integral { .. } => None,
+        _ => Some(session.eval(value)),
+    }
+}
+
+/// Find an antiderivative of `src` with respect to `x` under `assumptions`
+fn antiderivative(src: &str, assumptions: &[(&str, Assumption)]) -> Option<String> {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let x = session.symbol("x");
+    let mut assumed = Assumptions::new();
+    for (name, assumption) in assumptions {
+        assumed.assume(session.symbol(name), *assumption).unwrap();
+    }
+    let antiderivative = session.pool.integrate_assuming(id, x, &assumed)?;
+    Some(session.display(antiderivative))
+}
+
+/// Evaluate and simplify the integrals in `src`
+fn evaluated(src: &str) -> String {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let value = session.pool.eval_integrals(&mut session.symbols, id);
+    let value = session.pool.simplify(value);
+    session.display(value)
+}
+
+fn assert_close(src: &str, expected: f64) {
+    let value = definite(src).unwrap_or_else(|| panic!("{src} was not evaluated"));
+    assert!((value - expected).abs() < 1e-12, "{src} = {value}");
+}
+
+#[test]
+fn singular_integrands_are_left_unevaluated() {
+    for src in [
+        "∫_-1^1 1/x^2 dx",
+        "∫_-1^1 1/x dx",
+        "∫_0^2 1/(x - 1) dx",
+        "∫_0^1 x^(-2) dx",
+        "∫_0^1 1/(1 - x) dx",
+        "∫_0^2 tan(x) dx",
+        "∫_1^∞ 1/x dx",
+    ] {
+        assert_eq!(definite(src), None, "{src}");
+    }
+}
+
+#[test]
+fn infinite_and_improper_bounds() {
+    assert_close("∫_0^∞ e^-x dx", 1.0);
+    assert_close("∫_1^∞ 1/x^2 dx", 1.0);
+    assert_close("∫_-∞^∞ 1/(1 + x^2) dx", std::f64::consts::PI);
+    assert_close("∫_0^1 x^(-1/2) dx", 2.0);
+}
+
+#[test]
+fn regular_integrands_are_evaluated() {
+    assert_close("∫_0^1 x^2 dx", 1.0 / 3.0);
+    assert_close("∫_1^2 1/x dx", 2f64.ln());
+    assert_close("∫_-2^-1 1/x dx", -(2f64.ln()));
+    assert_close("∫_2^1 x dx", -1.5);
+    assert_close("∫_0^1 tan(x) dx", -(1f64.cos().ln()));
+    assert_close("∫_0^pi sin(x) dx", 2.0);
+}
+
+#[test]
+fn logarithm_of_complex_argument_has_no_absolute_value() {
+    let mut session = Session::new();
+    let id = session.parse("1/(x - i)");
+    let x = session.symbol("x");
+    let antiderivative = session.pool.integrate(id, x).unwrap();
+    let printed = session.display(antiderivative);
+    assert!(!printed.contains("abs"), "{printed}");
+
+    let id = session.parse("1/(x - 1)");
+    let antiderivative = session.pool.integrate(id, x).unwrap();
+    assert_eq!(session.display(antiderivative), "ln(abs(x - 1))");
+}
+
+#[test]
+fn powers_of_e_integrate_without_logarithms() {
+    assert_eq!(antiderivative("e^x", &[]).unwrap(), "e^x");
+    assert_eq!(antiderivative("e^(2x)", &[]).unwrap(), "e^(2x)/2");
+    assert_eq!(antiderivative("x e^x", &[]).unwrap(), "e^x*x - e^x");
+    assert_eq!(antiderivative("2^x", &[]).unwrap(), "2^x/ln(2)");
+    assert_eq!(evaluated("∫_0^∞ e^(-x) dx"), "1");
+    assert_eq!(evaluated("∫_0^1 e^x dx"), "e - 1");
+    assert_eq!(evaluated("∫_0^pi sin(x) dx"), "2");
+}
+
+#[test]
+fn power_rule_needs_exponent_other_than_minus_one() {
+    assert_eq!(antiderivative("x^2", &[]).unwrap(), "x^3/3");
+    assert_eq!(antiderivative("x^-1", &[]).unwrap(), "ln(abs(x))");
+    assert_eq!(antiderivative("x^n", &[]), None);
+    assert_eq!(antiderivative("(2x + 1)^n", &[]), None);
+    assert_eq!(
+        antiderivative("x^n", &[("n", Assumption::Positive)]).unwrap(),
+        "x^(n + 1)/(n + 1)"
+    );
+}
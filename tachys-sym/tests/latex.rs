@@ -0,0 +1,68 @@
+mod common;
+
+use common::Session;
+use tachys_sym::latex::LatexOptions;
+
+fn latex(src: &str) -> String {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    session.pool.latex(id, &session.symbols).to_string()
+}
+
+#[test]
+fn compound_bases_are_parenthesized() {
+    assert_eq!(latex("(a/b)^2"), r"\left(\frac{a}{b}\right)^{2}");
+    assert_eq!(latex("sqrt(x)^2"), r"\left(\sqrt{x}\right)^{2}");
+    assert_eq!(latex("(1/2)^x"), r"\left(\frac{1}{2}\right)^{x}");
+    assert_eq!(latex("(a + b)^2"), r"\left(a + b\right)^{2}");
+    assert_eq!(latex("(-2)^2"), r"\left(-2\right)^{2}");
+    assert_eq!(latex("x_1^2"), "x_{1}^{2}");
+    assert_eq!(latex("abs(x)^2"), r"\left|x\right|^{2}");
+}
+
+#[test]
+fn operators_and_structures() {
+    assert_eq!(latex("x^2 + 3x/(y - 1)"), r"x^{2} + \frac{3 x}{y - 1}");
+    assert_eq!(latex("2*3x"), r"2 \cdot 3 x");
+    assert_eq!(latex("a <= b"), r"a \leq b");
+    assert_eq!(latex("∫_0^1 x^2 dx"), r"\int_{0}^{1} x^{2} \,dx");
+    assert_eq!(
+        latex("[[1, x], [y, 2]]"),
+        r"\begin{pmatrix}1 & x \\ y & 2\end{pmatrix}"
+    );
+    assert_eq!(latex("1.5e20"), r"1.5 \times 10^{20}");
+
+    let mut session = Session::new();
+    let id = session.parse("x/2 * y");
+    let options = LatexOptions {
+        dfrac: true,
+        cdot: true,
+    };
+    assert_eq!(
+        session
+            .pool
+            .latex(id, &session.symbols)
+            .options(options)
+            .to_string(),
+        r"\dfrac{x}{2} \cdot y"
+    );
+}
+
+#[test]
+fn exported_latex_imports_as_the_same_expression() {
+    for src in [
+        "(a/b)^2",
+        "sqrt(x)^2",
+        "-(a + b)^-2",
+        "sin(x)^2 + cos(x)^2",
+        "∫_0^1 x^2 dx",
+        "2 - 3i",
+    ] {
+        let mut session = Session::new();
+        let id = session.parse(src);
+        let exported = session.pool.latex(id, &session.symbols).to_string();
+        let imported = session.latex(&exported);
+        let (id, imported) = (session.pool.simplify(id), session.pool.simplify(imported));
+        assert_eq!(id, imported, "{src} exported as {exported}");
+    }
+}
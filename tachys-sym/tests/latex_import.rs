@@ -0,0 +1,141 @@
+mod common;
+
+use common::Session;
+use tachys_sym::{latex::parse_latex, parse::ParseErrorKind, Atom, BigOperator, Direction};
+
+#[test]
+fn big_operators() {
+    let mut session = Session::new();
+
+    let id = session.latex(r"\sum_{i=1}^n i^2");
+    let Atom::BigOperator { op, index, .. } = *session.pool.get(id) else {
+        panic!("expected a sum, got {}", session.display(id));
+    };
+    assert_eq!(op, BigOperator::Sum);
+    assert_eq!(session.symbols.name(index), "i");
+
+    let id = session.latex(r"\prod_{k=1}^{4} k + 1");
+    let value = session.pool.eval_big_operators(&mut session.symbols, id);
+    assert_eq!(session.eval(value), 25.0);
+
+    let Session { pool, symbols } = &mut session;
+    assert!(parse_latex(pool, symbols, r"\sum_{1}^n k").is_err());
+    assert!(parse_latex(pool, symbols, r"\sum k").is_err());
+}
+
+#[test]
+fn limits() {
+    let mut session = Session::new();
+
+    for (src, expected) in [
+        (r"\lim_{x\to0} \frac{\sin x}{x}", None),
+        (r"\lim_{x \to 0^+} \ln x", Some(Direction::Above)),
+        (r"\lim_{x \rightarrow 1^{-}} x", Some(Direction::Below)),
+    ] {
+        let id = session.latex(src);
+        let Atom::Limit {
+            variable,
+            direction,
+            ..
+        } = *session.pool.get(id)
+        else {
+            panic!("{src}: expected a limit, got {}", session.display(id));
+        };
+        assert_eq!(session.symbols.name(variable), "x", "{src}");
+        assert_eq!(direction, expected, "{src}");
+    }
+
+    let id = session.latex(r"\lim_{x\to0} \frac{\sin x}{x}");
+    let value = session.pool.eval_limits(&mut session.symbols, id);
+    assert_eq!(session.eval(value), 1.0);
+}
+
+#[test]
+fn differential_is_last_factor() {
+    let mut session = Session::new();
+
+    let id = session.latex(r"\int_0^1 delta\, dx");
+    let Atom::Integral {
+        variable,
+        integrand,
+        ..
+    } = *session.pool.get(id)
+    else {
+        panic!("expected an integral, got {}", session.display(id));
+    };
+    assert_eq!(session.symbols.name(variable), "x");
+    assert!(matches!(session.pool.get(integrand), Atom::Product(factors) if factors.len() == 5));
+
+    let id = session.latex(r"\int x \mathrm{d}y");
+    assert!(matches!(
+        *session.pool.get(id),
+        Atom::Integral { variable, .. } if session.symbols.name(variable) == "y"
+    ));
+}
+
+#[test]
+fn nested_integrals() {
+    let mut session = Session::new();
+    let id = session.latex(r"\int_0^1 \int_0^2 xy\,dx\,dy");
+    let Atom::Integral {
+        variable,
+        integrand,
+        ..
+    } = *session.pool.get(id)
+    else {
+        panic!("expected an integral, got {}", session.display(id));
+    };
+    assert_eq!(session.symbols.name(variable), "y");
+    assert!(matches!(
+        *session.pool.get(integrand),
+        Atom::Integral { variable, .. } if session.symbols.name(variable) == "x"
+    ));
+    let value = session.pool.eval_integrals(&mut session.symbols, id);
+    assert!((session.eval(value) - 1.0).abs() < 1e-12);
+
+    let Session { pool, symbols } = &mut session;
+    let err = parse_latex(pool, symbols, r"\int \int x \,dx").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::ExpectedDifferential);
+}
+
+#[test]
+fn inverse_functions_and_logarithm_bases() {
+    let mut session = Session::new();
+    for (src, expected) in [
+        (r"\cos^{-1} x", "acos(x)"),
+        (r"\sin^{-1}(x)", "asin(x)"),
+        (r"\tan^{-1} x", "atan(x)"),
+        (r"\sin^2 x", "sin(x)^2"),
+        (r"\log_2 x", "ln(x)/ln(2)"),
+        (r"\log_{b}^2 x", "(ln(x)/ln(b))^2"),
+    ] {
+        let id = session.latex(src);
+        assert_eq!(session.display(id), expected, "{src}");
+    }
+
+    let id = session.latex(r"\sinh^{-1} \frac{3}{4}");
+    assert!((session.eval(id) - 0.75f64.asinh()).abs() < 1e-12);
+    let id = session.latex(r"\tanh^{-1} \frac{1}{2}");
+    assert!((session.eval(id) - 0.5f64.atanh()).abs() < 1e-12);
+}
+
+#[test]
+fn missing_operand_of_operator_macro() {
+    let mut session = Session::new();
+    for (src, found) in [
+        (r"\cdot x", r"'\cdot'"),
+        (r"2 \cdot", "end of input"),
+        (r"2 \cdot \times 3", r"'\times'"),
+    ] {
+        let Session { pool, symbols } = &mut session;
+        let err = parse_latex(pool, symbols, src).unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::Unexpected {
+                expected: "an expression",
+                found: found.to_owned()
+            },
+            "{src}"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::Session;
+use tachys_sym::{
+    limit::{LimitError, LimitErrorKind},
+    rewrite::{Constraint, Pattern},
+    Direction,
+};
+
+/// Find the limit of `src` as `x` approaches `point` and print it
+fn limit(src: &str, point: &str, direction: Option<Direction>) -> Result<String, LimitErrorKind> {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let point = session.parse(point);
+    let x = session.symbol("x");
+    let Session { pool, symbols } = &mut session;
+    pool.limit(symbols, id, x, point, direction)
+        .map(|value| session.display(value))
+        .map_err(|LimitError { kind, .. }| kind)
+}
+
+#[test]
+fn indeterminate_forms() {
+    for (src, point, expected) in [
+        ("sin(x)/x", "0", "1"),
+        ("(1 - cos(x))/x^2", "0", "1/2"),
+        ("(1 + 1/x)^x", "∞", "e"),
+        ("x^2 e^-x", "∞", "0"),
+        ("ln(x)/x", "∞", "0"),
+        ("sqrt(x^2 + x) - x", "∞", "1/2"),
+        ("(2x^2 + 1)/(3x^2 - x)", "-∞", "2/3"),
+        ("exp(x)", "-∞", "0"),
+        ("x^2 + 1", "2", "5"),
+    ] {
+        assert_eq!(limit(src, point, None).as_deref(), Ok(expected), "{src}");
+    }
+}
+
+#[test]
+fn one_sided_limits() {
+    assert_eq!(
+        limit("x ln(x)", "0", Some(Direction::Above)).as_deref(),
+        Ok("0")
+    );
+    assert_eq!(
+        limit("1/x", "0", Some(Direction::Above)).as_deref(),
+        Ok("∞")
+    );
+    assert_eq!(
+        limit("1/x", "0", Some(Direction::Below)).as_deref(),
+        Ok("-∞")
+    );
+    assert_eq!(limit("1/x", "0", None), Err(LimitErrorKind::Sides));
+    assert_eq!(limit("abs(x)/x", "0", None), Err(LimitErrorKind::Sides));
+}
+
+#[test]
+fn limits_that_do_not_exist() {
+    assert_eq!(limit("sin(x)", "∞", None), Err(LimitErrorKind::Oscillates));
+    assert_eq!(
+        limit("sin(1/x)", "0", None),
+        Err(LimitErrorKind::Oscillates)
+    );
+    assert_eq!(limit("x", "x", None), Err(LimitErrorKind::Point));
+}
+
+#[test]
+fn limits_match_patterns_with_the_same_variable_and_direction() {
+    let mut session = Session::new();
+    let root = session.latex(r"\lim_{x \to a} f");
+    let [f, a] = ["f", "a"].map(|name| session.symbol(name));
+    let pattern = Pattern::new(root)
+        .wildcard(f, Constraint::Any)
+        .wildcard(a, Constraint::Any);
+
+    let id = session.latex(r"\lim_{x \to 0} \sin x");
+    let bindings = session.pool.match_pattern(&pattern, id).unwrap();
+    assert_eq!(session.display(bindings[&f]), "sin(x)");
+    assert_eq!(session.display(bindings[&a]), "0");
+
+    for src in [r"\lim_{y \to 0} \sin y", r"\lim_{x \to 0^+} \sin x"] {
+        let id = session.latex(src);
+        assert!(session.pool.match_pattern(&pattern, id).is_none(), "{src}");
+    }
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::Session;
+
+const OPEN: &str = r#"<math xmlns="http://www.w3.org/1998/Math/MathML">"#;
+
+/// Render `src` as presentation and content MathML, without the enclosing `<math>` element
+fn mathml(src: &str) -> (String, String) {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let render = session.pool.mathml(id, &session.symbols);
+    let strip = |markup: String| {
+        markup
+            .strip_prefix(OPEN)
+            .and_then(|m| m.strip_suffix("</math>"))
+            .unwrap_or_else(|| panic!("{src}: {markup}"))
+            .to_owned()
+    };
+    (
+        strip(render.to_string()),
+        strip(render.content().to_string()),
+    )
+}
+
+#[test]
+fn presentation_markup() {
+    assert_eq!(
+        mathml("x^2 + 1").0,
+        "<mrow><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><mn>1</mn></mrow>"
+    );
+    assert_eq!(mathml("a/b").0, "<mfrac><mi>a</mi><mi>b</mi></mfrac>");
+    assert_eq!(
+        mathml("sin(x)").0,
+        "<mrow><mi>sin</mi><mo>&#x2061;</mo><mrow><mo>(</mo><mi>x</mi><mo>)</mo></mrow></mrow>"
+    );
+    assert_eq!(
+        mathml("3x").0,
+        "<mrow><mn>3</mn><mo>&#x2062;</mo><mi>x</mi></mrow>"
+    );
+    assert_eq!(mathml("x_1").0, "<msub><mi>x</mi><mn>1</mn></msub>");
+    assert_eq!(mathml("sqrt(x)").0, "<msqrt><mi>x</mi></msqrt>");
+    assert_eq!(
+        mathml("a < b").0,
+        "<mrow><mi>a</mi><mo>&lt;</mo><mi>b</mi></mrow>"
+    );
+}
+
+#[test]
+fn content_markup() {
+    assert_eq!(
+        mathml("x^2 + 1").1,
+        concat!(
+            r#"<apply><plus/><apply><power/><ci>x</ci><cn type="integer">2</cn></apply>"#,
+            r#"<cn type="integer">1</cn></apply>"#
+        )
+    );
+    assert_eq!(mathml("-x").1, "<apply><minus/><ci>x</ci></apply>");
+    assert_eq!(
+        mathml("a < b").1,
+        "<apply><lt/><ci>a</ci><ci>b</ci></apply>"
+    );
+    assert_eq!(mathml("sqrt(x)").1, "<apply><root/><ci>x</ci></apply>");
+    assert_eq!(
+        mathml("∫_0^1 x dx").1,
+        concat!(
+            "<apply><int/><bvar><ci>x</ci></bvar>",
+            r#"<lowlimit><cn type="integer">0</cn></lowlimit>"#,
+            r#"<uplimit><cn type="integer">1</cn></uplimit><ci>x</ci></apply>"#
+        )
+    );
+    assert_eq!(
+        mathml("[[1, 2], [3, 4]]").1,
+        concat!(
+            r#"<matrix><matrixrow><cn type="integer">1</cn><cn type="integer">2</cn></matrixrow>"#,
+            r#"<matrixrow><cn type="integer">3</cn><cn type="integer">4</cn></matrixrow></matrix>"#
+        )
+    );
+    assert!(mathml("2 - 3i").1.contains("<imaginaryi/>"));
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::Session;
+use tachys_sym::eval::Env;
+
+/// Find the eigenvalues of the matrix `src` numerically from their closed forms, sorted by real
+/// and then imaginary part
+fn eigenvalues(src: &str) -> Vec<(f64, f64)> {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let Session { pool, symbols } = &mut session;
+    let mut values = pool
+        .eigenvalues(symbols, id)
+        .unwrap()
+        .into_iter()
+        .map(|solution| {
+            let value = pool
+                .eval_complex(solution.value, symbols, &Env::new())
+                .unwrap();
+            (value.re, value.im)
+        })
+        .collect::<Vec<_>>();
+    values.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    values
+}
+
+fn assert_close(actual: &[(f64, f64)], expected: &[(f64, f64)]) {
+    assert_eq!(actual.len(), expected.len(), "{actual:?}");
+    for (a, b) in actual.iter().zip(expected) {
+        assert!(
+            (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9,
+            "{actual:?}"
+        );
+    }
+}
+
+#[test]
+fn complex_eigenvalues() {
+    assert_close(&eigenvalues("[[0, -1], [1, 0]]"), &[(0., -1.), (0., 1.)]);
+    assert_close(&eigenvalues("[[1, -2], [2, 1]]"), &[(1., -2.), (1., 2.)]);
+    assert_close(&eigenvalues("[[2, 0], [0, 3]]"), &[(2., 0.), (3., 0.)]);
+}
+
+#[test]
+fn repeated_eigenvalues_keep_their_multiplicity() {
+    assert_close(&eigenvalues("[[1, 1], [0, 1]]"), &[(1., 0.), (1., 0.)]);
+    assert_close(&eigenvalues("[[2, 0], [0, 2]]"), &[(2., 0.), (2., 0.)]);
+    assert_close(
+        &eigenvalues("[[3, 1, 0], [0, 3, 0], [0, 0, -1]]"),
+        &[(-1., 0.), (3., 0.), (3., 0.)],
+    );
+    assert_close(
+        &eigenvalues("[[0, -1, 0, 0], [1, 0, 0, 0], [0, 0, 0, -1], [0, 0, 1, 0]]"),
+        &[(0., -1.), (0., -1.), (0., 1.), (0., 1.)],
+    );
+}
+
+#[test]
+fn eigenvalues_of_companion_matrices() {
+    // Companion matrices of x^3 + x + 1 and x^4 + x + 1, checked against the numeric eigenvalues
+    for src in [
+        "[[0, 0, -1], [1, 0, -1], [0, 1, 0]]",
+        "[[0, 0, 0, -1], [1, 0, 0, -1], [0, 1, 0, 0], [0, 0, 1, 0]]",
+    ] {
+        let mut session = Session::new();
+        let id = session.parse(src);
+        let numeric = session
+            .pool
+            .eval_matrix(id, &session.symbols, &Env::new())
+            .unwrap()
+            .eigenvalues()
+            .unwrap();
+        let exact = eigenvalues(src);
+        assert_eq!(exact.len(), numeric.len(), "{src}: {exact:?}");
+        for (re, im) in numeric {
+            assert!(
+                exact
+                    .iter()
+                    .any(|e| (e.0 - re).abs() < 1e-6 && (e.1 - im).abs() < 1e-6),
+                "{src}: {exact:?} lacks {re} + {im}i"
+            );
+        }
+    }
+}
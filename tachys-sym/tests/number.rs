@@ -0,0 +1,62 @@
+mod common;
+
+use common::{print, simplified};
+use tachys_sym::atom::{number::gcd, BigInt, Rational};
+
+fn rational(numerator: i64, denominator: i64) -> Rational {
+    Rational::new(numerator, denominator).unwrap()
+}
+
+#[test]
+fn rationals_are_exact_and_reduced() {
+    assert_eq!(&rational(1, 3) + &rational(1, 6), rational(1, 2));
+    assert_eq!(&rational(1, 2) - &rational(3, 4), rational(-1, 4));
+    assert_eq!(&rational(2, 3) * &rational(9, 4), rational(3, 2));
+    assert_eq!(-&rational(1, 2), rational(-1, 2));
+
+    let reduced = rational(6, -8);
+    assert_eq!(reduced.numerator(), &BigInt::from(-3));
+    assert_eq!(reduced.denominator(), &BigInt::from(4));
+    assert!(reduced.is_negative() && !reduced.is_integer());
+    assert_eq!(reduced.to_string(), "-3/4");
+    assert_eq!(reduced.floor(), BigInt::from(-1));
+    assert_eq!(rational(4, 2), Rational::integer(2));
+    assert_eq!(Rational::new(1, 0), None);
+}
+
+#[test]
+fn powers_and_division() {
+    assert_eq!(rational(2, 3).pow(-2), Some(rational(9, 4)));
+    assert_eq!(Rational::integer(0).pow(0), Some(Rational::integer(1)));
+    assert_eq!(Rational::integer(0).pow(-1), None);
+    assert_eq!(Rational::integer(0).recip(), None);
+    assert_eq!(rational(1, 2).checked_div(&Rational::integer(0)), None);
+    assert_eq!(
+        rational(1, 2).checked_div(&rational(1, 4)),
+        Some(Rational::integer(2))
+    );
+    assert_eq!(gcd(&BigInt::from(-12), &BigInt::from(18)), BigInt::from(6));
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(rational(1, 4).to_f64(), 0.25);
+    assert_eq!(Rational::from_f64(0.375), Some(rational(3, 8)));
+    assert_eq!(Rational::from_f64(f64::NAN), None);
+    assert_eq!(
+        rational(-5, 10).into_parts(),
+        (BigInt::from(-1), BigInt::from(2))
+    );
+}
+
+#[test]
+fn pool_arithmetic_stays_exact() {
+    assert_eq!(simplified("1/3 + 1/6"), "1/2");
+    assert_eq!(simplified("2^100"), "1267650600228229401496703205376");
+    assert_eq!(simplified("2^100 + 1 - 2^100"), "1");
+    assert_eq!(simplified("(2/3)^-2"), "9/4");
+    assert_eq!(
+        print("123456789012345678901234567890"),
+        "123456789012345678901234567890"
+    );
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::{print, Session};
+use tachys_sym::{
+    eval::Env,
+    parse::{parse, ParseError, ParseErrorKind},
+    Atom, Symbol, SymbolStore,
+};
+
+fn error(src: &str) -> ParseError {
+    let mut session = Session::new();
+    parse(&mut session.pool, &mut session.symbols, src).unwrap_err()
+}
+
+fn variable_of(src: &str) -> String {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    match session.pool.get(id) {
+        Atom::Integral { variable, .. } => session.symbols.name(*variable).to_owned(),
+        other => panic!("expected an integral, got {other:?}"),
+    }
+}
+
+#[test]
+fn differential_ends_integrand() {
+    assert_eq!(variable_of("∫_0^1 delta dx"), "x");
+    assert_eq!(variable_of("∫ density dV"), "V");
+    assert_eq!(variable_of("∫ density d volume"), "volume");
+    assert_eq!(variable_of("∫ x dθ"), "θ");
+    assert_eq!(variable_of("∫ dx"), "x");
+    assert_eq!(print("∫_0^1 delta dx"), print("∫_0^1 δ dx"));
+}
+
+#[test]
+fn signed_integral_bounds() {
+    assert_eq!(print("∫_-1^1 x dx"), print("∫_(-1)^1 x dx"));
+    assert_eq!(print("∫_-a^+a x dx"), print("∫_(-a)^a x dx"));
+}
+
+#[test]
+fn repeated_decimal_point() {
+    let err = error("1.2.3");
+    assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+    assert_eq!(err.span, 0..5);
+}
+
+#[test]
+fn error_at_end_of_input_has_span() {
+    for src in ["1 +", "sin(", "x^"] {
+        let err = error(src);
+        assert!(!err.span.is_empty(), "{src}: {err:?}");
+        assert!(err.span.end <= src.len(), "{src}: {err:?}");
+    }
+}
+
+#[test]
+fn greek_letters_are_separate_symbols() {
+    assert_eq!(print("iπ"), print("i π"));
+    assert_eq!(print("2πr"), print("2 π r"));
+    assert_eq!(print("αβ"), print("α β"));
+    assert_eq!(print("θ_1"), print("theta_1"));
+    assert_eq!(print("xy"), "xy");
+
+    let mut session = Session::new();
+    let id = session.parse("e^(iπ)");
+    let value = session
+        .pool
+        .eval_complex(id, &session.symbols, &Env::new())
+        .unwrap();
+    assert!((value.re + 1.).abs() < 1e-12 && value.im.abs() < 1e-12);
+}
+
+#[test]
+fn symbol_store_excludes_builtin_constants() {
+    let mut symbols = SymbolStore::default();
+    assert!(symbols.is_empty());
+    assert_eq!(symbols.len(), 0);
+    assert_eq!(symbols.iter().count(), 0);
+
+    symbols.intern("x");
+    assert_eq!(symbols.intern("i"), Symbol::IMAGINARY_UNIT);
+    assert_eq!(symbols.intern("e"), Symbol::E);
+    assert_eq!(symbols.intern("π"), Symbol::PI);
+    assert!(!symbols.is_empty());
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(
+        symbols.iter().map(|(_, name)| name).collect::<Vec<_>>(),
+        ["x"]
+    );
+}
+
+#[test]
+fn nested_integrals() {
+    assert_eq!(variable_of("∫_0^1 ∫_0^2 x y dx dy"), "y");
+    assert_eq!(
+        print("∫_0^1 ∫_0^2 x y dx dy"),
+        print("∫_0^1 (∫_0^2 x y dx) dy")
+    );
+    assert_eq!(error("∫ ∫ x dx").kind, ParseErrorKind::ExpectedDifferential);
+}
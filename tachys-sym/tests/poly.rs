@@ -0,0 +1,104 @@
+mod common;
+
+use common::Session;
+use tachys_sym::AtomId;
+
+/// Apply `op` to the expression parsed from `src` and display the result
+fn apply(src: &str, op: fn(&mut Session, AtomId) -> AtomId) -> String {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let out = op(&mut session, id);
+    session.display(out)
+}
+
+fn factored(src: &str) -> String {
+    apply(src, |session, id| session.pool.factor(id))
+}
+
+fn expanded(src: &str) -> String {
+    apply(src, |session, id| session.pool.expand(id))
+}
+
+/// Get the GCD of two polynomials
+fn gcd(a: &str, b: &str) -> String {
+    let mut session = Session::new();
+    let (a, b) = (session.parse(a), session.parse(b));
+    let (a, b) = (session.pool.polynomial(a), session.pool.polynomial(b));
+    let gcd = a.gcd(&b).to_atom(&mut session.pool);
+    let gcd = session.pool.simplify(gcd);
+    session.display(gcd)
+}
+
+#[test]
+fn factorization_over_the_integers() {
+    assert_eq!(factored("x^2 - 1"), "(x - 1)*(x + 1)");
+    assert_eq!(factored("2x^2 + 4x + 2"), "2(x + 1)^2");
+    assert_eq!(factored("6x^2 + 5x + 1"), "(2x + 1)*(3x + 1)");
+    assert_eq!(factored("x^4 + 4"), "(x^2 - 2x + 2)*(x^2 + 2x + 2)");
+    assert_eq!(factored("x^8 - 1"), "(x - 1)*(x + 1)*(x^2 + 1)*(x^4 + 1)");
+    assert_eq!(
+        factored("x^4 + x^3 + x^2 + x + 1"),
+        "x^4 + x^3 + x^2 + x + 1"
+    );
+    assert_eq!(factored("x^3 y - x y^3"), "x*y*(x + y)*(x - y)");
+    assert_eq!(factored("(x^2 - 1)/(x^2 + 2x + 1)"), "(x - 1)/(x + 1)");
+}
+
+#[test]
+fn factorization_multiplies_back_out() {
+    let mut session = Session::new();
+    for src in [
+        "x^6 - 1",
+        "12x^3 - 3x",
+        "(x - 1)^3 (2x + 3)^2",
+        "x^2 y^2 - 4",
+    ] {
+        let id = session.parse(src);
+        let id = session.pool.expand(id);
+        let polynomial = session.pool.polynomial(id);
+        assert_eq!(polynomial.factor().expand(), polynomial, "{src}");
+    }
+}
+
+#[test]
+fn square_free_decomposition() {
+    let mut session = Session::new();
+    let id = session.parse("(x - 1)^3 (x + 2)^2 (x + 3)");
+    let id = session.pool.expand(id);
+    let polynomial = session.pool.polynomial(id);
+    let factors = polynomial
+        .square_free()
+        .iter()
+        .map(|(factor, multiplicity)| {
+            let factor = factor.to_atom(&mut session.pool);
+            (session.display(factor), *multiplicity)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        factors,
+        [
+            ("x + 3".to_owned(), 1),
+            ("x + 2".to_owned(), 2),
+            ("x - 1".to_owned(), 3)
+        ]
+    );
+}
+
+#[test]
+fn greatest_common_divisors() {
+    assert_eq!(gcd("x^2 - 1", "x^2 + 2x + 1"), "x + 1");
+    assert_eq!(gcd("6x + 6", "4x + 4"), "x + 1");
+    assert_eq!(gcd("x^2 y - y", "x y + y"), "x*y + y");
+    assert_eq!(gcd("x^2 + 1", "x - 1"), "1");
+    assert_eq!(gcd("3", "6"), "1");
+    assert_eq!(gcd("0", "2x + 2"), "x + 1");
+}
+
+#[test]
+fn multivariate_expansion() {
+    assert_eq!(expanded("(x + y)^3"), "x^3 + 3x^2*y + 3x*y^2 + y^3");
+    assert_eq!(expanded("(a + b)(c + d)"), "a*c + a*d + b*c + b*d");
+    assert_eq!(expanded("(x + y + z)^2 - (x + y)^2"), "2x*z + 2y*z + z^2");
+    assert_eq!(expanded("sin((x + 1)^2)"), "sin(x^2 + 2x + 1)");
+    assert_eq!(expanded("1/(x + 1)^2"), "1/(x^2 + 2x + 1)");
+}
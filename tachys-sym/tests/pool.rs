@@ -0,0 +1,54 @@
+mod common;
+
+use common::Session;
+use tachys_sym::{Atom, ExprPool};
+
+#[test]
+fn identical_subtrees_share_an_id() {
+    let mut session = Session::new();
+    let a = session.parse("sin(x + 1)^2");
+    let len = session.pool.len();
+    let b = session.parse("sin(x + 1)^2");
+    assert_eq!(a, b);
+    assert_eq!(session.pool.len(), len);
+
+    let c = session.parse("sin(x + 2)^2");
+    assert_ne!(a, c);
+}
+
+#[test]
+fn constructors_normalize_trivial_atoms() {
+    let mut pool = ExprPool::new();
+    assert!(pool.is_empty());
+
+    let two = pool.integer(2);
+    assert_eq!(pool.rational(4, 2), Some(two));
+    assert_eq!(pool.rational(1, 0), None);
+    assert_eq!(pool.sum([two]), two);
+    assert_eq!(pool.product([two]), two);
+    let zero = pool.integer(0);
+    let one = pool.integer(1);
+    assert_eq!(pool.sum([]), zero);
+    assert_eq!(pool.product([]), one);
+    assert_eq!(pool.find(&Atom::Integer(2.into())), Some(two));
+    assert_eq!(pool.find(&Atom::Integer(3.into())), None);
+}
+
+#[test]
+fn traversal_and_substitution() {
+    let mut session = Session::new();
+    let id = session.parse("x*y + x");
+    let x = session.parse("x");
+    let y = session.symbol("y");
+    let z = session.symbol("z");
+    assert!(session.pool.contains(id, x));
+    assert!(session.pool.depends_on(id, y));
+    assert!(!session.pool.depends_on(id, z));
+    assert_eq!(session.pool.descendants(id).next(), Some(id));
+    assert_eq!(session.pool.descendants(id).filter(|&d| d == x).count(), 2);
+
+    let two = session.pool.integer(2);
+    let replaced = session.pool.substitute(id, x, two);
+    assert_eq!(replaced, session.parse("2y + 2"));
+    assert!(!session.pool.contains(replaced, x));
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::{print, Session};
+use tachys_sym::AtomId;
+
+/// Print `id`, parse the output back and check that it simplifies to the same expression
+fn round_trip(session: &mut Session, id: AtomId) {
+    let printed = session.display(id);
+    let reparsed = session.parse(&printed);
+    let original = session.pool.simplify(id);
+    let reparsed = session.pool.simplify(reparsed);
+    assert_eq!(
+        original,
+        reparsed,
+        "{printed} reparsed as {}",
+        session.display(reparsed)
+    );
+}
+
+#[test]
+fn parsed_expressions_round_trip() {
+    let mut session = Session::new();
+    for src in [
+        "x^2 + 3x/(y - 1) = sin(theta)",
+        "-(a + b)^-2",
+        "2 - 3i",
+        "∫_-1^1 x^2 dx",
+        "[[1, x], [y, 2]]",
+        "a <= b",
+    ] {
+        let id = session.parse(src);
+        round_trip(&mut session, id);
+    }
+}
+
+#[test]
+fn rational_and_negated_product_round_trip() {
+    let mut session = Session::new();
+    let x = session.symbol("x");
+    let x = session.pool.symbol(x);
+
+    let third = session.pool.rational(1, 3).unwrap();
+    let scaled = session.pool.product([third, x]);
+    round_trip(&mut session, third);
+    round_trip(&mut session, scaled);
+
+    let minus_one = session.pool.integer(-1);
+    let negated = session.pool.product([minus_one, x]);
+    round_trip(&mut session, negated);
+}
+
+#[test]
+fn infinite_float_round_trips() {
+    let mut session = Session::new();
+    for value in [f64::INFINITY, f64::NEG_INFINITY] {
+        let id = session.pool.float(value);
+        let printed = session.display(id);
+        let reparsed = session.parse(&printed);
+        assert_eq!(session.eval(reparsed), value);
+    }
+}
+
+#[test]
+fn derivatives_and_order_terms_round_trip() {
+    let mut session = Session::new();
+    let x = session.symbol("x");
+    let theta = session.symbol("θ");
+    let f = session.parse("x^3 + sin(x)");
+    let g = session.parse("cos(θ)");
+    let derivatives = [
+        session.pool.derivative(x, 1, f),
+        session.pool.derivative(x, 2, f),
+        session.pool.derivative(theta, 3, g),
+        session.pool.order(f),
+    ];
+    for id in derivatives {
+        for unicode in [false, true] {
+            let printed = match unicode {
+                true => session
+                    .pool
+                    .display(id, &session.symbols)
+                    .unicode()
+                    .to_string(),
+                false => session.display(id),
+            };
+            let reparsed = session.parse(&printed);
+            assert_eq!(
+                id,
+                reparsed,
+                "{printed} reparsed as {}",
+                session.display(reparsed)
+            );
+        }
+    }
+}
+
+#[test]
+fn derivative_syntax() {
+    let mut session = Session::new();
+    let id = session.parse("d/dx x^2 + 1");
+    let x = session.symbol("x");
+    let square = session.parse("x^2");
+    let derivative = session.pool.derivative(x, 1, square);
+    let one = session.pool.integer(1);
+    assert_eq!(id, session.pool.sum([derivative, one]));
+
+    // Without an operand, or with mismatched orders, the operator is an ordinary fraction
+    assert_eq!(print("d/dx"), "d/dx");
+    assert_eq!(print("d^2/dx^3 y"), "d^2/dx^3*y");
+}
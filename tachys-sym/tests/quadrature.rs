@@ -0,0 +1,80 @@
+mod common;
+
+use std::f64::consts::{E, PI};
+
+use common::Session;
+use tachys_sym::{
+    eval::{Env, EvalErrorKind},
+    quadrature::{integrate, Quadrature, QuadratureOptions},
+};
+
+/// Integrate `f` from `a` to `b` with the default options, checking that the result converged to
+/// `expected` within its error estimate
+fn assert_integral(f: impl FnMut(f64) -> f64, a: f64, b: f64, expected: f64) -> Quadrature {
+    let result = integrate(f, a, b, &QuadratureOptions::default());
+    assert!(result.converged, "{result:?}");
+    assert!((result.value - expected).abs() < 1e-9, "{result:?}");
+    assert!(result.error < 1e-9, "{result:?}");
+    result
+}
+
+#[test]
+fn gauss_kronrod_is_exact_for_smooth_integrands() {
+    let result = assert_integral(f64::sin, 0., PI, 2.);
+    assert_eq!(result.evaluations, 15);
+    assert_integral(f64::exp, 0., 1., E - 1.);
+    assert_integral(|x| x.powi(6), -1., 1., 2. / 7.);
+
+    let reversed = assert_integral(|x| x, 1., 0., -0.5);
+    assert_eq!(
+        integrate(|x| x, 1., 1., &QuadratureOptions::default()).value,
+        0.
+    );
+    assert_eq!(
+        reversed.error,
+        integrate(|x| x, 0., 1., &QuadratureOptions::default()).error
+    );
+}
+
+#[test]
+fn tanh_sinh_handles_endpoint_singularities() {
+    assert_integral(|x| 1. / x.sqrt(), 0., 1., 2.);
+    assert_integral(f64::ln, 0., 1., -1.);
+}
+
+#[test]
+fn infinite_bounds_are_mapped_to_finite_intervals() {
+    assert_integral(
+        |x| (-x * x).exp(),
+        f64::NEG_INFINITY,
+        f64::INFINITY,
+        PI.sqrt(),
+    );
+    assert_integral(|x| 1. / (1. + x * x), 0., f64::INFINITY, PI / 2.);
+    assert_integral(f64::exp, f64::NEG_INFINITY, 0., 1.);
+}
+
+#[test]
+fn divergent_integrals_do_not_converge() {
+    let result = integrate(|x| 1. / x, 0., 1., &QuadratureOptions::default());
+    assert!(!result.converged, "{result:?}");
+
+    let options = QuadratureOptions {
+        max_evaluations: 100,
+        ..QuadratureOptions::default()
+    };
+    let result = integrate(|x| (1. / x).sin(), 0., 1., &options);
+    assert!(!result.converged && result.evaluations < 200, "{result:?}");
+
+    let mut session = Session::new();
+    let id = session.parse("∫_0^1 1/x dx");
+    let err = session
+        .pool
+        .eval(id, &session.symbols, &Env::new())
+        .unwrap_err();
+    assert!(
+        matches!(err.kind, EvalErrorKind::NotConverged { error, .. } if error > 1e-9),
+        "{err:?}"
+    );
+    assert_eq!(err.atom, id);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::Session;
+use tachys_sym::rewrite::{Pattern, RewriteError, Rule};
+
+/// Parse a pattern in the session's pool
+fn pattern(session: &mut Session, src: &str) -> Pattern {
+    let Session { pool, symbols } = session;
+    Pattern::parse(pool, symbols, src).unwrap()
+}
+
+/// Parse a rule in the session's pool
+fn rule(session: &mut Session, pattern: &str, replacement: &str) -> Rule {
+    let Session { pool, symbols } = session;
+    Rule::parse(pool, symbols, pattern, replacement).unwrap()
+}
+
+#[test]
+fn sums_and_products_match_commutatively() {
+    let mut session = Session::new();
+    let pattern = pattern(&mut session, "?a * (?b + ?c)");
+    let id = session.parse("2x(y + z + 1)");
+    let [a, b, c] = ["?a", "?b", "?c"].map(|name| session.symbol(name));
+
+    let matches = session
+        .pool
+        .match_all(&pattern, id)
+        .iter()
+        .map(|bindings| [a, b, c].map(|sym| session.display(bindings[&sym])))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        matches,
+        [
+            ["2x", "y", "z + 1"],
+            ["2x", "z", "y + 1"],
+            ["2x", "1", "y + z"]
+        ]
+    );
+}
+
+#[test]
+fn wildcards_bind_consistently_and_respect_constraints() {
+    let mut session = Session::new();
+
+    let twice = pattern(&mut session, "?x + ?x");
+    for (src, expected) in [("y + y", true), ("y + z", false)] {
+        let id = session.parse(src);
+        assert_eq!(
+            session.pool.match_pattern(&twice, id).is_some(),
+            expected,
+            "{src}"
+        );
+    }
+
+    let power = pattern(&mut session, "?x^(?n: integer)");
+    for (src, expected) in [("y^3", true), ("y^(1/2)", false), ("y^n", false)] {
+        let id = session.parse(src);
+        assert_eq!(
+            session.pool.match_pattern(&power, id).is_some(),
+            expected,
+            "{src}"
+        );
+    }
+}
+
+#[test]
+fn rules_apply_bottom_up_and_to_a_fixed_point() {
+    let mut session = Session::new();
+    let rules = [rule(&mut session, "?a * (?b + ?c)", "?a ?b + ?a ?c")];
+    let id = session.parse("sin(2(x + y)) + 3(a + b + c)");
+
+    let once = session.pool.rewrite(id, &rules);
+    assert_eq!(session.display(once), "sin(2x + 2y) + 3a + 3(b + c)");
+
+    let fixed = session.pool.rewrite_fixpoint(id, &rules, 10).unwrap();
+    assert_eq!(session.display(fixed), "sin(2x + 2y) + 3a + 3b + 3c");
+
+    let rules = [rule(&mut session, "?x", "?x + 1")];
+    let id = session.parse("y");
+    assert_eq!(
+        session.pool.rewrite_fixpoint(id, &rules, 5),
+        Err(RewriteError::Diverged(5))
+    );
+}
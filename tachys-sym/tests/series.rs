@@ -0,0 +1,89 @@
+mod common;
+
+use common::Session;
+use tachys_sym::{
+    rewrite::Pattern,
+    series::{SeriesError, SeriesErrorKind},
+};
+
+/// Expand `src` in `x` around `point` below `x^order` and print the series
+fn series(src: &str, point: &str, order: i32) -> Result<String, SeriesErrorKind> {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let point = session.parse(point);
+    let x = session.symbol("x");
+    let Session { pool, symbols } = &mut session;
+    pool.series(symbols, id, x, point, order)
+        .map(|series| session.display(series))
+        .map_err(|SeriesError { kind, .. }| kind)
+}
+
+#[test]
+fn elementary_functions() {
+    assert_eq!(
+        series("sin(x)", "0", 6).unwrap(),
+        "x - x^3/6 + x^5/120 + O(x^6)"
+    );
+    assert_eq!(
+        series("ln(1 + x)", "0", 4).unwrap(),
+        "x - x^2/2 + x^3/3 + O(x^4)"
+    );
+    assert_eq!(
+        series("1/(x - x^2)", "0", 3).unwrap(),
+        "1/x + 1 + x + x^2 + O(x^3)"
+    );
+    assert_eq!(
+        series("exp(1/x)", "∞", 3).unwrap(),
+        "1 + 1/x + 1/(2x^2) + O(1/x^3)"
+    );
+    assert_eq!(
+        series("x^3 + x", "1", 5).unwrap(),
+        "2 + 4(x - 1) + 3(x - 1)^2 + (x - 1)^3"
+    );
+    assert_eq!(
+        series("1/(x^2 - 1)", "1", 2).unwrap(),
+        "1/(2(x - 1)) - 1/4 + (x - 1)/8 + O((x - 1)^2)"
+    );
+}
+
+#[test]
+fn odd_derivatives_of_cos_change_sign() {
+    assert_eq!(
+        series("cos(x)", "0", 6).unwrap(),
+        "1 - x^2/2 + x^4/24 + O(x^6)"
+    );
+    assert_eq!(
+        series("cos(x)", "pi/2", 4).unwrap(),
+        "-(-pi/2 + x) + (-pi/2 + x)^3/6 + O((-pi/2 + x)^4)"
+    );
+}
+
+#[test]
+fn compositions() {
+    assert_eq!(
+        series("exp(sin(x))", "0", 5).unwrap(),
+        "1 + x + x^2/2 - x^4/8 + O(x^5)"
+    );
+}
+
+#[test]
+fn singular_points() {
+    assert_eq!(series("ln(x)", "0", 3), Err(SeriesErrorKind::Branch));
+    assert_eq!(series("sqrt(x)", "0", 3), Err(SeriesErrorKind::Branch));
+    assert_eq!(
+        series("exp(1/x)", "0", 3),
+        Err(SeriesErrorKind::EssentialSingularity)
+    );
+    assert_eq!(series("sin(x)", "x", 3), Err(SeriesErrorKind::Point));
+}
+
+#[test]
+fn order_terms_match_patterns() {
+    let mut session = Session::new();
+    let Session { pool, symbols } = &mut session;
+    let pattern = Pattern::parse(pool, symbols, "?a + O(?b)").unwrap();
+    let id = session.parse("x - x^3/6 + O(x^5)");
+    let b = session.symbol("?b");
+    let bindings = session.pool.match_pattern(&pattern, id).unwrap();
+    assert_eq!(session.display(bindings[&b]), "x^5");
+}
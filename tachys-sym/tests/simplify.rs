@@ -0,0 +1,55 @@
+mod common;
+
+use common::{simplified, Session};
+use tachys_sym::assume::{Assumption, Assumptions};
+
+#[test]
+fn numeric_multiples_of_sums_cancel() {
+    assert_eq!(simplified("(x + 1) - (x + 1)"), "0");
+    assert_eq!(simplified("2(x + y) - 2x"), "2y");
+    assert_eq!(simplified("3(a - b) + 3b - 3a"), "0");
+}
+
+#[test]
+fn constant_factors_of_sums_are_canonical() {
+    assert_eq!(simplified("2(x + y)"), "2x + 2y");
+    assert_eq!(simplified("2(x + y) + 1"), "2x + 2y + 1");
+    assert_eq!(simplified("2(x + y)/(x + y)"), "2");
+    assert_eq!(simplified("(2x + 2y)/(x + y)"), "2");
+    assert_eq!(simplified("(6x + 3)/(2x + 1)"), "3");
+    assert_eq!(simplified("z(2x + 2y)"), simplified("2z(x + y)"));
+    assert_eq!(simplified("(2x + 2)^2"), "4(x + 1)^2");
+    assert_eq!(simplified("-(x - 1)"), simplified("1 - x"));
+}
+
+#[test]
+fn functions_at_special_points_of_constants() {
+    assert_eq!(simplified("ln(e)"), "1");
+    assert_eq!(simplified("ln(e^2)"), "2");
+    assert_eq!(simplified("cos(pi)"), "-1");
+    assert_eq!(simplified("sin(pi/6)"), "1/2");
+    assert_eq!(simplified("cos(pi/4)"), "sqrt(2)/2");
+    assert_eq!(simplified("tan(pi/3)"), "sqrt(3)");
+    assert_eq!(simplified("sin(-2pi/3)"), "-sqrt(3)/2");
+    assert_eq!(simplified("cos(5pi/6)"), "-sqrt(3)/2");
+    assert_eq!(simplified("tan(pi/2)"), "tan(pi/2)");
+    assert_eq!(simplified("sin(pi/5)"), "sin(pi/5)");
+    assert_eq!(simplified("1 - cos(pi)"), "2");
+}
+
+#[test]
+fn logarithm_of_exponential_needs_real_argument() {
+    assert_eq!(simplified("exp(ln(x))"), "x");
+    assert_ne!(simplified("ln(exp(x))"), "x");
+    assert_eq!(simplified("ln(exp(2pi*i))"), "ln(exp(2i*pi))");
+    assert_eq!(simplified("ln(exp(3))"), "3");
+
+    let mut session = Session::new();
+    let id = session.parse("ln(exp(x))");
+    let mut assumptions = Assumptions::new();
+    assumptions
+        .assume(session.symbol("x"), Assumption::Real)
+        .unwrap();
+    let id = session.pool.simplify_assuming(id, &assumptions);
+    assert_eq!(session.display(id), "x");
+}
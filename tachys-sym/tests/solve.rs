@@ -0,0 +1,51 @@
+mod common;
+
+use common::Session;
+use tachys_sym::eval::Env;
+
+/// Solve `src = 0` for `x`, checking that each solution is real, satisfies the equation and
+/// evaluates alike on the real and complex paths, and return them in increasing order
+fn real_roots(src: &str) -> Vec<f64> {
+    let mut session = Session::new();
+    let id = session.parse(src);
+    let x = session.symbol("x");
+    let Session { pool, symbols } = &mut session;
+    let mut roots = pool
+        .solve(symbols, id, x)
+        .unwrap()
+        .into_iter()
+        .map(|solution| {
+            let printed = pool.display(solution.value, symbols).to_string();
+            let real = pool.eval(solution.value, symbols, &Env::new()).unwrap();
+            let complex = pool
+                .eval_complex(solution.value, symbols, &Env::new())
+                .unwrap();
+            assert!((complex.re - real).abs() < 1e-9, "{src}: {printed}");
+            assert!(complex.im.abs() < 1e-9, "{src}: {printed}");
+
+            let mut env = Env::new();
+            env.bind(x, real);
+            let residual = pool.eval(id, symbols, &env).unwrap();
+            assert!(residual.abs() < 1e-9, "{src}: {printed} gives {residual}");
+            real
+        })
+        .collect::<Vec<_>>();
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+#[test]
+fn cardano_takes_real_cube_roots() {
+    assert_eq!(real_roots("x^3 + x + 1").len(), 1);
+    assert_eq!(real_roots("x^3 - 3x + 3").len(), 1);
+    assert_eq!(real_roots("2x^3 - x + 5").len(), 1);
+    assert_eq!(real_roots("x^3 + 2"), [-(2f64.cbrt())]);
+}
+
+#[test]
+fn general_quartics() {
+    assert!(real_roots("x^4 + x + 1").is_empty());
+    assert_eq!(real_roots("x^4 + x - 1").len(), 2);
+    assert_eq!(real_roots("x^4 - 10x^2 + 2x + 3").len(), 4);
+    assert_eq!(real_roots("x^4 - 4x - 1").len(), 2);
+}
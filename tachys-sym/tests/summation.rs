@@ -0,0 +1,85 @@
+mod common;
+
+use common::Session;
+use tachys_sym::BigOperator;
+
+/// Find the closed form of the sum or product of `term` over `k` from `lower` to `upper`
+fn closed_form(op: BigOperator, term: &str, lower: &str, upper: &str) -> Option<String> {
+    let mut session = Session::new();
+    let k = session.symbol("k");
+    let [term, lower, upper] = [term, lower, upper].map(|src| session.parse(src));
+    let Session { pool, symbols } = &mut session;
+    let value = match op {
+        BigOperator::Sum => pool.sum_over(symbols, term, k, lower, upper),
+        BigOperator::Product => pool.product_over(symbols, term, k, lower, upper),
+    }?;
+    Some(session.display(value))
+}
+
+#[test]
+fn empty_ranges() {
+    for (term, lower, upper) in [("k", "5", "1"), ("k^2", "1", "0"), ("2^k", "n", "n - 1")] {
+        assert_eq!(
+            closed_form(BigOperator::Sum, term, lower, upper).as_deref(),
+            Some("0"),
+            "{term} from {lower} to {upper}"
+        );
+        assert_eq!(
+            closed_form(BigOperator::Product, term, lower, upper).as_deref(),
+            Some("1"),
+            "{term} from {lower} to {upper}"
+        );
+    }
+    assert_eq!(
+        closed_form(BigOperator::Sum, "k", "1", "1").as_deref(),
+        Some("1")
+    );
+    assert_eq!(
+        closed_form(BigOperator::Sum, "k", "1", "5").as_deref(),
+        Some("15")
+    );
+}
+
+#[test]
+fn products_with_a_zero_factor_vanish() {
+    for (term, lower, upper) in [
+        ("k", "0", "n"),
+        ("k^2 - 1", "1", "n"),
+        ("(k - 3)/k", "1", "∞"),
+        ("k", "-2", "5"),
+    ] {
+        assert_eq!(
+            closed_form(BigOperator::Product, term, lower, upper).as_deref(),
+            Some("0"),
+            "{term} from {lower} to {upper}"
+        );
+    }
+    // The factor at `k = 2` is only in the range for `n ≥ 2`
+    assert_ne!(
+        closed_form(BigOperator::Product, "k - 2", "0", "n").as_deref(),
+        Some("0")
+    );
+}
+
+#[test]
+fn empty_sums_evaluate_to_positive_zero() {
+    let mut session = Session::new();
+    let id = session.latex(r"\sum_{k=5}^{1} k");
+    let value = session.eval(id);
+    assert!(value == 0. && value.is_sign_positive(), "{value:?}");
+}
+
+#[test]
+fn closed_form_agrees_with_evaluation() {
+    let mut session = Session::new();
+    for src in [
+        r"\sum_{k=5}^{1} k",
+        r"\prod_{k=3}^{2} k",
+        r"\sum_{k=2}^{4} k^2",
+    ] {
+        let id = session.latex(src);
+        let numeric = session.eval(id);
+        let closed = session.pool.eval_big_operators(&mut session.symbols, id);
+        assert_eq!(session.eval(closed), numeric, "{src}");
+    }
+}
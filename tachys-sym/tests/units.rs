@@ -0,0 +1,90 @@
+mod common;
+
+use common::Session;
+use tachys_sym::{
+    eval::{Env, EvalErrorKind},
+    units::UnitErrorKind,
+};
+
+/// Parse `src` with units enabled, simplify it and print the result
+fn simplified(src: &str) -> String {
+    let mut session = Session::new();
+    let id = session.parse_units(src);
+    let id = session.pool.simplify(id);
+    session.display(id)
+}
+
+#[test]
+fn evaluation_checks_dimensions() {
+    let mut session = Session::new();
+
+    for src in ["3 m + 2 s", "sin(3 m)", "1 m + 2"] {
+        let id = session.parse_units(src);
+        let err = session
+            .pool
+            .eval(id, &session.symbols, &Env::new())
+            .unwrap_err();
+        assert!(matches!(err.kind, EvalErrorKind::Units(_)), "{src}: {err}");
+        let err = session
+            .pool
+            .eval_complex(id, &session.symbols, &Env::new())
+            .unwrap_err();
+        assert!(matches!(err.kind, EvalErrorKind::Units(_)), "{src}: {err}");
+    }
+
+    let id = session.parse_units("sin(3 m)");
+    let err = session
+        .pool
+        .eval(id, &session.symbols, &Env::new())
+        .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        EvalErrorKind::Units(UnitErrorKind::NotDimensionless { func: "sin", .. })
+    ));
+
+    let id = session.parse_units("1 km + 1 m");
+    assert_eq!(session.eval(id), 1001.0);
+}
+
+#[test]
+fn like_dimensions_combine() {
+    assert_eq!(simplified("1 m + 1 km"), "1001 m");
+    assert_eq!(simplified("1 km + 1 m"), "1001 m");
+    assert_eq!(simplified("2 h + 30 min"), "150 min");
+    assert_eq!(simplified("1 m^2 + 1 km^2"), "1000001 m^2");
+    assert_eq!(simplified("1 kg m/s^2 + 1 N"), simplified("2 kg m/s^2"));
+    assert_eq!(simplified("3 m + 2 s"), "3 m + 2 s");
+}
+
+#[test]
+fn units_cancel_in_products() {
+    assert_eq!(simplified("1 Hz * 1 s"), "1");
+    assert_eq!(simplified("(1 km)/(1 m)"), "1000");
+    assert_eq!(simplified("(1 kg m/s^2)/(1 N)"), "1");
+    assert_eq!(simplified("(1 m/s)/(1 km/h)"), "18/5");
+    assert_eq!(simplified("1 m/s * 1 h"), "3600 m");
+    assert_eq!(simplified("1 Hz m s"), "m");
+    assert_eq!(simplified("1 Hz^2 s"), "Hz");
+    assert_eq!(simplified("2 min * 3 s"), "360 s^2");
+    assert_eq!(simplified("1 N m"), "m N");
+}
+
+#[test]
+fn prefixed_greek_units_are_single_words() {
+    let mut session = Session::new();
+    for (src, value) in [
+        ("1 kΩ", 1e3),
+        ("1 MΩ to Ω", 1e6),
+        ("1 μs", 1e-6),
+        ("3 μm", 3e-6),
+        ("1 μs + 1 ms", 1.001e-3),
+    ] {
+        let id = session.parse_units(src);
+        let actual = session.eval(id);
+        assert!((actual - value).abs() < 1e-12 * value, "{src} = {actual}");
+    }
+    assert_eq!(simplified("1 μs + 1 ms"), "1001 µs");
+
+    let id = session.parse_units("2iπ");
+    assert_eq!(session.display(id), "2i*pi");
+}
@@ -1,5 +1,7 @@
+use tachys_sym::{latex::parse_latex, parse::{self, ParseError}, AtomId, ExprPool};
+use tachys_document::rt::Runtime;
 use tiny_skia::{Color, PixmapPaint, Point, Rect, Transform};
-use winit::event::{ElementState, WindowEvent};
+use winit::{event::{ElementState, WindowEvent}, keyboard::{Key, ModifiersState}};
 
 use crate::ui::{element::Element, font::Glyph, LayoutCtx, PaintCtx, PixmapExtensions, UiContext, UiError};
 
@@ -7,7 +9,57 @@ use crate::ui::{element::Element, font::Glyph, LayoutCtx, PaintCtx, PixmapExtens
 #[derive(Default,)]
 pub struct Editor {
     edit: String,
+    /// Byte offset into `edit` at which text is inserted
     cursor: usize,
+    pool: ExprPool,
+    /// Document state whose symbol table the parsed expressions are interned into
+    runtime: Runtime,
+    parsed: Option<Result<AtomId, ParseError>>,
+    modifiers: ModifiersState,
+}
+
+impl Editor {
+    /// Parse the current contents of the editor, replacing the last parse result
+    fn reparse(&mut self) {
+        // Expressions from earlier edits are never referenced again, so start from an empty pool
+        // instead of letting it grow with every keystroke
+        self.pool = ExprPool::default();
+        self.parsed = Some(parse::parse(&mut self.pool, &mut self.runtime.symbols().write(), &self.edit));
+    }
+
+    /// Insert pasted text at the cursor, converting LaTeX such as `\frac{a}{b}` to linear syntax
+    fn paste(&mut self, text: &str) {
+        if !text.contains('\\') {
+            self.edit.insert_str(self.cursor, text);
+            self.cursor += text.len();
+            return self.reparse();
+        }
+
+        self.pool = ExprPool::default();
+        let parsed = parse_latex(&mut self.pool, &mut self.runtime.symbols().write(), text);
+        match parsed {
+            Ok(id) => {
+                let linear = self.pool.display(id, &self.runtime.symbols().read()).unicode().to_string();
+                let whole = self.edit.is_empty();
+                self.edit.insert_str(self.cursor, &linear);
+                self.cursor += linear.len();
+
+                // Sums and limits have no linear syntax, so keep the LaTeX parse if it is the
+                // whole expression rather than reparsing the text written for it
+                match whole {
+                    true => self.parsed = Some(Ok(id)),
+                    false => self.reparse(),
+                }
+            }
+            Err(e) => {
+                // Point the error at the pasted text within the rest of the edit
+                let span = self.cursor + e.span.start..self.cursor + e.span.end;
+                self.edit.insert_str(self.cursor, text);
+                self.cursor += text.len();
+                self.parsed = Some(Err(ParseError { span, ..e }));
+            }
+        }
+    }
 }
 
 impl Element for Editor {
@@ -22,6 +74,10 @@ impl Element for Editor {
         let font = ui.fonts_mut().get_mut(font_id);
 
         let mut pos = Point::default();
+        let error_span = match self.parsed {
+            Some(Err(ref e)) => Some(e.span.clone()),
+            _ => None,
+        };
 
         for (i, ch) in self.edit.char_indices() {
             let glyph = Glyph {
@@ -42,7 +98,13 @@ impl Element for Editor {
                 );
             }
 
-            if i == self.cursor - 1 {
+            if error_span.as_ref().is_some_and(|span| span.contains(&i)) {
+                if let Some(underline) = Rect::from_xywh(pos.x, pos.y + 20f32, render.advance as f32, 1f32) {
+                    pixmap.outline_rect(underline, Color::from_rgba8(255, 0, 0, 255));
+                }
+            }
+
+            if i + ch.len_utf8() == self.cursor {
                 pixmap.outline_rect(Rect::from_xywh(pos.x, pos.y, render.advance as f32, 20f32).unwrap(), Color::from_rgba8(255, 0, 0, 255));
             }
 
@@ -56,14 +118,24 @@ impl Element for Editor {
         match event {
             WindowEvent::KeyboardInput { device_id: _, event, is_synthetic: _ } => {
                 if event.state == ElementState::Pressed {
-                    if let Some(text) = event.text {
+                    if self.modifiers.control_key() && event.logical_key.as_ref() == Key::Character("v") {
+                        match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+                            Ok(text) => self.paste(&text),
+                            Err(e) => log::error!("Failed to read clipboard: {e}"),
+                        }
+                    } else if let Some(text) = event.text {
                         self.edit.insert_str(self.cursor, text.as_str());
-                        self.cursor += 1;
+                        self.cursor += text.len();
+                        self.reparse();
                     }
                 }
 
                 Ok(())
             },
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+                Ok(())
+            },
             _ => Ok(())
         }
     }